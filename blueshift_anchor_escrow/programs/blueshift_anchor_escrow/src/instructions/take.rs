@@ -15,7 +15,6 @@ pub struct Take<'info> {
   pub maker: SystemAccount<'info>,
   #[account(
       mut,
-      close = maker,
       seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
       bump = escrow.bump,
       has_one = maker @ EscrowError::InvalidMaker,
@@ -66,17 +65,46 @@ pub struct Take<'info> {
 
 impl<'info> Take<'info> {
 
-    pub fn handler(ctx: Context<Take>) -> Result<()> {
+    pub fn handler(ctx: Context<Take>, fill_amount: u64) -> Result<()> {
+        // Validate the fill: 0 < fill_amount <= receive
+        require_gt!(fill_amount, 0, EscrowError::InvalidAmount);
+        require_gte!(ctx.accounts.escrow.receive, fill_amount, EscrowError::InvalidAmount);
+
         // Transfer Token B to Maker
-        ctx.accounts.transfer_to_maker()?;
-    
-        // Withdraw and close the Vault
-        ctx.accounts.withdraw_and_close_vault()?;
-    
+        ctx.accounts.transfer_to_maker(fill_amount)?;
+
+        if fill_amount == ctx.accounts.escrow.receive {
+            // Full fill: withdraw and close the Vault, then close the Escrow
+            ctx.accounts.withdraw_and_close_vault()?;
+            ctx.accounts.escrow.close(ctx.accounts.maker.to_account_info())?;
+        } else {
+            // Partial fill: withdraw the proportional share and keep the Escrow open
+            let amount = ctx.accounts.proportional_share(fill_amount)?;
+            ctx.accounts.withdraw_from_vault(amount)?;
+            ctx.accounts.escrow.receive -= fill_amount;
+        }
+
         Ok(())
     }
-    
-    fn transfer_to_maker(&mut self) -> Result<()> {
+
+    /// # Share of Token A owed for `fill_amount` of Token B
+    ///
+    /// `vault.amount * fill_amount / receive`, rounded down so the maker never
+    /// gives out more than the remaining price covers.
+    fn proportional_share(&self, fill_amount: u64) -> Result<u64> {
+        let amount = (self.vault.amount as u128)
+            .checked_mul(fill_amount as u128)
+            .ok_or(EscrowError::InvalidAmount)?
+            .checked_div(self.escrow.receive as u128)
+            .ok_or(EscrowError::InvalidAmount)?;
+
+        // A fill too small to buy a single unit of Token A is rejected
+        require_gt!(amount, 0, EscrowError::InvalidAmount);
+
+        u64::try_from(amount).map_err(|_| EscrowError::InvalidAmount.into())
+    }
+
+    fn transfer_to_maker(&mut self, fill_amount: u64) -> Result<()> {
         transfer_checked(
             CpiContext::new(
                 self.token_program.to_account_info(),
@@ -87,14 +115,14 @@ impl<'info> Take<'info> {
                     authority: self.taker.to_account_info(),
                 },
             ),
-            self.escrow.receive,
+            fill_amount,
             self.mint_b.decimals,
         )?;
 
         Ok(())
     }
 
-    fn withdraw_and_close_vault(&mut self) -> Result<()> {
+    fn withdraw_from_vault(&mut self, amount: u64) -> Result<()> {
         // Create the signer seeds for the Vault
         let signer_seeds: [&[&[u8]]; 1] = [&[
             b"escrow",
@@ -115,10 +143,25 @@ impl<'info> Take<'info> {
                 },
                 &signer_seeds,
             ),
-            self.vault.amount,
+            amount,
             self.mint_a.decimals,
         )?;
 
+        Ok(())
+    }
+
+    fn withdraw_and_close_vault(&mut self) -> Result<()> {
+        // Transfer the rest of Token A (Vault -> Taker)
+        self.withdraw_from_vault(self.vault.amount)?;
+
+        // Create the signer seeds for the Vault
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"escrow",
+            self.maker.to_account_info().key.as_ref(),
+            &self.escrow.seed.to_le_bytes()[..],
+            &[self.escrow.bump],
+        ]];
+
         // Close the Vault
         close_account(CpiContext::new_with_signer(
             self.token_program.to_account_info(),
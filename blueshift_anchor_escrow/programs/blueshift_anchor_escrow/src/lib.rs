@@ -18,8 +18,8 @@ pub mod blueshift_anchor_escrow {
     }
 
     #[instruction(discriminator = 1)]
-    pub fn take(ctx: Context<Take>) -> Result<()> {
-        Take::handler(ctx)
+    pub fn take(ctx: Context<Take>, fill_amount: u64) -> Result<()> {
+        Take::handler(ctx, fill_amount)
     }
 
     #[instruction(discriminator = 2)]
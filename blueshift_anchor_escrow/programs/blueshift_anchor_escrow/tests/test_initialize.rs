@@ -444,9 +444,10 @@ fn test_take_success() {
         &spl_token_2022::id(),
     );
 
-    // Build instruction data: just discriminator for take
+    // Build instruction data: discriminator + fill_amount (full fill)
     let discriminator = get_discriminator(1);
-    let instruction_data = discriminator.to_vec();
+    let mut instruction_data = discriminator.to_vec();
+    instruction_data.extend_from_slice(&receive.to_le_bytes());
 
     let instruction = Instruction {
         program_id,
@@ -486,6 +487,209 @@ fn test_take_success() {
     println!("Take instruction result: {:?}", result.program_result);
 }
 
+/// Read the token amount from a Token-2022 account
+fn token_amount(account: &Account) -> u64 {
+    TokenAccount::unpack(&account.data[..TokenAccount::LEN]).unwrap().amount
+}
+
+#[test]
+fn test_take_partial_fill_then_complete() {
+    let mollusk = setup_mollusk();
+    let program_id = blueshift_anchor_escrow::id();
+
+    // Get program accounts from mollusk helpers
+    let (ata_program_id, ata_program_account) = associated_token::keyed_account();
+    let (token_program_id, token_program_account) = token2022::keyed_account();
+
+    // Setup keys
+    let maker = Pubkey::new_unique();
+    let taker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+    let mint_b = Pubkey::new_unique();
+    let seed: u64 = 12345;
+    let receive: u64 = 500;
+    let vault_amount: u64 = 1000;
+
+    // Calculate PDAs
+    let (escrow_pda, bump) = Pubkey::find_program_address(
+        &[b"escrow", maker.as_ref(), &seed.to_le_bytes()],
+        &program_id,
+    );
+
+    let vault = get_associated_token_address_with_program_id(
+        &escrow_pda,
+        &mint_a,
+        &spl_token_2022::id(),
+    );
+
+    let taker_ata_a = get_associated_token_address_with_program_id(
+        &taker,
+        &mint_a,
+        &spl_token_2022::id(),
+    );
+
+    let taker_ata_b = get_associated_token_address_with_program_id(
+        &taker,
+        &mint_b,
+        &spl_token_2022::id(),
+    );
+
+    let maker_ata_b = get_associated_token_address_with_program_id(
+        &maker,
+        &mint_b,
+        &spl_token_2022::id(),
+    );
+
+    let take_instruction = |fill_amount: u64| {
+        let mut data = get_discriminator(1).to_vec();
+        data.extend_from_slice(&fill_amount.to_le_bytes());
+
+        Instruction {
+            program_id,
+            accounts: vec![
+                AccountMeta::new(taker, true),
+                AccountMeta::new(maker, false),
+                AccountMeta::new(escrow_pda, false),
+                AccountMeta::new_readonly(mint_a, false),
+                AccountMeta::new_readonly(mint_b, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new(taker_ata_a, false),
+                AccountMeta::new(taker_ata_b, false),
+                AccountMeta::new(maker_ata_b, false),
+                AccountMeta::new_readonly(ata_program_id, false),
+                AccountMeta::new_readonly(token_program_id, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data,
+        }
+    };
+
+    let accounts = vec![
+        (taker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (maker, create_system_account(LAMPORTS_PER_SOL)),
+        (escrow_pda, create_escrow_account(&program_id, seed, &maker, &mint_a, &mint_b, receive, bump)),
+        (mint_a, create_mint_account(&maker, 6)),
+        (mint_b, create_mint_account(&maker, 6)),
+        (vault, create_token_account(&mint_a, &escrow_pda, vault_amount)),
+        (taker_ata_a, Account::default()),
+        (taker_ata_b, create_token_account(&mint_b, &taker, 10_000)),
+        (maker_ata_b, Account::default()),
+        (ata_program_id, ata_program_account),
+        (token_program_id, token_program_account),
+        (system_program::id(), create_system_program_account()),
+    ];
+
+    // 50% fill: half of Token B buys half of the vault, escrow stays open
+    let result = mollusk.process_instruction(&take_instruction(receive / 2), &accounts);
+    assert!(result.program_result.is_ok(), "Partial take failed: {:?}", result.program_result);
+
+    let escrow = result.get_account(&escrow_pda).unwrap();
+    let remaining = u64::from_le_bytes(escrow.data[112..120].try_into().unwrap());
+    assert_eq!(remaining, receive / 2);
+    assert_eq!(token_amount(result.get_account(&vault).unwrap()), vault_amount / 2);
+    assert_eq!(token_amount(result.get_account(&taker_ata_a).unwrap()), vault_amount / 2);
+    assert_eq!(token_amount(result.get_account(&maker_ata_b).unwrap()), receive / 2);
+
+    // Completing fill: the rest of the vault goes to the taker, vault and escrow are closed
+    let result = mollusk.process_instruction(&take_instruction(remaining), &result.resulting_accounts);
+    assert!(result.program_result.is_ok(), "Completing take failed: {:?}", result.program_result);
+
+    assert_eq!(token_amount(result.get_account(&taker_ata_a).unwrap()), vault_amount);
+    assert_eq!(token_amount(result.get_account(&maker_ata_b).unwrap()), receive);
+    assert_eq!(result.get_account(&vault).unwrap().lamports, 0);
+    assert_eq!(result.get_account(&escrow_pda).unwrap().lamports, 0);
+}
+
+#[test]
+fn test_take_overfill_fails() {
+    let mollusk = setup_mollusk();
+    let program_id = blueshift_anchor_escrow::id();
+
+    // Get program accounts from mollusk helpers
+    let (ata_program_id, ata_program_account) = associated_token::keyed_account();
+    let (token_program_id, token_program_account) = token2022::keyed_account();
+
+    // Setup keys
+    let maker = Pubkey::new_unique();
+    let taker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+    let mint_b = Pubkey::new_unique();
+    let seed: u64 = 12345;
+    let receive: u64 = 500;
+    let vault_amount: u64 = 1000;
+
+    // Calculate PDAs
+    let (escrow_pda, bump) = Pubkey::find_program_address(
+        &[b"escrow", maker.as_ref(), &seed.to_le_bytes()],
+        &program_id,
+    );
+
+    let vault = get_associated_token_address_with_program_id(
+        &escrow_pda,
+        &mint_a,
+        &spl_token_2022::id(),
+    );
+
+    let taker_ata_a = get_associated_token_address_with_program_id(
+        &taker,
+        &mint_a,
+        &spl_token_2022::id(),
+    );
+
+    let taker_ata_b = get_associated_token_address_with_program_id(
+        &taker,
+        &mint_b,
+        &spl_token_2022::id(),
+    );
+
+    let maker_ata_b = get_associated_token_address_with_program_id(
+        &maker,
+        &mint_b,
+        &spl_token_2022::id(),
+    );
+
+    // fill_amount larger than the outstanding receive
+    let mut instruction_data = get_discriminator(1).to_vec();
+    instruction_data.extend_from_slice(&(receive + 1).to_le_bytes());
+
+    let instruction = Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(taker, true),
+            AccountMeta::new(maker, false),
+            AccountMeta::new(escrow_pda, false),
+            AccountMeta::new_readonly(mint_a, false),
+            AccountMeta::new_readonly(mint_b, false),
+            AccountMeta::new(vault, false),
+            AccountMeta::new(taker_ata_a, false),
+            AccountMeta::new(taker_ata_b, false),
+            AccountMeta::new(maker_ata_b, false),
+            AccountMeta::new_readonly(ata_program_id, false),
+            AccountMeta::new_readonly(token_program_id, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: instruction_data,
+    };
+
+    let accounts = vec![
+        (taker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (maker, create_system_account(LAMPORTS_PER_SOL)),
+        (escrow_pda, create_escrow_account(&program_id, seed, &maker, &mint_a, &mint_b, receive, bump)),
+        (mint_a, create_mint_account(&maker, 6)),
+        (mint_b, create_mint_account(&maker, 6)),
+        (vault, create_token_account(&mint_a, &escrow_pda, vault_amount)),
+        (taker_ata_a, Account::default()),
+        (taker_ata_b, create_token_account(&mint_b, &taker, 10_000)),
+        (maker_ata_b, Account::default()),
+        (ata_program_id, ata_program_account),
+        (token_program_id, token_program_account),
+        (system_program::id(), create_system_program_account()),
+    ];
+
+    let result = mollusk.process_instruction(&instruction, &accounts);
+    assert!(result.program_result.is_err(), "Take above receive should fail");
+}
+
 // ============================================================================
 // Refund Instruction Tests
 // ============================================================================
@@ -0,0 +1,14 @@
+use pinocchio::program_error::ProgramError;
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EscrowError {
+    EscrowExpired = 0, //已超过 escrow 的 expiry，不能再 take（maker 仍可随时 refund）
+    MemoTooLong = 1, //SetMemo 的 memo 超过 Escrow::MAX_MEMO_LEN 字节
+}
+
+impl From<EscrowError> for ProgramError {
+    fn from(e: EscrowError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
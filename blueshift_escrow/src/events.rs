@@ -0,0 +1,151 @@
+use pinocchio::pubkey::Pubkey;
+
+// ============================================================================
+// 事件
+// ============================================================================
+//
+// 每条指令结束时用 sol_log_data 输出一个数据段，indexer 不需要再从代币转账反推 escrow 的状态。
+//
+// 公共布局（EVENT_VERSION = 1），整数均为小端，Pubkey 为原始 32 字节：
+//   [0]      version，字段布局变化时递增，客户端据此选择解析方式
+//   [1]      事件类型：0 = EscrowMade，1 = EscrowTaken，2 = EscrowRefunded
+//   [2..10)  seed: u64
+//   [10..42) maker
+//   ...      各事件自己的字段（见各结构体的注释）
+
+/// 事件布局版本，位于第一个字节
+pub const EVENT_VERSION: u8 = 1;
+
+/// 按顺序写入字段的小工具
+struct EventWriter<'a, const N: usize> {
+    buf: &'a mut [u8; N],
+    offset: usize,
+}
+
+impl<'a, const N: usize> EventWriter<'a, N> {
+    #[inline(always)]
+    fn new(buf: &'a mut [u8; N], discriminator: u8, seed: u64, maker: &Pubkey) -> Self {
+        buf[0] = EVENT_VERSION;
+        buf[1] = discriminator;
+        Self { buf, offset: 2 }.u64(seed).bytes(maker)
+    }
+
+    #[inline(always)]
+    fn bytes(mut self, value: &[u8]) -> Self {
+        self.buf[self.offset..self.offset + value.len()].copy_from_slice(value);
+        self.offset += value.len();
+        self
+    }
+
+    #[inline(always)]
+    fn u64(self, value: u64) -> Self {
+        self.bytes(&value.to_le_bytes())
+    }
+}
+
+/// 各事件的 `LEN`：`to_bytes` 的返回类型不能写成 `[u8; Self::LEN]`（带生命周期参数的 Self 不能用在数组长度中）
+const ESCROW_MADE_LEN: usize = 2 + 8 + 32 + 32 + 32 + 8 + 8;
+
+/// Make 完成：maker 存入 `amount` 个 mint_a，要求 `receive` 个 mint_b
+///
+/// 布局：公共头 + mint_a (32) + mint_b (32) + amount (u64) + receive (u64)
+pub struct EscrowMade<'a> {
+    pub seed: u64,
+    pub maker: &'a Pubkey,
+    pub mint_a: &'a Pubkey,
+    pub mint_b: &'a Pubkey,
+    pub amount: u64,
+    pub receive: u64,
+}
+
+impl EscrowMade<'_> {
+    pub const DISCRIMINATOR: u8 = 0;
+    pub const LEN: usize = ESCROW_MADE_LEN;
+
+    pub fn to_bytes(&self) -> [u8; ESCROW_MADE_LEN] {
+        let mut buf = [0u8; ESCROW_MADE_LEN];
+        EventWriter::new(&mut buf, Self::DISCRIMINATOR, self.seed, self.maker)
+            .bytes(self.mint_a)
+            .bytes(self.mint_b)
+            .u64(self.amount)
+            .u64(self.receive);
+        buf
+    }
+
+    #[inline(always)]
+    pub fn emit(&self) {
+        pinocchio::log::sol_log_data(&[&self.to_bytes()]);
+    }
+}
+
+const ESCROW_TAKEN_LEN: usize = 2 + 8 + 32 + 32 + 32 + 32 + 8 + 8 + 8;
+
+/// Take 完成：taker 取走金库中的 `amount` 个 mint_a，支付 `receive` 个 mint_b，其中 `platform_fee` 给平台
+///
+/// 布局：公共头 + taker (32) + mint_a (32) + mint_b (32) + amount (u64) + receive (u64) + platform_fee (u64)
+pub struct EscrowTaken<'a> {
+    pub seed: u64,
+    pub maker: &'a Pubkey,
+    pub taker: &'a Pubkey,
+    pub mint_a: &'a Pubkey,
+    pub mint_b: &'a Pubkey,
+    pub amount: u64,
+    pub receive: u64,
+    pub platform_fee: u64,
+}
+
+impl EscrowTaken<'_> {
+    pub const DISCRIMINATOR: u8 = 1;
+    pub const LEN: usize = ESCROW_TAKEN_LEN;
+
+    pub fn to_bytes(&self) -> [u8; ESCROW_TAKEN_LEN] {
+        let mut buf = [0u8; ESCROW_TAKEN_LEN];
+        EventWriter::new(&mut buf, Self::DISCRIMINATOR, self.seed, self.maker)
+            .bytes(self.taker)
+            .bytes(self.mint_a)
+            .bytes(self.mint_b)
+            .u64(self.amount)
+            .u64(self.receive)
+            .u64(self.platform_fee);
+        buf
+    }
+
+    #[inline(always)]
+    pub fn emit(&self) {
+        pinocchio::log::sol_log_data(&[&self.to_bytes()]);
+    }
+}
+
+const ESCROW_REFUNDED_LEN: usize = 2 + 8 + 32 + 32 + 8 + 8;
+
+/// Refund / PartialRefund 完成：maker 取回 `amount` 个 mint_a，金库中还剩 `remaining`
+///
+/// `remaining` 为 0 表示 escrow 已关闭；否则是 PartialRefund，escrow 保持打开
+///
+/// 布局：公共头 + mint_a (32) + amount (u64) + remaining (u64)
+pub struct EscrowRefunded<'a> {
+    pub seed: u64,
+    pub maker: &'a Pubkey,
+    pub mint_a: &'a Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+impl EscrowRefunded<'_> {
+    pub const DISCRIMINATOR: u8 = 2;
+    pub const LEN: usize = ESCROW_REFUNDED_LEN;
+
+    pub fn to_bytes(&self) -> [u8; ESCROW_REFUNDED_LEN] {
+        let mut buf = [0u8; ESCROW_REFUNDED_LEN];
+        EventWriter::new(&mut buf, Self::DISCRIMINATOR, self.seed, self.maker)
+            .bytes(self.mint_a)
+            .u64(self.amount)
+            .u64(self.remaining);
+        buf
+    }
+
+    #[inline(always)]
+    pub fn emit(&self) {
+        pinocchio::log::sol_log_data(&[&self.to_bytes()]);
+    }
+}
@@ -1,12 +1,14 @@
 use pinocchio::{
     account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction, Signer},
+    program::invoke_signed,
     program_error::ProgramError,
     pubkey::Pubkey,
     ProgramResult,
     sysvars::{rent::Rent, Sysvar},
 };
-use pinocchio_system::instructions::CreateAccount;
-use pinocchio_token::state::Mint;
+use pinocchio_system::instructions::{CreateAccount, Transfer};
+use pinocchio_token::state::{Mint, TokenAccount};
 
 // Associated Token Program ID
 // Pubkey: ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL
@@ -41,6 +43,29 @@ impl SignerAccount {
     }
 }
 
+/// 辅助结构体用于系统账户检查
+pub struct SystemAccount;
+
+impl SystemAccount {
+    /// 检查账户是否由系统程序拥有
+    #[inline(always)]
+    pub fn check(account: &AccountInfo) -> ProgramResult {
+        if !account.is_owned_by(&pinocchio_system::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(())
+    }
+
+    /// 检查账户是否为系统程序本身
+    #[inline(always)]
+    pub fn check_program(account: &AccountInfo) -> ProgramResult {
+        if account.key() != &pinocchio_system::ID {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        Ok(())
+    }
+}
+
 /// 辅助结构体用于 Program Account 操作
 pub struct ProgramAccount;
 
@@ -79,10 +104,31 @@ impl ProgramAccount {
     }
 
     /// 关闭账户时设置的 discriminator，防止重新初始化攻击
-    const CLOSED_ACCOUNT_DISCRIMINATOR: u8 = 255;
+    pub const CLOSED_ACCOUNT_DISCRIMINATOR: u8 = 255;
+
+    /// 是否为 `close` 写入的 [255, 0, 0, ...]
+    /// 只看第一个字节不够：escrow 的第一个字段是 seed，低字节可能恰好是 255
+    #[inline(always)]
+    pub fn is_closed(data: &[u8]) -> bool {
+        matches!(data.split_first(), Some((&Self::CLOSED_ACCOUNT_DISCRIMINATOR, rest)) if rest.iter().all(|b| *b == 0))
+    }
+
+    /// 检查账户是否未被关闭，在重新读取账户状态之前调用
+    /// 同一笔交易中被关闭的账户可能又被转入 lamports 复活，数据仍是关闭标记
+    #[inline(always)]
+    pub fn check_not_closed(account: &AccountInfo) -> ProgramResult {
+        if Self::is_closed(&account.try_borrow_data()?) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(())
+    }
 
     /// 关闭 Program Account，将 lamports 转移到目标账户
     /// 设置第一个字节为 255 防止账户被重新初始化
+    ///
+    /// 这里直接修改 lamports 而不是 CPI System Program 的 Transfer：
+    /// Transfer 要求 from 由 System Program 拥有，而 escrow 由本程序拥有，
+    /// 本程序可以直接扣减自己账户的 lamports
     pub fn close(account: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
         // 获取账户余额
         let dest_starting_lamports = destination.lamports();
@@ -105,6 +151,35 @@ impl ProgramAccount {
 
         Ok(())
     }
+
+    /// 重新分配账户空间，不足的租金由 payer 补齐
+    ///
+    /// 先 resize 再补足租金：resize 失败时不会有 lamports 被转走。
+    /// payer 是系统账户，本程序不能直接扣减它的 lamports（与 `close` 相反），所以通过 System Program 转账。
+    /// 缩小时多余的 lamports 留在账户中，不退还。
+    pub fn realloc(
+        account: &AccountInfo,
+        payer: &AccountInfo,
+        new_size: usize,
+    ) -> ProgramResult {
+        // 重新分配空间
+        account.resize(new_size)?;
+
+        let new_minimum_balance = Rent::get()?.minimum_balance(new_size);
+        let current_lamports = account.lamports();
+
+        // 如果需要更多 lamports，从 payer 转移
+        if new_minimum_balance > current_lamports {
+            Transfer {
+                from: payer,
+                to: account,
+                lamports: new_minimum_balance - current_lamports,
+            }
+            .invoke()?;
+        }
+
+        Ok(())
+    }
 }
 
 /// 辅助结构体用于 Mint 检查
@@ -132,10 +207,143 @@ impl MintInterface {
             return Err(ProgramError::InvalidAccountOwner);
         }
 
+        // 长度正确但未初始化的账户不是有效的 Mint
+        let mint = unsafe { Mint::from_account_info_unchecked(account)? };
+        if !mint.is_initialized() {
+            return Err(ProgramError::UninitializedAccount);
+        }
+
+        Ok(())
+    }
+
+    /// 检查账户是否为有效的 Token Mint，并且由传入的 token_program 拥有
+    ///
+    /// 之后的 CPI 调用的是 token_program，mint 必须属于同一个程序
+    #[inline(always)]
+    pub fn check_with_program(account: &AccountInfo, token_program: &AccountInfo) -> ProgramResult {
+        if !account.is_owned_by(token_program.key()) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Self::check(account)
+    }
+
+    /// 读取 mint 的精度，用于 TransferChecked
+    /// 调用前需先通过 `MintInterface::check`
+    #[inline(always)]
+    pub fn decimals(account: &AccountInfo) -> Result<u8, ProgramError> {
+        Ok(unsafe { Mint::from_account_info_unchecked(account)? }.decimals())
+    }
+}
+
+/// 辅助结构体用于 Token Program 检查
+pub struct TokenProgram;
+
+impl TokenProgram {
+    /// 转账/关闭的 CPI 直接调用传入的 token_program，必须是 SPL Token 或 Token-2022
+    #[inline(always)]
+    pub fn check(account: &AccountInfo) -> ProgramResult {
+        if account.key() != &pinocchio_token::ID && account.key() != &SPL_TOKEN_2022_ID {
+            return Err(ProgramError::IncorrectProgramId);
+        }
         Ok(())
     }
 }
 
+/// 辅助结构体用于 Token Account 检查
+pub struct TokenAccountInterface;
+
+impl TokenAccountInterface {
+    /// 检查 Token Account 的 mint 是否为预期的 mint
+    /// 调用前需先确认账户由 Token Program 拥有（例如 AssociatedTokenAccount::check）
+    #[inline(always)]
+    pub fn check_mint(account: &AccountInfo, expected_mint: &Pubkey) -> ProgramResult {
+        if account.data_len() < TokenAccount::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        let token_account = unsafe { TokenAccount::from_account_info_unchecked(account)? };
+        if token_account.mint() != expected_mint {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(())
+    }
+
+    /// 读取 Token Account 的余额（SPL Token 与 Token-2022 基础布局相同）
+    /// 调用前需先确认账户由 Token Program 拥有（例如 AssociatedTokenAccount::check）
+    #[inline(always)]
+    pub fn amount(account: &AccountInfo) -> Result<u64, ProgramError> {
+        if account.data_len() < TokenAccount::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { TokenAccount::from_account_info_unchecked(account)? }.amount())
+    }
+}
+
+// pinocchio-token 的指令结构体固定调用 SPL Token Program，这里手动构造指令，
+// 调用传入的 token_program，同时支持 SPL Token 和 Token-2022（两者基础指令编码相同）
+
+/// TransferChecked：由 token program 校验 mint 与精度，Token-2022 的 mint 必须使用它
+///
+/// 字段与 `pinocchio_token::instructions::TransferChecked` 相同，另外带上要调用的 token_program
+pub struct TransferChecked<'a> {
+    pub token_program: &'a AccountInfo,
+    pub from: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub to: &'a AccountInfo,
+    pub authority: &'a AccountInfo,
+    pub amount: u64,
+    pub decimals: u8,
+}
+
+impl TransferChecked<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        // [12] + amount (u64 LE) + decimals (u8)
+        let mut data = [0u8; 10];
+        data[0] = 12;
+        data[1..9].copy_from_slice(&self.amount.to_le_bytes());
+        data[9] = self.decimals;
+
+        let instruction = Instruction {
+            program_id: self.token_program.key(),
+            accounts: &[
+                AccountMeta::writable(self.from.key()),
+                AccountMeta::readonly(self.mint.key()),
+                AccountMeta::writable(self.to.key()),
+                AccountMeta::readonly_signer(self.authority.key()),
+            ],
+            data: &data,
+        };
+
+        invoke_signed(&instruction, &[self.from, self.mint, self.to, self.authority], signers)
+    }
+}
+
+/// CloseAccount：把 Token Account 的 lamports 转给 destination
+pub fn close_token_account(
+    token_program: &AccountInfo,
+    account: &AccountInfo,
+    destination: &AccountInfo,
+    authority: &AccountInfo,
+    signers: &[Signer],
+) -> ProgramResult {
+    let instruction = Instruction {
+        program_id: token_program.key(),
+        accounts: &[
+            AccountMeta::writable(account.key()),
+            AccountMeta::writable(destination.key()),
+            AccountMeta::readonly_signer(authority.key()),
+        ],
+        data: &[9u8],
+    };
+
+    invoke_signed(&instruction, &[account, destination, authority], signers)
+}
+
 /// 辅助结构体用于 Associated Token Account 操作
 pub struct AssociatedTokenAccount;
 
@@ -148,12 +356,22 @@ impl AssociatedTokenAccount {
         owner: &AccountInfo,
         system_program: &AccountInfo,
         token_program: &AccountInfo,
+    ) -> ProgramResult {
+        Self::init_with_bump(account, mint, payer, owner, system_program, token_program, None)
+    }
+
+    /// 同 `init`；传入 bump 时用 create_program_address 验证地址，省去 find_program_address
+    pub fn init_with_bump(
+        account: &AccountInfo,
+        mint: &AccountInfo,
+        payer: &AccountInfo,
+        owner: &AccountInfo,
+        system_program: &AccountInfo,
+        token_program: &AccountInfo,
+        bump: Option<u8>,
     ) -> ProgramResult {
         // 验证 ATA 地址是否正确（支持 Token 和 Token-2022）
-        let ata_address = get_associated_token_address(owner.key(), mint.key(), token_program.key());
-        if account.key() != &ata_address {
-            return Err(ProgramError::InvalidSeeds);
-        }
+        Self::check_address(account, owner, mint, token_program, bump)?;
 
         // 使用 pinocchio-associated-token-account crate
         // 由于我们使用的是老版本的 AccountInfo，这里我们手动调用 ATA program
@@ -175,6 +393,19 @@ impl AssociatedTokenAccount {
         owner: &AccountInfo,
         system_program: &AccountInfo,
         token_program: &AccountInfo,
+    ) -> ProgramResult {
+        Self::init_if_needed_with_bump(account, mint, payer, owner, system_program, token_program, None)
+    }
+
+    /// 同 `init_if_needed`，需要创建时使用已知的 bump 验证地址
+    pub fn init_if_needed_with_bump(
+        account: &AccountInfo,
+        mint: &AccountInfo,
+        payer: &AccountInfo,
+        owner: &AccountInfo,
+        system_program: &AccountInfo,
+        token_program: &AccountInfo,
+        bump: Option<u8>,
     ) -> ProgramResult {
         // 如果账户已经初始化，直接返回
         if account.lamports() > 0 {
@@ -182,7 +413,7 @@ impl AssociatedTokenAccount {
         }
 
         // 否则创建账户
-        Self::init(account, mint, payer, owner, system_program, token_program)
+        Self::init_with_bump(account, mint, payer, owner, system_program, token_program, bump)
     }
 
     /// 检查 Associated Token Account 是否有效
@@ -191,6 +422,17 @@ impl AssociatedTokenAccount {
         owner: &AccountInfo,
         mint: &AccountInfo,
         token_program: &AccountInfo,
+    ) -> ProgramResult {
+        Self::check_with_bump(account, owner, mint, token_program, None)
+    }
+
+    /// 同 `check`；传入 bump 时用 create_program_address 验证地址，省去 find_program_address
+    pub fn check_with_bump(
+        account: &AccountInfo,
+        owner: &AccountInfo,
+        mint: &AccountInfo,
+        token_program: &AccountInfo,
+        bump: Option<u8>,
     ) -> ProgramResult {
         // 检查账户是否由 Token Program 拥有
         if account.owner() != token_program.key() {
@@ -198,11 +440,25 @@ impl AssociatedTokenAccount {
         }
 
         // 验证 ATA 地址是否正确（支持 Token 和 Token-2022）
-        let ata_address = get_associated_token_address(owner.key(), mint.key(), token_program.key());
+        Self::check_address(account, owner, mint, token_program, bump)
+    }
+
+    /// 验证账户地址是 owner/mint 的 ATA；没有 bump 时用 find_program_address 派生
+    #[inline(always)]
+    fn check_address(
+        account: &AccountInfo,
+        owner: &AccountInfo,
+        mint: &AccountInfo,
+        token_program: &AccountInfo,
+        bump: Option<u8>,
+    ) -> ProgramResult {
+        let ata_address = match bump {
+            Some(bump) => get_associated_token_address_with_bump(owner.key(), mint.key(), token_program.key(), bump)?,
+            None => get_associated_token_address(owner.key(), mint.key(), token_program.key()),
+        };
         if account.key() != &ata_address {
             return Err(ProgramError::InvalidSeeds);
         }
-
         Ok(())
     }
 }
@@ -228,6 +484,27 @@ pub fn get_associated_token_address(
     address
 }
 
+/// 使用已知的 bump 计算 Associated Token Address
+///
+/// create_program_address 只做一次哈希，find_program_address 最坏要尝试 255 次；
+/// bump 不对（得到的地址在曲线上）时返回 InvalidSeeds
+pub fn get_associated_token_address_with_bump(
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program_id: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    let seeds = &[
+        wallet.as_ref(),
+        token_program_id.as_ref(),
+        mint.as_ref(),
+        &[bump],
+    ];
+
+    pinocchio::pubkey::create_program_address(seeds, &ASSOCIATED_TOKEN_PROGRAM_ID)
+        .map_err(|_| ProgramError::InvalidSeeds)
+}
+
 /// 手动调用 Associated Token Account Program 创建 ATA
 fn invoke_create_associated_token_account(
     payer: &AccountInfo,
@@ -237,8 +514,6 @@ fn invoke_create_associated_token_account(
     system_program: &AccountInfo,
     token_program: &AccountInfo,
 ) -> ProgramResult {
-    use pinocchio::instruction::{AccountMeta, Instruction};
-    
     let instruction = Instruction {
         program_id: &ASSOCIATED_TOKEN_PROGRAM_ID,
         accounts: &[
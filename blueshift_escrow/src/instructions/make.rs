@@ -1,10 +1,10 @@
-use crate::state::Escrow;
+use crate::events::EscrowMade;
+use crate::state::{Escrow, escrow_seeds_from_parts, MAX_PLATFORM_FEE_BPS};
 use core::mem::size_of;
 use pinocchio::{
-    account_info::AccountInfo, program_error::ProgramError, pubkey::find_program_address,
-    instruction::Seed, ProgramResult,
+    account_info::AccountInfo, program_error::ProgramError, pubkey::{find_program_address, Pubkey},
+    ProgramResult,
 };
-use pinocchio_token::instructions::Transfer;
 use super::helpers::*;
 
 pub struct Make<'a> {
@@ -28,17 +28,33 @@ impl<'a> Make<'a> {
             *self.accounts.mint_b.key(),
             self.instruction_data.receive,
             [self.bump],
+            self.instruction_data.platform,
+            self.instruction_data.platform_fee_bps,
+            self.instruction_data.expiry,
         );
 
         // Transfer tokens to vault
-        Transfer {
+        TransferChecked {
+            token_program: self.accounts.token_program,
             from: self.accounts.maker_ata_a,
+            mint: self.accounts.mint_a,
             to: self.accounts.vault,
             authority: self.accounts.maker,
             amount: self.instruction_data.amount,
+            decimals: MintInterface::decimals(self.accounts.mint_a)?,
         }
         .invoke()?;
 
+        EscrowMade {
+            seed: self.instruction_data.seed,
+            maker: self.accounts.maker.key(),
+            mint_a: self.accounts.mint_a.key(),
+            mint_b: self.accounts.mint_b.key(),
+            amount: self.instruction_data.amount,
+            receive: self.instruction_data.receive,
+        }
+        .emit();
+
         Ok(())
     }
 }
@@ -62,12 +78,7 @@ impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Make<'a> {
 
         let seed_binding = instruction_data.seed.to_le_bytes();
         let bump_binding = [bump];
-        let escrow_seeds = [
-            Seed::from(b"escrow"),
-            Seed::from(accounts.maker.key().as_ref()),
-            Seed::from(&seed_binding),
-            Seed::from(&bump_binding),
-        ];
+        let escrow_seeds = escrow_seeds_from_parts(accounts.maker.key(), &seed_binding, &bump_binding);
 
         ProgramAccount::init::<Escrow>(
             accounts.maker,
@@ -117,6 +128,7 @@ impl<'a> TryFrom<&'a [AccountInfo]> for MakeAccounts<'a> {
 
         // Basic Accounts Checks
         SignerAccount::check(maker)?;
+        TokenProgram::check(token_program)?;
         MintInterface::check(mint_a)?;
         MintInterface::check(mint_b)?;
         AssociatedTokenAccount::check(maker_ata_a, maker, mint_a, token_program)?;
@@ -135,26 +147,59 @@ impl<'a> TryFrom<&'a [AccountInfo]> for MakeAccounts<'a> {
     }
 }
 
+/// 指令数据：seed (u64) + receive (u64) + amount (u64)，可选追加 platform (Pubkey) + platform_fee_bps (u16)，
+/// 最后可选追加 expiry (i64)
+///
+/// 不带平台字段时不收平台手续费，taker 支付的 receive 全部给 maker；不带 expiry 时永不过期
 pub struct MakeInstructionData {
     pub seed: u64,
     pub receive: u64,
     pub amount: u64,
+    pub platform: Pubkey,
+    pub platform_fee_bps: u16,
+    /// 过期时间戳，之后不能再 take；0 表示永不过期
+    pub expiry: i64,
 }
 
 impl<'a> TryFrom<&'a [u8]> for MakeInstructionData {
     type Error = ProgramError;
 
     fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
-        if data.len() != size_of::<u64>() * 3 {
-            return Err(ProgramError::InvalidInstructionData);
-        }
+        const BASE_LEN: usize = size_of::<u64>() * 3;
+        const WITH_PLATFORM_LEN: usize = BASE_LEN + size_of::<Pubkey>() + size_of::<u16>();
+        const EXPIRY_LEN: usize = size_of::<i64>();
+
+        let (data, expiry) = match data.len() {
+            BASE_LEN | WITH_PLATFORM_LEN => (data, 0),
+            len if len == BASE_LEN + EXPIRY_LEN || len == WITH_PLATFORM_LEN + EXPIRY_LEN => {
+                let (data, expiry) = data.split_at(len - EXPIRY_LEN);
+                (data, i64::from_le_bytes(expiry.try_into().unwrap()))
+            }
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
 
         let seed = u64::from_le_bytes(data[0..8].try_into().unwrap());
         let receive = u64::from_le_bytes(data[8..16].try_into().unwrap());
         let amount = u64::from_le_bytes(data[16..24].try_into().unwrap());
 
+        let (platform, platform_fee_bps): (Pubkey, u16) = if data.len() == WITH_PLATFORM_LEN {
+            (
+                data[24..56].try_into().unwrap(),
+                u16::from_le_bytes(data[56..58].try_into().unwrap()),
+            )
+        } else {
+            ([0u8; 32], 0)
+        };
+
         // Instruction Checks
-        if amount == 0 {
+        // receive 为 0 时任何人都可以不付代价取走 vault
+        if amount == 0 || receive == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        if platform_fee_bps > MAX_PLATFORM_FEE_BPS {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        if expiry < 0 {
             return Err(ProgramError::InvalidInstructionData);
         }
 
@@ -162,6 +207,9 @@ impl<'a> TryFrom<&'a [u8]> for MakeInstructionData {
             seed,
             receive,
             amount,
+            platform,
+            platform_fee_bps,
+            expiry,
         })
     }
 }
@@ -1,8 +1,12 @@
 pub mod make;
 pub mod take;
 pub mod refund;
+pub mod partial_refund;
+pub mod set_memo;
 pub mod helpers;
 
 pub use make::*;
 pub use take::*;
-pub use refund::*;
\ No newline at end of file
+pub use refund::*;
+pub use partial_refund::*;
+pub use set_memo::*;
\ No newline at end of file
@@ -0,0 +1,98 @@
+use crate::events::EscrowRefunded;
+use crate::state::Escrow;
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, instruction::Signer, ProgramResult,
+};
+use super::{helpers::*, Refund};
+
+/// 只取回金库中的一部分代币 A，escrow 保持打开
+///
+/// 剩余部分按原价格继续挂单：receive 按 剩余数量 / 原金库余额 等比缩小（向上取整，对 maker 有利）。
+/// amount 等于金库余额时等同于 Refund：关闭金库和 escrow。
+///
+/// 账户与 Refund 相同；指令数据：amount (u64)，之后可选地附加与 Refund 相同的 `RefundBumps`
+pub struct PartialRefund<'a> {
+    pub refund: Refund<'a>,
+    pub amount: u64,
+}
+
+impl<'a> PartialRefund<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &3;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let accounts = &self.refund.accounts;
+
+        let vault_amount = TokenAccountInterface::amount(accounts.vault)?;
+        if self.amount > vault_amount {
+            return Err(ProgramError::InsufficientFunds);
+        }
+
+        // 取空金库时走完整的 Refund 流程
+        if self.amount == vault_amount {
+            return self.refund.process();
+        }
+
+        ProgramAccount::check_not_closed(accounts.escrow)?;
+        let data = accounts.escrow.try_borrow_data()?;
+        let escrow = Escrow::load(&data)?;
+        accounts.validate_escrow(escrow)?;
+
+        // 按剩余比例缩小 receive，保持 receive / 金库余额 的价格不变
+        let remaining = vault_amount - self.amount;
+        let receive = (escrow.receive as u128 * remaining as u128).div_ceil(vault_amount as u128) as u64;
+
+        let escrow_seeds = escrow.escrow_signer_seeds();
+        let signer = Signer::from(&escrow_seeds);
+
+        // Transfer part of the Vault back to the Maker
+        TransferChecked {
+            token_program: accounts.token_program,
+            from: accounts.vault,
+            mint: accounts.mint_a,
+            to: accounts.maker_ata_a,
+            authority: accounts.escrow,
+            amount: self.amount,
+            decimals: MintInterface::decimals(accounts.mint_a)?,
+        }
+        .invoke_signed(&[signer])?;
+
+        // CPI 期间 escrow 作为签名账户不能被可变借用，转账之后再更新条款
+        let seed = escrow.seed;
+        drop(data);
+        let mut data = accounts.escrow.try_borrow_mut_data()?;
+        Escrow::load_mut(&mut data)?.receive = receive;
+
+        EscrowRefunded {
+            seed,
+            maker: accounts.maker.key(),
+            mint_a: accounts.mint_a.key(),
+            amount: self.amount,
+            remaining,
+        }
+        .emit();
+
+        Ok(())
+    }
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for PartialRefund<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        // amount，之后可选地附加 RefundBumps
+        if data.len() < size_of::<u64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (amount, bumps) = data.split_at(size_of::<u64>());
+        let amount = u64::from_le_bytes(amount.try_into().unwrap());
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        // 账户检查（maker 签名、金库归属、mint_a）与 Refund 相同
+        let refund = Refund::try_from((bumps, accounts))?;
+
+        Ok(Self { refund, amount })
+    }
+}
@@ -1,9 +1,9 @@
+use crate::events::EscrowRefunded;
 use crate::state::Escrow;
 use pinocchio::{
     account_info::AccountInfo, program_error::ProgramError, pubkey::create_program_address,
-    instruction::{Seed, Signer}, ProgramResult,
+    instruction::Signer, ProgramResult,
 };
-use pinocchio_token::{instructions::{Transfer, CloseAccount}, state::TokenAccount};
 use super::helpers::*;
 
 pub struct Refund<'a> {
@@ -14,82 +14,81 @@ impl<'a> Refund<'a> {
     pub const DISCRIMINATOR: &'a u8 = &2;
 
     pub fn process(&mut self) -> ProgramResult {
+        ProgramAccount::check_not_closed(self.accounts.escrow)?;
         let data = self.accounts.escrow.try_borrow_data()?;
         let escrow = Escrow::load(&data)?;
+        self.accounts.validate_escrow(escrow)?;
 
-        // Check if the escrow is valid
-        let escrow_key = create_program_address(
-            &[
-                b"escrow",
-                self.accounts.maker.key(),
-                &escrow.seed.to_le_bytes(),
-                &escrow.bump,
-            ],
-            &crate::ID,
-        )?;
-        if &escrow_key != self.accounts.escrow.key() {
-            return Err(ProgramError::InvalidAccountOwner);
-        }
-
-        //todo 为什么没有检测vault是否是escrow的associated token account?
-
-        let seed_binding = escrow.seed.to_le_bytes();
-        let bump_binding = escrow.bump;
-        let escrow_seeds = [
-            Seed::from(b"escrow"),
-            Seed::from(self.accounts.maker.key().as_ref()),
-            Seed::from(&seed_binding),
-            Seed::from(&bump_binding),
-        ];
+        let escrow_seeds = escrow.escrow_signer_seeds();
         let signer = Signer::from(&escrow_seeds);
 
-        let amount = TokenAccount::from_account_info(self.accounts.vault)?.amount();
+        let amount = TokenAccountInterface::amount(self.accounts.vault)?;
 
         //将代币 A 的全部余额从保险库转回创建者，然后关闭保险库账户。
 
         // Transfer from the Vault to the Maker
-        Transfer {
+        TransferChecked {
+            token_program: self.accounts.token_program,
             from: self.accounts.vault,
+            mint: self.accounts.mint_a,
             to: self.accounts.maker_ata_a,
             authority: self.accounts.escrow,
             amount,
+            decimals: MintInterface::decimals(self.accounts.mint_a)?,
         }
-        .invoke_signed(&[signer.clone()])?;
+        .invoke_signed(core::slice::from_ref(&signer))?;
 
         // Close the Vault
-        CloseAccount {
-            account: self.accounts.vault,
-            destination: self.accounts.maker,
-            authority: self.accounts.escrow,
-        }
-        .invoke_signed(&[signer.clone()])?;
+        close_token_account(
+            self.accounts.token_program,
+            self.accounts.vault,
+            self.accounts.maker,
+            self.accounts.escrow,
+            core::slice::from_ref(&signer),
+        )?;
 
         //关闭托管 PDA，并将其租金 lamports 返还给创建者。
 
-        // Close the Escrow
+        // Close the Escrow：租金给 rent_receiver（缺省为 maker），代币已经退还给 maker
+        let seed = escrow.seed;
         drop(data);
-        ProgramAccount::close(self.accounts.escrow, self.accounts.maker)?; //lamports 返还给了谁？
+        ProgramAccount::close(self.accounts.escrow, self.accounts.rent_receiver)?;
+
+        EscrowRefunded {
+            seed,
+            maker: self.accounts.maker.key(),
+            mint_a: self.accounts.mint_a.key(),
+            amount,
+            remaining: 0,
+        }
+        .emit();
 
         Ok(())
     }
 }
 
-impl<'a> TryFrom<&'a [AccountInfo]> for Refund<'a> {
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Refund<'a> {
     type Error = ProgramError;
 
-    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
-        let accounts = RefundAccounts::try_from(accounts)?;
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let bumps = RefundBumps::parse(data)?;
+        let accounts = RefundAccounts::try_from((accounts, bumps))?;
 
         // Initialize necessary accounts
-        AssociatedTokenAccount::init_if_needed(
+        AssociatedTokenAccount::init_if_needed_with_bump(
             accounts.maker_ata_a,
             accounts.mint_a,
             accounts.maker,
             accounts.maker,
             accounts.system_program,
             accounts.token_program,
+            bumps.map(|bumps| bumps.maker_ata_a),
         )?;
 
+        // maker_ata_a 可能是刚创建的；金库和 maker_ata_a 都必须是 mint_a 的账户，否则退款会落到别的 mint 上
+        TokenAccountInterface::check_mint(accounts.vault, accounts.mint_a.key())?;
+        TokenAccountInterface::check_mint(accounts.maker_ata_a, accounts.mint_a.key())?;
+
         Ok(Self { accounts })
     }
 }
@@ -102,13 +101,74 @@ pub struct RefundAccounts<'a> {
     pub maker_ata_a: &'a AccountInfo,
     pub system_program: &'a AccountInfo,
     pub token_program: &'a AccountInfo,
+    /// 接收 escrow 租金的账户：可选的末尾账户（例如代为提交交易的 relayer），不传时为 maker
+    pub rent_receiver: &'a AccountInfo,
+}
+
+impl<'a> RefundAccounts<'a> {
+    /// escrow 必须是 maker 的 PDA，传入的 mint_a 必须与 escrow 记录的一致
+    ///
+    /// 签名者不是 escrow 记录的 maker 时返回 `IncorrectAuthority`；
+    /// maker 正确但 escrow 地址与种子不符时返回 `InvalidSeeds`。
+    /// Refund 与 PartialRefund 共用
+    pub fn validate_escrow(&self, escrow: &Escrow) -> ProgramResult {
+        // 只有创建者可以退款
+        if self.maker.key() != &escrow.maker {
+            return Err(ProgramError::IncorrectAuthority);
+        }
+
+        // Check if the escrow is valid
+        let escrow_key = create_program_address(
+            &[
+                b"escrow",
+                self.maker.key(),
+                escrow.seed_bytes(),
+                &escrow.bump,
+            ],
+            &crate::ID,
+        )?;
+        if &escrow_key != self.escrow.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        //todo 为什么没有检测vault是否是escrow的associated token account?
+
+        // 传入的 mint_a 必须与 escrow 记录的一致
+        if self.mint_a.key() != &escrow.mint_a {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(())
+    }
+}
+
+/// 可选的指令数据：客户端预先算好的 vault 与 maker_ata_a 的 ATA bump
+///
+/// 传入时用 create_program_address 验证地址，省去两次 find_program_address；
+/// 不传时（旧客户端，空数据）仍然在链上派生
+#[derive(Clone, Copy)]
+pub struct RefundBumps {
+    pub vault: u8,
+    pub maker_ata_a: u8,
+}
+
+impl RefundBumps {
+    pub const LEN: usize = 2;
+
+    pub fn parse(data: &[u8]) -> Result<Option<Self>, ProgramError> {
+        match data {
+            [] => Ok(None),
+            [vault, maker_ata_a] => Ok(Some(Self { vault: *vault, maker_ata_a: *maker_ata_a })),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
 }
 
-impl<'a> TryFrom<&'a [AccountInfo]> for RefundAccounts<'a> {
+impl<'a> TryFrom<(&'a [AccountInfo], Option<RefundBumps>)> for RefundAccounts<'a> {
     type Error = ProgramError;
 
-    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
-        let [maker, escrow, mint_a, vault, maker_ata_a, system_program, token_program, _] =
+    fn try_from((accounts, bumps): (&'a [AccountInfo], Option<RefundBumps>)) -> Result<Self, Self::Error> {
+        let [maker, escrow, mint_a, vault, maker_ata_a, system_program, token_program, _, remaining @ ..] =
             accounts
         else {
             return Err(ProgramError::NotEnoughAccountKeys);
@@ -116,11 +176,25 @@ impl<'a> TryFrom<&'a [AccountInfo]> for RefundAccounts<'a> {
 
         // Basic Accounts Checks
         SignerAccount::check(maker)?;
+        // system_program 会传给 init_if_needed 创建 maker_ata_a，不能是伪造的程序
+        SystemAccount::check_program(system_program)?;
+        TokenProgram::check(token_program)?;
         ProgramAccount::check(escrow)?;
-        MintInterface::check(mint_a)?;
-        AssociatedTokenAccount::check(vault, escrow, mint_a, token_program)?;
+        // 同一笔交易中已经退款过的 escrow：金库也已关闭，先于金库检查返回 InvalidAccountData
+        ProgramAccount::check_not_closed(escrow)?;
+        MintInterface::check_with_program(mint_a, token_program)?;
+        AssociatedTokenAccount::check_with_bump(vault, escrow, mint_a, token_program, bumps.map(|bumps| bumps.vault))?;
         // 不检查 maker_ata_a，因为它可能还没有初始化，会在 init_if_needed 中创建
 
+        // 单独传入的租金接收方必须是普通钱包账户
+        let rent_receiver = match remaining.first() {
+            Some(rent_receiver) => {
+                SystemAccount::check(rent_receiver)?;
+                rent_receiver
+            }
+            None => maker,
+        };
+
         // Return the accounts
         Ok(Self {
             maker,
@@ -130,6 +204,7 @@ impl<'a> TryFrom<&'a [AccountInfo]> for RefundAccounts<'a> {
             maker_ata_a,
             system_program,
             token_program,
+            rent_receiver,
         })
     }
 }
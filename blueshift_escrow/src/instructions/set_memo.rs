@@ -0,0 +1,97 @@
+use crate::errors::EscrowError;
+use crate::state::Escrow;
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::create_program_address,
+    ProgramResult,
+};
+use super::helpers::*;
+
+/// maker 给 escrow 附加一段 memo（例如链下对账用的订单号）
+///
+/// memo 以 UTF-8 原样存放在固定字段之后，账户长度变为 `Escrow::LEN + memo.len()`，增加的租金由 maker 支付。
+/// 再次调用会覆盖旧的 memo；空 memo 清除它（缩小时多余的租金不退还，关闭 escrow 时一并返还 maker）。
+///
+/// 账户：`[maker, escrow, system_program]`；指令数据：memo（0..=MAX_MEMO_LEN 字节）
+pub struct SetMemo<'a> {
+    pub accounts: SetMemoAccounts<'a>,
+    pub memo: &'a [u8],
+}
+
+impl<'a> SetMemo<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &4;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let accounts = &self.accounts;
+
+        // 先确认 escrow 是 maker 的 PDA，再改变它的大小
+        ProgramAccount::check_not_closed(accounts.escrow)?;
+        let data = accounts.escrow.try_borrow_data()?;
+        let escrow = Escrow::load(&data)?;
+        if accounts.maker.key() != &escrow.maker {
+            return Err(ProgramError::IncorrectAuthority);
+        }
+        let escrow_key = create_program_address(
+            &[
+                b"escrow",
+                accounts.maker.key(),
+                escrow.seed_bytes(),
+                &escrow.bump,
+            ],
+            &crate::ID,
+        )?;
+        if &escrow_key != accounts.escrow.key() {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        drop(data);
+
+        ProgramAccount::realloc(accounts.escrow, accounts.maker, Escrow::LEN + self.memo.len())?;
+        accounts.escrow.try_borrow_mut_data()?[Escrow::LEN..].copy_from_slice(self.memo);
+
+        Ok(())
+    }
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for SetMemo<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        if data.len() > Escrow::MAX_MEMO_LEN {
+            return Err(EscrowError::MemoTooLong.into());
+        }
+        if core::str::from_utf8(data).is_err() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let accounts = SetMemoAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts, memo: data })
+    }
+}
+
+pub struct SetMemoAccounts<'a> {
+    pub maker: &'a AccountInfo,
+    pub escrow: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for SetMemoAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [maker, escrow, system_program, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // maker 签名并支付租金；补租金通过 System Program 转账
+        SignerAccount::check(maker)?;
+        SystemAccount::check(maker)?;
+        SystemAccount::check_program(system_program)?;
+        ProgramAccount::check(escrow)?;
+
+        Ok(Self {
+            maker,
+            escrow,
+            system_program,
+        })
+    }
+}
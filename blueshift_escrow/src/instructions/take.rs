@@ -1,9 +1,10 @@
+use crate::errors::EscrowError;
+use crate::events::EscrowTaken;
 use crate::state::Escrow;
 use pinocchio::{
     account_info::AccountInfo, program_error::ProgramError, pubkey::create_program_address,
-    instruction::{Seed, Signer}, ProgramResult,
+    instruction::Signer, sysvars::{clock::Clock, Sysvar}, ProgramResult,
 };
-use pinocchio_token::{instructions::{Transfer, CloseAccount}, state::TokenAccount};
 use super::helpers::*;
 
 pub struct Take<'a> {
@@ -14,6 +15,7 @@ impl<'a> Take<'a> {
     pub const DISCRIMINATOR: &'a u8 = &1;
 
     pub fn process(&mut self) -> ProgramResult {
+        ProgramAccount::check_not_closed(self.accounts.escrow)?;
         let data = self.accounts.escrow.try_borrow_data()?;
         let escrow = Escrow::load(&data)?;
 
@@ -22,7 +24,7 @@ impl<'a> Take<'a> {
             &[
                 b"escrow",
                 self.accounts.maker.key(),
-                &escrow.seed.to_le_bytes(),
+                escrow.seed_bytes(),
                 &escrow.bump,
             ],
             &crate::ID,
@@ -31,49 +33,102 @@ impl<'a> Take<'a> {
             return Err(ProgramError::InvalidAccountOwner);
         }
 
+        // 传入的 mint 必须是 escrow 记录的 mint，否则 taker 可以用无价值的代币支付
+        if self.accounts.mint_a.key() != &escrow.mint_a || self.accounts.mint_b.key() != &escrow.mint_b {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // 过期后只能由 maker refund
+        if escrow.is_expired(Clock::get()?.unix_timestamp) {
+            return Err(EscrowError::EscrowExpired.into());
+        }
+
         //todo 为什么没有检测vault是否是escrow的associated token account?
 
-        let seed_binding = escrow.seed.to_le_bytes();
-        let bump_binding = escrow.bump;
-        let escrow_seeds = [
-            Seed::from(b"escrow"),
-            Seed::from(self.accounts.maker.key().as_ref()),
-            Seed::from(&seed_binding),
-            Seed::from(&bump_binding),
-        ];
+        let escrow_seeds = escrow.escrow_signer_seeds();
         let signer = Signer::from(&escrow_seeds);
 
-        let amount = TokenAccount::from_account_info(self.accounts.vault)?.amount();
+        let amount = TokenAccountInterface::amount(self.accounts.vault)?;
+        let decimals_a = MintInterface::decimals(self.accounts.mint_a)?;
+        let decimals_b = MintInterface::decimals(self.accounts.mint_b)?;
 
         // Transfer from the Vault to the Taker
-        Transfer {
+        TransferChecked {
+            token_program: self.accounts.token_program,
             from: self.accounts.vault,
+            mint: self.accounts.mint_a,
             to: self.accounts.taker_ata_a,
             authority: self.accounts.escrow,
             amount,
+            decimals: decimals_a,
         }
-        .invoke_signed(&[signer.clone()])?;
+        .invoke_signed(core::slice::from_ref(&signer))?;
 
         // Close the Vault
-        CloseAccount {
-            account: self.accounts.vault,
-            destination: self.accounts.maker,
-            authority: self.accounts.escrow,
+        close_token_account(
+            self.accounts.token_program,
+            self.accounts.vault,
+            self.accounts.maker,
+            self.accounts.escrow,
+            core::slice::from_ref(&signer),
+        )?;
+
+        // 按 escrow 记录的平台手续费拆分 receive
+        let (platform_fee, maker_amount) = escrow.split_receive()?;
+        if platform_fee > 0 {
+            let platform_ata_b = self.accounts.platform_ata_b.ok_or(ProgramError::NotEnoughAccountKeys)?;
+            let expected = get_associated_token_address(
+                &escrow.platform,
+                self.accounts.mint_b.key(),
+                self.accounts.token_program.key(),
+            );
+            if platform_ata_b.key() != &expected {
+                return Err(ProgramError::InvalidSeeds);
+            }
+            TokenAccountInterface::check_mint(platform_ata_b, self.accounts.mint_b.key())?;
+
+            // Transfer the platform fee from the Taker to the Platform
+            TransferChecked {
+                token_program: self.accounts.token_program,
+                from: self.accounts.taker_ata_b,
+                mint: self.accounts.mint_b,
+                to: platform_ata_b,
+                authority: self.accounts.taker,
+                amount: platform_fee,
+                decimals: decimals_b,
+            }
+            .invoke()?;
         }
-        .invoke_signed(&[signer.clone()])?;
 
         // Transfer from the Taker to the Maker
-        Transfer {
+        TransferChecked {
+            token_program: self.accounts.token_program,
             from: self.accounts.taker_ata_b,
+            mint: self.accounts.mint_b,
             to: self.accounts.maker_ata_b,
             authority: self.accounts.taker,
-            amount: escrow.receive,
+            amount: maker_amount,
+            decimals: decimals_b,
         }
         .invoke()?;
 
-        // Close the Escrow
+        // Close the Escrow：escrow 的租金是 maker 在 Make 时付的，与 vault 一样退还给 maker
+        // 关闭后不能再读 escrow，先复制事件需要的字段
+        let (seed, receive) = (escrow.seed, escrow.receive);
         drop(data);
-        ProgramAccount::close(self.accounts.escrow, self.accounts.taker)?;//todo lamports 返还给了谁？
+        ProgramAccount::close(self.accounts.escrow, self.accounts.maker)?;
+
+        EscrowTaken {
+            seed,
+            maker: self.accounts.maker.key(),
+            taker: self.accounts.taker.key(),
+            mint_a: self.accounts.mint_a.key(),
+            mint_b: self.accounts.mint_b.key(),
+            amount,
+            receive,
+            platform_fee,
+        }
+        .emit();
 
         Ok(())
     }
@@ -104,6 +159,10 @@ impl<'a> TryFrom<&'a [AccountInfo]> for Take<'a> {
             accounts.token_program,
         )?;
 
+        // maker_ata_b 可能是刚创建的，这里统一检查 B 侧的 ATA
+        AssociatedTokenAccount::check(accounts.maker_ata_b, accounts.maker, accounts.mint_b, accounts.token_program)?;
+        TokenAccountInterface::check_mint(accounts.maker_ata_b, accounts.mint_b.key())?;
+
         Ok(Self { accounts })
     }
 }
@@ -120,13 +179,15 @@ pub struct TakeAccounts<'a> {
     pub maker_ata_b: &'a AccountInfo,
     pub system_program: &'a AccountInfo,
     pub token_program: &'a AccountInfo,
+    /// 平台的 mint_b ATA，放在末尾的可选账户；只有 escrow 设置了平台手续费时才需要
+    pub platform_ata_b: Option<&'a AccountInfo>,
 }
 
 impl<'a> TryFrom<&'a [AccountInfo]> for TakeAccounts<'a> {
     type Error = ProgramError;
 
     fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
-        let [taker, maker, escrow, mint_a, mint_b, vault, taker_ata_a, taker_ata_b, maker_ata_b, system_program, token_program, _] =
+        let [taker, maker, escrow, mint_a, mint_b, vault, taker_ata_a, taker_ata_b, maker_ata_b, system_program, token_program, _, remaining @ ..] =
             accounts
         else {
             return Err(ProgramError::NotEnoughAccountKeys);
@@ -134,10 +195,12 @@ impl<'a> TryFrom<&'a [AccountInfo]> for TakeAccounts<'a> {
 
         // Basic Accounts Checks
         SignerAccount::check(taker)?;
+        TokenProgram::check(token_program)?;
         ProgramAccount::check(escrow)?;
         MintInterface::check(mint_a)?;
         MintInterface::check(mint_b)?;
         AssociatedTokenAccount::check(taker_ata_b, taker, mint_b, token_program)?;
+        TokenAccountInterface::check_mint(taker_ata_b, mint_b.key())?;
         AssociatedTokenAccount::check(vault, escrow, mint_a, token_program)?;
 
         // Return the accounts
@@ -153,6 +216,7 @@ impl<'a> TryFrom<&'a [AccountInfo]> for TakeAccounts<'a> {
             vault,
             system_program,
             token_program,
+            platform_ata_b: remaining.first(),
         })
     }
 }
@@ -4,11 +4,12 @@ use pinocchio::{
 };
 entrypoint!(process_instruction);
 
-mod instructions;
+pub mod instructions;
 use instructions::*;
 
-mod state;
-// mod errors;
+pub mod state;
+pub mod errors;
+pub mod events;
 
 // 22222222222222222222222222222222222222222222
 pub const ID: Pubkey = [
@@ -24,7 +25,11 @@ fn process_instruction(
     match instruction_data.split_first() {
         Some((Make::DISCRIMINATOR, data)) => Make::try_from((data, accounts))?.process(),
         Some((Take::DISCRIMINATOR, _)) => Take::try_from(accounts)?.process(),
-        Some((Refund::DISCRIMINATOR, _)) => Refund::try_from(accounts)?.process(),
+        Some((Refund::DISCRIMINATOR, data)) => Refund::try_from((data, accounts))?.process(),
+        Some((PartialRefund::DISCRIMINATOR, data)) => {
+            PartialRefund::try_from((data, accounts))?.process()
+        }
+        Some((SetMemo::DISCRIMINATOR, data)) => SetMemo::try_from((data, accounts))?.process(),
         _ => Err(ProgramError::InvalidInstructionData),
     }
 }
@@ -1,5 +1,25 @@
 use core::mem::size_of;
-use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+use crate::instructions::helpers::ProgramAccount;
+use pinocchio::{instruction::Seed, program_error::ProgramError, pubkey::Pubkey};
+
+/// 从原始参数构造 escrow PDA 的种子数组（seeds = [b"escrow", maker, seed, bump]）
+///
+/// 用于 make 阶段（还没有 Escrow 实例）；已有 Escrow 时使用 `Escrow::escrow_signer_seeds`
+///
+/// 注意：seed_binding 必须由调用方在栈上创建并传入引用，以确保生命周期正确
+#[inline(always)]
+pub fn escrow_seeds_from_parts<'a>(
+    maker: &'a Pubkey,
+    seed_binding: &'a [u8; 8],
+    bump: &'a [u8; 1],
+) -> [Seed<'a>; 4] {
+    [
+        Seed::from(b"escrow"),
+        Seed::from(maker.as_ref()),
+        Seed::from(seed_binding.as_ref()),
+        Seed::from(bump.as_ref()),
+    ]
+}
 
 // #[repr(C)] 属性确保我们的结构体具有可预测的内存布局
 #[repr(C)]
@@ -10,19 +30,32 @@ pub struct Escrow {
     pub mint_b: Pubkey, // Token being requested
     pub receive: u64,   // Amount of token B wanted
     pub bump: [u8; 1],  // PDA bump seed
+    pub platform: Pubkey,           // 平台手续费接收方（platform_fee_bps 为 0 时忽略）
+    pub platform_fee_bps: [u8; 2],  // take 时从 receive 中分给平台的比例（bps, u16 LE）
+    pub expiry: [u8; 8],            // 过期时间戳（i64 LE），之后不能再 take；0 表示永不过期
 }
 
+/// 平台手续费上限：100%
+pub const MAX_PLATFORM_FEE_BPS: u16 = 10_000;
+
 impl Escrow {
     pub const LEN: usize = size_of::<u64>()
         + size_of::<Pubkey>()
         + size_of::<Pubkey>()
         + size_of::<Pubkey>()
         + size_of::<u64>()
-        + size_of::<[u8; 1]>();
+        + size_of::<[u8; 1]>()
+        + size_of::<Pubkey>()
+        + size_of::<[u8; 2]>()
+        + size_of::<[u8; 8]>();
 
+    /// 固定字段之后可选的 memo 最大长度（`SetMemo` 写入）
+    pub const MAX_MEMO_LEN: usize = 64;
+
+    /// 账户长度为 LEN 加上 memo 的长度，只解析开头的固定字段
     #[inline(always)]
     pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
-        if bytes.len() != Escrow::LEN {
+        if bytes.len() < Escrow::LEN {
             return Err(ProgramError::InvalidAccountData);
         }
         Ok(unsafe { &mut *core::mem::transmute::<*mut u8, *mut Self>(bytes.as_mut_ptr()) })
@@ -30,12 +63,76 @@ impl Escrow {
 
     #[inline(always)]
     pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
-        if bytes.len() != Escrow::LEN {
+        if bytes.len() < Escrow::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if Self::is_closed(bytes) {
             return Err(ProgramError::InvalidAccountData);
         }
         Ok(unsafe { &*core::mem::transmute::<*const u8, *const Self>(bytes.as_ptr()) })
     }
 
+    /// 固定字段之后的 memo（UTF-8），没有设置时为空
+    #[inline(always)]
+    pub fn memo(bytes: &[u8]) -> &[u8] {
+        bytes.get(Escrow::LEN..).unwrap_or(&[])
+    }
+
+    /// 是否为已关闭的账户（ProgramAccount::close 写入的 [255, 0, 0, ...]）
+    /// 正常的 escrow 中 maker 一定非零，所以不会与 seed 低字节恰好为 255 的情况混淆
+    #[inline(always)]
+    pub fn is_closed(bytes: &[u8]) -> bool {
+        ProgramAccount::is_closed(bytes)
+    }
+
+    /// seed 的字节表示，与 PDA 派生时使用的 `seed.to_le_bytes()` 相同
+    #[inline(always)]
+    pub fn seed_bytes(&self) -> &[u8; 8] {
+        // Safety: Solana 运行在小端平台上，u64 的内存表示就是 to_le_bytes()；[u8; 8] 没有对齐要求
+        unsafe { &*(&self.seed as *const u64 as *const [u8; 8]) }
+    }
+
+    /// 构造此 escrow PDA 的种子数组，用于签名操作
+    ///
+    /// 种子直接引用 Escrow 自身的字段，调用方不需要再准备 seed/bump 的临时绑定：
+    /// ```ignore
+    /// let seeds = escrow.escrow_signer_seeds();
+    /// xxx.invoke_signed(&[Signer::from(&seeds)])?;
+    /// ```
+    #[inline(always)]
+    pub fn escrow_signer_seeds(&self) -> [Seed<'_>; 4] {
+        escrow_seeds_from_parts(&self.maker, self.seed_bytes(), &self.bump)
+    }
+
+    #[inline(always)]
+    pub fn platform_fee_bps(&self) -> u16 {
+        u16::from_le_bytes(self.platform_fee_bps)
+    }
+
+    #[inline(always)]
+    pub fn expiry(&self) -> i64 {
+        i64::from_le_bytes(self.expiry)
+    }
+
+    /// 在 `now` 时刻是否已过期（expiry 为 0 时永不过期）
+    #[inline(always)]
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expiry() != 0 && now > self.expiry()
+    }
+
+    /// 按 platform_fee_bps 拆分 receive：返回 (给平台的部分, 给 maker 的部分)
+    ///
+    /// 平台部分向下取整，余数归 maker；fee 为 0 时 maker 拿到全部
+    #[inline(always)]
+    pub fn split_receive(&self) -> Result<(u64, u64), ProgramError> {
+        let fee = (self.receive as u128)
+            .checked_mul(self.platform_fee_bps() as u128)
+            .ok_or(ProgramError::ArithmeticOverflow)?
+            / MAX_PLATFORM_FEE_BPS as u128;
+        let fee = fee as u64;
+        Ok((fee, self.receive - fee))
+    }
+
     // #[inline(always)]
     // pub fn set_seed(&mut self, seed: u64) {
     //     self.seed = seed;
@@ -67,6 +164,7 @@ impl Escrow {
     // }
 
     #[inline(always)]
+    #[allow(clippy::too_many_arguments)]
     pub fn set_inner(
         &mut self,
         seed: u64,
@@ -75,6 +173,9 @@ impl Escrow {
         mint_b: Pubkey,
         receive: u64,
         bump: [u8; 1],
+        platform: Pubkey,
+        platform_fee_bps: u16,
+        expiry: i64,
     ) {
         self.seed = seed;
         self.maker = maker;
@@ -82,5 +183,8 @@ impl Escrow {
         self.mint_b = mint_b;
         self.receive = receive;
         self.bump = bump;
+        self.platform = platform;
+        self.platform_fee_bps = platform_fee_bps.to_le_bytes();
+        self.expiry = expiry.to_le_bytes();
     }
 }
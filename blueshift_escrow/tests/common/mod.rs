@@ -0,0 +1,459 @@
+#![allow(dead_code)]
+
+use {
+    blueshift_escrow::errors::EscrowError,
+    mollusk_svm::Mollusk,
+    mollusk_svm_programs_token::{associated_token, token, token2022},
+    solana_account::Account,
+    solana_sdk::{
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        program_option::COption,
+        program_pack::Pack,
+        pubkey::Pubkey,
+    },
+    spl_associated_token_account::get_associated_token_address_with_program_id,
+    spl_token::state::{Account as TokenAccount, AccountState, Mint},
+};
+
+/// `EscrowError` only converts into pinocchio's `ProgramError`; Mollusk checks compare against the solana_sdk one
+pub fn escrow_err(error: EscrowError) -> ProgramError {
+    ProgramError::Custom(error as u32)
+}
+
+/// `solana_sdk::system_program` is deprecated; the program id is the same one the escrow invokes
+pub mod system_program {
+    use solana_sdk::pubkey::Pubkey;
+
+    pub fn id() -> Pubkey {
+        Pubkey::new_from_array(pinocchio_system::ID)
+    }
+}
+
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+/// seed (8) + maker (32) + mint_a (32) + mint_b (32) + receive (8) + bump (1)
+/// + platform (32) + platform_fee_bps (2) + expiry (8)
+pub const ESCROW_LEN: usize = 8 + 32 + 32 + 32 + 8 + 1 + 32 + 2 + 8;
+
+// ============================================================================
+// Setup
+// ============================================================================
+
+pub fn program_id() -> Pubkey {
+    Pubkey::new_from_array(blueshift_escrow::ID)
+}
+
+/// Setup Mollusk with the escrow program, SPL Token, Token-2022 and the ATA program
+pub fn setup_mollusk() -> Mollusk {
+    let mut mollusk = Mollusk::new(&program_id(), "blueshift_escrow");
+
+    token::add_program(&mut mollusk);
+    token2022::add_program(&mut mollusk);
+    associated_token::add_program(&mut mollusk);
+
+    mollusk
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+pub fn create_system_account(lamports: u64) -> Account {
+    Account {
+        lamports,
+        data: vec![],
+        owner: system_program::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+pub fn create_system_program_account() -> Account {
+    Account {
+        lamports: LAMPORTS_PER_SOL,
+        data: vec![],
+        owner: solana_sdk::native_loader::id(),
+        executable: true,
+        rent_epoch: 0,
+    }
+}
+
+pub fn create_mint_account(mint_authority: &Pubkey, decimals: u8, supply: u64) -> Account {
+    create_mint_account_with_program(mint_authority, decimals, supply, &spl_token::id())
+}
+
+/// Create a Mint account owned by `token_program` (SPL Token or Token-2022 share the base layout)
+pub fn create_mint_account_with_program(
+    mint_authority: &Pubkey,
+    decimals: u8,
+    supply: u64,
+    token_program: &Pubkey,
+) -> Account {
+    let mint_state = Mint {
+        mint_authority: COption::Some(*mint_authority),
+        supply,
+        decimals,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+
+    let mut data = vec![0u8; Mint::LEN];
+    Pack::pack(mint_state, &mut data).unwrap();
+
+    Account {
+        lamports: LAMPORTS_PER_SOL,
+        data,
+        owner: *token_program,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Create a Token-2022 Mint carrying a MintCloseAuthority extension, so the account is longer than `Mint::LEN`
+pub fn create_extended_mint_account(mint_authority: &Pubkey, decimals: u8, supply: u64) -> Account {
+    let mut account = create_mint_account_with_program(mint_authority, decimals, supply, &spl_token_2022::id());
+    // 基础数据填充到 165 字节，account type = Mint (1)，之后是 TLV：MintCloseAuthority (3)，32 字节，全 0 表示没有
+    account.data.resize(165, 0);
+    account.data.push(1);
+    account.data.extend_from_slice(&3u16.to_le_bytes());
+    account.data.extend_from_slice(&32u16.to_le_bytes());
+    account.data.extend_from_slice(&[0u8; 32]);
+    account
+}
+
+pub fn create_token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+    create_token_account_with_program(mint, owner, amount, &spl_token::id())
+}
+
+/// Create a Token account owned by `token_program`
+pub fn create_token_account_with_program(
+    mint: &Pubkey,
+    owner: &Pubkey,
+    amount: u64,
+    token_program: &Pubkey,
+) -> Account {
+    let token_state = TokenAccount {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        delegate: COption::None,
+        state: AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    };
+
+    let mut data = vec![0u8; TokenAccount::LEN];
+    Pack::pack(token_state, &mut data).unwrap();
+
+    Account {
+        lamports: LAMPORTS_PER_SOL,
+        data,
+        owner: *token_program,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Create an escrow account owned by the program with the given state
+pub fn create_escrow_account(
+    mollusk: &Mollusk,
+    seed: u64,
+    maker: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    receive: u64,
+    bump: u8,
+) -> Account {
+    create_escrow_account_with_platform_fee(mollusk, seed, maker, mint_a, mint_b, receive, bump, &Pubkey::default(), 0)
+}
+
+/// Create an escrow account that can no longer be taken after `expiry`
+#[allow(clippy::too_many_arguments)]
+pub fn create_escrow_account_with_expiry(
+    mollusk: &Mollusk,
+    seed: u64,
+    maker: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    receive: u64,
+    bump: u8,
+    expiry: i64,
+) -> Account {
+    let mut account = create_escrow_account(mollusk, seed, maker, mint_a, mint_b, receive, bump);
+    account.data[ESCROW_LEN - 8..].copy_from_slice(&expiry.to_le_bytes());
+    account
+}
+
+/// Create an escrow account that routes `fee_bps` of `receive` to `platform` on take
+#[allow(clippy::too_many_arguments)]
+pub fn create_escrow_account_with_platform_fee(
+    mollusk: &Mollusk,
+    seed: u64,
+    maker: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    receive: u64,
+    bump: u8,
+    platform: &Pubkey,
+    fee_bps: u16,
+) -> Account {
+    let mut data = Vec::with_capacity(ESCROW_LEN);
+    data.extend_from_slice(&seed.to_le_bytes());
+    data.extend_from_slice(maker.as_ref());
+    data.extend_from_slice(mint_a.as_ref());
+    data.extend_from_slice(mint_b.as_ref());
+    data.extend_from_slice(&receive.to_le_bytes());
+    data.push(bump);
+    data.extend_from_slice(platform.as_ref());
+    data.extend_from_slice(&fee_bps.to_le_bytes());
+    // expiry = 0：永不过期
+    data.extend_from_slice(&0i64.to_le_bytes());
+
+    Account {
+        lamports: mollusk.sysvars.rent.minimum_balance(ESCROW_LEN),
+        data,
+        owner: program_id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+pub fn token_amount(account: &Account) -> u64 {
+    TokenAccount::unpack_from_slice(&account.data[..TokenAccount::LEN])
+        .unwrap()
+        .amount
+}
+
+// ============================================================================
+// PDAs
+// ============================================================================
+
+pub fn escrow_pda(maker: &Pubkey, seed: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"escrow", maker.as_ref(), &seed.to_le_bytes()],
+        &program_id(),
+    )
+}
+
+pub fn ata(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    ata_with_program(owner, mint, &spl_token::id())
+}
+
+pub fn ata_with_program(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, token_program)
+}
+
+/// SPL Token 下 owner/mint 的 ATA bump（Refund 的可选指令数据）
+pub fn ata_bump(owner: &Pubkey, mint: &Pubkey) -> u8 {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), spl_token::id().as_ref(), mint.as_ref()],
+        &spl_associated_token_account::id(),
+    )
+    .1
+}
+
+// ============================================================================
+// Instructions
+// ============================================================================
+
+pub fn make_ix(
+    maker: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    seed: u64,
+    receive: u64,
+    amount: u64,
+) -> Instruction {
+    let (escrow, _) = escrow_pda(maker, seed);
+
+    let mut data = vec![0u8];
+    data.extend_from_slice(&seed.to_le_bytes());
+    data.extend_from_slice(&receive.to_le_bytes());
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(*maker, true),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new_readonly(*mint_a, false),
+            AccountMeta::new_readonly(*mint_b, false),
+            AccountMeta::new(ata(maker, mint_a), false),
+            AccountMeta::new(ata(&escrow, mint_a), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data,
+    }
+}
+
+pub fn take_ix(
+    taker: &Pubkey,
+    maker: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    seed: u64,
+) -> Instruction {
+    let (escrow, _) = escrow_pda(maker, seed);
+
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(*taker, true),
+            AccountMeta::new(*maker, false),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new_readonly(*mint_a, false),
+            AccountMeta::new_readonly(*mint_b, false),
+            AccountMeta::new(ata(&escrow, mint_a), false),
+            AccountMeta::new(ata(taker, mint_a), false),
+            AccountMeta::new(ata(taker, mint_b), false),
+            AccountMeta::new(ata(maker, mint_b), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: vec![1u8],
+    }
+}
+
+/// `Take` against mints owned by `token_program`
+pub fn take_ix_with_program(
+    taker: &Pubkey,
+    maker: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    seed: u64,
+    token_program: &Pubkey,
+) -> Instruction {
+    let (escrow, _) = escrow_pda(maker, seed);
+
+    let mut ix = take_ix(taker, maker, mint_a, mint_b, seed);
+    ix.accounts[5].pubkey = ata_with_program(&escrow, mint_a, token_program);
+    ix.accounts[6].pubkey = ata_with_program(taker, mint_a, token_program);
+    ix.accounts[7].pubkey = ata_with_program(taker, mint_b, token_program);
+    ix.accounts[8].pubkey = ata_with_program(maker, mint_b, token_program);
+    ix.accounts[10].pubkey = *token_program;
+    ix
+}
+
+/// take 并在末尾追加平台的 mint_b ATA（escrow 设置了平台手续费时需要）
+pub fn take_ix_with_platform(
+    taker: &Pubkey,
+    maker: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    seed: u64,
+    platform: &Pubkey,
+) -> Instruction {
+    let mut ix = take_ix(taker, maker, mint_a, mint_b, seed);
+    ix.accounts.push(AccountMeta::new(ata(platform, mint_b), false));
+    ix
+}
+
+/// `Make` against mints owned by `token_program`
+pub fn make_ix_with_program(
+    maker: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    seed: u64,
+    receive: u64,
+    amount: u64,
+    token_program: &Pubkey,
+) -> Instruction {
+    let (escrow, _) = escrow_pda(maker, seed);
+
+    let mut ix = make_ix(maker, mint_a, mint_b, seed, receive, amount);
+    ix.accounts[4].pubkey = ata_with_program(maker, mint_a, token_program);
+    ix.accounts[5].pubkey = ata_with_program(&escrow, mint_a, token_program);
+    ix.accounts[7].pubkey = *token_program;
+    ix
+}
+
+/// make 并追加平台手续费字段
+#[allow(clippy::too_many_arguments)]
+pub fn make_ix_with_platform_fee(
+    maker: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+    seed: u64,
+    receive: u64,
+    amount: u64,
+    platform: &Pubkey,
+    fee_bps: u16,
+) -> Instruction {
+    let mut ix = make_ix(maker, mint_a, mint_b, seed, receive, amount);
+    ix.data.extend_from_slice(platform.as_ref());
+    ix.data.extend_from_slice(&fee_bps.to_le_bytes());
+    ix
+}
+
+pub fn refund_ix(maker: &Pubkey, mint_a: &Pubkey, seed: u64) -> Instruction {
+    let (escrow, _) = escrow_pda(maker, seed);
+
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(*maker, true),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new_readonly(*mint_a, false),
+            AccountMeta::new(ata(&escrow, mint_a), false),
+            AccountMeta::new(ata(maker, mint_a), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ],
+        data: vec![2u8],
+    }
+}
+
+/// `Refund` 附带 vault 与 maker_ata_a 的 ATA bump
+pub fn refund_ix_with_bumps(maker: &Pubkey, mint_a: &Pubkey, seed: u64) -> Instruction {
+    let (escrow, _) = escrow_pda(maker, seed);
+
+    let mut ix = refund_ix(maker, mint_a, seed);
+    ix.data.extend_from_slice(&[ata_bump(&escrow, mint_a), ata_bump(maker, mint_a)]);
+    ix
+}
+
+/// `Refund` against mints owned by `token_program`
+pub fn refund_ix_with_program(maker: &Pubkey, mint_a: &Pubkey, seed: u64, token_program: &Pubkey) -> Instruction {
+    let (escrow, _) = escrow_pda(maker, seed);
+
+    let mut ix = refund_ix(maker, mint_a, seed);
+    ix.accounts[3].pubkey = ata_with_program(&escrow, mint_a, token_program);
+    ix.accounts[4].pubkey = ata_with_program(maker, mint_a, token_program);
+    ix.accounts[6].pubkey = *token_program;
+    ix
+}
+
+pub fn partial_refund_ix(maker: &Pubkey, mint_a: &Pubkey, seed: u64, amount: u64) -> Instruction {
+    let mut ix = refund_ix(maker, mint_a, seed);
+    ix.data = vec![3u8];
+    ix.data.extend_from_slice(&amount.to_le_bytes());
+    ix
+}
+
+pub fn set_memo_ix(maker: &Pubkey, seed: u64, memo: &[u8]) -> Instruction {
+    let (escrow, _) = escrow_pda(maker, seed);
+
+    let mut data = vec![4u8];
+    data.extend_from_slice(memo);
+
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(*maker, true),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data,
+    }
+}
+
+/// escrow 账户数据中的 receive（seed 8 + maker/mint_a/mint_b 96 之后）
+pub fn escrow_receive(account: &Account) -> u64 {
+    u64::from_le_bytes(account.data[104..112].try_into().unwrap())
+}
@@ -0,0 +1,125 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_escrow::{instructions::helpers::ProgramAccount, state::Escrow},
+    common::*,
+    mollusk_svm::result::Check,
+    mollusk_svm_programs_token::{associated_token, token},
+    solana_sdk::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+const SEED: u64 = 7;
+
+#[test]
+fn test_refunded_escrow_cannot_be_loaded() {
+    let mollusk = setup_mollusk();
+    let (token_program, token_program_account) = token::keyed_account();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+
+    let maker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+    let mint_b = Pubkey::new_unique();
+    let (escrow, bump) = escrow_pda(&maker, SEED);
+
+    let accounts = vec![
+        (maker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (escrow, create_escrow_account(&mollusk, SEED, &maker, &mint_a, &mint_b, 500, bump)),
+        (mint_a, create_mint_account(&maker, 6, 1_000_000)),
+        (ata(&escrow, &mint_a), create_token_account(&mint_a, &escrow, 1_000)),
+        (ata(&maker, &mint_a), create_token_account(&mint_a, &maker, 0)),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (ata_program, ata_program_account),
+    ];
+    let result = mollusk.process_and_validate_instruction(
+        &refund_ix(&maker, &mint_a, SEED),
+        &accounts,
+        &[Check::success()],
+    );
+
+    let closed = result.get_account(&escrow).unwrap();
+    assert_eq!(closed.lamports, 0);
+    assert_eq!(closed.data[0], ProgramAccount::CLOSED_ACCOUNT_DISCRIMINATOR);
+    assert_eq!(Escrow::load(&closed.data).err(), Some(pinocchio::program_error::ProgramError::InvalidAccountData));
+}
+
+#[test]
+fn test_live_escrow_with_seed_low_byte_255_still_loads() {
+    let mollusk = setup_mollusk();
+    let maker = Pubkey::new_unique();
+    let seed = 255u64;
+    let (_, bump) = escrow_pda(&maker, seed);
+
+    let account = create_escrow_account(&mollusk, seed, &maker, &Pubkey::new_unique(), &Pubkey::new_unique(), 500, bump);
+    assert!(Escrow::load(&account.data).is_ok());
+}
+
+#[test]
+fn test_resurrected_escrow_cannot_be_refunded() {
+    let mollusk = setup_mollusk();
+    let (token_program, token_program_account) = token::keyed_account();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+
+    let maker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+    let mint_b = Pubkey::new_unique();
+    let (escrow, bump) = escrow_pda(&maker, SEED);
+
+    // 同一笔交易中关闭后又被转入租金：数据仍是 [255, 0, 0, ...]
+    let mut resurrected = create_escrow_account(&mollusk, SEED, &maker, &mint_a, &mint_b, 500, bump);
+    resurrected.data[0] = ProgramAccount::CLOSED_ACCOUNT_DISCRIMINATOR;
+    resurrected.data[1..].fill(0);
+
+    let accounts = vec![
+        (maker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (escrow, resurrected),
+        (mint_a, create_mint_account(&maker, 6, 1_000_000)),
+        (ata(&escrow, &mint_a), create_token_account(&mint_a, &escrow, 1_000)),
+        (ata(&maker, &mint_a), create_token_account(&mint_a, &maker, 0)),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (ata_program, ata_program_account),
+    ];
+    mollusk.process_and_validate_instruction(
+        &refund_ix(&maker, &mint_a, SEED),
+        &accounts,
+        &[Check::err(ProgramError::InvalidAccountData)],
+    );
+}
+
+/// 同一笔交易中的第二次 Refund：escrow 和金库都已关闭，返回 InvalidAccountData
+#[test]
+fn test_second_refund_in_same_transaction_fails() {
+    let mollusk = setup_mollusk();
+    let (token_program, token_program_account) = token::keyed_account();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+
+    let maker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+    let mint_b = Pubkey::new_unique();
+    let (escrow, bump) = escrow_pda(&maker, SEED);
+
+    let accounts = vec![
+        (maker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (escrow, create_escrow_account(&mollusk, SEED, &maker, &mint_a, &mint_b, 500, bump)),
+        (mint_a, create_mint_account(&maker, 6, 1_000_000)),
+        (ata(&escrow, &mint_a), create_token_account(&mint_a, &escrow, 1_000)),
+        (ata(&maker, &mint_a), create_token_account(&mint_a, &maker, 0)),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (ata_program, ata_program_account),
+    ];
+    let refund = refund_ix(&maker, &mint_a, SEED);
+    let result = mollusk.process_and_validate_instruction_chain(
+        &[
+            (&refund, &[Check::success()]),
+            (&refund, &[Check::err(ProgramError::InvalidAccountData)]),
+        ],
+        &accounts,
+    );
+
+    // 第一次退款的结果不受影响
+    assert_eq!(token_amount(result.get_account(&ata(&maker, &mint_a)).unwrap()), 1_000);
+}
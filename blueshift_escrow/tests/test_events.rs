@@ -0,0 +1,67 @@
+use {
+    blueshift_escrow::events::{EscrowMade, EscrowRefunded, EscrowTaken, EVENT_VERSION},
+    solana_sdk::pubkey::Pubkey,
+};
+
+fn u64_at(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+#[test]
+fn test_made_event_layout() {
+    let maker = Pubkey::new_unique().to_bytes();
+    let mint_a = Pubkey::new_unique().to_bytes();
+    let mint_b = Pubkey::new_unique().to_bytes();
+    let data = EscrowMade { seed: 7, maker: &maker, mint_a: &mint_a, mint_b: &mint_b, amount: 1_000, receive: 500 }
+        .to_bytes();
+
+    assert_eq!(data.len(), EscrowMade::LEN);
+    assert_eq!((data[0], data[1]), (EVENT_VERSION, 0));
+    assert_eq!(u64_at(&data, 2), 7);
+    assert_eq!(&data[10..42], maker);
+    assert_eq!(&data[42..74], mint_a);
+    assert_eq!(&data[74..106], mint_b);
+    assert_eq!((u64_at(&data, 106), u64_at(&data, 114)), (1_000, 500));
+}
+
+#[test]
+fn test_taken_event_layout() {
+    let maker = Pubkey::new_unique().to_bytes();
+    let taker = Pubkey::new_unique().to_bytes();
+    let mint_a = Pubkey::new_unique().to_bytes();
+    let mint_b = Pubkey::new_unique().to_bytes();
+    let data = EscrowTaken {
+        seed: 7,
+        maker: &maker,
+        taker: &taker,
+        mint_a: &mint_a,
+        mint_b: &mint_b,
+        amount: 1_000,
+        receive: 500,
+        platform_fee: 5,
+    }
+    .to_bytes();
+
+    assert_eq!(data.len(), EscrowTaken::LEN);
+    assert_eq!((data[0], data[1]), (EVENT_VERSION, 1));
+    assert_eq!(u64_at(&data, 2), 7);
+    assert_eq!(&data[10..42], maker);
+    assert_eq!(&data[42..74], taker);
+    assert_eq!(&data[74..106], mint_a);
+    assert_eq!(&data[106..138], mint_b);
+    assert_eq!((u64_at(&data, 138), u64_at(&data, 146), u64_at(&data, 154)), (1_000, 500, 5));
+}
+
+#[test]
+fn test_refunded_event_layout() {
+    let maker = Pubkey::new_unique().to_bytes();
+    let mint_a = Pubkey::new_unique().to_bytes();
+    let data = EscrowRefunded { seed: 7, maker: &maker, mint_a: &mint_a, amount: 400, remaining: 600 }.to_bytes();
+
+    assert_eq!(data.len(), EscrowRefunded::LEN);
+    assert_eq!((data[0], data[1]), (EVENT_VERSION, 2));
+    assert_eq!(u64_at(&data, 2), 7);
+    assert_eq!(&data[10..42], maker);
+    assert_eq!(&data[42..74], mint_a);
+    assert_eq!((u64_at(&data, 74), u64_at(&data, 82)), (400, 600));
+}
@@ -0,0 +1,117 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_escrow::{errors::EscrowError, state::Escrow},
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    mollusk_svm_programs_token::{associated_token, token},
+    solana_account::Account,
+    solana_sdk::pubkey::Pubkey,
+};
+
+const SEED: u64 = 64;
+const RECEIVE: u64 = 500;
+const DEPOSIT: u64 = 1_000;
+const NOW: i64 = 1_700_000_000;
+
+/// 当前时间为 NOW、expiry 为给定值的 escrow，taker 与 maker 的 ATA 都已存在
+fn accounts(mollusk: &Mollusk, maker: &Pubkey, taker: &Pubkey, mint_a: &Pubkey, mint_b: &Pubkey, expiry: i64) -> Vec<(Pubkey, Account)> {
+    let (token_program, token_program_account) = token::keyed_account();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+    let (escrow, bump) = escrow_pda(maker, SEED);
+
+    vec![
+        (*taker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (*maker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (escrow, create_escrow_account_with_expiry(mollusk, SEED, maker, mint_a, mint_b, RECEIVE, bump, expiry)),
+        (*mint_a, create_mint_account(maker, 6, 1_000_000)),
+        (*mint_b, create_mint_account(taker, 6, 1_000_000)),
+        (ata(&escrow, mint_a), create_token_account(mint_a, &escrow, DEPOSIT)),
+        (ata(taker, mint_a), create_token_account(mint_a, taker, 0)),
+        (ata(taker, mint_b), create_token_account(mint_b, taker, RECEIVE)),
+        (ata(maker, mint_b), create_token_account(mint_b, maker, 0)),
+        (ata(maker, mint_a), create_token_account(mint_a, maker, 0)),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (ata_program, ata_program_account),
+    ]
+}
+
+fn setup() -> (Mollusk, Pubkey, Pubkey, Pubkey, Pubkey) {
+    let mut mollusk = setup_mollusk();
+    mollusk.sysvars.clock.unix_timestamp = NOW;
+    (mollusk, Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique())
+}
+
+#[test]
+fn test_take_before_expiry_succeeds() {
+    let (mollusk, maker, taker, mint_a, mint_b) = setup();
+    let accounts = accounts(&mollusk, &maker, &taker, &mint_a, &mint_b, NOW);
+
+    mollusk.process_and_validate_instruction(
+        &take_ix(&taker, &maker, &mint_a, &mint_b, SEED),
+        &accounts,
+        &[Check::success()],
+    );
+}
+
+#[test]
+fn test_take_after_expiry_fails_but_refund_succeeds() {
+    let (mollusk, maker, taker, mint_a, mint_b) = setup();
+    let accounts = accounts(&mollusk, &maker, &taker, &mint_a, &mint_b, NOW - 1);
+
+    mollusk.process_and_validate_instruction(
+        &take_ix(&taker, &maker, &mint_a, &mint_b, SEED),
+        &accounts,
+        &[Check::err(escrow_err(EscrowError::EscrowExpired))],
+    );
+
+    let result = mollusk.process_and_validate_instruction(
+        &refund_ix(&maker, &mint_a, SEED),
+        &accounts,
+        &[Check::success()],
+    );
+    assert_eq!(token_amount(result.get_account(&ata(&maker, &mint_a)).unwrap()), DEPOSIT);
+}
+
+#[test]
+fn test_zero_expiry_never_expires() {
+    let (mut mollusk, maker, taker, mint_a, mint_b) = setup();
+    mollusk.sysvars.clock.unix_timestamp = i64::MAX;
+    let accounts = accounts(&mollusk, &maker, &taker, &mint_a, &mint_b, 0);
+
+    mollusk.process_and_validate_instruction(
+        &take_ix(&taker, &maker, &mint_a, &mint_b, SEED),
+        &accounts,
+        &[Check::success()],
+    );
+}
+
+#[test]
+fn test_make_stores_expiry() {
+    let (mollusk, maker, _, mint_a, mint_b) = setup();
+    let (token_program, token_program_account) = token::keyed_account();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+    let (escrow, _) = escrow_pda(&maker, SEED);
+
+    let mut ix = make_ix(&maker, &mint_a, &mint_b, SEED, RECEIVE, DEPOSIT);
+    ix.data.extend_from_slice(&(NOW + 3_600).to_le_bytes());
+    let accounts = vec![
+        (maker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (escrow, Account::default()),
+        (mint_a, create_mint_account(&maker, 6, 1_000_000)),
+        (mint_b, create_mint_account(&maker, 6, 1_000_000)),
+        (ata(&maker, &mint_a), create_token_account(&mint_a, &maker, DEPOSIT)),
+        (ata(&escrow, &mint_a), Account::default()),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (ata_program, ata_program_account),
+    ];
+    let result = mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::success()]);
+
+    let escrow_account = result.get_account(&escrow).unwrap();
+    assert_eq!(escrow_account.data.len(), ESCROW_LEN);
+    assert_eq!(Escrow::load(&escrow_account.data).unwrap().expiry(), NOW + 3_600);
+}
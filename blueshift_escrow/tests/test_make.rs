@@ -0,0 +1,83 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    common::*,
+    mollusk_svm::result::Check,
+    mollusk_svm_programs_token::{associated_token, token},
+    solana_account::Account,
+    solana_sdk::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+const SEED: u64 = 71;
+const DEPOSIT: u64 = 1_000;
+const RECEIVE: u64 = 500;
+
+/// maker 持有 DEPOSIT 个 A；escrow 和 vault 都还不存在
+fn setup(maker: &Pubkey, mint_a: &Pubkey, mint_b: &Pubkey) -> Vec<(Pubkey, Account)> {
+    let (token_program, token_program_account) = token::keyed_account();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+    let (escrow, _) = escrow_pda(maker, SEED);
+
+    vec![
+        (*maker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (escrow, Account::default()),
+        (*mint_a, create_mint_account(maker, 6, 1_000_000)),
+        (*mint_b, create_mint_account(&Pubkey::new_unique(), 6, 1_000_000)),
+        (ata(maker, mint_a), create_token_account(mint_a, maker, DEPOSIT)),
+        (ata(&escrow, mint_a), Account::default()),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (ata_program, ata_program_account),
+    ]
+}
+
+#[test]
+fn test_make_initializes_escrow_and_funds_vault() {
+    let mollusk = setup_mollusk();
+    let (maker, mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let result = mollusk.process_and_validate_instruction(
+        &make_ix(&maker, &mint_a, &mint_b, SEED, RECEIVE, DEPOSIT),
+        &setup(&maker, &mint_a, &mint_b),
+        &[Check::success()],
+    );
+    let (escrow, bump) = escrow_pda(&maker, SEED);
+
+    // escrow：seed, maker, mint_a, mint_b, receive, bump
+    let account = result.get_account(&escrow).unwrap();
+    assert_eq!(account.owner, program_id());
+    assert_eq!(account.data.len(), ESCROW_LEN);
+    assert_eq!(account.data[0..8], SEED.to_le_bytes());
+    assert_eq!(account.data[8..40], maker.to_bytes());
+    assert_eq!(account.data[40..72], mint_a.to_bytes());
+    assert_eq!(account.data[72..104], mint_b.to_bytes());
+    assert_eq!(escrow_receive(account), RECEIVE);
+    assert_eq!(account.data[112], bump);
+
+    // 存款全部进入 escrow 的 vault
+    assert_eq!(token_amount(result.get_account(&ata(&escrow, &mint_a)).unwrap()), DEPOSIT);
+    assert_eq!(token_amount(result.get_account(&ata(&maker, &mint_a)).unwrap()), 0);
+}
+
+#[test]
+fn test_make_rejects_zero_receive() {
+    let mollusk = setup_mollusk();
+    let (maker, mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    mollusk.process_and_validate_instruction(
+        &make_ix(&maker, &mint_a, &mint_b, SEED, 0, DEPOSIT),
+        &setup(&maker, &mint_a, &mint_b),
+        &[Check::err(ProgramError::InvalidInstructionData)],
+    );
+}
+
+#[test]
+fn test_make_rejects_zero_deposit() {
+    let mollusk = setup_mollusk();
+    let (maker, mint_a, mint_b) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    mollusk.process_and_validate_instruction(
+        &make_ix(&maker, &mint_a, &mint_b, SEED, RECEIVE, 0),
+        &setup(&maker, &mint_a, &mint_b),
+        &[Check::err(ProgramError::InvalidInstructionData)],
+    );
+}
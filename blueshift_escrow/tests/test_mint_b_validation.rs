@@ -0,0 +1,165 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    mollusk_svm_programs_token::{associated_token, token},
+    solana_account::Account,
+    solana_sdk::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+const SEED: u64 = 42;
+const RECEIVE: u64 = 500;
+const DEPOSIT: u64 = 1_000;
+
+struct TakeSetup {
+    taker: Pubkey,
+    maker: Pubkey,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    escrow: Pubkey,
+    escrow_account: Account,
+}
+
+fn take_setup(mollusk: &Mollusk) -> TakeSetup {
+    let taker = Pubkey::new_unique();
+    let maker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+    let mint_b = Pubkey::new_unique();
+    let (escrow, bump) = escrow_pda(&maker, SEED);
+    let escrow_account = create_escrow_account(mollusk, SEED, &maker, &mint_a, &mint_b, RECEIVE, bump);
+
+    TakeSetup {
+        taker,
+        maker,
+        mint_a,
+        mint_b,
+        escrow,
+        escrow_account,
+    }
+}
+
+/// 构造 take 所需的账户，`mint_b` 为 taker 实际传入的 B 侧 mint
+fn take_accounts(s: &TakeSetup, mint_b: &Pubkey, maker_ata_b: Account) -> Vec<(Pubkey, Account)> {
+    let (token_program, token_program_account) = token::keyed_account();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+    let mint_authority = Pubkey::new_unique();
+
+    vec![
+        (s.taker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (s.maker, create_system_account(LAMPORTS_PER_SOL)),
+        (s.escrow, s.escrow_account.clone()),
+        (s.mint_a, create_mint_account(&mint_authority, 6, 1_000_000)),
+        (*mint_b, create_mint_account(&mint_authority, 6, 1_000_000)),
+        (ata(&s.escrow, &s.mint_a), create_token_account(&s.mint_a, &s.escrow, DEPOSIT)),
+        (ata(&s.taker, &s.mint_a), create_token_account(&s.mint_a, &s.taker, 0)),
+        (ata(&s.taker, mint_b), create_token_account(mint_b, &s.taker, RECEIVE)),
+        (ata(&s.maker, mint_b), maker_ata_b),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (ata_program, ata_program_account),
+    ]
+}
+
+#[test]
+fn test_take_pays_maker_in_mint_b() {
+    let mollusk = setup_mollusk();
+    let s = take_setup(&mollusk);
+
+    let accounts = take_accounts(&s, &s.mint_b, create_token_account(&s.mint_b, &s.maker, 0));
+    let result = mollusk.process_and_validate_instruction(
+        &take_ix(&s.taker, &s.maker, &s.mint_a, &s.mint_b, SEED),
+        &accounts,
+        &[Check::success()],
+    );
+
+    assert_eq!(token_amount(result.get_account(&ata(&s.maker, &s.mint_b)).unwrap()), RECEIVE);
+    assert_eq!(token_amount(result.get_account(&ata(&s.taker, &s.mint_a)).unwrap()), DEPOSIT);
+}
+
+#[test]
+fn test_take_rejects_mint_b_not_matching_escrow() {
+    let mollusk = setup_mollusk();
+    let s = take_setup(&mollusk);
+
+    // taker 试图用另一种（无价值的）代币支付
+    let worthless_mint = Pubkey::new_unique();
+    let accounts = take_accounts(
+        &s,
+        &worthless_mint,
+        create_token_account(&worthless_mint, &s.maker, 0),
+    );
+    mollusk.process_and_validate_instruction(
+        &take_ix(&s.taker, &s.maker, &s.mint_a, &worthless_mint, SEED),
+        &accounts,
+        &[Check::err(ProgramError::InvalidAccountData)],
+    );
+}
+
+#[test]
+fn test_take_rejects_maker_ata_b_with_wrong_mint() {
+    let mollusk = setup_mollusk();
+    let s = take_setup(&mollusk);
+
+    // 地址是 maker 的 mint_b ATA，但账户数据记录的是另一个 mint
+    let other_mint = Pubkey::new_unique();
+    let accounts = take_accounts(&s, &s.mint_b, create_token_account(&other_mint, &s.maker, 0));
+    mollusk.process_and_validate_instruction(
+        &take_ix(&s.taker, &s.maker, &s.mint_a, &s.mint_b, SEED),
+        &accounts,
+        &[Check::err(ProgramError::InvalidAccountData)],
+    );
+}
+
+fn make_accounts(maker: &Pubkey, mint_a: &Pubkey, mint_b: &Pubkey, mint_b_account: Account) -> Vec<(Pubkey, Account)> {
+    let (token_program, token_program_account) = token::keyed_account();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+    let (escrow, _) = escrow_pda(maker, SEED);
+
+    vec![
+        (*maker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (escrow, Account::default()),
+        (*mint_a, create_mint_account(maker, 6, 1_000_000)),
+        (*mint_b, mint_b_account),
+        (ata(maker, mint_a), create_token_account(mint_a, maker, DEPOSIT)),
+        (ata(&escrow, mint_a), Account::default()),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (ata_program, ata_program_account),
+    ]
+}
+
+#[test]
+fn test_make_rejects_token_account_as_mint_b() {
+    let mollusk = setup_mollusk();
+    let maker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+    let mint_b = Pubkey::new_unique();
+
+    let accounts = make_accounts(&maker, &mint_a, &mint_b, create_token_account(&mint_a, &maker, 0));
+    mollusk.process_and_validate_instruction(
+        &make_ix(&maker, &mint_a, &mint_b, SEED, RECEIVE, DEPOSIT),
+        &accounts,
+        &[Check::err(ProgramError::InvalidAccountData)],
+    );
+}
+
+#[test]
+fn test_make_rejects_uninitialized_mint_b() {
+    let mollusk = setup_mollusk();
+    let maker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+    let mint_b = Pubkey::new_unique();
+
+    let mut uninitialized = create_mint_account(&maker, 6, 0);
+    uninitialized.data.fill(0);
+
+    let accounts = make_accounts(&maker, &mint_a, &mint_b, uninitialized);
+    mollusk.process_and_validate_instruction(
+        &make_ix(&maker, &mint_a, &mint_b, SEED, RECEIVE, DEPOSIT),
+        &accounts,
+        &[Check::err(ProgramError::UninitializedAccount)],
+    );
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    mollusk_svm_programs_token::{associated_token, token},
+    solana_account::Account,
+    solana_sdk::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+const SEED: u64 = 11;
+const DEPOSIT: u64 = 1_000;
+const RECEIVE: u64 = 500;
+
+fn refund_accounts(mollusk: &Mollusk, maker: &Pubkey, mint_a: &Pubkey) -> Vec<(Pubkey, Account)> {
+    let (token_program, token_program_account) = token::keyed_account();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+    let (escrow, bump) = escrow_pda(maker, SEED);
+
+    vec![
+        (*maker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (escrow, create_escrow_account(mollusk, SEED, maker, mint_a, &Pubkey::new_unique(), RECEIVE, bump)),
+        (*mint_a, create_mint_account(maker, 6, 1_000_000)),
+        (ata(&escrow, mint_a), create_token_account(mint_a, &escrow, DEPOSIT)),
+        (ata(maker, mint_a), create_token_account(mint_a, maker, 0)),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (ata_program, ata_program_account),
+    ]
+}
+
+#[test]
+fn test_partial_refund_leaves_escrow_open_with_reduced_terms() {
+    let mollusk = setup_mollusk();
+    let maker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+    let (escrow, _) = escrow_pda(&maker, SEED);
+
+    let accounts = refund_accounts(&mollusk, &maker, &mint_a);
+    let result = mollusk.process_and_validate_instruction(
+        &partial_refund_ix(&maker, &mint_a, SEED, 300),
+        &accounts,
+        &[Check::success()],
+    );
+
+    assert_eq!(token_amount(result.get_account(&ata(&maker, &mint_a)).unwrap()), 300);
+    assert_eq!(token_amount(result.get_account(&ata(&escrow, &mint_a)).unwrap()), 700);
+
+    // escrow 仍然打开，receive 按 700 / 1000 缩小
+    let escrow_account = result.get_account(&escrow).unwrap();
+    assert!(escrow_account.lamports > 0);
+    assert_eq!(escrow_receive(escrow_account), 350);
+}
+
+#[test]
+fn test_partial_refund_rounds_receive_up_for_maker() {
+    let mollusk = setup_mollusk();
+    let maker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+    let (escrow, _) = escrow_pda(&maker, SEED);
+
+    // 500 * 999 / 1000 = 499.5 -> 500
+    let accounts = refund_accounts(&mollusk, &maker, &mint_a);
+    let result = mollusk.process_and_validate_instruction(
+        &partial_refund_ix(&maker, &mint_a, SEED, 1),
+        &accounts,
+        &[Check::success()],
+    );
+    assert_eq!(escrow_receive(result.get_account(&escrow).unwrap()), 500);
+}
+
+#[test]
+fn test_partial_refund_of_whole_vault_closes_escrow() {
+    let mollusk = setup_mollusk();
+    let maker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+    let (escrow, _) = escrow_pda(&maker, SEED);
+
+    let accounts = refund_accounts(&mollusk, &maker, &mint_a);
+    let result = mollusk.process_and_validate_instruction(
+        &partial_refund_ix(&maker, &mint_a, SEED, DEPOSIT),
+        &accounts,
+        &[Check::success()],
+    );
+
+    assert_eq!(token_amount(result.get_account(&ata(&maker, &mint_a)).unwrap()), DEPOSIT);
+    assert_eq!(result.get_account(&escrow).unwrap().lamports, 0);
+    assert_eq!(result.get_account(&ata(&escrow, &mint_a)).unwrap().lamports, 0);
+}
+
+#[test]
+fn test_partial_refund_rejects_amount_above_vault() {
+    let mollusk = setup_mollusk();
+    let maker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+
+    let accounts = refund_accounts(&mollusk, &maker, &mint_a);
+    mollusk.process_and_validate_instruction(
+        &partial_refund_ix(&maker, &mint_a, SEED, DEPOSIT + 1),
+        &accounts,
+        &[Check::err(ProgramError::InsufficientFunds)],
+    );
+}
+
+#[test]
+fn test_partial_refund_requires_maker_signature() {
+    let mollusk = setup_mollusk();
+    let maker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+
+    let accounts = refund_accounts(&mollusk, &maker, &mint_a);
+    let mut ix = partial_refund_ix(&maker, &mint_a, SEED, 300);
+    ix.accounts[0].is_signer = false;
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &accounts,
+        &[Check::err(ProgramError::MissingRequiredSignature)],
+    );
+}
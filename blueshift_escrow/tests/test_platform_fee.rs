@@ -0,0 +1,153 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    mollusk_svm_programs_token::{associated_token, token},
+    solana_account::Account,
+    solana_sdk::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+const SEED: u64 = 7;
+const RECEIVE: u64 = 10_000;
+const DEPOSIT: u64 = 1_000;
+
+struct Setup {
+    taker: Pubkey,
+    maker: Pubkey,
+    platform: Pubkey,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+}
+
+fn setup() -> Setup {
+    Setup {
+        taker: Pubkey::new_unique(),
+        maker: Pubkey::new_unique(),
+        platform: Pubkey::new_unique(),
+        mint_a: Pubkey::new_unique(),
+        mint_b: Pubkey::new_unique(),
+    }
+}
+
+fn take_accounts(mollusk: &Mollusk, s: &Setup, fee_bps: u16) -> Vec<(Pubkey, Account)> {
+    let (token_program, token_program_account) = token::keyed_account();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+    let mint_authority = Pubkey::new_unique();
+    let (escrow, bump) = escrow_pda(&s.maker, SEED);
+
+    vec![
+        (s.taker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (s.maker, create_system_account(LAMPORTS_PER_SOL)),
+        (
+            escrow,
+            create_escrow_account_with_platform_fee(
+                mollusk, SEED, &s.maker, &s.mint_a, &s.mint_b, RECEIVE, bump, &s.platform, fee_bps,
+            ),
+        ),
+        (s.mint_a, create_mint_account(&mint_authority, 6, 1_000_000)),
+        (s.mint_b, create_mint_account(&mint_authority, 6, 1_000_000)),
+        (ata(&escrow, &s.mint_a), create_token_account(&s.mint_a, &escrow, DEPOSIT)),
+        (ata(&s.taker, &s.mint_a), create_token_account(&s.mint_a, &s.taker, 0)),
+        (ata(&s.taker, &s.mint_b), create_token_account(&s.mint_b, &s.taker, RECEIVE)),
+        (ata(&s.maker, &s.mint_b), create_token_account(&s.mint_b, &s.maker, 0)),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (ata_program, ata_program_account),
+        (ata(&s.platform, &s.mint_b), create_token_account(&s.mint_b, &s.platform, 0)),
+    ]
+}
+
+#[test]
+fn test_take_splits_receive_with_platform() {
+    let mollusk = setup_mollusk();
+    let s = setup();
+
+    // 2.5% 给平台
+    let accounts = take_accounts(&mollusk, &s, 250);
+    let result = mollusk.process_and_validate_instruction(
+        &take_ix_with_platform(&s.taker, &s.maker, &s.mint_a, &s.mint_b, SEED, &s.platform),
+        &accounts,
+        &[Check::success()],
+    );
+
+    assert_eq!(token_amount(result.get_account(&ata(&s.platform, &s.mint_b)).unwrap()), 250);
+    assert_eq!(token_amount(result.get_account(&ata(&s.maker, &s.mint_b)).unwrap()), RECEIVE - 250);
+    assert_eq!(token_amount(result.get_account(&ata(&s.taker, &s.mint_b)).unwrap()), 0);
+}
+
+#[test]
+fn test_take_without_platform_fee_pays_maker_everything() {
+    let mollusk = setup_mollusk();
+    let s = setup();
+
+    // fee 为 0 时不需要平台账户
+    let mut accounts = take_accounts(&mollusk, &s, 0);
+    accounts.pop();
+    let result = mollusk.process_and_validate_instruction(
+        &take_ix(&s.taker, &s.maker, &s.mint_a, &s.mint_b, SEED),
+        &accounts,
+        &[Check::success()],
+    );
+
+    assert_eq!(token_amount(result.get_account(&ata(&s.maker, &s.mint_b)).unwrap()), RECEIVE);
+}
+
+#[test]
+fn test_take_with_platform_fee_requires_platform_ata() {
+    let mollusk = setup_mollusk();
+    let s = setup();
+
+    let mut accounts = take_accounts(&mollusk, &s, 250);
+    accounts.pop();
+    mollusk.process_and_validate_instruction(
+        &take_ix(&s.taker, &s.maker, &s.mint_a, &s.mint_b, SEED),
+        &accounts,
+        &[Check::err(ProgramError::NotEnoughAccountKeys)],
+    );
+}
+
+#[test]
+fn test_take_rejects_platform_ata_of_other_owner() {
+    let mollusk = setup_mollusk();
+    let s = setup();
+
+    // 传入的是 taker 自己的 ATA 而不是平台的
+    let attacker = Pubkey::new_unique();
+    let mut accounts = take_accounts(&mollusk, &s, 250);
+    accounts.pop();
+    accounts.push((ata(&attacker, &s.mint_b), create_token_account(&s.mint_b, &attacker, 0)));
+    mollusk.process_and_validate_instruction(
+        &take_ix_with_platform(&s.taker, &s.maker, &s.mint_a, &s.mint_b, SEED, &attacker),
+        &accounts,
+        &[Check::err(ProgramError::InvalidSeeds)],
+    );
+}
+
+#[test]
+fn test_make_rejects_platform_fee_above_100_percent() {
+    let mollusk = setup_mollusk();
+    let s = setup();
+    let (token_program, token_program_account) = token::keyed_account();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+    let (escrow, _) = escrow_pda(&s.maker, SEED);
+
+    let accounts = vec![
+        (s.maker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (escrow, Account::default()),
+        (s.mint_a, create_mint_account(&s.maker, 6, 1_000_000)),
+        (s.mint_b, create_mint_account(&s.maker, 6, 1_000_000)),
+        (ata(&s.maker, &s.mint_a), create_token_account(&s.mint_a, &s.maker, DEPOSIT)),
+        (ata(&escrow, &s.mint_a), Account::default()),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (ata_program, ata_program_account),
+    ];
+    mollusk.process_and_validate_instruction(
+        &make_ix_with_platform_fee(&s.maker, &s.mint_a, &s.mint_b, SEED, RECEIVE, DEPOSIT, &s.platform, 10_001),
+        &accounts,
+        &[Check::err(ProgramError::InvalidInstructionData)],
+    );
+}
@@ -0,0 +1,100 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    mollusk_svm_programs_token::{associated_token, token},
+    solana_account::Account,
+    solana_sdk::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+const DEPOSIT: u64 = 1_000;
+
+/// maker 的 ATA 尚未创建，由 Refund 创建
+fn setup(mollusk: &Mollusk, maker: &Pubkey, mint_a: &Pubkey, seed: u64) -> Vec<(Pubkey, Account)> {
+    let (token_program, token_program_account) = token::keyed_account();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+    let (escrow, bump) = escrow_pda(maker, seed);
+
+    vec![
+        (*maker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (escrow, create_escrow_account(mollusk, seed, maker, mint_a, &Pubkey::new_unique(), 500, bump)),
+        (*mint_a, create_mint_account(maker, 6, 1_000_000)),
+        (ata(&escrow, mint_a), create_token_account(mint_a, &escrow, DEPOSIT)),
+        (ata(maker, mint_a), Account::default()),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (ata_program, ata_program_account),
+    ]
+}
+
+#[test]
+fn test_refund_with_bumps_matches_refund_without() {
+    let mollusk = setup_mollusk();
+    let (maker, mint_a) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = setup(&mollusk, &maker, &mint_a, 21);
+
+    let with_bumps = mollusk.process_and_validate_instruction(
+        &refund_ix_with_bumps(&maker, &mint_a, 21),
+        &accounts,
+        &[Check::success()],
+    );
+    assert_eq!(token_amount(with_bumps.get_account(&ata(&maker, &mint_a)).unwrap()), DEPOSIT);
+
+    let without = mollusk.process_and_validate_instruction(&refund_ix(&maker, &mint_a, 21), &accounts, &[Check::success()]);
+    // 省去两次 find_program_address
+    assert!(
+        with_bumps.compute_units_consumed < without.compute_units_consumed,
+        "with bumps {} without {}",
+        with_bumps.compute_units_consumed,
+        without.compute_units_consumed,
+    );
+}
+
+#[test]
+fn test_refund_rejects_wrong_vault_bump() {
+    let mollusk = setup_mollusk();
+    let (maker, mint_a) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = setup(&mollusk, &maker, &mint_a, 22);
+
+    let mut ix = refund_ix_with_bumps(&maker, &mint_a, 22);
+    ix.data[1] = ix.data[1].wrapping_sub(1);
+    mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::err(ProgramError::InvalidSeeds)]);
+}
+
+#[test]
+fn test_refund_rejects_wrong_maker_ata_bump() {
+    let mollusk = setup_mollusk();
+    let (maker, mint_a) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = setup(&mollusk, &maker, &mint_a, 23);
+
+    let mut ix = refund_ix_with_bumps(&maker, &mint_a, 23);
+    ix.data[2] = ix.data[2].wrapping_sub(1);
+    mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::err(ProgramError::InvalidSeeds)]);
+}
+
+#[test]
+fn test_refund_rejects_partial_bumps() {
+    let mollusk = setup_mollusk();
+    let (maker, mint_a) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = setup(&mollusk, &maker, &mint_a, 24);
+
+    let mut ix = refund_ix_with_bumps(&maker, &mint_a, 24);
+    ix.data.pop();
+    mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::err(ProgramError::InvalidInstructionData)]);
+}
+
+#[test]
+fn test_partial_refund_accepts_bumps() {
+    let mollusk = setup_mollusk();
+    let (maker, mint_a) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = setup(&mollusk, &maker, &mint_a, 25);
+    let (escrow, _) = escrow_pda(&maker, 25);
+
+    let mut ix = partial_refund_ix(&maker, &mint_a, 25, DEPOSIT / 2);
+    ix.data.extend_from_slice(&[ata_bump(&escrow, &mint_a), ata_bump(&maker, &mint_a)]);
+    let result = mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::success()]);
+    assert_eq!(token_amount(result.get_account(&ata(&maker, &mint_a)).unwrap()), DEPOSIT / 2);
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    mollusk_svm_programs_token::{associated_token, token},
+    solana_account::Account,
+    solana_sdk::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+const SEED: u64 = 9;
+const DEPOSIT: u64 = 1_000;
+
+/// 构造 refund 所需的账户，`maker_ata_a` 为 maker 的 mint_a ATA 地址上的账户
+fn refund_accounts(mollusk: &Mollusk, maker: &Pubkey, mint_a: &Pubkey, maker_ata_a: Account) -> Vec<(Pubkey, Account)> {
+    let (token_program, token_program_account) = token::keyed_account();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+    let (escrow, bump) = escrow_pda(maker, SEED);
+
+    vec![
+        (*maker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (escrow, create_escrow_account(mollusk, SEED, maker, mint_a, &Pubkey::new_unique(), 500, bump)),
+        (*mint_a, create_mint_account(maker, 6, 1_000_000)),
+        (ata(&escrow, mint_a), create_token_account(mint_a, &escrow, DEPOSIT)),
+        (ata(maker, mint_a), maker_ata_a),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (ata_program, ata_program_account),
+    ]
+}
+
+#[test]
+fn test_refund_returns_mint_a_to_maker() {
+    let mollusk = setup_mollusk();
+    let maker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+
+    let accounts = refund_accounts(&mollusk, &maker, &mint_a, create_token_account(&mint_a, &maker, 0));
+    let result = mollusk.process_and_validate_instruction(
+        &refund_ix(&maker, &mint_a, SEED),
+        &accounts,
+        &[Check::success()],
+    );
+
+    assert_eq!(token_amount(result.get_account(&ata(&maker, &mint_a)).unwrap()), DEPOSIT);
+}
+
+#[test]
+fn test_refund_rejects_maker_ata_a_with_wrong_mint() {
+    let mollusk = setup_mollusk();
+    let maker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+
+    // 地址是 maker 的 mint_a ATA，但账户数据记录的是另一个 mint
+    let other_mint = Pubkey::new_unique();
+    let accounts = refund_accounts(&mollusk, &maker, &mint_a, create_token_account(&other_mint, &maker, 0));
+    mollusk.process_and_validate_instruction(
+        &refund_ix(&maker, &mint_a, SEED),
+        &accounts,
+        &[Check::err(ProgramError::InvalidAccountData)],
+    );
+}
@@ -0,0 +1,84 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    mollusk_svm_programs_token::{associated_token, token},
+    solana_account::Account,
+    solana_sdk::{instruction::AccountMeta, program_error::ProgramError, pubkey::Pubkey},
+};
+
+const SEED: u64 = 101;
+const DEPOSIT: u64 = 1_000;
+
+fn refund_accounts(mollusk: &Mollusk, maker: &Pubkey, mint_a: &Pubkey) -> Vec<(Pubkey, Account)> {
+    let (token_program, token_program_account) = token::keyed_account();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+    let (escrow, bump) = escrow_pda(maker, SEED);
+
+    vec![
+        (*maker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (escrow, create_escrow_account(mollusk, SEED, maker, mint_a, &Pubkey::new_unique(), 500, bump)),
+        (*mint_a, create_mint_account(maker, 6, 1_000_000)),
+        (ata(&escrow, mint_a), create_token_account(mint_a, &escrow, DEPOSIT)),
+        (ata(maker, mint_a), create_token_account(mint_a, maker, 0)),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (ata_program, ata_program_account),
+    ]
+}
+
+fn lamports(accounts: &[(Pubkey, Account)], key: &Pubkey) -> u64 {
+    accounts.iter().find(|(k, _)| k == key).unwrap().1.lamports
+}
+
+/// 单独传入的 rent_receiver 拿到 escrow 的租金，代币仍然退还给 maker
+#[test]
+fn test_escrow_rent_goes_to_rent_receiver() {
+    let mollusk = setup_mollusk();
+    let (maker, mint_a, relayer) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let (escrow, _) = escrow_pda(&maker, SEED);
+    let mut accounts = refund_accounts(&mollusk, &maker, &mint_a);
+    accounts.push((relayer, create_system_account(LAMPORTS_PER_SOL)));
+    let escrow_rent = lamports(&accounts, &escrow);
+
+    let mut ix = refund_ix(&maker, &mint_a, SEED);
+    ix.accounts.push(AccountMeta::new(relayer, false));
+    let result = mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::success()]);
+
+    assert_eq!(result.get_account(&relayer).unwrap().lamports, LAMPORTS_PER_SOL + escrow_rent);
+    assert_eq!(token_amount(result.get_account(&ata(&maker, &mint_a)).unwrap()), DEPOSIT);
+    assert_eq!(result.get_account(&escrow).unwrap().lamports, 0);
+}
+
+/// 不传 rent_receiver 时与以前一样，租金退还给 maker
+#[test]
+fn test_escrow_rent_defaults_to_maker() {
+    let mollusk = setup_mollusk();
+    let (maker, mint_a) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (escrow, _) = escrow_pda(&maker, SEED);
+    let accounts = refund_accounts(&mollusk, &maker, &mint_a);
+    let maker_lamports = lamports(&accounts, &maker) + lamports(&accounts, &escrow);
+
+    let result =
+        mollusk.process_and_validate_instruction(&refund_ix(&maker, &mint_a, SEED), &accounts, &[Check::success()]);
+
+    // maker 还收到了 vault 的租金
+    assert!(result.get_account(&maker).unwrap().lamports >= maker_lamports);
+    assert_eq!(result.get_account(&escrow).unwrap().lamports, 0);
+}
+
+/// rent_receiver 不能是程序拥有的账户
+#[test]
+fn test_rent_receiver_must_be_system_owned() {
+    let mollusk = setup_mollusk();
+    let (maker, mint_a) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = refund_accounts(&mollusk, &maker, &mint_a);
+
+    // 把 maker 的代币账户当作 rent_receiver
+    let mut ix = refund_ix(&maker, &mint_a, SEED);
+    ix.accounts.push(AccountMeta::new(ata(&maker, &mint_a), false));
+    mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::err(ProgramError::InvalidAccountOwner)]);
+}
@@ -0,0 +1,68 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    common::*,
+    mollusk_svm::result::Check,
+    mollusk_svm_programs_token::{associated_token, token},
+    solana_sdk::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+const SEED: u64 = 61;
+const DEPOSIT: u64 = 1_000;
+
+/// maker 的 ATA 尚未创建，Refund 会把 system_program 传给 init_if_needed
+#[test]
+fn test_refund_rejects_spoofed_system_program() {
+    let mollusk = setup_mollusk();
+    let (maker, mint_a) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (token_program, token_program_account) = token::keyed_account();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+    let (escrow, bump) = escrow_pda(&maker, SEED);
+    let spoofed = Pubkey::new_unique();
+
+    let accounts = vec![
+        (maker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (escrow, create_escrow_account(&mollusk, SEED, &maker, &mint_a, &Pubkey::new_unique(), 500, bump)),
+        (mint_a, create_mint_account(&maker, 6, 1_000_000)),
+        (ata(&escrow, &mint_a), create_token_account(&mint_a, &escrow, DEPOSIT)),
+        (ata(&maker, &mint_a), Default::default()),
+        (system_program::id(), create_system_program_account()),
+        (spoofed, create_system_account(LAMPORTS_PER_SOL)),
+        (token_program, token_program_account),
+        (ata_program, ata_program_account),
+    ];
+
+    let mut ix = refund_ix(&maker, &mint_a, SEED);
+    ix.accounts[5].pubkey = spoofed;
+    mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::err(ProgramError::IncorrectProgramId)]);
+
+    // 真正的系统程序可以正常退款
+    mollusk.process_and_validate_instruction(&refund_ix(&maker, &mint_a, SEED), &accounts, &[Check::success()]);
+}
+
+/// 伪造的 token_program 在 init_if_needed 创建 maker 的 ATA 之前就被拒绝
+#[test]
+fn test_refund_rejects_bogus_token_program_before_creating_maker_ata() {
+    let mollusk = setup_mollusk();
+    let (maker, mint_a) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+    let (escrow, bump) = escrow_pda(&maker, SEED);
+    let bogus = Pubkey::new_unique();
+
+    let accounts = vec![
+        (maker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (escrow, create_escrow_account(&mollusk, SEED, &maker, &mint_a, &Pubkey::new_unique(), 500, bump)),
+        (mint_a, create_mint_account(&maker, 6, 1_000_000)),
+        (ata(&escrow, &mint_a), create_token_account(&mint_a, &escrow, DEPOSIT)),
+        (ata(&maker, &mint_a), Default::default()),
+        (system_program::id(), create_system_program_account()),
+        (bogus, create_system_account(LAMPORTS_PER_SOL)),
+        (ata_program, ata_program_account),
+    ];
+
+    let mut ix = refund_ix(&maker, &mint_a, SEED);
+    ix.accounts[6].pubkey = bogus;
+    mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::err(ProgramError::IncorrectProgramId)]);
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    common::*,
+    mollusk_svm::result::Check,
+    mollusk_svm_programs_token::{associated_token, token},
+    solana_sdk::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+const SEED: u64 = 11;
+const DEPOSIT: u64 = 1_000;
+
+/// 其他人签名、指向 maker 的 escrow 发起退款，返回明确的 IncorrectAuthority
+#[test]
+fn test_refund_by_wrong_maker_is_rejected() {
+    let mollusk = setup_mollusk();
+    let maker = Pubkey::new_unique();
+    let attacker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+    let (token_program, token_program_account) = token::keyed_account();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+    let (escrow, bump) = escrow_pda(&maker, SEED);
+
+    // 签名者是 attacker，但 escrow/vault 是 maker 的
+    let mut ix = refund_ix(&attacker, &mint_a, SEED);
+    ix.accounts[1].pubkey = escrow;
+    ix.accounts[3].pubkey = ata(&escrow, &mint_a);
+
+    let accounts = vec![
+        (attacker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (escrow, create_escrow_account(&mollusk, SEED, &maker, &mint_a, &Pubkey::new_unique(), 500, bump)),
+        (mint_a, create_mint_account(&maker, 6, 1_000_000)),
+        (ata(&escrow, &mint_a), create_token_account(&mint_a, &escrow, DEPOSIT)),
+        (ata(&attacker, &mint_a), create_token_account(&mint_a, &attacker, 0)),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (ata_program, ata_program_account),
+    ];
+
+    mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::err(ProgramError::IncorrectAuthority)]);
+}
@@ -0,0 +1,151 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    common::*,
+    mollusk_svm::result::Check,
+    mollusk_svm_programs_token::{associated_token, token, token2022},
+    solana_account::Account,
+    solana_sdk::{instruction::Instruction, pubkey::Pubkey},
+};
+
+const SEED: u64 = 31;
+const DEPOSIT: u64 = 1_000;
+const RECEIVE: u64 = 500;
+
+struct Fixture {
+    maker: Pubkey,
+    taker: Pubkey,
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    escrow: Pubkey,
+    vault: Pubkey,
+    token_program: Pubkey,
+    accounts: Vec<(Pubkey, Account)>,
+}
+
+impl Fixture {
+    /// maker 持有 A，taker 持有 B；双方的 ATA 都已存在，lamports 的变化只来自 escrow 和 vault 的租金
+    fn new(token_program: Pubkey) -> Self {
+        let token_program_account = match token_program == spl_token_2022::id() {
+            true => token2022::keyed_account().1,
+            false => token::keyed_account().1,
+        };
+        let (ata_program, ata_program_account) = associated_token::keyed_account();
+
+        let maker = Pubkey::new_unique();
+        let taker = Pubkey::new_unique();
+        let mint_a = Pubkey::new_unique();
+        let mint_b = Pubkey::new_unique();
+        let (escrow, _) = escrow_pda(&maker, SEED);
+        let vault = ata_with_program(&escrow, &mint_a, &token_program);
+        let token_account = |mint: &Pubkey, owner: &Pubkey, amount: u64| {
+            (
+                ata_with_program(owner, mint, &token_program),
+                create_token_account_with_program(mint, owner, amount, &token_program),
+            )
+        };
+
+        let accounts = vec![
+            (maker, create_system_account(10 * LAMPORTS_PER_SOL)),
+            (taker, create_system_account(10 * LAMPORTS_PER_SOL)),
+            (escrow, Account::default()),
+            (vault, Account::default()),
+            (mint_a, create_mint_account_with_program(&maker, 6, 1_000_000, &token_program)),
+            (mint_b, create_mint_account_with_program(&taker, 9, 1_000_000, &token_program)),
+            token_account(&mint_a, &maker, DEPOSIT),
+            token_account(&mint_b, &maker, 0),
+            token_account(&mint_a, &taker, 0),
+            token_account(&mint_b, &taker, RECEIVE),
+            (system_program::id(), create_system_program_account()),
+            (token_program, token_program_account),
+            (ata_program, ata_program_account),
+        ];
+
+        Self { maker, taker, mint_a, mint_b, escrow, vault, token_program, accounts }
+    }
+
+    fn make_ix(&self) -> Instruction {
+        make_ix_with_program(&self.maker, &self.mint_a, &self.mint_b, SEED, RECEIVE, DEPOSIT, &self.token_program)
+    }
+
+    fn ata(&self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        ata_with_program(owner, mint, &self.token_program)
+    }
+
+    fn lamports(&self, accounts: &[(Pubkey, Account)], key: &Pubkey) -> u64 {
+        accounts.iter().find(|(k, _)| k == key).map_or(0, |(_, a)| a.lamports)
+    }
+}
+
+fn make_then_take(token_program: Pubkey) {
+    let mollusk = setup_mollusk();
+    let f = Fixture::new(token_program);
+    let maker_lamports_before = f.lamports(&f.accounts, &f.maker);
+    let taker_lamports_before = f.lamports(&f.accounts, &f.taker);
+
+    let result = mollusk.process_and_validate_instruction_chain(
+        &[
+            (&f.make_ix(), &[Check::success()]),
+            (
+                &take_ix_with_program(&f.taker, &f.maker, &f.mint_a, &f.mint_b, SEED, &f.token_program),
+                &[Check::success()],
+            ),
+        ],
+        &f.accounts,
+    );
+
+    assert_eq!(token_amount(result.get_account(&f.ata(&f.maker, &f.mint_b)).unwrap()), RECEIVE);
+    assert_eq!(token_amount(result.get_account(&f.ata(&f.taker, &f.mint_a)).unwrap()), DEPOSIT);
+    assert_eq!(token_amount(result.get_account(&f.ata(&f.taker, &f.mint_b)).unwrap()), 0);
+
+    // escrow 和 vault 都已关闭，租金全部回到付租金的 maker，taker 的 lamports 不变
+    let accounts = &result.resulting_accounts;
+    assert_eq!(f.lamports(accounts, &f.escrow), 0);
+    assert_eq!(f.lamports(accounts, &f.vault), 0);
+    assert_eq!(f.lamports(accounts, &f.maker), maker_lamports_before);
+    assert_eq!(f.lamports(accounts, &f.taker), taker_lamports_before);
+}
+
+fn make_then_refund(token_program: Pubkey) {
+    let mollusk = setup_mollusk();
+    let f = Fixture::new(token_program);
+    let maker_lamports_before = f.lamports(&f.accounts, &f.maker);
+
+    let result = mollusk.process_and_validate_instruction_chain(
+        &[
+            (&f.make_ix(), &[Check::success()]),
+            (&refund_ix_with_program(&f.maker, &f.mint_a, SEED, &f.token_program), &[Check::success()]),
+        ],
+        &f.accounts,
+    );
+
+    assert_eq!(token_amount(result.get_account(&f.ata(&f.maker, &f.mint_a)).unwrap()), DEPOSIT);
+
+    // 退款时 escrow 和 vault 的租金都回到 maker
+    let accounts = &result.resulting_accounts;
+    assert_eq!(f.lamports(accounts, &f.escrow), 0);
+    assert_eq!(f.lamports(accounts, &f.vault), 0);
+    assert_eq!(f.lamports(accounts, &f.maker), maker_lamports_before);
+}
+
+#[test]
+fn test_make_take_round_trip() {
+    make_then_take(spl_token::id());
+}
+
+#[test]
+fn test_make_take_round_trip_token_2022() {
+    make_then_take(spl_token_2022::id());
+}
+
+#[test]
+fn test_make_refund_round_trip() {
+    make_then_refund(spl_token::id());
+}
+
+#[test]
+fn test_make_refund_round_trip_token_2022() {
+    make_then_refund(spl_token_2022::id());
+}
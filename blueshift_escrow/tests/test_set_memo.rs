@@ -0,0 +1,104 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_escrow::{errors::EscrowError, state::Escrow},
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    mollusk_svm_programs_token::{associated_token, token},
+    solana_account::Account,
+    solana_sdk::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+const SEED: u64 = 81;
+const DEPOSIT: u64 = 1_000;
+const MEMO: &[u8] = "order-42 / 对账".as_bytes();
+
+/// maker 的 escrow 和装有 DEPOSIT 的 vault，以及退款需要的程序账户
+fn setup(mollusk: &Mollusk, maker: &Pubkey, mint_a: &Pubkey) -> Vec<(Pubkey, Account)> {
+    let (token_program, token_program_account) = token::keyed_account();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+    let (escrow, bump) = escrow_pda(maker, SEED);
+
+    vec![
+        (*maker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (escrow, create_escrow_account(mollusk, SEED, maker, mint_a, &Pubkey::new_unique(), 500, bump)),
+        (*mint_a, create_mint_account(maker, 6, 1_000_000)),
+        (ata(&escrow, mint_a), create_token_account(mint_a, &escrow, DEPOSIT)),
+        (ata(maker, mint_a), create_token_account(mint_a, maker, 0)),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (ata_program, ata_program_account),
+    ]
+}
+
+#[test]
+fn test_set_memo_grows_escrow_and_charges_maker() {
+    let mollusk = setup_mollusk();
+    let maker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+    let (escrow, _) = escrow_pda(&maker, SEED);
+    let accounts = setup(&mollusk, &maker, &mint_a);
+
+    let result = mollusk.process_and_validate_instruction(
+        &set_memo_ix(&maker, SEED, MEMO),
+        &accounts,
+        &[Check::success()],
+    );
+
+    // memo 紧跟在固定字段之后，固定字段不变
+    let account = result.get_account(&escrow).unwrap();
+    assert_eq!(account.data.len(), ESCROW_LEN + MEMO.len());
+    assert_eq!(&account.data[ESCROW_LEN..], MEMO);
+    assert_eq!(account.data[..ESCROW_LEN], accounts[1].1.data[..]);
+
+    // 增加的租金由 maker 支付
+    let rent = mollusk.sysvars.rent.minimum_balance(ESCROW_LEN + MEMO.len());
+    assert_eq!(account.lamports, rent);
+    let maker_lamports = result.get_account(&maker).unwrap().lamports;
+    assert_eq!(maker_lamports, 10 * LAMPORTS_PER_SOL - (rent - accounts[1].1.lamports));
+
+    // 带 memo 的 escrow 仍然可以正常退款
+    let refund = mollusk.process_and_validate_instruction(
+        &refund_ix(&maker, &mint_a, SEED),
+        &result.resulting_accounts,
+        &[Check::success()],
+    );
+    assert_eq!(token_amount(refund.get_account(&ata(&maker, &mint_a)).unwrap()), DEPOSIT);
+}
+
+#[test]
+fn test_set_memo_rejects_memo_longer_than_max() {
+    let mollusk = setup_mollusk();
+    let maker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+    let accounts = setup(&mollusk, &maker, &mint_a);
+
+    mollusk.process_and_validate_instruction(
+        &set_memo_ix(&maker, SEED, &[b'a'; Escrow::MAX_MEMO_LEN]),
+        &accounts,
+        &[Check::success()],
+    );
+    mollusk.process_and_validate_instruction(
+        &set_memo_ix(&maker, SEED, &[b'a'; Escrow::MAX_MEMO_LEN + 1]),
+        &accounts,
+        &[Check::err(escrow_err(EscrowError::MemoTooLong))],
+    );
+}
+
+#[test]
+fn test_set_memo_by_other_signer_is_rejected() {
+    let mollusk = setup_mollusk();
+    let maker = Pubkey::new_unique();
+    let attacker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+    let mut accounts = setup(&mollusk, &maker, &mint_a);
+    accounts.push((attacker, create_system_account(10 * LAMPORTS_PER_SOL)));
+
+    // 签名者是 attacker，但 escrow 是 maker 的
+    let mut ix = set_memo_ix(&attacker, SEED, MEMO);
+    ix.accounts[1].pubkey = escrow_pda(&maker, SEED).0;
+
+    mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::err(ProgramError::IncorrectAuthority)]);
+}
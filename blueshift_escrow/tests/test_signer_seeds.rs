@@ -0,0 +1,34 @@
+mod common;
+
+use {
+    blueshift_escrow::state::Escrow,
+    common::*,
+    solana_sdk::pubkey::Pubkey,
+};
+
+#[test]
+fn test_escrow_signer_seeds_reproduce_pda() {
+    let maker = Pubkey::new_unique();
+    // 低字节为 255、高字节非零，确保字节序正确
+    let seed = 0x0102_0304_0506_07ffu64;
+    let (escrow, bump) = escrow_pda(&maker, seed);
+
+    let mut data = vec![0u8; Escrow::LEN];
+    Escrow::load_mut(&mut data).unwrap().set_inner(
+        seed,
+        maker.to_bytes(),
+        Pubkey::new_unique().to_bytes(),
+        Pubkey::new_unique().to_bytes(),
+        500,
+        [bump],
+        Pubkey::default().to_bytes(),
+        0,
+        0,
+    );
+
+    let state = Escrow::load(&data).unwrap();
+    let seeds = state.escrow_signer_seeds();
+    let seeds: Vec<&[u8]> = seeds.iter().map(|seed| &**seed).collect();
+
+    assert_eq!(Pubkey::create_program_address(&seeds, &program_id()).unwrap(), escrow);
+}
@@ -0,0 +1,66 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    mollusk_svm_programs_token::{associated_token, token, token2022},
+    solana_account::Account,
+    solana_sdk::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+const SEED: u64 = 91;
+const DEPOSIT: u64 = 1_000;
+
+/// maker 的 escrow，vault 和 maker 的 ATA 都属于 token_program
+fn accounts(mollusk: &Mollusk, maker: &Pubkey, mint_a: &Pubkey, mint: Account, token_program: (Pubkey, Account)) -> Vec<(Pubkey, Account)> {
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+    let (escrow, bump) = escrow_pda(maker, SEED);
+    let program = token_program.0;
+
+    vec![
+        (*maker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (escrow, create_escrow_account(mollusk, SEED, maker, mint_a, &Pubkey::new_unique(), 500, bump)),
+        (*mint_a, mint),
+        (ata_with_program(&escrow, mint_a, &program), create_token_account_with_program(mint_a, &escrow, DEPOSIT, &program)),
+        (ata_with_program(maker, mint_a, &program), create_token_account_with_program(mint_a, maker, 0, &program)),
+        (system_program::id(), create_system_program_account()),
+        token_program,
+        (ata_program, ata_program_account),
+    ]
+}
+
+/// 带扩展的 Token-2022 mint 比 Mint::LEN 长，仍然可以正常退款
+#[test]
+fn test_refund_with_extended_token_2022_mint() {
+    let mollusk = setup_mollusk();
+    let (maker, mint_a) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let token_program = token2022::keyed_account();
+    let program = token_program.0;
+    let mint = create_extended_mint_account(&maker, 6, 1_000_000);
+    assert!(mint.data.len() > 82);
+
+    let result = mollusk.process_and_validate_instruction(
+        &refund_ix_with_program(&maker, &mint_a, SEED, &program),
+        &accounts(&mollusk, &maker, &mint_a, mint, token_program),
+        &[Check::success()],
+    );
+    assert_eq!(token_amount(result.get_account(&ata_with_program(&maker, &mint_a, &program)).unwrap()), DEPOSIT);
+}
+
+/// mint 必须属于传入的 token_program：SPL Token 的 mint 不能搭配 Token-2022 使用
+#[test]
+fn test_refund_rejects_mint_from_other_token_program() {
+    let mollusk = setup_mollusk();
+    let (maker, mint_a) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let token_program = token2022::keyed_account();
+    let program = token_program.0;
+    let mint = create_mint_account_with_program(&maker, 6, 1_000_000, &token::keyed_account().0);
+
+    mollusk.process_and_validate_instruction(
+        &refund_ix_with_program(&maker, &mint_a, SEED, &program),
+        &accounts(&mollusk, &maker, &mint_a, mint, token_program),
+        &[Check::err(ProgramError::InvalidAccountOwner)],
+    );
+}
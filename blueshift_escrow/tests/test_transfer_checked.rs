@@ -0,0 +1,71 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    common::*,
+    mollusk_svm::result::Check,
+    mollusk_svm_programs_token::{associated_token, token2022},
+    solana_sdk::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+const SEED: u64 = 21;
+const DEPOSIT: u64 = 1_000;
+
+/// Token-2022 的 mint：退款通过传入的 token_program 执行 TransferChecked 和 CloseAccount
+#[test]
+fn test_refund_with_token_2022_mint() {
+    let mollusk = setup_mollusk();
+    let maker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+    let (token_program, token_program_account) = token2022::keyed_account();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+    let (escrow, bump) = escrow_pda(&maker, SEED);
+    let vault = ata_with_program(&escrow, &mint_a, &token_program);
+    let maker_ata_a = ata_with_program(&maker, &mint_a, &token_program);
+
+    let accounts = vec![
+        (maker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (escrow, create_escrow_account(&mollusk, SEED, &maker, &mint_a, &Pubkey::new_unique(), 500, bump)),
+        (mint_a, create_mint_account_with_program(&maker, 9, 1_000_000, &token_program)),
+        (vault, create_token_account_with_program(&mint_a, &escrow, DEPOSIT, &token_program)),
+        (maker_ata_a, create_token_account_with_program(&mint_a, &maker, 0, &token_program)),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (ata_program, ata_program_account),
+    ];
+
+    let result = mollusk.process_and_validate_instruction(
+        &refund_ix_with_program(&maker, &mint_a, SEED, &token_program),
+        &accounts,
+        &[Check::success()],
+    );
+
+    assert_eq!(token_amount(result.get_account(&maker_ata_a).unwrap()), DEPOSIT);
+    assert_eq!(result.get_account(&vault).unwrap().lamports, 0);
+}
+
+/// token_program 必须是 SPL Token 或 Token-2022，CPI 不会调用任意程序
+#[test]
+fn test_refund_rejects_unknown_token_program() {
+    let mollusk = setup_mollusk();
+    let maker = Pubkey::new_unique();
+    let mint_a = Pubkey::new_unique();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+    let (escrow, bump) = escrow_pda(&maker, SEED);
+
+    let mut ix = refund_ix(&maker, &mint_a, SEED);
+    ix.accounts[6].pubkey = system_program::id();
+
+    let accounts = vec![
+        (maker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (escrow, create_escrow_account(&mollusk, SEED, &maker, &mint_a, &Pubkey::new_unique(), 500, bump)),
+        (mint_a, create_mint_account(&maker, 6, 1_000_000)),
+        (ata(&escrow, &mint_a), create_token_account(&mint_a, &escrow, DEPOSIT)),
+        (ata(&maker, &mint_a), create_token_account(&mint_a, &maker, 0)),
+        (system_program::id(), create_system_program_account()),
+        (ata_program, ata_program_account),
+    ];
+
+    mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::err(ProgramError::IncorrectProgramId)]);
+}
@@ -0,0 +1,218 @@
+use crate::instructions::helpers::{
+    add_u64, compute_swap, compute_swap_exact_out, deposit_amounts_from_lp, locked_lp_supply,
+    lp_for_withdraw_amounts, lp_from_deposit_amounts, price_impact_bps, single_sided_swap_amount,
+    sqrt_u128, sub_u64, withdraw_amounts_from_lp,
+};
+use crate::state::EQUAL_WEIGHT_BPS;
+use pinocchio::program_error::ProgramError;
+
+pub mod weighted;
+
+// ============================================================================
+// 恒定乘积曲线的统一入口
+// ============================================================================
+//
+// swap 和存取款虽然都基于 x * y = k，但需要的参数完全不同：
+// - swap：输入/输出两侧的储备（按方向区分）和费率，与 LP supply 无关
+// - deposit/withdraw：两侧储备和 LP supply（包含锁定的 MINIMUM_LIQUIDITY），与费率无关
+//
+// 之前 swap 曾把 X 储备当作 liquidity 传入曲线，这里用两个构造函数把参数固定下来，
+// 所有 handler 都通过它们计算，避免再传错参数。
+//
+// 加权池子（例如 80/20，见 `weighted`）只影响 swap：按比例存取款不改变两侧储备的比例，
+// 与权重无关；权重只在衡量每份 LP 的价值（`liquidity_per_lp`）时用到。
+
+/// 构造曲线的入口
+pub struct Curve;
+
+impl Curve {
+    /// 50/50 池子的 swap 曲线：`is_x` 为 true 时 X -> Y，否则 Y -> X
+    #[inline(always)]
+    pub fn for_swap(vault_x: u64, vault_y: u64, is_x: bool, fee_bps: u16) -> SwapCurve {
+        Self::for_weighted_swap(vault_x, vault_y, is_x, fee_bps, EQUAL_WEIGHT_BPS)
+    }
+
+    /// 加权池子的 swap 曲线，`weight_x` 为 X 一侧的权重（`Config::weight_x`）
+    #[inline(always)]
+    pub fn for_weighted_swap(vault_x: u64, vault_y: u64, is_x: bool, fee_bps: u16, weight_x: u16) -> SwapCurve {
+        let (reserve_in, reserve_out, weight_in) = match is_x {
+            true => (vault_x, vault_y, weight_x),
+            false => (vault_y, vault_x, 10_000 - weight_x),
+        };
+
+        SwapCurve {
+            reserve_in,
+            reserve_out,
+            fee_bps,
+            weight_in,
+        }
+    }
+
+    /// 存取款使用的曲线：`mint_supply` 为 LP mint 上实际的 supply，锁定的 MINIMUM_LIQUIDITY 在这里加上
+    #[inline(always)]
+    pub fn for_liquidity(
+        vault_x: u64,
+        vault_y: u64,
+        mint_supply: u64,
+    ) -> Result<LiquidityCurve, ProgramError> {
+        Ok(LiquidityCurve {
+            reserve_x: vault_x,
+            reserve_y: vault_y,
+            lp_supply: locked_lp_supply(mint_supply)?,
+            weight_x: EQUAL_WEIGHT_BPS,
+        })
+    }
+}
+
+/// swap 方向上的储备、费率和输入一侧的权重
+pub struct SwapCurve {
+    pub reserve_in: u64,
+    pub reserve_out: u64,
+    pub fee_bps: u16,
+    pub weight_in: u16,
+}
+
+impl SwapCurve {
+    /// 50/50 时使用 x * y = k 的精确整数公式，与加权之前的结果逐位一致
+    #[inline(always)]
+    fn is_equal_weight(&self) -> bool {
+        self.weight_in == EQUAL_WEIGHT_BPS
+    }
+
+    /// 输入 `amount_in` 可得到的输出数量
+    #[inline(always)]
+    pub fn amount_out(&self, amount_in: u64) -> Result<u64, ProgramError> {
+        match self.is_equal_weight() {
+            true => compute_swap(self.reserve_in, self.reserve_out, amount_in, self.fee_bps),
+            false => weighted::compute_swap(self.reserve_in, self.reserve_out, amount_in, self.fee_bps, self.weight_in),
+        }
+    }
+
+    /// 得到 `amount_out` 所需的最小输入（exact-out）
+    #[inline(always)]
+    pub fn amount_in(&self, amount_out: u64) -> Result<u64, ProgramError> {
+        match self.is_equal_weight() {
+            true => compute_swap_exact_out(self.reserve_in, self.reserve_out, amount_out, self.fee_bps),
+            false => {
+                weighted::compute_swap_exact_out(self.reserve_in, self.reserve_out, amount_out, self.fee_bps, self.weight_in)
+            }
+        }
+    }
+
+    /// 输入 `amount_in` 的价格影响（基点）
+    #[inline(always)]
+    pub fn price_impact_bps(&self, amount_in: u64) -> Result<u16, ProgramError> {
+        match self.is_equal_weight() {
+            true => price_impact_bps(self.reserve_in, self.reserve_out, amount_in, self.fee_bps),
+            false => {
+                weighted::price_impact_bps(self.reserve_in, self.reserve_out, amount_in, self.fee_bps, self.weight_in)
+            }
+        }
+    }
+}
+
+/// 两侧储备和计入锁定部分的 LP supply
+pub struct LiquidityCurve {
+    pub reserve_x: u64,
+    pub reserve_y: u64,
+    pub lp_supply: u64,
+    /// 只用于 `liquidity_per_lp`，`for_liquidity` 缺省为 50/50
+    pub weight_x: u16,
+}
+
+impl LiquidityCurve {
+    /// 加权池子使用 `Config::weight_x`
+    #[inline(always)]
+    pub fn with_weight_x(self, weight_x: u16) -> Self {
+        Self { weight_x, ..self }
+    }
+
+    /// 铸造 `lp` 份额需要存入的 x/y（向上取整）
+    #[inline(always)]
+    pub fn deposit_amounts(&self, lp: u64) -> Result<(u64, u64), ProgramError> {
+        deposit_amounts_from_lp(self.reserve_x, self.reserve_y, self.lp_supply, lp)
+    }
+
+    /// 最多存入 x/y 时可铸造的 LP（向下取整）
+    #[inline(always)]
+    pub fn lp_for_amounts(&self, x: u64, y: u64) -> Result<u64, ProgramError> {
+        lp_from_deposit_amounts(self.reserve_x, self.reserve_y, self.lp_supply, x, y)
+    }
+
+    /// 销毁 `lp` 份额可取回的 x/y（向下取整）
+    #[inline(always)]
+    pub fn withdraw_amounts(&self, lp: u64) -> Result<(u64, u64), ProgramError> {
+        withdraw_amounts_from_lp(self.reserve_x, self.reserve_y, self.lp_supply, lp)
+    }
+
+    /// 至少取回 x/y 需要销毁的 LP（向上取整）
+    #[inline(always)]
+    pub fn lp_for_withdraw(&self, x: u64, y: u64) -> Result<u64, ProgramError> {
+        lp_for_withdraw_amounts(self.reserve_x, self.reserve_y, self.lp_supply, x, y)
+    }
+
+    /// 每份 LP 对应的 sqrt(x*y)（Q64.64），加权池子为 x^wx · y^wy
+    ///
+    /// 存取款按比例进行，不改变这个值（舍入只会让它变大）；swap 手续费留在池子里使它增长，
+    /// 因此两个时刻的比值就是这段时间的手续费收益
+    #[inline(always)]
+    pub fn liquidity_per_lp(&self) -> Result<u128, ProgramError> {
+        if self.lp_supply == 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let liquidity = match self.weight_x {
+            EQUAL_WEIGHT_BPS => sqrt_u128(self.reserve_x as u128 * self.reserve_y as u128),
+            weight_x => weighted::geometric_mean(self.reserve_x, self.reserve_y, weight_x)? as u128,
+        };
+        // liquidity <= u64::MAX，左移 64 位不会溢出
+        Ok((liquidity << 64) / self.lp_supply as u128)
+    }
+
+    /// 单边存款：只存入 `from_x` 指定一侧的 `amount_in`，其中一部分先在当前储备上兑换成另一侧（收取 swap 手续费），
+    /// 再把剩下的部分和兑换输出按兑换后储备的比例存入
+    ///
+    /// 返回 (lp, 兑换输出, price_impact_bps)。被兑换出的代币从未离开金库，最终储备只有输入一侧增加 amount_in。
+    /// 舍入：兑换数量、兑换输出和 LP 都向下取整，LP 按两侧中较小的比例计算，多余的部分留在池子里
+    #[inline(always)]
+    pub fn single_sided_deposit(&self, amount_in: u64, from_x: bool, fee_bps: u16) -> Result<(u64, u64, u16), ProgramError> {
+        let swap = Curve::for_swap(self.reserve_x, self.reserve_y, from_x, fee_bps);
+        let swapped = single_sided_swap_amount(swap.reserve_in, amount_in, fee_bps)?;
+        if swapped == 0 {
+            return Ok((0, 0, 0));
+        }
+        let swap_out = swap.amount_out(swapped)?;
+
+        // 兑换后的储备上按比例存入：输入一侧 + swapped，输出一侧 - swap_out
+        let kept = sub_u64(amount_in, swapped)?;
+        let after_in = add_u64(swap.reserve_in, swapped)?;
+        let after_out = sub_u64(swap.reserve_out, swap_out)?;
+        let (reserve_x, reserve_y, x, y) = match from_x {
+            true => (after_in, after_out, kept, swap_out),
+            false => (after_out, after_in, swap_out, kept),
+        };
+        // lp_supply 已经包含锁定部分，直接构造
+        let after = LiquidityCurve { reserve_x, reserve_y, ..*self };
+
+        Ok((after.lp_for_amounts(x, y)?, swap_out, swap.price_impact_bps(swapped)?))
+    }
+
+    /// 单边取款：先按比例取回 x/y，再把另一侧在取款后的储备上兑换成 `to_x` 指定的一侧（收取 swap 手续费）
+    ///
+    /// 返回 (amount_out, price_impact_bps)，价格影响为内部兑换那一步的影响
+    #[inline(always)]
+    pub fn single_sided_withdraw(&self, lp: u64, to_x: bool, fee_bps: u16) -> Result<(u64, u16), ProgramError> {
+        let (x, y) = self.withdraw_amounts(lp)?;
+        // MINIMUM_LIQUIDITY 锁定在 supply 中，取款后两侧储备都不会归零
+        let swap = Curve::for_swap(sub_u64(self.reserve_x, x)?, sub_u64(self.reserve_y, y)?, !to_x, fee_bps);
+        let (kept, swapped) = match to_x {
+            true => (x, y),
+            false => (y, x),
+        };
+        if swapped == 0 {
+            return Ok((kept, 0));
+        }
+
+        let amount_out = add_u64(kept, swap.amount_out(swapped)?)?;
+        Ok((amount_out, swap.price_impact_bps(swapped)?))
+    }
+}
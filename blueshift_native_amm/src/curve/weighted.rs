@@ -0,0 +1,216 @@
+use crate::instructions::helpers::{mul_div, mul_div_ceil, FEE_BPS_DENOMINATOR};
+use crate::state::{MAX_WEIGHT_BPS, MIN_WEIGHT_BPS};
+use pinocchio::program_error::ProgramError;
+
+// ============================================================================
+// 加权恒定乘积曲线：x^wx · y^wy = k，wx + wy = 10000 基点
+// ============================================================================
+//
+// 50/50 时就是 x * y = k，仍然走 helpers 中精确的整数公式；其余权重的兑换涉及分数次幂：
+// - exact-in：out = R_out · (1 - (R_in / (R_in + a))^(w_in / w_out))
+// - exact-out：a = R_in · ((R_out / (R_out - out))^(w_out / w_in) - 1)
+//
+// 分数次幂用 2^(e · log2(b)) 计算，log2 / exp2 都是 Q1.63 定点数上的逐位算法（只用 u128 乘法和移位）。
+// 截断误差约 2^-56，这里在指数上再留出 2^-40 的余量并朝对池子有利的方向取整：
+// 输出向下、输入向上，报价只会比理论值略差，不会被套利取走储备。
+
+/// Q1.63 定点数的小数位数
+const FRAC_BITS: u32 = 63;
+/// Q1.63 的 1
+const ONE: u128 = 1 << FRAC_BITS;
+/// 指数上的舍入余量：2^-40
+const LOG2_MARGIN: i128 = 1 << (FRAC_BITS - 40);
+
+/// 2^(2^-i)，i = 1..=63，Q1.63，向上取整
+const EXP2_TABLE: [u64; 63] = [
+    0xb504f333f9de6485, 0x9837f0518db8a970, 0x8b95c1e3ea8bd6e7,
+    0x85aac367cc487b15, 0x82cd8698ac2ba1d8, 0x8164d1f3bc030774,
+    0x80b1ed4fd999ab6d, 0x8058d7d2d5e5f6b1, 0x802c6436d0e04f51,
+    0x8016302f17467629, 0x800b179c82028fd1, 0x80058baf7fee3b5e,
+    0x8002c5d00fdcfcb7, 0x800162e61bed4a49, 0x8000b17292f702a4,
+    0x800058b92abbae03, 0x80002c5c8dade4d8, 0x8000162e44eaf637,
+    0x80000b1721fa7c19, 0x8000058b90de7e4d, 0x800002c5c8678f37,
+    0x80000162e431dba0, 0x800000b1721872d1, 0x80000058b90c1aa9,
+    0x8000002c5c8605a5, 0x800000162e4300e7, 0x8000000b17217ff9,
+    0x800000058b90bfde, 0x80000002c5c85fe7, 0x8000000162e42ff2,
+    0x80000000b17217f9, 0x8000000058b90bfd, 0x800000002c5c85fe,
+    0x80000000162e42ff, 0x800000000b172180, 0x80000000058b90c0,
+    0x8000000002c5c860, 0x800000000162e430, 0x8000000000b17218,
+    0x800000000058b90c, 0x80000000002c5c86, 0x8000000000162e43,
+    0x80000000000b1722, 0x8000000000058b91, 0x800000000002c5c9,
+    0x80000000000162e5, 0x800000000000b173, 0x80000000000058ba,
+    0x8000000000002c5d, 0x800000000000162f, 0x8000000000000b18,
+    0x800000000000058c, 0x80000000000002c6, 0x8000000000000163,
+    0x80000000000000b2, 0x8000000000000059, 0x800000000000002d,
+    0x8000000000000017, 0x800000000000000c, 0x8000000000000006,
+    0x8000000000000003, 0x8000000000000002, 0x8000000000000001,
+];
+
+/// 权重必须在 [MIN_WEIGHT_BPS, MAX_WEIGHT_BPS] 内，返回 (w_in, w_out)
+#[inline(always)]
+fn weights(weight_in: u16) -> Result<(u128, u128), ProgramError> {
+    if !(MIN_WEIGHT_BPS..=MAX_WEIGHT_BPS).contains(&weight_in) {
+        return Err(ProgramError::InvalidArgument);
+    }
+    Ok((weight_in as u128, FEE_BPS_DENOMINATOR as u128 - weight_in as u128))
+}
+
+/// log2(n)，Q.63（向下取整），n 必须大于 0
+#[inline(always)]
+fn log2(n: u64) -> i128 {
+    let msb = 63 - n.leading_zeros();
+    // 归一化到 [1, 2)：m < 2^64，m * m 不会溢出 u128
+    let mut m = (n as u128) << (FRAC_BITS - msb);
+    let mut result = (msb as i128) << FRAC_BITS;
+    for bit in (0..FRAC_BITS).rev() {
+        m = (m * m) >> FRAC_BITS;
+        if m >= 2 * ONE {
+            m >>= 1;
+            result |= 1 << bit;
+        }
+    }
+    result
+}
+
+/// 2^l，返回 (mantissa, exponent)，值为 mantissa · 2^(exponent - 63)，mantissa ∈ [2^63, 2^64]（向上取整）
+#[inline(always)]
+fn exp2(l: i128) -> (u128, i128) {
+    let exponent = l >> FRAC_BITS;
+    let frac = l - (exponent << FRAC_BITS);
+    let mut mantissa = ONE;
+    for (i, factor) in EXP2_TABLE.iter().enumerate() {
+        if frac & (1 << (FRAC_BITS as usize - 1 - i)) != 0 {
+            // mantissa <= 2^64，factor < 2^64，乘积不会溢出 u128
+            mantissa = (mantissa * *factor as u128).div_ceil(ONE);
+        }
+    }
+    (mantissa, exponent)
+}
+
+/// (num / den)^(exp_num / exp_den)，`round_up` 决定在指数上加还是减去余量
+#[inline(always)]
+fn pow_ratio(num: u64, den: u64, exp_num: u128, exp_den: u128, round_up: bool) -> (u128, i128) {
+    // |log2(num) - log2(den)| < 2^70，乘以不超过 10000 的权重不会溢出 i128
+    let l = (log2(num) - log2(den)) * exp_num as i128 / exp_den as i128;
+    exp2(match round_up {
+        true => l + LOG2_MARGIN,
+        false => l - LOG2_MARGIN,
+    })
+}
+
+/// amount · mantissa · 2^(exponent - 63)，`round_up` 决定右移时的取整方向
+#[inline(always)]
+fn scale(amount: u64, (mantissa, exponent): (u128, i128), round_up: bool) -> Result<u64, ProgramError> {
+    // amount < 2^64，mantissa <= 2^64，乘积不会溢出 u128
+    let product = amount as u128 * mantissa;
+    let shift = FRAC_BITS as i128 - exponent;
+    let result = match shift {
+        s if s >= 128 => (round_up && product != 0) as u128,
+        s if s >= 0 => match round_up {
+            true => product.div_ceil(1 << s),
+            false => product >> s,
+        },
+        s if (-s) < product.leading_zeros() as i128 => product << (-s),
+        _ => return Err(ProgramError::ArithmeticOverflow),
+    };
+    u64::try_from(result).map_err(|_| ProgramError::ArithmeticOverflow)
+}
+
+/// 加权曲线的 exact-in：输入 `amount_in` 可得到的输出数量（向下取整）
+///
+/// 手续费与 `compute_swap` 相同，先从输入中扣除；`weight_in` 为输入一侧的权重（基点）
+#[inline(always)]
+pub fn compute_swap(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_in: u64,
+    fee_bps: u16,
+    weight_in: u16,
+) -> Result<u64, ProgramError> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if fee_bps as u64 >= FEE_BPS_DENOMINATOR {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let (w_in, w_out) = weights(weight_in)?;
+
+    let amount_in_after_fee = mul_div(amount_in, FEE_BPS_DENOMINATOR - fee_bps as u64, FEE_BPS_DENOMINATOR)?;
+    let new_reserve_in = reserve_in
+        .checked_add(amount_in_after_fee)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    // 兑换后留在池子里的输出一侧向上取整，输出即向下取整；比值不超过 1，结果不会超过 reserve_out 太多
+    let remaining = scale(reserve_out, pow_ratio(reserve_in, new_reserve_in, w_in, w_out, true), true)?;
+    Ok(reserve_out.saturating_sub(remaining))
+}
+
+/// 加权曲线的 exact-out：得到 `amount_out` 所需的输入（含手续费，向上取整）
+///
+/// 由于舍入余量，结果可能比 `compute_swap` 意义下的最小输入略大几个最小单位。
+/// `amount_out` 必须小于 reserve_out
+#[inline(always)]
+pub fn compute_swap_exact_out(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_out: u64,
+    fee_bps: u16,
+    weight_in: u16,
+) -> Result<u64, ProgramError> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if fee_bps as u64 >= FEE_BPS_DENOMINATOR || amount_out >= reserve_out {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let (w_in, w_out) = weights(weight_in)?;
+
+    let new_reserve_in = scale(
+        reserve_in,
+        pow_ratio(reserve_out, reserve_out - amount_out, w_out, w_in, true),
+        true,
+    )?;
+    let amount_in_after_fee = new_reserve_in.saturating_sub(reserve_in);
+    mul_div_ceil(amount_in_after_fee, FEE_BPS_DENOMINATOR, FEE_BPS_DENOMINATOR - fee_bps as u64)
+}
+
+/// 加权曲线上一次假设兑换的价格影响（基点），含义与 `price_impact_bps` 相同
+///
+/// spot = (reserve_out / w_out) / (reserve_in / w_in)，effective = amount_out / amount_in
+#[inline(always)]
+pub fn price_impact_bps(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_in: u64,
+    fee_bps: u16,
+    weight_in: u16,
+) -> Result<u16, ProgramError> {
+    if amount_in == 0 {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let (w_in, w_out) = weights(weight_in)?;
+    let amount_out = compute_swap(reserve_in, reserve_out, amount_in, fee_bps, weight_in)?;
+
+    let executed_bps = (amount_out as u128)
+        .checked_mul(reserve_in as u128)
+        .and_then(|v| v.checked_mul(w_out * FEE_BPS_DENOMINATOR as u128))
+        .ok_or(ProgramError::ArithmeticOverflow)?
+        / ((amount_in as u128) * (reserve_out as u128))
+            .checked_mul(w_in)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    Ok((FEE_BPS_DENOMINATOR as u128).saturating_sub(executed_bps) as u16)
+}
+
+/// 加权几何平均 x^(wx/10000) · y^(wy/10000)（向下取整），即加权池子的不变量在 50/50 意义下的 sqrt(x*y)
+///
+/// 结果介于 x 与 y 之间，不会超过 u64
+#[inline(always)]
+pub fn geometric_mean(x: u64, y: u64, weight_x: u16) -> Result<u64, ProgramError> {
+    if x == 0 || y == 0 {
+        return Ok(0);
+    }
+    let (w_x, w_y) = weights(weight_x)?;
+    let l = (log2(x) * w_x as i128 + log2(y) * w_y as i128) / FEE_BPS_DENOMINATOR as i128;
+    scale(1, exp2(l - LOG2_MARGIN), false)
+}
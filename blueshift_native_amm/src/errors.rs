@@ -1,11 +1,159 @@
 use pinocchio::program_error::ProgramError;
 
 #[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum AmmError {
     InvalidAmmState = 0,
+    InvalidAuthority = 1, //签名者不是对应的管理员
+    InsufficientInitialLiquidity = 2, //首次存款的 sqrt(x*y) 不足以覆盖 MINIMUM_LIQUIDITY
+    InvalidVault = 3, //金库账户不可信，例如设置了第三方 close_authority
+    OrderExpired = 4, //超过 expiration（已计入 grace_secs）
+    DuplicateAccount = 5, //同一个账户被传入了多个必须互不相同的位置（例如用户 ATA 与金库）
+    OutputCapExceeded = 6, //单次 swap 的输出超过 Config 中的 max_swap_out
+    LpSupplyChanged = 7, //LP supply 与客户端报价时的预期偏差超过容忍度（报价后有并发的存取款）
+    PoolAlreadyExists = 8, //enforce_unique 打开时，同一 (mint_x, mint_y, fee) 已经登记过池子
+    PriceImpactTooHigh = 9, //价格影响超过允许的上限（例如单边取款时池子太浅）
+    SwapDirectionMismatch = 10, //swap 方向与用户 ATA 不一致（is_x 对应的付款 ATA 不是输入代币）
+    CurveOverflow = 11, //份额/曲线计算溢出 u64，例如铸造 LP 后 supply 超过 u64::MAX
+    DownstreamProgramNotAllowed = 12, //swap 输出转交的下游程序不在 GlobalConfig 的白名单中
+    LpSlippageExceeded = 13, //存款实际铸造的 LP 低于用户给出的 min_lp_out
+    UnknownAmmState = 14, //SetState 传入的状态不是 Initialized / Disabled / WithdrawOnly 之一
+    SlippageExceeded = 15, //输出低于用户给出的最小值（swap 的 min、取款的 min_x / min_y / min_out），或存款需要的 x/y 超过 max_x / max_y
+    ZeroAmount = 16, //数量为 0：指令中的 amount / 滑点参数为 0，或 swap 的输出被舍入为 0
+    InvalidLpMint = 17, //mint_lp 不是这个池子的 LP mint（PDA 或精度不符）
+    FlashSwapNotRepaid = 18, //flash swap 回调返回后金库余额没有增加 amount + fee
+    ReserveMismatch = 19, //金库余额低于 Config 中记录的储备快照
+    AccountFrozen = 20, //Token Account 已被冻结，无法转账
+    RouteMismatch = 21, //swap_route 中下一跳的输入 ATA 不是上一跳的输出 ATA
+    InitialPriceOutOfRange = 22, //首次存款没有给出价格区间，或 x/y 不在调用方给出的 [min, max] 区间内
+    EmptyPool = 23, //池子任一侧储备为 0（例如初始化后还没有存款），不能 swap
+    MintDecimalsMismatch = 24, //TransferChecked 使用的精度与 mint 实际精度不一致
+    PoolNotEmpty = 25, //DisablePool 时仍有未赎回的 LP
+    FeeTooHigh = 26, //SetConfig 修改的费率超过 MAX_FEE_BPS
+    InvalidStateTransition = 27, //AmmState 的非法迁移（例如回到 Uninitialized）
+    Reentrancy = 28, //池子正在执行外部 CPI（下游程序 / 闪电兑换回调）时再次进入
+    WeightedPoolUnsupported = 29, //单边存取款的内部兑换只支持 50/50 池子
+    LpNotSoulbound = 30, //冷却期 / 退出费只能在 soulbound LP 的池子上开启
+    WsolAccountNotEmpty = 31, //解包输出会关闭用户的 WSOL 账户，swap 前账户必须没有余额
     // 可按需增加更多，例如：
-    // InvalidVault = 1,
-    // InvalidLpMint = 2,
+    // InvalidPosition = 32,
+}
+
+/// 错误码表：(code, name)，与 TypeScript 客户端共享的错误约定
+///
+/// 新增 `AmmError` 变体时必须同步更新这里（以及 `AmmError::name`），tests/test_error_codes.rs 会检查
+pub const ERROR_CODES: [(u32, &str); 32] = [
+    (AmmError::InvalidAmmState as u32, "InvalidAmmState"),
+    (AmmError::InvalidAuthority as u32, "InvalidAuthority"),
+    (AmmError::InsufficientInitialLiquidity as u32, "InsufficientInitialLiquidity"),
+    (AmmError::InvalidVault as u32, "InvalidVault"),
+    (AmmError::OrderExpired as u32, "OrderExpired"),
+    (AmmError::DuplicateAccount as u32, "DuplicateAccount"),
+    (AmmError::OutputCapExceeded as u32, "OutputCapExceeded"),
+    (AmmError::LpSupplyChanged as u32, "LpSupplyChanged"),
+    (AmmError::PoolAlreadyExists as u32, "PoolAlreadyExists"),
+    (AmmError::PriceImpactTooHigh as u32, "PriceImpactTooHigh"),
+    (AmmError::SwapDirectionMismatch as u32, "SwapDirectionMismatch"),
+    (AmmError::CurveOverflow as u32, "CurveOverflow"),
+    (AmmError::DownstreamProgramNotAllowed as u32, "DownstreamProgramNotAllowed"),
+    (AmmError::LpSlippageExceeded as u32, "LpSlippageExceeded"),
+    (AmmError::UnknownAmmState as u32, "UnknownAmmState"),
+    (AmmError::SlippageExceeded as u32, "SlippageExceeded"),
+    (AmmError::ZeroAmount as u32, "ZeroAmount"),
+    (AmmError::InvalidLpMint as u32, "InvalidLpMint"),
+    (AmmError::FlashSwapNotRepaid as u32, "FlashSwapNotRepaid"),
+    (AmmError::ReserveMismatch as u32, "ReserveMismatch"),
+    (AmmError::AccountFrozen as u32, "AccountFrozen"),
+    (AmmError::RouteMismatch as u32, "RouteMismatch"),
+    (AmmError::InitialPriceOutOfRange as u32, "InitialPriceOutOfRange"),
+    (AmmError::EmptyPool as u32, "EmptyPool"),
+    (AmmError::MintDecimalsMismatch as u32, "MintDecimalsMismatch"),
+    (AmmError::PoolNotEmpty as u32, "PoolNotEmpty"),
+    (AmmError::FeeTooHigh as u32, "FeeTooHigh"),
+    (AmmError::InvalidStateTransition as u32, "InvalidStateTransition"),
+    (AmmError::Reentrancy as u32, "Reentrancy"),
+    (AmmError::WeightedPoolUnsupported as u32, "WeightedPoolUnsupported"),
+    (AmmError::LpNotSoulbound as u32, "LpNotSoulbound"),
+    (AmmError::WsolAccountNotEmpty as u32, "WsolAccountNotEmpty"),
+];
+
+impl AmmError {
+    /// 错误名称，与 `ERROR_CODES` 中的 name 一致
+    pub const fn name(self) -> &'static str {
+        match self {
+            AmmError::InvalidAmmState => "InvalidAmmState",
+            AmmError::InvalidAuthority => "InvalidAuthority",
+            AmmError::InsufficientInitialLiquidity => "InsufficientInitialLiquidity",
+            AmmError::InvalidVault => "InvalidVault",
+            AmmError::OrderExpired => "OrderExpired",
+            AmmError::DuplicateAccount => "DuplicateAccount",
+            AmmError::OutputCapExceeded => "OutputCapExceeded",
+            AmmError::LpSupplyChanged => "LpSupplyChanged",
+            AmmError::PoolAlreadyExists => "PoolAlreadyExists",
+            AmmError::PriceImpactTooHigh => "PriceImpactTooHigh",
+            AmmError::SwapDirectionMismatch => "SwapDirectionMismatch",
+            AmmError::CurveOverflow => "CurveOverflow",
+            AmmError::DownstreamProgramNotAllowed => "DownstreamProgramNotAllowed",
+            AmmError::LpSlippageExceeded => "LpSlippageExceeded",
+            AmmError::UnknownAmmState => "UnknownAmmState",
+            AmmError::SlippageExceeded => "SlippageExceeded",
+            AmmError::ZeroAmount => "ZeroAmount",
+            AmmError::InvalidLpMint => "InvalidLpMint",
+            AmmError::FlashSwapNotRepaid => "FlashSwapNotRepaid",
+            AmmError::ReserveMismatch => "ReserveMismatch",
+            AmmError::AccountFrozen => "AccountFrozen",
+            AmmError::RouteMismatch => "RouteMismatch",
+            AmmError::InitialPriceOutOfRange => "InitialPriceOutOfRange",
+            AmmError::EmptyPool => "EmptyPool",
+            AmmError::MintDecimalsMismatch => "MintDecimalsMismatch",
+            AmmError::PoolNotEmpty => "PoolNotEmpty",
+            AmmError::FeeTooHigh => "FeeTooHigh",
+            AmmError::InvalidStateTransition => "InvalidStateTransition",
+            AmmError::Reentrancy => "Reentrancy",
+            AmmError::WeightedPoolUnsupported => "WeightedPoolUnsupported",
+            AmmError::LpNotSoulbound => "LpNotSoulbound",
+            AmmError::WsolAccountNotEmpty => "WsolAccountNotEmpty",
+        }
+    }
+
+    /// 按错误码查找变体，客户端解析 `Custom(code)` 时使用
+    pub const fn from_code(code: u32) -> Option<Self> {
+        match code {
+            0 => Some(AmmError::InvalidAmmState),
+            1 => Some(AmmError::InvalidAuthority),
+            2 => Some(AmmError::InsufficientInitialLiquidity),
+            3 => Some(AmmError::InvalidVault),
+            4 => Some(AmmError::OrderExpired),
+            5 => Some(AmmError::DuplicateAccount),
+            6 => Some(AmmError::OutputCapExceeded),
+            7 => Some(AmmError::LpSupplyChanged),
+            8 => Some(AmmError::PoolAlreadyExists),
+            9 => Some(AmmError::PriceImpactTooHigh),
+            10 => Some(AmmError::SwapDirectionMismatch),
+            11 => Some(AmmError::CurveOverflow),
+            12 => Some(AmmError::DownstreamProgramNotAllowed),
+            13 => Some(AmmError::LpSlippageExceeded),
+            14 => Some(AmmError::UnknownAmmState),
+            15 => Some(AmmError::SlippageExceeded),
+            16 => Some(AmmError::ZeroAmount),
+            17 => Some(AmmError::InvalidLpMint),
+            18 => Some(AmmError::FlashSwapNotRepaid),
+            19 => Some(AmmError::ReserveMismatch),
+            20 => Some(AmmError::AccountFrozen),
+            21 => Some(AmmError::RouteMismatch),
+            22 => Some(AmmError::InitialPriceOutOfRange),
+            23 => Some(AmmError::EmptyPool),
+            24 => Some(AmmError::MintDecimalsMismatch),
+            25 => Some(AmmError::PoolNotEmpty),
+            26 => Some(AmmError::FeeTooHigh),
+            27 => Some(AmmError::InvalidStateTransition),
+            28 => Some(AmmError::Reentrancy),
+            29 => Some(AmmError::WeightedPoolUnsupported),
+            30 => Some(AmmError::LpNotSoulbound),
+            31 => Some(AmmError::WsolAccountNotEmpty),
+            _ => None,
+        }
+    }
 }
 
 impl From<AmmError> for ProgramError {
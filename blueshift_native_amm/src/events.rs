@@ -0,0 +1,237 @@
+use pinocchio::pubkey::Pubkey;
+
+// ============================================================================
+// Anchor 兼容的事件
+// ============================================================================
+//
+// 线上格式与 Anchor 的 `emit!` 相同：sol_log_data 的单个数据段 =
+// 8 字节 discriminator (sha256("event:<名称>")[..8]) + Borsh 序列化的字段
+// （整数小端、bool 1 字节、Pubkey 原始 32 字节），现有的 Anchor indexer 可以直接解析。
+//
+// 只有启用 `events` feature 时才会真正输出日志，否则 `emit` 是空操作。
+//
+// 所有事件的公共布局（EVENT_VERSION = 1）：
+//   [0..8)   discriminator
+//   [8]      version，字段布局变化时递增，客户端据此选择解析方式
+//   [9..41)  pool
+//   [41..73) user
+//   ...      各事件自己的字段（见各结构体的注释）
+//   末尾     timestamp: i64，emit 时的 Clock::unix_timestamp
+
+/// 事件布局版本，紧跟在 discriminator 之后
+pub const EVENT_VERSION: u8 = 1;
+
+/// 按 Borsh 规则顺序写入字段的小工具
+struct EventWriter<'a, const N: usize> {
+    buf: &'a mut [u8; N],
+    offset: usize,
+}
+
+impl<'a, const N: usize> EventWriter<'a, N> {
+    #[inline(always)]
+    fn new(buf: &'a mut [u8; N], discriminator: &[u8; 8]) -> Self {
+        buf[..8].copy_from_slice(discriminator);
+        buf[8] = EVENT_VERSION;
+        Self { buf, offset: 9 }
+    }
+
+    #[inline(always)]
+    fn bytes(mut self, value: &[u8]) -> Self {
+        self.buf[self.offset..self.offset + value.len()].copy_from_slice(value);
+        self.offset += value.len();
+        self
+    }
+
+    #[inline(always)]
+    fn u16(self, value: u16) -> Self {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    #[inline(always)]
+    fn u64(self, value: u64) -> Self {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    #[inline(always)]
+    fn i64(self, value: i64) -> Self {
+        self.bytes(&value.to_le_bytes())
+    }
+
+    #[inline(always)]
+    fn bool(self, value: bool) -> Self {
+        self.bytes(&[value as u8])
+    }
+}
+
+#[cfg(feature = "events")]
+#[inline(always)]
+fn emit_data(data: &[u8]) {
+    pinocchio::log::sol_log_data(&[data]);
+}
+
+/// 事件的时间戳；只在启用 `events` feature 时读取 Clock，读取失败时记为 0（不影响指令本身）
+#[cfg(feature = "events")]
+#[inline(always)]
+fn now() -> i64 {
+    use pinocchio::sysvars::{Sysvar, clock::Clock};
+    Clock::get().map(|clock| clock.unix_timestamp).unwrap_or(0)
+}
+
+/// 各事件的 `LEN`：`to_bytes` 的返回类型不能写成 `[u8; Self::LEN]`（带生命周期参数的 Self 不能用在数组长度中）
+const DEPOSIT_EVENT_LEN: usize = 8 + 1 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 8;
+
+/// Deposit 完成：`user` 存入 x/y，获得 `lp`
+///
+/// 布局：公共头 + mint_x (32) + mint_y (32) + lp (u64) + x (u64) + y (u64) + timestamp (i64)
+pub struct DepositEvent<'a> {
+    pub pool: &'a Pubkey,
+    pub user: &'a Pubkey,
+    pub mint_x: &'a Pubkey,
+    pub mint_y: &'a Pubkey,
+    pub lp: u64,
+    pub x: u64,
+    pub y: u64,
+}
+
+impl DepositEvent<'_> {
+    /// sha256("event:DepositEvent")[..8]
+    pub const DISCRIMINATOR: [u8; 8] = [0x78, 0xf8, 0x3d, 0x53, 0x1f, 0x8e, 0x6b, 0x90];
+    pub const LEN: usize = DEPOSIT_EVENT_LEN;
+
+    pub fn to_bytes(&self, timestamp: i64) -> [u8; DEPOSIT_EVENT_LEN] {
+        let mut buf = [0u8; DEPOSIT_EVENT_LEN];
+        EventWriter::new(&mut buf, &Self::DISCRIMINATOR)
+            .bytes(self.pool)
+            .bytes(self.user)
+            .bytes(self.mint_x)
+            .bytes(self.mint_y)
+            .u64(self.lp)
+            .u64(self.x)
+            .u64(self.y)
+            .i64(timestamp);
+        buf
+    }
+
+    #[inline(always)]
+    pub fn emit(&self) {
+        #[cfg(feature = "events")]
+        emit_data(&self.to_bytes(now()));
+    }
+}
+
+const WITHDRAW_EVENT_LEN: usize = 8 + 1 + 32 + 32 + 32 + 32 + 8 + 8 + 8 + 8;
+
+/// Withdraw 完成：`user` 销毁 `lp`，取回 x/y
+///
+/// 布局：公共头 + mint_x (32) + mint_y (32) + lp (u64) + x (u64) + y (u64) + timestamp (i64)
+pub struct WithdrawEvent<'a> {
+    pub pool: &'a Pubkey,
+    pub user: &'a Pubkey,
+    pub mint_x: &'a Pubkey,
+    pub mint_y: &'a Pubkey,
+    pub lp: u64,
+    pub x: u64,
+    pub y: u64,
+}
+
+impl WithdrawEvent<'_> {
+    /// sha256("event:WithdrawEvent")[..8]
+    pub const DISCRIMINATOR: [u8; 8] = [0x16, 0x09, 0x85, 0x1a, 0xa0, 0x2c, 0x47, 0xc0];
+    pub const LEN: usize = WITHDRAW_EVENT_LEN;
+
+    pub fn to_bytes(&self, timestamp: i64) -> [u8; WITHDRAW_EVENT_LEN] {
+        let mut buf = [0u8; WITHDRAW_EVENT_LEN];
+        EventWriter::new(&mut buf, &Self::DISCRIMINATOR)
+            .bytes(self.pool)
+            .bytes(self.user)
+            .bytes(self.mint_x)
+            .bytes(self.mint_y)
+            .u64(self.lp)
+            .u64(self.x)
+            .u64(self.y)
+            .i64(timestamp);
+        buf
+    }
+
+    #[inline(always)]
+    pub fn emit(&self) {
+        #[cfg(feature = "events")]
+        emit_data(&self.to_bytes(now()));
+    }
+}
+
+const SWAP_EVENT_LEN: usize = 8 + 1 + 32 + 32 + 32 + 32 + 1 + 8 + 8 + 8;
+
+/// Swap 完成：`is_x` 为 true 时 X -> Y
+///
+/// 布局：公共头 + mint_in (32) + mint_out (32) + is_x (bool) + amount_in (u64) + amount_out (u64) + timestamp (i64)
+pub struct SwapEvent<'a> {
+    pub pool: &'a Pubkey,
+    pub user: &'a Pubkey,
+    pub mint_in: &'a Pubkey,
+    pub mint_out: &'a Pubkey,
+    pub is_x: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
+impl SwapEvent<'_> {
+    /// sha256("event:SwapEvent")[..8]
+    pub const DISCRIMINATOR: [u8; 8] = [0x40, 0xc6, 0xcd, 0xe8, 0x26, 0x08, 0x71, 0xe2];
+    pub const LEN: usize = SWAP_EVENT_LEN;
+
+    pub fn to_bytes(&self, timestamp: i64) -> [u8; SWAP_EVENT_LEN] {
+        let mut buf = [0u8; SWAP_EVENT_LEN];
+        EventWriter::new(&mut buf, &Self::DISCRIMINATOR)
+            .bytes(self.pool)
+            .bytes(self.user)
+            .bytes(self.mint_in)
+            .bytes(self.mint_out)
+            .bool(self.is_x)
+            .u64(self.amount_in)
+            .u64(self.amount_out)
+            .i64(timestamp);
+        buf
+    }
+
+    #[inline(always)]
+    pub fn emit(&self) {
+        #[cfg(feature = "events")]
+        emit_data(&self.to_bytes(now()));
+    }
+}
+
+const FEE_UPDATED_EVENT_LEN: usize = 8 + 1 + 32 + 32 + 2 + 2 + 8;
+
+/// SetConfig 修改了费率：`user` 是签名的 fee_admin
+///
+/// 布局：公共头 + old_fee (u16) + new_fee (u16) + timestamp (i64)
+pub struct FeeUpdatedEvent<'a> {
+    pub pool: &'a Pubkey,
+    pub user: &'a Pubkey,
+    pub old_fee: u16,
+    pub new_fee: u16,
+}
+
+impl FeeUpdatedEvent<'_> {
+    /// sha256("event:FeeUpdatedEvent")[..8]
+    pub const DISCRIMINATOR: [u8; 8] = [0x7c, 0x00, 0x21, 0x70, 0x26, 0x98, 0xb2, 0xc3];
+    pub const LEN: usize = FEE_UPDATED_EVENT_LEN;
+
+    pub fn to_bytes(&self, timestamp: i64) -> [u8; FEE_UPDATED_EVENT_LEN] {
+        let mut buf = [0u8; FEE_UPDATED_EVENT_LEN];
+        EventWriter::new(&mut buf, &Self::DISCRIMINATOR)
+            .bytes(self.pool)
+            .bytes(self.user)
+            .u16(self.old_fee)
+            .u16(self.new_fee)
+            .i64(timestamp);
+        buf
+    }
+
+    #[inline(always)]
+    pub fn emit(&self) {
+        #[cfg(feature = "events")]
+        emit_data(&self.to_bytes(now()));
+    }
+}
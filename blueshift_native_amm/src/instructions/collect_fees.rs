@@ -0,0 +1,155 @@
+use super::helpers::*;
+use crate::errors::AmmError;
+use crate::state::{Config, ConfigRole};
+use pinocchio::{ProgramResult, account_info::AccountInfo, program_error::ProgramError};
+
+/// 取出留在金库中的协议费（见 `Config::accrues_protocol_fee`），必须由池子的 owner（authority）签名
+///
+/// 转给 owner 指定的两个代币账户，之后把 fees_x / fees_y 清零。
+/// 每一侧最多转出金库中不属于储备快照的部分，记账的协议费超过它时按它封顶，不会动用 LP 的储备
+///
+/// 账户：`[authority, config, vault_x, vault_y, destination_x, destination_y, token_program, _, (mint_x, mint_y)]`；
+/// 没有指令数据
+pub struct CollectFees<'a> {
+    pub accounts: CollectFeesAccounts<'a>,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for CollectFees<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        if !data.is_empty() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let accounts = CollectFeesAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> CollectFees<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &19;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let accounts = &self.accounts;
+        let config = Config::load_checked(accounts.config)?;
+        config.check_authority(accounts.authority, ConfigRole::Owner)?;
+
+        // 协议费留在金库中、不计入快照，因此一定在“金库余额 - 快照”之内；超出的部分无法取出，按上限封顶
+        let (available_x, available_y) = PoolReserves::load(&config, accounts.vault_x, accounts.vault_y)?.donated();
+        let x = config.fees_x().min(available_x);
+        let y = config.fees_y().min(available_y);
+
+        let config_seeds = config.config_seeds();
+        let mints = accounts.mints.as_ref();
+        if x > 0 {
+            transfer_pool_tokens(
+                &config,
+                mints,
+                accounts.token_program,
+                accounts.vault_x,
+                accounts.destination_x,
+                accounts.config,
+                x,
+                true,
+                &config_seeds,
+            )?;
+        }
+        if y > 0 {
+            transfer_pool_tokens(
+                &config,
+                mints,
+                accounts.token_program,
+                accounts.vault_y,
+                accounts.destination_y,
+                accounts.config,
+                y,
+                false,
+                &config_seeds,
+            )?;
+        }
+
+        // 储备快照不变：转出的只是金库中超出快照的部分
+        drop(config);
+        Config::load_mut(accounts.config)?.reset_fees();
+
+        Ok(())
+    }
+}
+
+pub struct CollectFeesAccounts<'a> {
+    pub authority: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub vault_x: &'a AccountInfo,
+    pub vault_y: &'a AccountInfo,
+    pub destination_x: &'a AccountInfo,
+    pub destination_y: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    /// require_checked_transfers 的池子必须在固定账户之后传入 mint_x / mint_y
+    pub mints: Option<PoolMints<'a>>,
+}
+
+impl<'a> AccountsValidator<'a> for CollectFeesAccounts<'a> {
+    fn validate(accounts: &'a [AccountInfo]) -> Result<Self, ProgramError> {
+        let [
+            authority,
+            config,
+            vault_x,
+            vault_y,
+            destination_x,
+            destination_y,
+            token_program,
+            _,
+            rest @ ..,
+        ] = accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // authority 是否为 owner 在 process 中检查（InvalidAuthority）
+        let config_data = Self::check_common(authority, token_program, config)?;
+        ProgramAccount::check_writable(config)?;
+        Self::check_data_accounts([config, vault_x, vault_y, destination_x, destination_y])?;
+        let (mints, _) = PoolMints::split(config_data.require_checked_transfers(), rest)?;
+
+        config_data.validate_distinct_accounts([destination_x, destination_y, vault_x, vault_y, config])?;
+
+        TokenAccountInterface::check_with_program(vault_x, token_program)?;
+        TokenAccountInterface::check_with_program(vault_y, token_program)?;
+        TokenAccountInterface::check_with_program(destination_x, token_program)?;
+        TokenAccountInterface::check_with_program(destination_y, token_program)?;
+
+        // 金库必须是 config 的 ATA，否则“金库余额 - 快照”没有意义
+        let bumps = config_data.vault_bumps();
+        if !is_pool_vault(vault_x, config.key(), config_data.mint_x(), token_program, bumps.0)
+            || !is_pool_vault(vault_y, config.key(), config_data.mint_y(), token_program, bumps.1)
+        {
+            return Err(AmmError::InvalidVault.into());
+        }
+        TokenAccountInterface::check_owner_and_mint(vault_x, config.key(), config_data.mint_x())?;
+        TokenAccountInterface::check_owner_and_mint(vault_y, config.key(), config_data.mint_y())?;
+
+        // 目标账户的 owner 由 authority 决定，这里只检查 mint
+        TokenAccountInterface::check_mint(destination_x, config_data.mint_x())?;
+        TokenAccountInterface::check_mint(destination_y, config_data.mint_y())?;
+
+        Ok(Self {
+            authority,
+            config,
+            vault_x,
+            vault_y,
+            destination_x,
+            destination_y,
+            token_program,
+            mints,
+        })
+    }
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for CollectFeesAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        Self::validate(accounts)
+    }
+}
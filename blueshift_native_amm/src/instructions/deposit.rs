@@ -1,22 +1,17 @@
 use super::helpers::*;
+use crate::curve::Curve;
+use crate::events::DepositEvent;
 use crate::errors::AmmError;
-use crate::state::{AmmState, Config};
+use crate::state::{AmmState, Config, GlobalConfig};
 use core::mem::size_of;
-use constant_product_curve::ConstantProduct;
 use pinocchio::{
     ProgramResult,
     account_info::AccountInfo,
-    instruction::Signer,
     program_error::ProgramError,
     pubkey::find_program_address,
-    sysvars::{Sysvar, clock::Clock},
-};
-use pinocchio_token::state::Mint;
-use pinocchio_token::{
-    instructions::{Transfer, MintTo},
-    state::TokenAccount,
 };
 
+/// 存入 x/y 换取 LP。存款不收手续费（只有 Swap 收取），数量严格按份额比例向上取整
 pub struct Deposit<'a> {
     pub accounts: DepositAccounts<'a>,
     pub instruction_data: DepositInstructionData,
@@ -41,104 +36,113 @@ impl<'a> Deposit<'a> {
     pub const DISCRIMINATOR: &'a u8 = &1;
 
     pub fn process(&mut self) -> ProgramResult {
-        let config = Config::load(&self.accounts.config)?; //or load_unchecked ?
+        let config = self.accounts.config;
+        with_pool_locked(config, || self.execute())
+    }
+
+    fn execute(&mut self) -> ProgramResult {
+        // config 的 owner / 长度已在 validate 的 check_common 中检查过，这里不再重复
+        // Safety: 下面写 config 账户之前不再使用 config
+        let config = unsafe { Config::load_unchecked(self.accounts.config) };
 
         let accounts = &self.accounts;
         let data = &self.instruction_data;
 
         //todo 这个检查多余吗？
         //check amm state
-        if config.state() != AmmState::Initialized as u8 {
-            return Err(AmmError::InvalidAmmState.into());
+        match config.amm_state()? {
+            AmmState::Initialized => {}
+            AmmState::Uninitialized | AmmState::Disabled | AmmState::WithdrawOnly => {
+                return Err(AmmError::InvalidAmmState.into());
+            }
         }
 
+        // 过期检查（允许全局配置中的 grace_secs 时钟误差）
+        let grace_secs = GlobalConfig::grace_secs_or_default(accounts.global_config)?;
+        data.expiration_kind.check(data.expiration, grace_secs)?;
+
         //todo 这个检查多余吗？
         //检查 vault_x 和 vault_y 的派生是否为关联代币账户（Associated Token Accounts）
-        let (vault_x, _) = find_program_address(
-            &[
-                self.accounts.config.key(),
-                self.accounts.token_program.key(),
-                config.mint_x(),
-            ],
-            &pinocchio_associated_token_account::ID.to_bytes(),
-        );
-        if vault_x.ne(self.accounts.vault_x.key()) {
-            return Err(ProgramError::InvalidAccountData);
-        }
-
-        //check vault_y
-        let (vault_y, _) = find_program_address(
-            &[
-                self.accounts.config.key(),
-                self.accounts.token_program.key(),
-                config.mint_y(),
-            ],
-            &pinocchio_associated_token_account::ID.to_bytes(),
-        );
-        if vault_y.ne(self.accounts.vault_y.key()) {
+        // 使用 Initialize 记录的金库 bump，不需要 find_program_address 搜索
+        let (config_key, token_program) = (self.accounts.config.key(), self.accounts.token_program);
+        let bumps = config.vault_bumps();
+        if !is_pool_vault(self.accounts.vault_x, config_key, config.mint_x(), token_program, bumps.0)
+            || !is_pool_vault(self.accounts.vault_y, config_key, config.mint_y(), token_program, bumps.1)
+        {
             return Err(ProgramError::InvalidAccountData);
         }
 
         //计算存款数量
         // Deserialize the token accounts
-        let mint_lp = unsafe { Mint::from_account_info_unchecked(self.accounts.mint_lp)? };
-        let vault_x = unsafe { TokenAccount::from_account_info_unchecked(self.accounts.vault_x)? };
-        let vault_y = unsafe { TokenAccount::from_account_info_unchecked(self.accounts.vault_y)? };
+        let mint_lp = MintInterface::get(self.accounts.mint_lp)?;
+        // 份额计算与精度无关（见 LP_DECIMALS），这里只确认 LP mint 的精度与 Initialize 记录的一致
+        if mint_lp.decimals() != config.lp_decimals() {
+            return Err(AmmError::InvalidLpMint.into());
+        }
+        // 份额按 config 中的储备快照计算，直接转入金库的代币不计入
+        let reserves = PoolReserves::load(config, self.accounts.vault_x, self.accounts.vault_y)?;
+        let (reserve_x, reserve_y) = (reserves.x, reserves.y);
 
+        // 报价后 LP supply 变化过大则拒绝
+        if let Some(expected) = &data.expected_lp_supply {
+            expected.check(mint_lp.supply())?;
+        }
 
         // Grab the amounts to deposit
-        let (x, y) = match mint_lp.supply() == 0 && vault_x.amount() == 0 && vault_y.amount() == 0 {
-            //如果是首次存款，我们可以跳过 LP 代币和存款的计算，直接采用用户建议的数值
-            true => (self.instruction_data.max_x, self.instruction_data.max_y),
+        let (x, y, lp) = match mint_lp.supply() == 0 && reserve_x == 0 && reserve_y == 0 {
+            //如果是首次存款，直接采用用户建议的数值，LP 由 sqrt(x*y) 决定（此时 amount 被忽略）
+            //max_x / max_y 决定了池子的初始价格，必须落在调用方给出的价格区间内
+            true => {
+                let band = data.price_band.as_ref().ok_or(AmmError::InitialPriceOutOfRange)?;
+                band.check(data.max_x, data.max_y, config.weight_x())?;
+                (data.max_x, data.max_y, first_deposit_lp(data.max_x, data.max_y)?)
+            }
+            //按份额比例计算（向上取整），存款不收手续费
+            //向上取整后实际存入的 x/y 可能比 amount 份额多一点，按实际存入量反算 LP（向下取整），
+            //铸造的 LP 与真实贡献一致，不会多于存入的价值
             false => {
-                let amounts = ConstantProduct::xy_deposit_amounts_from_l(
-                    vault_x.amount(),
-                    vault_y.amount(),
-                    mint_lp.supply(),
-                    self.instruction_data.amount,
-                    mint_lp.decimals() as u32,
-                )
-                .map_err(|_| ProgramError::InvalidArgument)?;
-
-                (amounts.x, amounts.y)
+                let curve = Curve::for_liquidity(reserve_x, reserve_y, mint_lp.supply())?;
+                let (x, y) = curve.deposit_amounts(data.amount)?;
+
+                (x, y, curve.lp_for_amounts(x, y)?)
             }
         };
 
         // Check for slippage
         if !(x <= self.instruction_data.max_x && y <= self.instruction_data.max_y) {
-            return Err(ProgramError::InvalidArgument);
+            return Err(AmmError::SlippageExceeded.into());
         }
-
-        //todo 首次的LP 数量 怎么计算得来的？
-        // 执行代币转移 (用户 -> 金库)
-        Transfer {
-            from: accounts.user_x_ata,
-            to: accounts.vault_x,
-            authority: accounts.user,
-            amount: x,
+        // LP 侧的滑点：首次存款的 LP 由 sqrt(x*y) 决定，与 amount 无关
+        if lp < data.min_lp_out {
+            return Err(AmmError::LpSlippageExceeded.into());
         }
-        .invoke()?;
 
-        Transfer {
-            from: accounts.user_y_ata,
-            to: accounts.vault_y,
-            authority: accounts.user,
-            amount: y,
-        }
-        .invoke()?;
+        // 大池子：铸造后 LP supply 不能溢出 u64（首次存款 supply 为 0，不会触发）
+        check_lp_mint_headroom(mint_lp.supply(), lp)?;
+
+        // anti-JIT / 退出费：记录存款后每份 LP 的流动性和存款时间，冷却期内取款按这个值结算
+        let tracks_position = config.tracks_positions();
+        let liquidity_per_lp = match tracks_position {
+            false => 0,
+            true => Curve::for_liquidity(
+                add_u64(reserve_x, x)?,
+                add_u64(reserve_y, y)?,
+                add_u64(mint_lp.supply(), lp)?,
+            )?
+            .with_weight_x(config.weight_x())
+            .liquidity_per_lp()?,
+        };
+        accounts.deposit_and_mint(config, x, y, lp)?;
 
-        //  签署并执行 MintTo (Config PDA -> 用户)
-        let config_seeds = config.config_seeds();
-        let signer = Signer::from(&config_seeds);
+        // 用存款前的储备更新 TWAP 累加器
+        // 之后会写 config 账户，不能再使用 config（见 Config::load_unchecked）
+        reserves.sync(accounts.config, accounts.vault_x, accounts.vault_y)?;
+        update_price_cumulative(accounts.config, reserve_x, reserve_y)?;
 
-        MintTo {
-            mint: accounts.mint_lp,
-            account: accounts.user_lp_ata,
-            mint_authority: accounts.config,
-            amount: data.amount,
+        if tracks_position {
+            let position = accounts.position.ok_or(ProgramError::NotEnoughAccountKeys)?;
+            PositionAccount::record(position, accounts.config, accounts.user, liquidity_per_lp)?;
         }
-        .invoke_signed(&[signer])?;
-
         Ok(())
     }
 }
@@ -153,12 +157,17 @@ pub struct DepositAccounts<'a> {
     pub user_lp_ata: &'a AccountInfo,
     pub config: &'a AccountInfo,
     pub token_program: &'a AccountInfo,
+    /// GlobalConfig PDA，InitializeGlobal 之前可以是空账户（见 `GlobalConfig::load_if_initialized`）
+    pub global_config: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    /// require_checked_transfers 的池子必须在固定账户之后传入 mint_x / mint_y
+    pub mints: Option<PoolMints<'a>>,
+    /// 可选的末尾账户：池子启用 anti-JIT 冷却期或退出费时必须传入用户的 LpPosition PDA
+    pub position: Option<&'a AccountInfo>,
 }
 
-impl<'a> TryFrom<&'a [AccountInfo]> for DepositAccounts<'a> {
-    type Error = ProgramError;
-
-    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+impl<'a> AccountsValidator<'a> for DepositAccounts<'a> {
+    fn validate(accounts: &'a [AccountInfo]) -> Result<Self, ProgramError> {
         let [
             user,
             mint_lp,
@@ -169,23 +178,29 @@ impl<'a> TryFrom<&'a [AccountInfo]> for DepositAccounts<'a> {
             user_lp_ata,
             config,
             token_program,
+            global_config,
+            system_program,
             _,
+            rest @ ..,
         ] = accounts
         else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
-        
-        //todo 这些检查多余吗？
-        SignerAccount::check(user)?;
-        TokenProgram::check(token_program)?;
-        let config_data = Config::load(config)?;
+
+        let config_data = Self::check_common(user, token_program, config)?;
+        Self::check_data_accounts([config, mint_lp, vault_x, vault_y, user_x_ata, user_y_ata, user_lp_ata])?;
+        let (mints, rest) = PoolMints::split(config_data.require_checked_transfers(), rest)?;
+
+        // 用户 ATA 与金库必须互不相同，否则会出现自转账；
+        // config 既是数据账户又是 LP 的 mint authority，也不能与 mint_lp/金库重合
+        config_data.validate_distinct_accounts([user_x_ata, user_y_ata, user_lp_ata, vault_x, vault_y, config, mint_lp])?;
 
         MintInterface::check_with_program(mint_lp, token_program)?;
 
         let (expected_mint_lp, _) =
             find_program_address(&[b"mint_lp", config.key().as_ref()], &crate::ID);
         if mint_lp.key() != &expected_mint_lp {
-            return Err(ProgramError::InvalidSeeds);
+            return Err(AmmError::InvalidLpMint.into());
         }
 
         //todo check
@@ -193,9 +208,12 @@ impl<'a> TryFrom<&'a [AccountInfo]> for DepositAccounts<'a> {
         TokenAccountInterface::check_with_program(vault_y, token_program)?;
         TokenAccountInterface::check_with_program(user_x_ata, token_program)?;
         TokenAccountInterface::check_with_program(user_y_ata, token_program)?;
+
+        // 首次存款的用户可能还没有 LP ATA，这里由 user 付费创建（末尾的账户为 Associated Token Program）
+        AssociatedTokenAccount::init_if_needed(user_lp_ata, mint_lp, user, user, system_program, token_program)?;
         TokenAccountInterface::check_with_program(user_lp_ata, token_program)?;
 
-        let vault_x_account = unsafe { TokenAccount::from_account_info_unchecked(vault_x)? };
+        let vault_x_account = TokenAccountInterface::get(vault_x)?;
         if vault_x_account.owner() != config.key() {
             return Err(ProgramError::InvalidAccountOwner);
         }
@@ -203,15 +221,17 @@ impl<'a> TryFrom<&'a [AccountInfo]> for DepositAccounts<'a> {
             return Err(ProgramError::InvalidAccountData);
         }
 
-        let vault_y_account = unsafe { TokenAccount::from_account_info_unchecked(vault_y)? };
+        let vault_y_account = TokenAccountInterface::get(vault_y)?;
         if vault_y_account.owner() != config.key() {
             return Err(ProgramError::InvalidAccountOwner);
         }
         if vault_y_account.mint() != config_data.mint_y() {
             return Err(ProgramError::InvalidAccountData);
         }
+        TokenAccountInterface::check_no_external_close_authority(vault_x, config.key())?;
+        TokenAccountInterface::check_no_external_close_authority(vault_y, config.key())?;
 
-        let user_x_account = unsafe { TokenAccount::from_account_info_unchecked(user_x_ata)? };
+        let user_x_account = TokenAccountInterface::get(user_x_ata)?;
         if user_x_account.owner() != user.key() {
             return Err(ProgramError::InvalidAccountOwner);
         }
@@ -219,15 +239,17 @@ impl<'a> TryFrom<&'a [AccountInfo]> for DepositAccounts<'a> {
             return Err(ProgramError::InvalidAccountData);
         }
 
-        let user_y_account = unsafe { TokenAccount::from_account_info_unchecked(user_y_ata)? };
+        let user_y_account = TokenAccountInterface::get(user_y_ata)?;
         if user_y_account.owner() != user.key() {
             return Err(ProgramError::InvalidAccountOwner);
         }
         if user_y_account.mint() != config_data.mint_y() {
             return Err(ProgramError::InvalidAccountData);
         }
+        TokenAccountInterface::check_not_frozen(user_x_ata)?;
+        TokenAccountInterface::check_not_frozen(user_y_ata)?;
 
-        let user_lp_account = unsafe { TokenAccount::from_account_info_unchecked(user_lp_ata)? };
+        let user_lp_account = TokenAccountInterface::get(user_lp_ata)?;
         if user_lp_account.owner() != user.key() {
             return Err(ProgramError::InvalidAccountOwner);
         }
@@ -245,53 +267,126 @@ impl<'a> TryFrom<&'a [AccountInfo]> for DepositAccounts<'a> {
             user_lp_ata,
             config,
             token_program,
+            global_config,
+            system_program,
+            mints,
+            position: rest.first(),
         })
     }
 }
 
+impl<'a> TryFrom<&'a [AccountInfo]> for DepositAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        Self::validate(accounts)
+    }
+}
+
+impl<'a> DepositAccounts<'a> {
+    /// 存款的公共执行路径：用户 -> 金库转入 x/y，再由 config PDA 签名给用户铸造 lp
+    ///
+    /// Deposit 与 InitializeAndSeed 都走这里，保证两条路径的转账/铸造逻辑一致
+    pub fn deposit_and_mint(&self, config: &Config, x: u64, y: u64, lp: u64) -> ProgramResult {
+        // 执行代币转移 (用户 -> 金库)
+        let mints = self.mints.as_ref();
+        transfer_pool_tokens(config, mints, self.token_program, self.user_x_ata, self.vault_x, self.user, x, true, &[])?;
+        transfer_pool_tokens(config, mints, self.token_program, self.user_y_ata, self.vault_y, self.user, y, false, &[])?;
+
+        //  签署并执行 MintTo (Config PDA -> 用户)
+        let config_seeds = config.config_seeds();
+        mint_tokens_signed(
+            self.token_program,
+            self.mint_lp,
+            self.user_lp_ata,
+            self.config,
+            lp,
+            &config_seeds,
+        )?;
+
+        DepositEvent {
+            pool: self.config.key(),
+            user: self.user.key(),
+            mint_x: config.mint_x(),
+            mint_y: config.mint_y(),
+            lp,
+            x,
+            y,
+        }
+        .emit();
+
+        Ok(())
+    }
+}
+
 pub struct DepositInstructionData {
     pub amount: u64,
     pub max_x: u64,
     pub max_y: u64,
+    /// 实际铸造的 LP 不能低于这个值（LP 侧的滑点保护），0 表示不检查
+    pub min_lp_out: u64,
     pub expiration: i64,
+    /// 可选：expiration 是时间戳还是 slot，缺省为时间戳
+    pub expiration_kind: ExpirationKind,
+    /// 可选：报价时的 LP supply 及容忍度，见 `ExpectedLpSupply`
+    pub expected_lp_supply: Option<ExpectedLpSupply>,
+    /// 首次存款必须给出的价格区间，见 `PriceBand`
+    pub price_band: Option<PriceBand>,
 }
 
 impl<'a> TryFrom<&'a [u8]> for DepositInstructionData {
     type Error = ProgramError;
 
     fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
-        const DEPOSIT_DATA_LEN: usize = size_of::<DepositInstructionData>();
+        // amount + max_x + max_y + min_lp_out + expiration，之后可选地附加 ExpectedLpSupply、PriceBand，
+        // 最后可选地附加 expiration_kind
+        const DEPOSIT_DATA_LEN: usize = size_of::<u64>() * 4 + size_of::<i64>();
+        const BOTH_LEN: usize = ExpectedLpSupply::LEN + PriceBand::LEN;
 
         //len check
-        if data.len() != DEPOSIT_DATA_LEN {
+        if data.len() < DEPOSIT_DATA_LEN {
             return Err(ProgramError::InvalidInstructionData);
         }
+        let (rest, expiration_kind) = ExpirationKind::split_suffix(
+            &data[DEPOSIT_DATA_LEN..],
+            &[1, ExpectedLpSupply::LEN + 1, PriceBand::LEN + 1, BOTH_LEN + 1],
+        )?;
+        // 两个可选字段的长度不同，按剩余长度区分
+        let (expected_lp_supply, price_band) = match rest.len() {
+            PriceBand::LEN => (None, Some(PriceBand::parse(rest)?)),
+            BOTH_LEN => {
+                let (expected, band) = rest.split_at(ExpectedLpSupply::LEN);
+                (ExpectedLpSupply::parse(expected)?, Some(PriceBand::parse(band)?))
+            }
+            _ => (ExpectedLpSupply::parse(rest)?, None),
+        };
 
         let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
         let max_x = u64::from_le_bytes(data[8..16].try_into().unwrap());
         let max_y = u64::from_le_bytes(data[16..24].try_into().unwrap());
-        let expiration = i64::from_le_bytes(data[24..32].try_into().unwrap());
+        let min_lp_out = u64::from_le_bytes(data[24..32].try_into().unwrap());
+        let expiration = i64::from_le_bytes(data[32..40].try_into().unwrap());
 
-        //确保任何数量，例如 amount、max_y 和 max_x 都大于零，并且订单尚未过期，可以使用 Clock sysvar 进行检查。
+        //确保任何数量，例如 amount、max_y 和 max_x 都大于零。过期检查需要 global config 中的 grace_secs，在 process 中进行
         if amount == 0 {
-            return Err(ProgramError::InvalidInstructionData);
+            return Err(AmmError::ZeroAmount.into());
         }
         if max_x == 0 {
-            return Err(ProgramError::InvalidInstructionData);
+            return Err(AmmError::ZeroAmount.into());
         }
         if max_y == 0 {
-            return Err(ProgramError::InvalidInstructionData);
-        }
-        if expiration <= Clock::get()?.unix_timestamp {
-            //todo 更有意思的error code
-            return Err(ProgramError::InvalidInstructionData);
+            return Err(AmmError::ZeroAmount.into());
         }
 
         Ok(Self {
             amount,
             max_x,
             max_y,
+            min_lp_out,
             expiration,
+            expiration_kind,
+            expected_lp_supply,
+            price_band,
         })
     }
 }
@@ -0,0 +1,311 @@
+use super::helpers::*;
+use super::withdraw_single_sided::MAX_SINGLE_SIDED_IMPACT_BPS;
+use crate::curve::Curve;
+use crate::errors::AmmError;
+use crate::events::DepositEvent;
+use crate::state::{AmmState, Config, GlobalConfig};
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::find_program_address,
+};
+
+/// 只存入 X 或 Y 其中一种换取 LP
+///
+/// 等价于先把输入的一部分在池子里兑换成另一侧（收取 swap 手续费），再按比例存款。
+/// 兑换出的代币从未离开金库，因此只有输入代币一笔转账。
+pub struct DepositSingleSided<'a> {
+    pub accounts: DepositSingleSidedAccounts<'a>,
+    pub instruction_data: DepositSingleSidedInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for DepositSingleSided<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = DepositSingleSidedAccounts::try_from(accounts)?;
+        let instruction_data = DepositSingleSidedInstructionData::try_from(data)?;
+
+        Ok(Self { accounts, instruction_data })
+    }
+}
+
+impl<'a> DepositSingleSided<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &15;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let config = self.accounts.config;
+        with_pool_locked(config, || self.execute())
+    }
+
+    fn execute(&mut self) -> ProgramResult {
+        let config = Config::load_checked(self.accounts.config)?;
+
+        let accounts = &self.accounts;
+        let data = &self.instruction_data;
+
+        // 内部包含一次兑换，只有可以 swap 的池子才允许
+        match config.amm_state()? {
+            AmmState::Initialized => {}
+            AmmState::Uninitialized | AmmState::Disabled | AmmState::WithdrawOnly => {
+                return Err(AmmError::InvalidAmmState.into());
+            }
+        }
+        // 兑换比例按 x * y = k 求解，加权池子请使用按比例存款
+        if !config.is_equal_weight() {
+            return Err(AmmError::WeightedPoolUnsupported.into());
+        }
+
+        // 过期检查（允许全局配置中的 grace_secs 时钟误差）
+        let grace_secs = GlobalConfig::grace_secs_or_default(accounts.global_config)?;
+        data.expiration_kind.check(data.expiration, grace_secs)?;
+
+        let mint_in = match data.from_x {
+            true => config.mint_x(),
+            false => config.mint_y(),
+        };
+        TokenAccountInterface::check_owner_and_mint(accounts.user_in_ata, accounts.user.key(), mint_in)?;
+
+        let mint_lp = MintInterface::get(accounts.mint_lp)?;
+        if mint_lp.decimals() != config.lp_decimals() {
+            return Err(AmmError::InvalidLpMint.into());
+        }
+        // 与 Deposit 相同，按储备快照计算
+        let reserves = PoolReserves::load(&config, accounts.vault_x, accounts.vault_y)?;
+        let (reserve_x, reserve_y) = (reserves.x, reserves.y);
+
+        // 单边存款无法确定初始价格，首次存款必须走 Deposit
+        if mint_lp.supply() == 0 || reserve_x == 0 || reserve_y == 0 {
+            return Err(AmmError::InvalidAmmState.into());
+        }
+
+        let curve = Curve::for_liquidity(reserve_x, reserve_y, mint_lp.supply())?;
+        let (lp, swap_out, impact) = curve.single_sided_deposit(data.amount, data.from_x, config.fee())?;
+        if lp == 0 {
+            return Err(AmmError::ZeroAmount.into());
+        }
+
+        if impact > MAX_SINGLE_SIDED_IMPACT_BPS {
+            return Err(AmmError::PriceImpactTooHigh.into());
+        }
+        // 内部兑换的输出同样受熔断限制
+        config.check_swap_out(swap_out)?;
+        // 滑点检查
+        if lp < data.min_lp_out {
+            return Err(AmmError::LpSlippageExceeded.into());
+        }
+        check_lp_mint_headroom(mint_lp.supply(), lp)?;
+
+        // anti-JIT / 退出费：与 Deposit 相同，记录存款后每份 LP 的流动性
+        let tracks_position = config.tracks_positions();
+        let liquidity_per_lp = match tracks_position {
+            false => 0,
+            true => {
+                let (x, y) = match data.from_x {
+                    true => (reserve_x.checked_add(data.amount).ok_or(ProgramError::ArithmeticOverflow)?, reserve_y),
+                    false => (reserve_x, reserve_y.checked_add(data.amount).ok_or(ProgramError::ArithmeticOverflow)?),
+                };
+                Curve::for_liquidity(x, y, mint_lp.supply().checked_add(lp).ok_or(ProgramError::ArithmeticOverflow)?)?
+                    .liquidity_per_lp()?
+            }
+        };
+
+        let vault_in = match data.from_x {
+            true => accounts.vault_x,
+            false => accounts.vault_y,
+        };
+        transfer_pool_tokens(
+            &config,
+            accounts.mints.as_ref(),
+            accounts.token_program,
+            accounts.user_in_ata,
+            vault_in,
+            accounts.user,
+            data.amount,
+            data.from_x,
+            &[],
+        )?;
+        let config_seeds = config.config_seeds();
+        mint_tokens_signed(
+            accounts.token_program,
+            accounts.mint_lp,
+            accounts.user_lp_ata,
+            accounts.config,
+            lp,
+            &config_seeds,
+        )?;
+
+        // 事件在 config 释放之后发出，先复制两侧的 mint
+        let (mint_x, mint_y) = (*config.mint_x(), *config.mint_y());
+        drop(config);
+        reserves.sync(accounts.config, accounts.vault_x, accounts.vault_y)?;
+        update_price_cumulative(accounts.config, reserve_x, reserve_y)?;
+
+        if tracks_position {
+            let position = accounts.position.ok_or(ProgramError::NotEnoughAccountKeys)?;
+            PositionAccount::record(position, accounts.config, accounts.user, liquidity_per_lp)?;
+        }
+
+        let (x, y) = match data.from_x {
+            true => (data.amount, 0),
+            false => (0, data.amount),
+        };
+        DepositEvent {
+            pool: accounts.config.key(),
+            user: accounts.user.key(),
+            mint_x: &mint_x,
+            mint_y: &mint_y,
+            lp,
+            x,
+            y,
+        }
+        .emit();
+
+        Ok(())
+    }
+}
+
+pub struct DepositSingleSidedAccounts<'a> {
+    pub user: &'a AccountInfo,
+    pub mint_lp: &'a AccountInfo,
+    pub vault_x: &'a AccountInfo,
+    pub vault_y: &'a AccountInfo,
+    /// 用户支付输入代币（X 或 Y）的 ATA
+    pub user_in_ata: &'a AccountInfo,
+    pub user_lp_ata: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    /// GlobalConfig PDA，InitializeGlobal 之前可以是空账户（见 `GlobalConfig::load_if_initialized`）
+    pub global_config: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    /// require_checked_transfers 的池子必须在固定账户之后传入 mint_x / mint_y
+    pub mints: Option<PoolMints<'a>>,
+    /// 可选的末尾账户：池子启用 anti-JIT 冷却期或退出费时必须传入用户的 LpPosition PDA
+    pub position: Option<&'a AccountInfo>,
+}
+
+impl<'a> AccountsValidator<'a> for DepositSingleSidedAccounts<'a> {
+    fn validate(accounts: &'a [AccountInfo]) -> Result<Self, ProgramError> {
+        let [
+            user,
+            mint_lp,
+            vault_x,
+            vault_y,
+            user_in_ata,
+            user_lp_ata,
+            config,
+            token_program,
+            global_config,
+            system_program,
+            _,
+            rest @ ..,
+        ] = accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let config_data = Self::check_common(user, token_program, config)?;
+        Self::check_data_accounts([config, mint_lp, vault_x, vault_y, user_in_ata, user_lp_ata])?;
+        let (mints, rest) = PoolMints::split(config_data.require_checked_transfers(), rest)?;
+
+        config_data.validate_distinct_accounts([user_in_ata, user_lp_ata, vault_x, vault_y, config, mint_lp])?;
+
+        MintInterface::check_with_program(mint_lp, token_program)?;
+        let (expected_mint_lp, _) =
+            find_program_address(&[b"mint_lp", config.key().as_ref()], &crate::ID);
+        if mint_lp.key() != &expected_mint_lp {
+            return Err(AmmError::InvalidLpMint.into());
+        }
+
+        TokenAccountInterface::check_with_program(vault_x, token_program)?;
+        TokenAccountInterface::check_with_program(vault_y, token_program)?;
+        TokenAccountInterface::check_with_program(user_in_ata, token_program)?;
+
+        // 金库必须是 config 的 ATA，份额按它们的余额计算
+        let bumps = config_data.vault_bumps();
+        if !is_pool_vault(vault_x, config.key(), config_data.mint_x(), token_program, bumps.0)
+            || !is_pool_vault(vault_y, config.key(), config_data.mint_y(), token_program, bumps.1)
+        {
+            return Err(AmmError::InvalidVault.into());
+        }
+        TokenAccountInterface::check_owner_and_mint(vault_x, config.key(), config_data.mint_x())?;
+        TokenAccountInterface::check_owner_and_mint(vault_y, config.key(), config_data.mint_y())?;
+        TokenAccountInterface::check_no_external_close_authority(vault_x, config.key())?;
+        TokenAccountInterface::check_no_external_close_authority(vault_y, config.key())?;
+
+        // 用户可能还没有 LP ATA，这里由 user 付费创建（末尾的账户为 Associated Token Program）
+        AssociatedTokenAccount::init_if_needed(user_lp_ata, mint_lp, user, user, system_program, token_program)?;
+        TokenAccountInterface::check_with_program(user_lp_ata, token_program)?;
+        TokenAccountInterface::check_owner_and_mint(user_lp_ata, user.key(), mint_lp.key())?;
+
+        Ok(Self {
+            user,
+            mint_lp,
+            vault_x,
+            vault_y,
+            user_in_ata,
+            user_lp_ata,
+            config,
+            token_program,
+            global_config,
+            system_program,
+            mints,
+            position: rest.first(),
+        })
+    }
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for DepositSingleSidedAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        Self::validate(accounts)
+    }
+}
+
+pub struct DepositSingleSidedInstructionData {
+    /// true 时存入 X（max_x），否则存入 Y（max_y）
+    pub from_x: bool,
+    pub amount: u64,
+    /// 实际铸造的 LP 不能低于这个值
+    pub min_lp_out: u64,
+    pub expiration: i64,
+    /// 可选：expiration 是时间戳还是 slot，缺省为时间戳
+    pub expiration_kind: ExpirationKind,
+}
+
+impl<'a> TryFrom<&'a [u8]> for DepositSingleSidedInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        // from_x (1) + amount (8) + min_lp_out (8) + expiration (8)，末尾可选地附加 1 字节 expiration_kind
+        const DEPOSIT_SINGLE_SIDED_DATA_LEN: usize = 25;
+
+        if data.len() != DEPOSIT_SINGLE_SIDED_DATA_LEN && data.len() != DEPOSIT_SINGLE_SIDED_DATA_LEN + 1 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (_, expiration_kind) = ExpirationKind::split_suffix(&data[DEPOSIT_SINGLE_SIDED_DATA_LEN..], &[1])?;
+
+        let from_x = match data[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+        let amount = u64::from_le_bytes(data[1..9].try_into().unwrap());
+        let min_lp_out = u64::from_le_bytes(data[9..17].try_into().unwrap());
+        let expiration = i64::from_le_bytes(data[17..25].try_into().unwrap());
+
+        if amount == 0 {
+            return Err(AmmError::ZeroAmount.into());
+        }
+
+        Ok(Self {
+            from_x,
+            amount,
+            min_lp_out,
+            expiration,
+            expiration_kind,
+        })
+    }
+}
@@ -0,0 +1,85 @@
+use crate::errors::AmmError;
+use crate::state::{AmmState, Config, ConfigRole};
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::find_program_address,
+};
+
+use super::helpers::*;
+
+/// 下线池子：把状态切到 Disabled，由池子的 owner（authority）签名
+///
+/// 与 SetState 的暂停不同，这里要求 LP 已经全部赎回（`mint_lp.supply() == 0`），否则返回 `PoolNotEmpty`。
+/// 池子必须先用 SetState 切到 WithdrawOnly，等 LP 取完后再调用本指令；Disabled 是终态
+///
+/// 账户：`[authority, config, mint_lp]`；没有指令数据
+pub struct DisablePool<'a> {
+    pub accounts: DisablePoolAccounts<'a>,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for DisablePool<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        if !data.is_empty() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let accounts = DisablePoolAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> DisablePool<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &20;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let accounts = &self.accounts;
+        let mut config = Config::load_mut(accounts.config)?;
+        config.check_authority(accounts.authority, ConfigRole::Owner)?;
+
+        if config.amm_state()? == AmmState::Uninitialized {
+            return Err(AmmError::InvalidAmmState.into());
+        }
+
+        // supply 只有来自池子自己的 LP mint 才有意义
+        let (expected_mint_lp, _) =
+            find_program_address(&[b"mint_lp", accounts.config.key().as_ref()], &crate::ID);
+        if accounts.mint_lp.key() != &expected_mint_lp {
+            return Err(AmmError::InvalidLpMint.into());
+        }
+        if MintInterface::get(accounts.mint_lp)?.supply() != 0 {
+            return Err(AmmError::PoolNotEmpty.into());
+        }
+
+        config.set_state(AmmState::Disabled)
+    }
+}
+
+pub struct DisablePoolAccounts<'a> {
+    pub authority: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub mint_lp: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for DisablePoolAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [authority, config, mint_lp, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SignerAccount::check(authority)?;
+        ProgramAccount::check_writable(config)?;
+        MintInterface::check(mint_lp)?;
+
+        Ok(Self {
+            authority,
+            config,
+            mint_lp,
+        })
+    }
+}
@@ -0,0 +1,222 @@
+use super::helpers::*;
+use crate::errors::AmmError;
+use crate::state::{AmmState, Config};
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program_error::ProgramError,
+};
+
+/// 闪电兑换：先把金库中的 `amount` 借给用户，调用用户指定的回调程序，回调返回后金库必须收回 amount + fee
+///
+/// fee 按池子的 swap 费率对 amount 向上取整收取，留在金库中归 LP。
+///
+/// 账户布局：
+/// `[user, vault_x, vault_y, user_out_ata, config, token_program, _, (mint_x, mint_y), callback_program, callback_accounts..]`
+/// - require_checked_transfers 的池子在 `_` 之后传入 mint_x / mint_y
+/// - callback_program 之后的所有账户原样转发给回调（保留 writable / signer 标记），数量不超过 MAX_DOWNSTREAM_ACCOUNTS
+///
+/// 重入：回调不附带任何 PDA 签名，只能使用用户本人的签名把代币还回金库。
+/// 运行时不允许 A -> B -> A 形式的重入，回调无法在借出期间调用本程序的 Swap / Deposit / Withdraw；
+/// 唯一允许的直接自调用（回调程序就是本程序）在这里被拒绝；整条指令执行期间池子还被锁定（见 `with_pool_locked`）。
+pub struct FlashSwap<'a> {
+    pub accounts: FlashSwapAccounts<'a>,
+    pub instruction_data: FlashSwapInstructionData<'a>,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for FlashSwap<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = FlashSwapAccounts::try_from(accounts)?;
+        let instruction_data = FlashSwapInstructionData::try_from(data)?;
+
+        Ok(Self { accounts, instruction_data })
+    }
+}
+
+impl<'a> FlashSwap<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &16;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let config = self.accounts.config;
+        with_pool_locked(config, || self.execute())
+    }
+
+    fn execute(&mut self) -> ProgramResult {
+        let accounts = &self.accounts;
+        let data = &self.instruction_data;
+
+        let config = Config::load_checked(accounts.config)?;
+        // 借出期间池子价格不可信，只有可以 swap 的池子才允许
+        match config.amm_state()? {
+            AmmState::Initialized => {}
+            AmmState::Uninitialized | AmmState::Disabled | AmmState::WithdrawOnly => {
+                return Err(AmmError::InvalidAmmState.into());
+            }
+        }
+
+        let vault = match data.is_x {
+            true => accounts.vault_x,
+            false => accounts.vault_y,
+        };
+        // 只能借出储备快照内的数量，直接转入金库的代币不能被借出
+        let reserves = PoolReserves::load(&config, accounts.vault_x, accounts.vault_y)?;
+        let (reserve_x, reserve_y) = (reserves.x, reserves.y);
+        let reserve = match data.is_x {
+            true => reserve_x,
+            false => reserve_y,
+        };
+        if data.amount > reserve {
+            return Err(ProgramError::InsufficientFunds);
+        }
+        // 借出的数量同样受熔断限制
+        config.check_swap_out(data.amount)?;
+        let fee = mul_div_ceil(data.amount, config.fee() as u64, FEE_BPS_DENOMINATOR)?;
+
+        let config_seeds = config.config_seeds();
+        transfer_pool_tokens(
+            &config,
+            accounts.mints.as_ref(),
+            accounts.token_program,
+            vault,
+            accounts.user_out_ata,
+            accounts.config,
+            data.amount,
+            data.is_x,
+            &config_seeds,
+        )?;
+        // 回调期间不持有 config 的借用
+        drop(config);
+
+        // 借出后的余额，回调返回后与它比较
+        let lent = TokenAccountInterface::get(vault)?.amount();
+        invoke_downstream(
+            accounts.callback_program,
+            accounts.callback_accounts,
+            data.callback_data,
+        )?;
+
+        // 按金库实际余额判断是否还款：Token-2022 转账费等由回调方承担
+        let repaid = TokenAccountInterface::get(vault)?.amount().saturating_sub(lent);
+        let required = data.amount.checked_add(fee).ok_or(ProgramError::ArithmeticOverflow)?;
+        if repaid < required {
+            return Err(AmmError::FlashSwapNotRepaid.into());
+        }
+
+        // 还款（含手续费）计入储备快照
+        reserves.sync(accounts.config, accounts.vault_x, accounts.vault_y)?;
+        // 用借出前的储备更新 TWAP 累加器
+        update_price_cumulative(accounts.config, reserve_x, reserve_y)?;
+
+        Ok(())
+    }
+}
+
+pub struct FlashSwapAccounts<'a> {
+    pub user: &'a AccountInfo,
+    pub vault_x: &'a AccountInfo,
+    pub vault_y: &'a AccountInfo,
+    /// 接收借出代币的账户（通常是用户的 ATA，也可以是回调程序使用的中转账户）
+    pub user_out_ata: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    /// require_checked_transfers 的池子必须在固定账户之后传入 mint_x / mint_y
+    pub mints: Option<PoolMints<'a>>,
+    pub callback_program: &'a AccountInfo,
+    /// 回调程序之后的所有账户，原样转发给回调程序
+    pub callback_accounts: &'a [AccountInfo],
+}
+
+impl<'a> AccountsValidator<'a> for FlashSwapAccounts<'a> {
+    fn validate(accounts: &'a [AccountInfo]) -> Result<Self, ProgramError> {
+        let [user, vault_x, vault_y, user_out_ata, config, token_program, _, rest @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let config_data = Self::check_common(user, token_program, config)?;
+        Self::check_data_accounts([config, vault_x, vault_y, user_out_ata])?;
+        let (mints, rest) = PoolMints::split(config_data.require_checked_transfers(), rest)?;
+        let [callback_program, callback_accounts @ ..] = rest else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        config_data.validate_distinct_accounts([user_out_ata, vault_x, vault_y, config])?;
+
+        // 金库必须是 config 的 ATA：还款按它们的余额判断
+        let bumps = config_data.vault_bumps();
+        if !is_pool_vault(vault_x, config.key(), config_data.mint_x(), token_program, bumps.0)
+            || !is_pool_vault(vault_y, config.key(), config_data.mint_y(), token_program, bumps.1)
+        {
+            return Err(AmmError::InvalidVault.into());
+        }
+        TokenAccountInterface::check_with_program(vault_x, token_program)?;
+        TokenAccountInterface::check_with_program(vault_y, token_program)?;
+        TokenAccountInterface::check_owner_and_mint(vault_x, config.key(), config_data.mint_x())?;
+        TokenAccountInterface::check_owner_and_mint(vault_y, config.key(), config_data.mint_y())?;
+        TokenAccountInterface::check_no_external_close_authority(vault_x, config.key())?;
+        TokenAccountInterface::check_no_external_close_authority(vault_y, config.key())?;
+
+        // 回调不能是本程序：直接自调用是运行时唯一允许的重入方式
+        if callback_program.key() == &crate::ID {
+            return Err(AmmError::DownstreamProgramNotAllowed.into());
+        }
+
+        Ok(Self {
+            user,
+            vault_x,
+            vault_y,
+            user_out_ata,
+            config,
+            token_program,
+            mints,
+            callback_program,
+            callback_accounts,
+        })
+    }
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for FlashSwapAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        Self::validate(accounts)
+    }
+}
+
+pub struct FlashSwapInstructionData<'a> {
+    /// true 时借出 X，否则借出 Y
+    pub is_x: bool,
+    pub amount: u64,
+    /// 原样转发给回调程序的指令数据
+    pub callback_data: &'a [u8],
+}
+
+impl<'a> TryFrom<&'a [u8]> for FlashSwapInstructionData<'a> {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        // is_x (1) + amount (8)，之后是转发给回调程序的数据
+        const FLASH_SWAP_DATA_LEN: usize = 9;
+
+        if data.len() < FLASH_SWAP_DATA_LEN || data.len() > FLASH_SWAP_DATA_LEN + MAX_DOWNSTREAM_DATA_LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let is_x = match data[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+        let amount = u64::from_le_bytes(data[1..9].try_into().unwrap());
+        if amount == 0 {
+            return Err(AmmError::ZeroAmount.into());
+        }
+
+        Ok(Self {
+            is_x,
+            amount,
+            callback_data: &data[FLASH_SWAP_DATA_LEN..],
+        })
+    }
+}
@@ -1,13 +1,19 @@
 use pinocchio::{
-    account_info::AccountInfo,
+    account_info::{AccountInfo, Ref},
+    instruction::{AccountMeta, Instruction, Seed, Signer},
+    program::{invoke, invoke_signed},
     program_error::ProgramError,
     pubkey::Pubkey,
     ProgramResult,
-    sysvars::{rent::Rent, Sysvar},
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
 };
-use pinocchio_system::instructions::CreateAccount;
+use pinocchio_system::instructions::{Allocate, Assign, CreateAccount};
 use pinocchio_token::state::{Mint, TokenAccount};
-use pinocchio_token::instructions::InitializeMint2;
+use crate::errors::AmmError;
+use crate::state::{
+    Config, EXIT_FEE_DISABLED, JIT_COOLDOWN_DISABLED, LpPosition, MINIMUM_LIQUIDITY, PRICE_FRACTION_BITS,
+    position_seeds_from_parts, weighted_price,
+};
 
 // ============================================================================
 // Program ID 常量
@@ -31,6 +37,31 @@ pub const SPL_TOKEN_2022_ID: Pubkey = [
     0x3a, 0x8c, 0xf5, 0x85, 0x7e, 0xff, 0x00, 0xa9,
 ];
 
+// Wrapped SOL 的 Mint（SPL Token）
+// Pubkey: So11111111111111111111111111111111111111112
+pub const NATIVE_MINT: Pubkey = [
+    0x06, 0x9b, 0x88, 0x57, 0xfe, 0xab, 0x81, 0x84,
+    0xfb, 0x68, 0x7f, 0x63, 0x46, 0x18, 0xc0, 0x35,
+    0xda, 0xc4, 0x39, 0xdc, 0x1a, 0xeb, 0x3b, 0x55,
+    0x98, 0xa0, 0xf0, 0x00, 0x00, 0x00, 0x00, 0x01,
+];
+
+// BPF Upgradeable Loader Program ID
+// Pubkey: BPFLoaderUpgradeab1e11111111111111111111111
+pub const BPF_LOADER_UPGRADEABLE_ID: Pubkey = [
+    0x02, 0xa8, 0xf6, 0x91, 0x4e, 0x88, 0xa1, 0xb0,
+    0xe2, 0x10, 0x15, 0x3e, 0xf7, 0x63, 0xae, 0x2b,
+    0x00, 0xc2, 0xb9, 0x3d, 0x16, 0xc1, 0x24, 0xd2,
+    0xc0, 0x53, 0x7a, 0x10, 0x04, 0x80, 0x00, 0x00,
+];
+
+/// 运行时允许单条指令内账户数据增长的上限（10 KiB）
+pub const MAX_PERMITTED_DATA_INCREASE: usize = 10 * 1024;
+
+/// 带 NonTransferable 扩展的 Token-2022 Mint 大小：
+/// Mint 填充到 Account::LEN (165) + account type (1) + 扩展 TLV 头 (type 2 + length 2，无数据)
+pub const NON_TRANSFERABLE_MINT_LEN: usize = 165 + 1 + 4;
+
 // Memo Program ID
 // Pubkey: MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr
 pub const MEMO_PROGRAM_ID: Pubkey = [
@@ -95,6 +126,42 @@ impl SystemAccount {
     }
 }
 
+// ============================================================================
+// ProgramData 账户检查
+// ============================================================================
+
+/// 辅助结构体用于本程序的 ProgramData 账户（BPF Upgradeable Loader）检查
+pub struct ProgramDataAccount;
+
+impl ProgramDataAccount {
+    /// UpgradeableLoaderState::ProgramData 的 bincode 布局：
+    /// tag (u32 = 3) + slot (u64) + Option<Pubkey>（1 字节标记 + 32 字节）
+    const TAG: [u8; 4] = 3u32.to_le_bytes();
+    const AUTHORITY_OFFSET: usize = 4 + 8;
+    const HEADER_LEN: usize = Self::AUTHORITY_OFFSET + 1 + 32;
+
+    /// 检查账户是本程序的 ProgramData（PDA：seeds = [program_id]），且 upgrade authority 为 `authority`
+    pub fn check_upgrade_authority(program_data: &AccountInfo, authority: &Pubkey) -> ProgramResult {
+        let (expected, _) = pinocchio::pubkey::find_program_address(&[crate::ID.as_ref()], &BPF_LOADER_UPGRADEABLE_ID);
+        if program_data.key() != &expected {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        if !program_data.is_owned_by(&BPF_LOADER_UPGRADEABLE_ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        let data = program_data.try_borrow_data()?;
+        if data.len() < Self::HEADER_LEN || data[..4] != Self::TAG {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        // 不可升级的程序（authority 为 None）没有人可以执行
+        if data[Self::AUTHORITY_OFFSET] != 1 || &data[Self::AUTHORITY_OFFSET + 1..Self::HEADER_LEN] != authority {
+            return Err(AmmError::InvalidAuthority.into());
+        }
+        Ok(())
+    }
+}
+
 // ============================================================================
 // Program Account 操作
 // ============================================================================
@@ -107,6 +174,9 @@ impl ProgramAccount {
     pub const CLOSED_ACCOUNT_DISCRIMINATOR: u8 = 255;
 
     /// 初始化一个 PDA 账户
+    ///
+    /// PDA 地址可以提前算出，任何人都能先向它转入 lamports，此时 CreateAccount 会失败；
+    /// 这种情况下由 payer 补足租金，再用 PDA 签名 Allocate + Assign
     pub fn init<T>(
         payer: &AccountInfo,
         account: &AccountInfo,
@@ -115,17 +185,41 @@ impl ProgramAccount {
     ) -> ProgramResult {
         let rent = Rent::get()?;
         let lamports = rent.minimum_balance(space);
+        let signers = [pinocchio::instruction::Signer::from(seeds)];
 
-        CreateAccount {
-            from: payer,
-            to: account,
-            lamports,
+        let current_lamports = account.lamports();
+        if current_lamports == 0 {
+            return CreateAccount {
+                from: payer,
+                to: account,
+                lamports,
+                space: space as u64,
+                owner: &crate::ID,
+            }
+            .invoke_signed(&signers);
+        }
+
+        if lamports > current_lamports {
+            transfer_sol(payer, account, lamports - current_lamports)?;
+        }
+        Allocate {
+            account,
             space: space as u64,
+        }
+        .invoke_signed(&signers)?;
+        Assign {
+            account,
             owner: &crate::ID,
         }
-        .invoke_signed(&[pinocchio::instruction::Signer::from(seeds)])?;
+        .invoke_signed(&signers)
+    }
 
-        Ok(())
+    /// 账户是否已由本程序创建为 `len` 字节的数据账户
+    ///
+    /// 不能用 lamports 判断：任何人都可以向尚未创建的 PDA 转账
+    #[inline(always)]
+    pub fn is_created(account: &AccountInfo, len: usize) -> bool {
+        account.owner() == &crate::ID && account.data_len() == len
     }
 
     /// 初始化一个 PDA 账户，使用指定的 owner
@@ -239,31 +333,72 @@ impl ProgramAccount {
     }
 
     /// 重新分配账户空间
+    ///
+    /// 先 resize 再调整租金：resize 失败（例如超过单条指令的增长上限）时不会有 lamports 被转走。
+    /// 增长时由 payer 通过 System Program 转账补足新大小的租金（调用方需在账户列表中传入 System Program）；缩小时 `reclaim` 为 true 则把释放出的租金退还给 payer，
+    /// 否则多余的 lamports 留在账户中。金额见 `realloc_target_lamports`
     pub fn realloc(
         account: &AccountInfo,
         payer: &AccountInfo,
         new_size: usize,
+        reclaim: bool,
     ) -> ProgramResult {
+        Self::check_realloc_growth(account.data_len(), new_size)?;
+
         let rent = Rent::get()?;
-        let new_minimum_balance = rent.minimum_balance(new_size);
+        let old_minimum_balance = rent.minimum_balance(account.data_len());
+
+        // 重新分配空间
+        account.resize(new_size)?;
+
         let current_lamports = account.lamports();
+        let target_lamports = Self::realloc_target_lamports(
+            current_lamports,
+            old_minimum_balance,
+            rent.minimum_balance(new_size),
+            reclaim,
+        );
+
+        if target_lamports > current_lamports {
+            // payer 是系统账户，本程序不能直接扣减它的 lamports，通过 System Program 转账
+            transfer_sol(payer, account, target_lamports - current_lamports)?;
+        } else if target_lamports < current_lamports {
+            // 释放出的租金退还给 payer（账户由本程序拥有，可以直接扣减）
+            transfer_lamports_unchecked(account, payer, current_lamports - target_lamports)?;
+        }
+
+        Ok(())
+    }
 
-        // 如果需要更多 lamports，从 payer 转移
+    /// realloc 之后账户应持有的 lamports
+    ///
+    /// - 不足新大小的租金：补到 `new_minimum_balance`
+    /// - 缩小且 `reclaim`：退还 `old_minimum_balance - new_minimum_balance`，但账户至少保留新大小的租金；
+    ///   账户中超出原租金的 lamports（例如别人转入的）不属于释放出的租金，不会被取走
+    /// - 其它情况保持不变
+    #[inline(always)]
+    pub fn realloc_target_lamports(
+        current_lamports: u64,
+        old_minimum_balance: u64,
+        new_minimum_balance: u64,
+        reclaim: bool,
+    ) -> u64 {
         if new_minimum_balance > current_lamports {
-            let lamports_diff = new_minimum_balance - current_lamports;
-            let payer_lamports = payer.lamports();
-
-            unsafe {
-                *payer.borrow_mut_lamports_unchecked() = payer_lamports
-                    .checked_sub(lamports_diff)
-                    .ok_or(ProgramError::InsufficientFunds)?;
-                *account.borrow_mut_lamports_unchecked() = new_minimum_balance;
-            }
+            return new_minimum_balance;
         }
+        if !reclaim || new_minimum_balance >= old_minimum_balance {
+            return current_lamports;
+        }
+        let released = old_minimum_balance - new_minimum_balance;
+        current_lamports - released.min(current_lamports - new_minimum_balance)
+    }
 
-        // 重新分配空间
-        account.resize(new_size)?;
-
+    /// 单条指令内账户数据最多增长 MAX_PERMITTED_DATA_INCREASE 字节，超过时给出明确的 InvalidRealloc
+    #[inline(always)]
+    pub fn check_realloc_growth(current_size: usize, new_size: usize) -> ProgramResult {
+        if new_size.saturating_sub(current_size) > MAX_PERMITTED_DATA_INCREASE {
+            return Err(ProgramError::InvalidRealloc);
+        }
         Ok(())
     }
 }
@@ -277,16 +412,20 @@ pub struct MintInterface;
 
 impl MintInterface {
 
-    //todo 写得对不对
-    /// 初始化一个 Mint 账户
-    pub fn init<T>(
+    /// 初始化一个不带扩展的 Mint 账户（PDA，由 `seeds` 签名创建）
+    ///
+    /// SPL Token 与 Token-2022 的基础 Mint 都是 `Mint::LEN` 字节；InitializeMint2 发给传入的
+    /// token_program（pinocchio-token 的 `InitializeMint2` 固定调用 SPL Token，Token-2022 池子会失败）
+    pub fn init(
         payer: &AccountInfo,
         account: &AccountInfo,
-        seeds: &[pinocchio::instruction::Seed],
+        seeds: &[Seed],
         num_decimals: u8,
         mint_authority: &Pubkey,
-        token_program: &Pubkey,
+        token_program: &AccountInfo,
     ) -> ProgramResult {
+        TokenProgram::check(token_program)?;
+
         let rent = Rent::get()?;
         let lamports = rent.minimum_balance(Mint::LEN);
 
@@ -295,19 +434,58 @@ impl MintInterface {
             to: account,
             lamports,
             space: Mint::LEN as u64,
-            owner: token_program,
+            owner: token_program.key(),
         }
-        .invoke_signed(&[pinocchio::instruction::Signer::from(seeds)])?;
+        .invoke_signed(&[Signer::from(seeds)])?;
 
-        InitializeMint2 {
-            mint: account,
-            decimals: num_decimals,
-            mint_authority: mint_authority,
-            freeze_authority: None,
+        Self::initialize_mint2(token_program, account, num_decimals, mint_authority)
+    }
+
+    /// 初始化一个带 NonTransferable 扩展的 Token-2022 Mint（不可转让，只能铸造和销毁）
+    ///
+    /// 扩展必须在 InitializeMint2 之前初始化，所以这里手动构造两条 Token-2022 指令
+    pub fn init_non_transferable(
+        payer: &AccountInfo,
+        account: &AccountInfo,
+        seeds: &[Seed],
+        num_decimals: u8,
+        mint_authority: &Pubkey,
+        token_program: &AccountInfo,
+    ) -> ProgramResult {
+        TokenProgram::check_2022(token_program)?;
+
+        let rent = Rent::get()?;
+        let lamports = rent.minimum_balance(NON_TRANSFERABLE_MINT_LEN);
+
+        CreateAccount {
+            from: payer,
+            to: account,
+            lamports,
+            space: NON_TRANSFERABLE_MINT_LEN as u64,
+            owner: token_program.key(),
         }
-        .invoke_signed(&[pinocchio::instruction::Signer::from(seeds)])?;
+        .invoke_signed(&[Signer::from(seeds)])?;
 
-        Ok(())
+        // InitializeNonTransferableMint: [32]
+        invoke_token(token_program, &[AccountMeta::writable(account.key())], &[32], &[account], &[])?;
+
+        Self::initialize_mint2(token_program, account, num_decimals, mint_authority)
+    }
+
+    /// InitializeMint2，freeze_authority 为 None（不需要 rent sysvar，也不需要签名）
+    #[inline(always)]
+    fn initialize_mint2(
+        token_program: &AccountInfo,
+        account: &AccountInfo,
+        num_decimals: u8,
+        mint_authority: &Pubkey,
+    ) -> ProgramResult {
+        // InitializeMint2: [20, decimals, mint_authority, freeze_authority(None)]
+        let mut data = [0u8; 35];
+        data[0] = 20;
+        data[1] = num_decimals;
+        data[2..34].copy_from_slice(mint_authority);
+        invoke_token(token_program, &[AccountMeta::writable(account.key())], &data, &[account], &[])
     }
 
     /// 检查账户是否为有效的 Token Mint
@@ -363,7 +541,8 @@ impl MintInterface {
     pub fn get(account: &AccountInfo) -> Result<&Mint, ProgramError> {
         Self::check(account)?;
         // Safety: 我们已经验证了账户的 owner 和数据长度
-        unsafe { Ok(Mint::from_account_info_unchecked(account)?) }
+        // 不使用 from_account_info_unchecked：它只接受 SPL Token 且要求长度精确相等，Token-2022 的扩展账户会被拒绝
+        unsafe { Ok(Mint::from_bytes_unchecked(account.borrow_data_unchecked())) }
     }
 
     /// 检查 Mint 是否已初始化
@@ -376,11 +555,126 @@ impl MintInterface {
         Ok(())
     }
 
+    /// 读取 Mint 的精度
+    #[inline(always)]
+    pub fn decimals(account: &AccountInfo) -> Result<u8, ProgramError> {
+        Ok(Self::get(account)?.decimals())
+    }
+
+    /// 检查 Mint 的精度是否等于 `expected`；TransferChecked 之前调用，
+    /// 避免把错误的精度交给 token program 后得到难以定位的 CPI 失败
+    #[inline(always)]
+    pub fn check_decimals(account: &AccountInfo, expected: u8) -> ProgramResult {
+        if Self::decimals(account)? != expected {
+            return Err(AmmError::MintDecimalsMismatch.into());
+        }
+        Ok(())
+    }
+
     /// 判断是否为 Token-2022 Mint
     #[inline(always)]
     pub fn is_token_2022(account: &AccountInfo) -> bool {
         account.is_owned_by(&SPL_TOKEN_2022_ID)
     }
+
+    /// 判断是否为带 NonTransferable 扩展的 Token-2022 Mint（soulbound LP）
+    #[inline(always)]
+    pub fn is_non_transferable(account: &AccountInfo) -> Result<bool, ProgramError> {
+        Self::check(account)?;
+        if !Self::is_token_2022(account) {
+            return Ok(false);
+        }
+        let data = account.try_borrow_data()?;
+        Ok(find_mint_extension(&data, NON_TRANSFERABLE_EXTENSION)?.is_some())
+    }
+
+    /// 读取 Token-2022 Mint 的 TransferFeeConfig 扩展，返回当前 epoch 生效的费率；
+    /// SPL Token Mint 或没有该扩展的 Mint 返回 None
+    #[inline(always)]
+    pub fn transfer_fee(account: &AccountInfo, epoch: u64) -> Result<Option<TransferFee>, ProgramError> {
+        Self::check(account)?;
+        if !Self::is_token_2022(account) {
+            return Ok(None);
+        }
+        let data = account.try_borrow_data()?;
+        TransferFee::from_mint_data(&data, epoch)
+    }
+}
+
+// ============================================================================
+// Token-2022 TransferFee 扩展
+// ============================================================================
+
+/// Token-2022 扩展 TLV 从这里开始：Mint 填充到 Account::LEN (165) 之后的 account type (1)
+const MINT_EXTENSIONS_OFFSET: usize = 165 + 1;
+/// ExtensionType::TransferFeeConfig
+const TRANSFER_FEE_CONFIG_EXTENSION: u16 = 1;
+/// TransferFeeConfig: 两个 authority (32 + 32) + withheld_amount (8) + older / newer TransferFee (各 18)
+const TRANSFER_FEE_CONFIG_LEN: usize = 32 + 32 + 8 + 18 + 18;
+/// ExtensionType::NonTransferable（没有数据）
+const NON_TRANSFERABLE_EXTENSION: u16 = 9;
+
+/// 在 Mint 账户数据的扩展 TLV 中查找 `extension_type`，返回扩展的数据
+///
+/// 只有基础 Mint 数据（没有扩展）时返回 None；TLV 长度超出账户数据视为账户损坏
+fn find_mint_extension(data: &[u8], extension_type: u16) -> Result<Option<&[u8]>, ProgramError> {
+    let mut tlv = match data.get(MINT_EXTENSIONS_OFFSET..) {
+        Some(tlv) => tlv,
+        None => return Ok(None),
+    };
+    while tlv.len() >= 4 {
+        let current = u16::from_le_bytes([tlv[0], tlv[1]]);
+        let len = u16::from_le_bytes([tlv[2], tlv[3]]) as usize;
+        let value = tlv.get(4..4 + len).ok_or(ProgramError::InvalidAccountData)?;
+        if current == extension_type {
+            return Ok(Some(value));
+        }
+        tlv = &tlv[4 + len..];
+    }
+    Ok(None)
+}
+
+/// 某个 epoch 生效的转账手续费（Token-2022 TransferFee）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransferFee {
+    pub epoch: u64,
+    pub maximum_fee: u64,
+    pub basis_points: u16,
+}
+
+impl TransferFee {
+    #[inline(always)]
+    fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            epoch: u64::from_le_bytes(bytes[0..8].try_into().unwrap()),
+            maximum_fee: u64::from_le_bytes(bytes[8..16].try_into().unwrap()),
+            basis_points: u16::from_le_bytes(bytes[16..18].try_into().unwrap()),
+        }
+    }
+
+    /// 从 Mint 账户数据中找到 TransferFeeConfig，按 epoch 选择 older / newer 费率
+    ///
+    /// 只有基础 Mint 数据（没有扩展）时返回 None；扩展数据长度不对视为账户损坏
+    pub fn from_mint_data(data: &[u8], epoch: u64) -> Result<Option<Self>, ProgramError> {
+        let Some(value) = find_mint_extension(data, TRANSFER_FEE_CONFIG_EXTENSION)? else {
+            return Ok(None);
+        };
+        if value.len() != TRANSFER_FEE_CONFIG_LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let older = Self::from_bytes(&value[72..90]);
+        let newer = Self::from_bytes(&value[90..108]);
+        Ok(Some(if epoch >= newer.epoch { newer } else { older }))
+    }
+
+    /// 转账 `amount` 时被扣下的手续费：ceil(amount * bps / 10000)，不超过 maximum_fee（与 Token-2022 一致）
+    #[inline(always)]
+    pub fn fee(&self, amount: u64) -> Result<u64, ProgramError> {
+        if self.basis_points == 0 || amount == 0 {
+            return Ok(0);
+        }
+        Ok(mul_div_ceil(amount, self.basis_points as u64, FEE_BPS_DENOMINATOR)?.min(self.maximum_fee))
+    }
 }
 
 // ============================================================================
@@ -441,7 +735,8 @@ impl TokenAccountInterface {
     #[inline(always)]
     pub fn get(account: &AccountInfo) -> Result<&TokenAccount, ProgramError> {
         Self::check(account)?;
-        unsafe { Ok(TokenAccount::from_account_info_unchecked(account)?) }
+        // Safety: 同 MintInterface::get
+        unsafe { Ok(TokenAccount::from_bytes_unchecked(account.borrow_data_unchecked())) }
     }
 
     /// 检查 Token Account 的 owner
@@ -481,6 +776,32 @@ impl TokenAccountInterface {
         Ok(())
     }
 
+    /// 检查 Token Account 没有设置第三方 close_authority
+    /// 只允许未设置，或设置为 `expected`（通常是 config PDA），否则第三方可以在池子不知情时关闭金库
+    #[inline(always)]
+    pub fn check_no_external_close_authority(
+        account: &AccountInfo,
+        expected: &Pubkey,
+    ) -> ProgramResult {
+        let token_account = Self::get(account)?;
+        match token_account.close_authority() {
+            Some(close_authority) if close_authority != expected => {
+                Err(AmmError::InvalidVault.into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// 检查 Token Account 没有被冻结
+    /// 冻结的账户无法转入转出，提前给出 AccountFrozen，而不是在转账 CPI 中失败
+    #[inline(always)]
+    pub fn check_not_frozen(account: &AccountInfo) -> ProgramResult {
+        if Self::get(account)?.is_frozen() {
+            return Err(AmmError::AccountFrozen.into());
+        }
+        Ok(())
+    }
+
     /// 检查 Token Account 是否有足够的余额
     #[inline(always)]
     pub fn check_balance(account: &AccountInfo, min_amount: u64) -> ProgramResult {
@@ -540,6 +861,122 @@ impl TokenProgram {
     }
 }
 
+// ============================================================================
+// 账户校验
+// ============================================================================
+
+/// 数据账户（config、金库、mint、用户 ATA 等）不能是可执行的程序账户
+///
+/// 传入程序账户时原本要等到下游反序列化或 CPI 才失败，这里提前给出明确的 InvalidAccountData
+#[inline(always)]
+pub fn check_not_executable(account: &AccountInfo) -> ProgramResult {
+    if account.executable() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+/// 从账户切片解构并校验一个 handler 所需的账户
+///
+/// 各 handler 的 `TryFrom<&[AccountInfo]>` 直接委托给 `validate`；公共检查以默认方法提供，
+/// 保证 deposit/withdraw/swap 对 user、token_program、config 的检查完全一致
+pub trait AccountsValidator<'a>: Sized {
+    fn validate(accounts: &'a [AccountInfo]) -> Result<Self, ProgramError>;
+
+    #[inline(always)]
+    fn check_signer(account: &AccountInfo) -> ProgramResult {
+        SignerAccount::check(account)
+    }
+
+    /// CPI 直接调用传入的 token_program，必须是 SPL Token 或 Token-2022
+    #[inline(always)]
+    fn check_token_program(account: &AccountInfo) -> ProgramResult {
+        TokenProgram::check(account)
+    }
+
+    /// config 必须是本程序拥有的、长度正确的 Config 账户，并且没有在调用外部程序的途中（重入）
+    #[inline(always)]
+    fn load_config(account: &'a AccountInfo) -> Result<Ref<'a, Config>, ProgramError> {
+        let config = Config::load_checked(account)?;
+        config.check_unlocked()?;
+        Ok(config)
+    }
+
+    /// 对一组数据账户逐个执行 `check_not_executable`
+    #[inline(always)]
+    fn check_data_accounts<const N: usize>(accounts: [&AccountInfo; N]) -> ProgramResult {
+        accounts.into_iter().try_for_each(check_not_executable)
+    }
+
+    /// 用户签名 + token program + config，三个资金类 handler 的公共检查
+    #[inline(always)]
+    fn check_common(
+        user: &AccountInfo,
+        token_program: &AccountInfo,
+        config: &'a AccountInfo,
+    ) -> Result<Ref<'a, Config>, ProgramError> {
+        Self::check_signer(user)?;
+        Self::check_token_program(token_program)?;
+        Self::load_config(config)
+    }
+}
+
+/// require_checked_transfers 的池子在固定账户之后、其他可选账户之前额外传入的 mint_x / mint_y
+#[derive(Clone, Copy)]
+pub struct PoolMints<'a> {
+    pub mint_x: &'a AccountInfo,
+    pub mint_y: &'a AccountInfo,
+}
+
+impl<'a> PoolMints<'a> {
+    /// `checked` 时从 rest 开头取出两个 mint，返回剩下的可选账户
+    #[inline(always)]
+    pub fn split(checked: bool, rest: &'a [AccountInfo]) -> Result<(Option<Self>, &'a [AccountInfo]), ProgramError> {
+        if !checked {
+            return Ok((None, rest));
+        }
+        match rest {
+            [mint_x, mint_y, rest @ ..] => Ok((Some(Self { mint_x, mint_y }), rest)),
+            _ => Err(ProgramError::NotEnoughAccountKeys),
+        }
+    }
+}
+
+/// 池子 X/Y 的一次转账：require_checked_transfers 时使用 TransferChecked，精度直接从 mint 读取，
+/// 否则使用更便宜的 Transfer。`is_x` 表示转的是哪一侧的代币
+///
+/// Initialize 记录的精度与 mint 不一致时返回 `MintDecimalsMismatch`
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_pool_tokens(
+    config: &Config,
+    mints: Option<&PoolMints>,
+    token_program: &AccountInfo,
+    from: &AccountInfo,
+    to: &AccountInfo,
+    authority: &AccountInfo,
+    amount: u64,
+    is_x: bool,
+    seeds: &[Seed],
+) -> ProgramResult {
+    if !config.require_checked_transfers() {
+        return transfer_tokens_signed(token_program, from, to, authority, amount, seeds);
+    }
+
+    let mints = mints.ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let (mint, expected_mint, recorded_decimals) = match is_x {
+        true => (mints.mint_x, config.mint_x(), config.decimals_x()),
+        false => (mints.mint_y, config.mint_y(), config.decimals_y()),
+    };
+    if mint.key() != expected_mint {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let decimals = MintInterface::decimals(mint)?;
+    if decimals != recorded_decimals {
+        return Err(AmmError::MintDecimalsMismatch.into());
+    }
+    transfer_tokens_checked_signed(token_program, from, to, mint, authority, amount, decimals, seeds)
+}
+
 // ============================================================================
 // Associated Token Account 操作
 // ============================================================================
@@ -588,6 +1025,46 @@ impl AssociatedTokenAccount {
         Self::init(account, mint, payer, owner, system_program, token_program)
     }
 
+    /// 验证 `account` 是 `owner` 持有的 `mint` 的 ATA，并且由 `token_program` 拥有；返回 ATA 的 bump
+    ///
+    /// 地址按传入的 token_program 派生：同一个 owner / mint 在 SPL Token 和 Token-2022 下的 ATA 不同
+    #[inline(always)]
+    pub fn verify(
+        account: &AccountInfo,
+        owner: &Pubkey,
+        mint: &Pubkey,
+        token_program: &AccountInfo,
+    ) -> Result<u8, ProgramError> {
+        if !account.is_owned_by(token_program.key()) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let (address, bump) = get_associated_token_address_and_bump(owner, mint, token_program.key());
+        if account.key() != &address {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        Ok(bump)
+    }
+
+    /// 同 `verify`，但使用已知的 bump（例如 Config 记录的金库 bump），只做一次 create_program_address
+    #[inline(always)]
+    pub fn verify_with_bump(
+        account: &AccountInfo,
+        owner: &Pubkey,
+        mint: &Pubkey,
+        token_program: &AccountInfo,
+        bump: u8,
+    ) -> ProgramResult {
+        if !account.is_owned_by(token_program.key()) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        verify_pda_with_bump(
+            account,
+            &[owner.as_ref(), token_program.key().as_ref(), mint.as_ref()],
+            bump,
+            &ASSOCIATED_TOKEN_PROGRAM_ID,
+        )
+    }
+
     /// 创建 idempotent Associated Token Account（幂等操作，即使已存在也不会报错）
     pub fn init_idempotent(
         account: &AccountInfo,
@@ -644,6 +1121,86 @@ impl AssociatedTokenAccount {
     }
 }
 
+// ============================================================================
+// LP 仓位（anti-JIT 冷却期）
+// ============================================================================
+
+pub struct PositionAccount;
+
+impl PositionAccount {
+    /// position 必须是 (config, owner) 的仓位 PDA，返回 bump
+    #[inline(always)]
+    pub fn check(position: &AccountInfo, config: &AccountInfo, owner: &AccountInfo) -> Result<u8, ProgramError> {
+        verify_pda(position, &[b"position", config.key().as_ref(), owner.key().as_ref()], &crate::ID)
+    }
+
+    /// 存款后刷新仓位：不存在时由 owner 付费创建（别人预先转入的 lamports 不影响创建，见 `ProgramAccount::init`）
+    pub fn record(
+        position: &AccountInfo,
+        config: &AccountInfo,
+        owner: &AccountInfo,
+        liquidity_per_lp: u128,
+    ) -> ProgramResult {
+        let bump = [Self::check(position, config, owner)?];
+        if !ProgramAccount::is_created(position, LpPosition::LEN) {
+            let seeds = position_seeds_from_parts(config.key(), owner.key(), &bump);
+            ProgramAccount::init::<LpPosition>(owner, position, &seeds[..], LpPosition::LEN)?;
+        }
+
+        let clock = Clock::get()?;
+        LpPosition::load_mut(position)?.set_inner(clock.slot, clock.unix_timestamp, liquidity_per_lp, bump);
+        Ok(())
+    }
+
+    /// 当前适用的退出费（基点），见 `decayed_exit_fee_bps`
+    ///
+    /// 退出费关闭或仓位不存在时返回 0；退出费开启时必须传入 position
+    pub fn exit_fee_bps(
+        position: Option<&AccountInfo>,
+        config: &AccountInfo,
+        owner: &AccountInfo,
+        max_bps: u16,
+        window_secs: u64,
+    ) -> Result<u16, ProgramError> {
+        if max_bps == EXIT_FEE_DISABLED || window_secs == 0 {
+            return Ok(0);
+        }
+        let position = position.ok_or(ProgramError::NotEnoughAccountKeys)?;
+        Self::check(position, config, owner)?;
+        if !ProgramAccount::is_created(position, LpPosition::LEN) {
+            return Ok(0);
+        }
+
+        let elapsed = Clock::get()?.unix_timestamp.saturating_sub(LpPosition::load(position)?.deposit_ts());
+        Ok(decayed_exit_fee_bps(max_bps, window_secs, elapsed))
+    }
+
+    /// 仍在冷却期内时返回存款时的 liquidity_per_lp，取款方按它结算（见 `forfeit_fee_growth`）
+    ///
+    /// 冷却期关闭、仓位不存在或冷却期已过时返回 None；冷却期开启时必须传入 position
+    pub fn cooldown_liquidity_per_lp(
+        position: Option<&AccountInfo>,
+        config: &AccountInfo,
+        owner: &AccountInfo,
+        cooldown_slots: u64,
+    ) -> Result<Option<u128>, ProgramError> {
+        if cooldown_slots == JIT_COOLDOWN_DISABLED {
+            return Ok(None);
+        }
+        let position = position.ok_or(ProgramError::NotEnoughAccountKeys)?;
+        Self::check(position, config, owner)?;
+        if !ProgramAccount::is_created(position, LpPosition::LEN) {
+            return Ok(None);
+        }
+
+        let position = LpPosition::load(position)?;
+        match position.in_cooldown(Clock::get()?.slot, cooldown_slots) {
+            true => Ok(Some(position.liquidity_per_lp())),
+            false => Ok(None),
+        }
+    }
+}
+
 // ============================================================================
 // PDA 辅助函数
 // ============================================================================
@@ -717,6 +1274,20 @@ pub fn verify_pda_with_bump(
     Ok(())
 }
 
+/// 检查 `vault` 是否为 `config` 持有的 `mint` 的 ATA（见 `AssociatedTokenAccount::verify`）
+///
+/// 使用 Initialize 记录在 Config 中的 bump，只做一次 create_program_address
+#[inline(always)]
+pub fn is_pool_vault(
+    vault: &AccountInfo,
+    config: &Pubkey,
+    mint: &Pubkey,
+    token_program: &AccountInfo,
+    bump: u8,
+) -> bool {
+    AssociatedTokenAccount::verify_with_bump(vault, config, mint, token_program, bump).is_ok()
+}
+
 // ============================================================================
 // SOL 转账辅助函数
 // ============================================================================
@@ -775,42 +1346,73 @@ pub fn transfer_lamports_unchecked(
 // ============================================================================
 // Token 转账辅助函数
 // ============================================================================
+//
+// pinocchio-token 的指令结构体固定调用 SPL Token Program，这里手动构造指令，
+// 调用传入的 token_program，同时支持 SPL Token 和 Token-2022（两者基础指令编码相同）
 
-/// Token Transfer
-pub fn transfer_tokens(
-    from: &AccountInfo,
-    to: &AccountInfo,
-    authority: &AccountInfo,
-    amount: u64,
+/// 调用 token program 的指令，`seeds` 为空时不附带 PDA 签名
+#[inline(always)]
+fn invoke_token<const ACCOUNTS: usize>(
+    token_program: &AccountInfo,
+    metas: &[AccountMeta],
+    data: &[u8],
+    account_infos: &[&AccountInfo; ACCOUNTS],
+    seeds: &[Seed],
 ) -> ProgramResult {
-    pinocchio_token::instructions::Transfer {
-        from,
-        to,
-        authority,
-        amount,
+    let instruction = Instruction {
+        program_id: token_program.key(),
+        accounts: metas,
+        data,
+    };
+
+    if seeds.is_empty() {
+        invoke(&instruction, account_infos)
+    } else {
+        invoke_signed(&instruction, account_infos, &[Signer::from(seeds)])
     }
-    .invoke()
+}
+
+/// Token Transfer
+pub fn transfer_tokens(
+    token_program: &AccountInfo,
+    from: &AccountInfo,
+    to: &AccountInfo,
+    authority: &AccountInfo,
+    amount: u64,
+) -> ProgramResult {
+    transfer_tokens_signed(token_program, from, to, authority, amount, &[])
 }
 
 /// Token Transfer（使用 PDA 签名）
 pub fn transfer_tokens_signed(
+    token_program: &AccountInfo,
     from: &AccountInfo,
     to: &AccountInfo,
     authority: &AccountInfo,
     amount: u64,
-    seeds: &[pinocchio::instruction::Seed],
+    seeds: &[Seed],
 ) -> ProgramResult {
-    pinocchio_token::instructions::Transfer {
-        from,
-        to,
-        authority,
-        amount,
-    }
-    .invoke_signed(&[pinocchio::instruction::Signer::from(seeds)])
+    // Transfer: [3, amount]
+    let mut data = [0u8; 9];
+    data[0] = 3;
+    data[1..9].copy_from_slice(&amount.to_le_bytes());
+
+    invoke_token(
+        token_program,
+        &[
+            AccountMeta::writable(from.key()),
+            AccountMeta::writable(to.key()),
+            AccountMeta::readonly_signer(authority.key()),
+        ],
+        &data,
+        &[from, to, authority],
+        seeds,
+    )
 }
 
-/// Token Transfer Checked
+/// Token Transfer Checked（`decimals` 必须与 mint 一致，否则返回 `MintDecimalsMismatch`）
 pub fn transfer_tokens_checked(
+    token_program: &AccountInfo,
     from: &AccountInfo,
     to: &AccountInfo,
     mint: &AccountInfo,
@@ -818,36 +1420,71 @@ pub fn transfer_tokens_checked(
     amount: u64,
     decimals: u8,
 ) -> ProgramResult {
-    pinocchio_token::instructions::TransferChecked {
-        from,
-        to,
-        mint,
-        authority,
-        amount,
-        decimals,
-    }
-    .invoke()
+    MintInterface::check_decimals(mint, decimals)?;
+    transfer_tokens_checked_signed(token_program, from, to, mint, authority, amount, decimals, &[])
 }
 
 /// Token Transfer Checked（使用 PDA 签名）
+#[allow(clippy::too_many_arguments)]
 pub fn transfer_tokens_checked_signed(
+    token_program: &AccountInfo,
     from: &AccountInfo,
     to: &AccountInfo,
     mint: &AccountInfo,
     authority: &AccountInfo,
     amount: u64,
     decimals: u8,
-    seeds: &[pinocchio::instruction::Seed],
+    seeds: &[Seed],
 ) -> ProgramResult {
-    pinocchio_token::instructions::TransferChecked {
-        from,
-        to,
-        mint,
-        authority,
-        amount,
-        decimals,
-    }
-    .invoke_signed(&[pinocchio::instruction::Signer::from(seeds)])
+    // TransferChecked: [12, amount, decimals]
+    let mut data = [0u8; 10];
+    data[0] = 12;
+    data[1..9].copy_from_slice(&amount.to_le_bytes());
+    data[9] = decimals;
+
+    invoke_token(
+        token_program,
+        &[
+            AccountMeta::writable(from.key()),
+            AccountMeta::readonly(mint.key()),
+            AccountMeta::writable(to.key()),
+            AccountMeta::readonly_signer(authority.key()),
+        ],
+        &data,
+        &[from, mint, to, authority],
+        seeds,
+    )
+}
+
+/// Token Transfer Checked，并考虑 Token-2022 的 TransferFee 扩展（可选 PDA 签名）
+///
+/// 返回值是 `to` 实际收到的数量：
+/// - SPL Token Mint 或没有 TransferFeeConfig 的 Token-2022 Mint：等于 `amount`
+/// - 带 TransferFeeConfig 的 Mint：`amount` 减去当前 epoch 的手续费（手续费由 Token-2022 扣留在 `to` 的
+///   withheld 字段中，不计入余额）
+///
+/// `from` 总是被扣 `amount`。调用方（例如 Deposit / Swap 记账金库收到的数量）应使用返回值而不是 `amount`
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_tokens_checked_with_fee(
+    token_program: &AccountInfo,
+    from: &AccountInfo,
+    to: &AccountInfo,
+    mint: &AccountInfo,
+    authority: &AccountInfo,
+    amount: u64,
+    decimals: u8,
+    seeds: &[Seed],
+) -> Result<u64, ProgramError> {
+    MintInterface::check_decimals(mint, decimals)?;
+
+    let fee = match MintInterface::transfer_fee(mint, Clock::get()?.epoch)? {
+        Some(transfer_fee) => transfer_fee.fee(amount)?,
+        None => 0,
+    };
+
+    transfer_tokens_checked_signed(token_program, from, to, mint, authority, amount, decimals, seeds)?;
+
+    amount.checked_sub(fee).ok_or(ProgramError::InvalidAccountData)
 }
 
 // ============================================================================
@@ -856,68 +1493,78 @@ pub fn transfer_tokens_checked_signed(
 
 /// Mint Tokens
 pub fn mint_tokens(
+    token_program: &AccountInfo,
     mint: &AccountInfo,
     to: &AccountInfo,
     authority: &AccountInfo,
     amount: u64,
 ) -> ProgramResult {
-    pinocchio_token::instructions::MintTo {
-        mint,
-        account: to,
-        mint_authority: authority,
-        amount,
-    }
-    .invoke()
+    mint_tokens_signed(token_program, mint, to, authority, amount, &[])
 }
 
 /// Mint Tokens（使用 PDA 签名）
 pub fn mint_tokens_signed(
+    token_program: &AccountInfo,
     mint: &AccountInfo,
     to: &AccountInfo,
     authority: &AccountInfo,
     amount: u64,
-    seeds: &[pinocchio::instruction::Seed],
+    seeds: &[Seed],
 ) -> ProgramResult {
-    pinocchio_token::instructions::MintTo {
-        mint,
-        account: to,
-        mint_authority: authority,
-        amount,
-    }
-    .invoke_signed(&[pinocchio::instruction::Signer::from(seeds)])
+    // MintTo: [7, amount]
+    let mut data = [0u8; 9];
+    data[0] = 7;
+    data[1..9].copy_from_slice(&amount.to_le_bytes());
+
+    invoke_token(
+        token_program,
+        &[
+            AccountMeta::writable(mint.key()),
+            AccountMeta::writable(to.key()),
+            AccountMeta::readonly_signer(authority.key()),
+        ],
+        &data,
+        &[mint, to, authority],
+        seeds,
+    )
 }
 
 /// Burn Tokens
 pub fn burn_tokens(
+    token_program: &AccountInfo,
     from: &AccountInfo,
     mint: &AccountInfo,
     authority: &AccountInfo,
     amount: u64,
 ) -> ProgramResult {
-    pinocchio_token::instructions::Burn {
-        account: from,
-        mint,
-        authority,
-        amount,
-    }
-    .invoke()
+    burn_tokens_signed(token_program, from, mint, authority, amount, &[])
 }
 
 /// Burn Tokens（使用 PDA 签名）
 pub fn burn_tokens_signed(
+    token_program: &AccountInfo,
     from: &AccountInfo,
     mint: &AccountInfo,
     authority: &AccountInfo,
     amount: u64,
-    seeds: &[pinocchio::instruction::Seed],
+    seeds: &[Seed],
 ) -> ProgramResult {
-    pinocchio_token::instructions::Burn {
-        account: from,
-        mint,
-        authority,
-        amount,
-    }
-    .invoke_signed(&[pinocchio::instruction::Signer::from(seeds)])
+    // Burn: [8, amount]
+    let mut data = [0u8; 9];
+    data[0] = 8;
+    data[1..9].copy_from_slice(&amount.to_le_bytes());
+
+    invoke_token(
+        token_program,
+        &[
+            AccountMeta::writable(from.key()),
+            AccountMeta::writable(mint.key()),
+            AccountMeta::readonly_signer(authority.key()),
+        ],
+        &data,
+        &[from, mint, authority],
+        seeds,
+    )
 }
 
 // ============================================================================
@@ -926,31 +1573,107 @@ pub fn burn_tokens_signed(
 
 /// 关闭 Token Account
 pub fn close_token_account(
+    token_program: &AccountInfo,
     token_account: &AccountInfo,
     destination: &AccountInfo,
     authority: &AccountInfo,
 ) -> ProgramResult {
-    pinocchio_token::instructions::CloseAccount {
-        account: token_account,
-        destination,
-        authority,
-    }
-    .invoke()
+    close_token_account_signed(token_program, token_account, destination, authority, &[])
 }
 
 /// 关闭 Token Account（使用 PDA 签名）
 pub fn close_token_account_signed(
+    token_program: &AccountInfo,
     token_account: &AccountInfo,
     destination: &AccountInfo,
     authority: &AccountInfo,
-    seeds: &[pinocchio::instruction::Seed],
+    seeds: &[Seed],
 ) -> ProgramResult {
-    pinocchio_token::instructions::CloseAccount {
-        account: token_account,
-        destination,
-        authority,
+    // CloseAccount: [9]
+    invoke_token(
+        token_program,
+        &[
+            AccountMeta::writable(token_account.key()),
+            AccountMeta::writable(destination.key()),
+            AccountMeta::readonly_signer(authority.key()),
+        ],
+        &[9],
+        &[token_account, destination, authority],
+        seeds,
+    )
+}
+
+// ============================================================================
+// Wrapped SOL 辅助函数
+// ============================================================================
+
+/// SyncNative：把 WSOL 账户中超出租金的 lamports 同步为 token 余额
+pub fn sync_native(token_program: &AccountInfo, account: &AccountInfo) -> ProgramResult {
+    // SyncNative: [17]
+    invoke_token(token_program, &[AccountMeta::writable(account.key())], &[17], &[account], &[])
+}
+
+/// 把 `owner` 的 `amount` lamports 包装进它的 WSOL 账户：System Transfer 之后 SyncNative
+pub fn wrap_sol(
+    owner: &AccountInfo,
+    wsol_account: &AccountInfo,
+    token_program: &AccountInfo,
+    amount: u64,
+) -> ProgramResult {
+    transfer_sol(owner, wsol_account, amount)?;
+    sync_native(token_program, wsol_account)
+}
+
+/// 解包 WSOL：关闭 `owner` 的 WSOL 账户，余额和租金一起以 lamports 退还给 `owner`
+pub fn unwrap_sol(owner: &AccountInfo, wsol_account: &AccountInfo, token_program: &AccountInfo) -> ProgramResult {
+    close_token_account(token_program, wsol_account, owner, owner)
+}
+
+/// Swap 转交输出时最多转发给下游程序的账户数
+pub const MAX_DOWNSTREAM_ACCOUNTS: usize = 16;
+/// Swap 转交输出时最多转发给下游程序的指令数据长度
+pub const MAX_DOWNSTREAM_DATA_LEN: usize = 128;
+
+/// 调用下游程序：账户按原样转发（保留 writable / signer 标记），指令数据不做解析
+///
+/// 不附带任何 PDA 签名，下游程序只能使用用户本人在这笔交易中的签名
+pub fn invoke_downstream(program: &AccountInfo, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    if accounts.len() > MAX_DOWNSTREAM_ACCOUNTS || data.len() > MAX_DOWNSTREAM_DATA_LEN {
+        return Err(ProgramError::InvalidArgument);
     }
-    .invoke_signed(&[pinocchio::instruction::Signer::from(seeds)])
+
+    let metas: Vec<AccountMeta> = accounts
+        .iter()
+        .map(|account| AccountMeta::new(account.key(), account.is_writable(), account.is_signer()))
+        .collect();
+    let infos: Vec<&AccountInfo> = accounts.iter().collect();
+
+    pinocchio::cpi::slice_invoke(
+        &Instruction {
+            program_id: program.key(),
+            accounts: &metas,
+            data,
+        },
+        &infos,
+    )
+}
+
+/// 在池子锁定期间执行整条指令，正常返回后解锁
+///
+/// swap / deposit / withdraw 等修改池子状态的指令从一开始就持有锁，期间 `Config::locked` 为 1：
+/// 无论是下游程序、闪电兑换回调还是 Token-2022 的 transfer hook，只要经由
+/// `AccountsValidator::load_config` 再次进入本池子都会返回 `Reentrancy`。
+/// 运行时本身不允许 A -> B -> A 形式的重入，这里是额外的一层保护。
+/// 任何一步出错时整笔交易回滚，锁不会残留，因此不需要 Drop 式的守卫；
+/// `f` 内部不能持有 config 的借用越过返回点
+pub fn with_pool_locked<T>(
+    config: &AccountInfo,
+    f: impl FnOnce() -> Result<T, ProgramError>,
+) -> Result<T, ProgramError> {
+    Config::load_mut(config)?.set_locked(true);
+    let result = f()?;
+    Config::load_mut(config)?.set_locked(false);
+    Ok(result)
 }
 
 // ============================================================================
@@ -1021,6 +1744,18 @@ fn invoke_create_associated_token_account_idempotent(
 // 数学辅助函数
 // ============================================================================
 
+/// 储备 / 数量相加，溢出 u64 时返回 ArithmeticOverflow
+#[inline(always)]
+pub fn add_u64(a: u64, b: u64) -> Result<u64, ProgramError> {
+    a.checked_add(b).ok_or(ProgramError::ArithmeticOverflow)
+}
+
+/// 储备 / 数量相减，不够减时返回 InsufficientFunds
+#[inline(always)]
+pub fn sub_u64(a: u64, b: u64) -> Result<u64, ProgramError> {
+    a.checked_sub(b).ok_or(ProgramError::InsufficientFunds)
+}
+
 /// 安全乘法后除法，避免溢出
 /// 计算 (a * b) / c，使用 u128 中间值
 #[inline(always)]
@@ -1061,3 +1796,578 @@ pub fn mul_div_ceil(a: u64, b: u64, c: u64) -> Result<u64, ProgramError> {
     }
     Ok(result as u64)
 }
+
+/// 安全乘法后除法，四舍五入（恰好一半时向上）
+///
+/// 计算 (a * b + c / 2) / c，避免向下/向上取整带来的系统性偏差
+#[inline(always)]
+pub fn mul_div_round(a: u64, b: u64, c: u64) -> Result<u64, ProgramError> {
+    if c == 0 {
+        return Err(ProgramError::ArithmeticOverflow);
+    }
+    let numerator = (a as u128)
+        .checked_mul(b as u128)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    let result = numerator
+        .checked_add(c as u128 / 2)
+        .ok_or(ProgramError::ArithmeticOverflow)?
+        .checked_div(c as u128)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    if result > u64::MAX as u128 {
+        return Err(ProgramError::ArithmeticOverflow);
+    }
+    Ok(result as u64)
+}
+
+/// 去掉 `then` 之后累积的手续费：amount * then / now（向下取整），`now <= then` 时原样返回
+///
+/// then/now 是 LiquidityCurve::liquidity_per_lp（Q64.64），可能超过 u64，
+/// 先同时右移到 u64 范围内再做 mul_div，比例的精度损失可以忽略
+#[inline(always)]
+pub fn forfeit_fee_growth(amount: u64, then: u128, now: u128) -> Result<u64, ProgramError> {
+    if now <= then {
+        return Ok(amount);
+    }
+    let shift = 64u32.saturating_sub(now.leading_zeros());
+    mul_div(amount, (then >> shift) as u64, (now >> shift) as u64)
+}
+
+/// 存款后经过 `elapsed_secs` 秒的退出费：从 max_bps 在 window_secs 内线性衰减到 0
+///
+/// 结果向上取整，窗口内的退出费不会提前降到 0；时钟回拨（elapsed 为负）按 0 秒处理
+#[inline(always)]
+pub fn decayed_exit_fee_bps(max_bps: u16, window_secs: u64, elapsed_secs: i64) -> u16 {
+    let elapsed = elapsed_secs.max(0) as u64;
+    if window_secs == 0 || elapsed >= window_secs {
+        return 0;
+    }
+    // remaining < window_secs，且 max_bps <= u16::MAX，结果不会超过 max_bps
+    let remaining = (window_secs - elapsed) as u128;
+    (((max_bps as u128) * remaining).div_ceil(window_secs as u128)) as u16
+}
+
+/// 扣除退出费后用户实际取回的数量（向下取整），被扣下的部分留在金库里归剩余的 LP
+#[inline(always)]
+pub fn apply_exit_fee(amount: u64, fee_bps: u16) -> Result<u64, ProgramError> {
+    let fee_bps = (fee_bps as u64).min(FEE_BPS_DENOMINATOR);
+    mul_div(amount, FEE_BPS_DENOMINATOR - fee_bps, FEE_BPS_DENOMINATOR)
+}
+
+/// `apply_exit_fee` 的反向：扣除退出费后至少取回 `amount` 时扣费前需要的数量（向上取整）
+///
+/// 退出费为 100% 时无论销毁多少 LP 都取不回代币，返回 SlippageExceeded
+#[inline(always)]
+pub fn gross_up_exit_fee(amount: u64, fee_bps: u16) -> Result<u64, ProgramError> {
+    let fee_bps = (fee_bps as u64).min(FEE_BPS_DENOMINATOR);
+    if fee_bps == FEE_BPS_DENOMINATOR {
+        return Err(AmmError::SlippageExceeded.into());
+    }
+    mul_div_ceil(amount, FEE_BPS_DENOMINATOR, FEE_BPS_DENOMINATOR - fee_bps)
+}
+
+/// `forfeit_fee_growth` 的反向：没收手续费增长后至少剩下 `amount` 时没收前需要的数量（向上取整）
+#[inline(always)]
+pub fn gross_up_fee_growth(amount: u64, then: u128, now: u128) -> Result<u64, ProgramError> {
+    if now <= then {
+        return Ok(amount);
+    }
+    // 与 forfeit_fee_growth 使用相同的移位，保证两者互逆
+    let shift = 64u32.saturating_sub(now.leading_zeros());
+    mul_div_ceil(amount, (now >> shift) as u64, (then >> shift) as u64)
+}
+
+/// `split_protocol_fee` 的反向：进入曲线的部分至少为 `amount_in` 时用户需要支付的总输入（向上取整）
+#[inline(always)]
+pub fn gross_up_protocol_fee(amount_in: u64, protocol_fee_bps: u16) -> Result<u64, ProgramError> {
+    let protocol_fee_bps = (protocol_fee_bps as u64).min(FEE_BPS_DENOMINATOR - 1);
+    mul_div_ceil(amount_in, FEE_BPS_DENOMINATOR, FEE_BPS_DENOMINATOR - protocol_fee_bps)
+}
+
+/// 把 swap 输入拆成 (协议费, 进入曲线的部分)，协议费向下取整；protocol_fee 为 0 时输入原样进入曲线
+#[inline(always)]
+pub fn split_protocol_fee(amount: u64, protocol_fee_bps: u16) -> Result<(u64, u64), ProgramError> {
+    let protocol_cut = mul_div(amount, (protocol_fee_bps as u64).min(FEE_BPS_DENOMINATOR), FEE_BPS_DENOMINATOR)?;
+    Ok((protocol_cut, amount - protocol_cut))
+}
+
+/// 整数平方根（向下取整），牛顿迭代
+#[inline(always)]
+pub fn sqrt_u128(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = n;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+/// 首次存款（空池）铸造给用户的 LP 数量
+///
+/// 初始 LP = sqrt(x * y)，其中 MINIMUM_LIQUIDITY 被永久锁定，不会铸造给用户。
+/// Deposit 与 InitializeAndSeed 共用此函数，保证首次存款只有一条计算路径。
+#[inline(always)]
+pub fn first_deposit_lp(x: u64, y: u64) -> Result<u64, ProgramError> {
+    // sqrt(u64::MAX * u64::MAX) <= u64::MAX，结果一定能放进 u64
+    let liquidity = sqrt_u128((x as u128) * (y as u128)) as u64;
+
+    match liquidity.checked_sub(MINIMUM_LIQUIDITY) {
+        Some(lp) if lp > 0 => Ok(lp),
+        _ => Err(AmmError::InsufficientInitialLiquidity.into()),
+    }
+}
+
+/// 份额计算使用的 LP supply：实际 supply 加上永久锁定的 MINIMUM_LIQUIDITY
+#[inline(always)]
+pub fn locked_lp_supply(supply: u64) -> Result<u64, ProgramError> {
+    supply
+        .checked_add(MINIMUM_LIQUIDITY)
+        .ok_or(ProgramError::ArithmeticOverflow)
+}
+
+/// 铸造 `lp` 之前检查 LP supply 的余量：铸造后的 supply（含锁定的 MINIMUM_LIQUIDITY）必须放得进 u64
+///
+/// 在 MintTo CPI 之前给出明确的 CurveOverflow，而不是让份额计算或 token program 失败
+#[inline(always)]
+pub fn check_lp_mint_headroom(supply: u64, lp: u64) -> ProgramResult {
+    supply
+        .checked_add(MINIMUM_LIQUIDITY)
+        .and_then(|locked| locked.checked_add(lp))
+        .map(|_| ())
+        .ok_or(AmmError::CurveOverflow.into())
+}
+
+/// 存款：铸造 `lp` 份额需要存入的 x/y 数量（向上取整，舍入误差由存款人承担）
+///
+/// 存款和取款都不收手续费（只有 Swap 收取），数量严格按份额比例计算。
+#[inline(always)]
+pub fn deposit_amounts_from_lp(
+    vault_x: u64,
+    vault_y: u64,
+    lp_supply: u64,
+    lp: u64,
+) -> Result<(u64, u64), ProgramError> {
+    Ok((
+        mul_div_ceil(vault_x, lp, lp_supply)?,
+        mul_div_ceil(vault_y, lp, lp_supply)?,
+    ))
+}
+
+/// 取款：销毁 `lp` 份额可取回的 x/y 数量（向下取整，舍入误差留在池中）
+///
+/// 与 `deposit_amounts_from_lp` 配对：同一份额先存后取，每一侧最多损失 1 个最小单位。
+#[inline(always)]
+pub fn withdraw_amounts_from_lp(
+    vault_x: u64,
+    vault_y: u64,
+    lp_supply: u64,
+    lp: u64,
+) -> Result<(u64, u64), ProgramError> {
+    if lp > lp_supply {
+        return Err(ProgramError::InsufficientFunds);
+    }
+    Ok((
+        mul_div(vault_x, lp, lp_supply)?,
+        mul_div(vault_y, lp, lp_supply)?,
+    ))
+}
+
+/// 取款（exact-out）：至少取回 x/y 需要销毁的 LP 数量（两侧分别向上取整后取较大值）
+///
+/// 与 `withdraw_amounts_from_lp` 互逆：对返回的 lp 计算取回数量（向下取整）一定不少于 x/y。
+#[inline(always)]
+pub fn lp_for_withdraw_amounts(
+    vault_x: u64,
+    vault_y: u64,
+    lp_supply: u64,
+    x: u64,
+    y: u64,
+) -> Result<u64, ProgramError> {
+    if vault_x == 0 || vault_y == 0 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(mul_div_ceil(x, lp_supply, vault_x)?.max(mul_div_ceil(y, lp_supply, vault_y)?))
+}
+
+/// 存款：按比例存入 x/y 最多可铸造的 LP 数量（两侧分别向下取整后取较小值）
+///
+/// 与 `deposit_amounts_from_lp` 互逆：对返回的 lp 计算所需数量（向上取整）一定不超过 x/y。
+#[inline(always)]
+pub fn lp_from_deposit_amounts(
+    vault_x: u64,
+    vault_y: u64,
+    lp_supply: u64,
+    x: u64,
+    y: u64,
+) -> Result<u64, ProgramError> {
+    if vault_x == 0 || vault_y == 0 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(mul_div(x, lp_supply, vault_x)?.min(mul_div(y, lp_supply, vault_y)?))
+}
+
+/// 检查订单是否过期
+///
+/// 允许 `grace_secs` 的时钟误差：只有 now - grace_secs > expiration 时才视为过期
+#[inline(always)]
+pub fn check_expiration(expiration: i64, grace_secs: u16) -> ProgramResult {
+    let now = Clock::get()?.unix_timestamp;
+    if now.saturating_sub(grace_secs as i64) > expiration {
+        return Err(AmmError::OrderExpired.into());
+    }
+    Ok(())
+}
+
+/// 池子的储备：按 Config 中的储备快照计价，直接转入金库的代币（捐赠）不影响价格
+///
+/// 金库余额超出快照的部分记为 donated，在整条指令中保持不变；
+/// 转账完成后 `sync` 用金库余额减去 donated 得到新的快照，快照因此只随实际的存取款 / swap 转账变化
+pub struct PoolReserves {
+    pub x: u64,
+    pub y: u64,
+    donated_x: u64,
+    donated_y: u64,
+}
+
+impl PoolReserves {
+    /// 读取储备快照，金库余额超出快照的部分记为 donated
+    #[inline(always)]
+    pub fn load(config: &Config, vault_x: &AccountInfo, vault_y: &AccountInfo) -> Result<Self, ProgramError> {
+        let (balance_x, balance_y) = (
+            TokenAccountInterface::get(vault_x)?.amount(),
+            TokenAccountInterface::get(vault_y)?.amount(),
+        );
+        let (x, y) = (config.reserve_x(), config.reserve_y());
+        // 金库余额只会因为捐赠而多于快照，少于快照说明账本与金库脱节
+        if balance_x < x || balance_y < y {
+            return Err(AmmError::ReserveMismatch.into());
+        }
+        Ok(Self { x, y, donated_x: balance_x - x, donated_y: balance_y - y })
+    }
+
+    /// swap 之前确认两侧储备都不为 0：初始化后还没有存款的池子没有价格，给出明确的 EmptyPool
+    #[inline(always)]
+    pub fn check_not_empty(&self) -> ProgramResult {
+        if self.x == 0 || self.y == 0 {
+            return Err(AmmError::EmptyPool.into());
+        }
+        Ok(())
+    }
+
+    /// 金库余额中不属于储备的部分 (x, y)：捐赠和留在金库中的协议费
+    #[inline(always)]
+    pub fn donated(&self) -> (u64, u64) {
+        (self.donated_x, self.donated_y)
+    }
+
+    /// 协议费留在金库中时（见 `Config::accrues_protocol_fee`）：记入 config 的 fees_x / fees_y，
+    /// 并从接下来 `sync` 的快照中排除，这部分不会变成 LP 的储备
+    ///
+    /// 调用方在此之前不能持有 config 的借用
+    #[inline(always)]
+    pub fn accrue_protocol_fee(&mut self, config: &AccountInfo, is_x: bool, amount: u64) -> ProgramResult {
+        let donated = match is_x {
+            true => &mut self.donated_x,
+            false => &mut self.donated_y,
+        };
+        *donated = add_u64(*donated, amount)?;
+        Config::load_mut(config)?.accrue_fees(is_x, amount)
+    }
+
+    /// 转账完成后按金库余额更新快照
+    ///
+    /// 调用方在此之前不能持有 config 的借用
+    #[inline(always)]
+    pub fn sync(&self, config: &AccountInfo, vault_x: &AccountInfo, vault_y: &AccountInfo) -> ProgramResult {
+        let x = TokenAccountInterface::get(vault_x)?.amount().checked_sub(self.donated_x);
+        let y = TokenAccountInterface::get(vault_y)?.amount().checked_sub(self.donated_y);
+        let (Some(x), Some(y)) = (x, y) else {
+            return Err(AmmError::ReserveMismatch.into());
+        };
+        Config::load_mut(config)?.set_reserves(x, y);
+        Ok(())
+    }
+}
+
+/// 用储备变化前的数量更新 config 中的 TWAP 累加器
+///
+/// 调用方在此之前不能持有 config 的借用（Config::load_checked 返回的 Ref 需先 drop，load_unchecked 返回的引用不能再使用）
+#[inline(always)]
+pub fn update_price_cumulative(config: &AccountInfo, reserve_x: u64, reserve_y: u64) -> ProgramResult {
+    let now = Clock::get()?.unix_timestamp;
+    Config::load_mut(config)?.update_price_cumulative(reserve_x, reserve_y, now);
+    Ok(())
+}
+
+/// `expiration` 的含义，由指令数据末尾可选的一个字节指定，缺省为 Timestamp
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExpirationKind {
+    /// unix 时间戳，允许 GlobalConfig 中 grace_secs 的时钟误差
+    Timestamp = 0,
+    /// slot 高度，不受验证者时钟漂移影响，不使用 grace_secs
+    Slot = 1,
+}
+
+impl TryFrom<u8> for ExpirationKind {
+    type Error = ProgramError;
+
+    #[inline(always)]
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ExpirationKind::Timestamp),
+            1 => Ok(ExpirationKind::Slot),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+impl ExpirationKind {
+    /// 按 kind 检查 `expiration` 是否已过
+    #[inline(always)]
+    pub fn check(self, expiration: i64, grace_secs: u16) -> ProgramResult {
+        match self {
+            ExpirationKind::Timestamp => check_expiration(expiration, grace_secs),
+            ExpirationKind::Slot => check_slot_expiration(expiration),
+        }
+    }
+
+    /// 从固定字段之后的剩余数据中拆出末尾的 kind 字节
+    ///
+    /// `rest.len()` 属于 `with_kind` 时最后一个字节是 kind，否则整段原样返回、kind 为 Timestamp。
+    /// 各指令传入“带 kind 时的合法剩余长度”，其余可选字段的长度校验仍由调用方完成。
+    #[inline(always)]
+    pub fn split_suffix<'a>(rest: &'a [u8], with_kind: &[usize]) -> Result<(&'a [u8], Self), ProgramError> {
+        match rest.split_last() {
+            Some((kind, head)) if with_kind.contains(&rest.len()) => Ok((head, Self::try_from(*kind)?)),
+            _ => Ok((rest, ExpirationKind::Timestamp)),
+        }
+    }
+}
+
+/// 以 slot 为单位的截止时间：当前 slot 超过 expiration 即视为过期
+#[inline(always)]
+pub fn check_slot_expiration(expiration: i64) -> ProgramResult {
+    let slot = Clock::get()?.slot;
+    if expiration < 0 || slot > expiration as u64 {
+        return Err(AmmError::OrderExpired.into());
+    }
+    Ok(())
+}
+
+/// 费率的基点分母
+pub const FEE_BPS_DENOMINATOR: u64 = 10_000;
+
+/// 客户端报价时看到的 LP supply，可选地附加在 Deposit/Withdraw 指令数据末尾
+///
+/// 执行时实际 supply 与 `supply` 的偏差超过 `tolerance_bps` 说明报价后有并发的存取款，
+/// 此时拒绝交易（轻量的乐观并发检查）
+pub struct ExpectedLpSupply {
+    pub supply: u64,
+    pub tolerance_bps: u16,
+}
+
+impl ExpectedLpSupply {
+    /// supply (u64) + tolerance_bps (u16)
+    pub const LEN: usize = 10;
+
+    /// 解析指令数据中固定字段之后的剩余部分：为空表示不检查
+    #[inline(always)]
+    pub fn parse(rest: &[u8]) -> Result<Option<Self>, ProgramError> {
+        match rest.len() {
+            0 => Ok(None),
+            Self::LEN => {
+                let supply = u64::from_le_bytes(rest[0..8].try_into().unwrap());
+                let tolerance_bps = u16::from_le_bytes(rest[8..10].try_into().unwrap());
+                if tolerance_bps as u64 > FEE_BPS_DENOMINATOR {
+                    return Err(ProgramError::InvalidInstructionData);
+                }
+                Ok(Some(Self { supply, tolerance_bps }))
+            }
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+
+    /// 实际 supply 与预期的偏差不能超过 supply * tolerance_bps / 10000（向下取整）
+    #[inline(always)]
+    pub fn check(&self, actual: u64) -> ProgramResult {
+        let diff = actual.abs_diff(self.supply);
+        let allowed = mul_div(self.supply, self.tolerance_bps as u64, FEE_BPS_DENOMINATOR)?;
+        if diff > allowed {
+            return Err(AmmError::LpSupplyChanged.into());
+        }
+        Ok(())
+    }
+}
+
+/// 首次存款允许的价格区间，可选地附加在 Deposit 指令数据末尾（ExpectedLpSupply 之后）
+///
+/// 价格为 x/y 的 Q64.64 定点数（与 `price_cumulative_y` 相同）。空池的首次存款决定池子的初始价格，
+/// 必须给出区间，手误或恶意的初始价格在这里被拒绝；非首次存款时忽略
+pub struct PriceBand {
+    pub min_price: u128,
+    pub max_price: u128,
+}
+
+impl PriceBand {
+    /// min_price (u128) + max_price (u128)
+    pub const LEN: usize = 32;
+
+    #[inline(always)]
+    pub fn parse(bytes: &[u8]) -> Result<Self, ProgramError> {
+        if bytes.len() != Self::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let min_price = u128::from_le_bytes(bytes[0..16].try_into().unwrap());
+        let max_price = u128::from_le_bytes(bytes[16..32].try_into().unwrap());
+        if min_price > max_price {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        Ok(Self { min_price, max_price })
+    }
+
+    /// x/y 必须落在 [min_price, max_price] 内（y 为 0 时拒绝），加权池子按 `weighted_price` 换算
+    #[inline(always)]
+    pub fn check(&self, x: u64, y: u64, weight_x: u16) -> ProgramResult {
+        if y == 0 {
+            return Err(AmmError::InitialPriceOutOfRange.into());
+        }
+        // x < 2^64，左移 64 位不会溢出 u128
+        let price = weighted_price(((x as u128) << PRICE_FRACTION_BITS) / y as u128, 10_000 - weight_x);
+        if price < self.min_price || price > self.max_price {
+            return Err(AmmError::InitialPriceOutOfRange.into());
+        }
+        Ok(())
+    }
+}
+
+/// 恒定乘积兑换：输入 `amount_in` 可得到的输出数量（向下取整）
+///
+/// 手续费从输入中扣除并留在池中：amount_in_after_fee = amount_in * (10000 - fee_bps) / 10000，
+/// out = reserve_out * amount_in_after_fee / (reserve_in + amount_in_after_fee)。
+/// Swap 和 Quote 共用此函数，保证报价与实际执行一致。
+#[inline(always)]
+pub fn compute_swap(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_in: u64,
+    fee_bps: u16,
+) -> Result<u64, ProgramError> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if fee_bps as u64 >= FEE_BPS_DENOMINATOR {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let amount_in_after_fee = mul_div(amount_in, FEE_BPS_DENOMINATOR - fee_bps as u64, FEE_BPS_DENOMINATOR)?;
+    let new_reserve_in = reserve_in
+        .checked_add(amount_in_after_fee)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    mul_div(reserve_out, amount_in_after_fee, new_reserve_in)
+}
+
+/// 单边存款：`amount_in` 中需要先兑换成另一侧的数量 s，使兑换后剩下的 amount_in - s 与换得的输出
+/// 恰好符合兑换后储备的比例
+///
+/// 令 F = 10000, g = F - fee_bps，解 g·s² + R(F + g)·s - F·a·R = 0 得
+/// s = 2Fa / (sqrt((F + g)² + 4gFa / R) + (F + g))。
+/// 为了在 u128 内计算，分子分母同乘 K = 2^16；sqrt 向下取整，s 可能比最优值略大或略小一点，
+/// 多出的一侧不计入 LP，留在池子里
+#[inline(always)]
+pub fn single_sided_swap_amount(reserve_in: u64, amount_in: u64, fee_bps: u16) -> Result<u64, ProgramError> {
+    const K: u128 = 1 << 16;
+
+    if reserve_in == 0 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if fee_bps as u64 >= FEE_BPS_DENOMINATOR {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let f = FEE_BPS_DENOMINATOR as u128;
+    let g = f - fee_bps as u128;
+    let a = amount_in as u128;
+
+    // K²(F + g)² + 4gFaK² / R
+    let radicand = (K * (f + g))
+        .checked_mul(K * (f + g))
+        .and_then(|base| {
+            (4 * g * f)
+                .checked_mul(a)?
+                .checked_mul(K * K)
+                .map(|n| n / reserve_in as u128)
+                .and_then(|n| base.checked_add(n))
+        })
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    let swapped = (2 * f * a * K) / (sqrt_u128(radicand) + K * (f + g));
+
+    // 分母 >= 2K·F，s < a
+    Ok(swapped as u64)
+}
+
+/// exact-out：得到 `amount_out` 所需的最小输入（含手续费），即满足
+/// `compute_swap(reserve_in, reserve_out, amount_in, fee_bps) >= amount_out` 的最小 amount_in
+///
+/// 扣费后的输入 a' = ceil(amount_out * reserve_in / (reserve_out - amount_out))，
+/// 再反推扣费前的 amount_in = ceil(a' * 10000 / (10000 - fee_bps))。
+/// `amount_out` 必须小于 reserve_out（恒定乘积曲线不能取空一侧）
+#[inline(always)]
+pub fn compute_swap_exact_out(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_out: u64,
+    fee_bps: u16,
+) -> Result<u64, ProgramError> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if fee_bps as u64 >= FEE_BPS_DENOMINATOR || amount_out >= reserve_out {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let amount_in_after_fee = mul_div_ceil(amount_out, reserve_in, reserve_out - amount_out)?;
+    mul_div_ceil(amount_in_after_fee, FEE_BPS_DENOMINATOR, FEE_BPS_DENOMINATOR - fee_bps as u64)
+}
+
+/// 一次假设兑换的价格影响（基点）：现货价格与实际成交价格之差，包含手续费
+///
+/// spot = reserve_out / reserve_in，effective = amount_out / amount_in，
+/// impact = 10000 - effective / spot * 10000（成交比例向下取整，影响向上取整）
+#[inline(always)]
+pub fn price_impact_bps(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_in: u64,
+    fee_bps: u16,
+) -> Result<u16, ProgramError> {
+    if amount_in == 0 {
+        return Err(ProgramError::InvalidArgument);
+    }
+    let amount_out = compute_swap(reserve_in, reserve_out, amount_in, fee_bps)?;
+
+    // amount_out * reserve_in * 10000 在极端储备下可能超过 u128，使用 checked 运算
+    let executed_bps = (amount_out as u128)
+        .checked_mul(reserve_in as u128)
+        .and_then(|v| v.checked_mul(FEE_BPS_DENOMINATOR as u128))
+        .ok_or(ProgramError::ArithmeticOverflow)?
+        / ((amount_in as u128) * (reserve_out as u128));
+
+    Ok((FEE_BPS_DENOMINATOR as u128).saturating_sub(executed_bps) as u16)
+}
+
+/// 以共同计价单位表示的池子总锁仓价值：reserve_x * price_x + reserve_y * price_y
+///
+/// 价格由调用方提供（例如以 USDC 最小单位计价的单价）。单侧乘积最大约 2^128，不会溢出 u128，
+/// 两侧相加可能超过 u128，此时饱和到 u128::MAX。价格为 0 时该侧不计入。
+#[inline(always)]
+pub fn total_value_locked(reserve_x: u64, reserve_y: u64, price_x: u64, price_y: u64) -> u128 {
+    let value_x = reserve_x as u128 * price_x as u128;
+    let value_y = reserve_y as u128 * price_y as u128;
+    value_x.saturating_add(value_y)
+}
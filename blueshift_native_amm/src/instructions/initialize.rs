@@ -1,15 +1,28 @@
-use crate::state::{Config, config_seeds_from_parts};
-use core::mem::{size_of, MaybeUninit};
+use crate::errors::AmmError;
+use crate::state::{
+    Config, EQUAL_WEIGHT_BPS, GlobalConfig, LP_DECIMALS, PoolRegistry, USE_GLOBAL_DEFAULT_FEE,
+    config_seeds_from_parts, registry_seeds_from_parts,
+};
+use core::mem::size_of;
 use pinocchio::{
     ProgramResult,
     account_info::AccountInfo,
     instruction::Seed,
     program_error::ProgramError,
+    pubkey::find_program_address,
 };
-use pinocchio_token::state::Mint;
 
 use super::helpers::*;
 
+/// 创建池子：Config、LP mint，以及可选的 PoolRegistry 登记，池子没有储备
+///
+/// 需要在创建时由协议注入初始流动性（池子不经过零储备状态）时使用 `InitializeAndSeed`：
+/// 它以同一份指令数据调用本指令，随后在同一条指令中完成首次存款
+///
+/// 账户布局：`[initializer, mint_lp, config, system_program, token_program, _]`，之后依次为
+/// Token-2022 池子的 mint_x / mint_y、GlobalConfig PDA 和可选的 PoolRegistry。
+/// GlobalConfig PDA 总是必须传入（InitializeGlobal 尚未执行时可以是空账户）：
+/// 它存在且打开 enforce_unique 时，池子一定会登记到 PoolRegistry
 pub struct Initialize<'a> {
     pub accounts: InitializeAccounts<'a>,
     pub instruction_data: InitializeInstructionData,
@@ -32,6 +45,8 @@ impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Initialize<'a> {
             &instruction_data.config_bump,
         );
 
+        // 账户已存在时 CreateAccount 会失败，这里提前给出明确的错误（与 InitializeGlobal 相同）
+        ProgramAccount::check_uninitialized(accounts.config)?;
         ProgramAccount::init::<Config>(
             accounts.initializer,
             accounts.config,
@@ -46,14 +61,28 @@ impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Initialize<'a> {
             Seed::from(&instruction_data.lp_bump),
         ];
 
-        MintInterface::init::<Mint>(
-            accounts.initializer,
-            accounts.mint_lp,
-            &mint_lp_seeds[..],
-            crate::state::LP_DECIMALS,
-            accounts.initializer.key(),
-            accounts.token_program.key(),
-        )?;
+        ProgramAccount::check_uninitialized(accounts.mint_lp)?;
+
+        // soulbound：LP 使用带 NonTransferable 扩展的 Token-2022 mint，只能存入/取出，不能转让
+        if instruction_data.soulbound == 1 {
+            MintInterface::init_non_transferable(
+                accounts.initializer,
+                accounts.mint_lp,
+                &mint_lp_seeds[..],
+                instruction_data.lp_decimals,
+                accounts.config.key(), // LP 由 config PDA 签名铸造
+                accounts.token_program,
+            )?;
+        } else {
+            MintInterface::init(
+                accounts.initializer,
+                accounts.mint_lp,
+                &mint_lp_seeds[..],
+                instruction_data.lp_decimals,
+                accounts.config.key(), // LP 由 config PDA 签名铸造
+                accounts.token_program,
+            )?;
+        }
 
         Ok(Self {
             accounts,
@@ -70,15 +99,96 @@ impl<'a> Initialize<'a> {
         let mut data = self.accounts.config.try_borrow_mut_data()?;
         let config = unsafe { Config::load_mut_unchecked(data.as_mut())? };
 
+        // GlobalConfig PDA 必须传入：它一旦存在，enforce_unique 就不能靠省略账户绕过
+        let global_config = self.accounts.global_config.ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let global = GlobalConfig::load_if_initialized(global_config)?;
+
+        //未指定费率时使用全局默认费率，此时 GlobalConfig 必须已经创建
+        let fee = match (self.instruction_data.fee, &global) {
+            (USE_GLOBAL_DEFAULT_FEE, Some(global)) => global.default_fee(),
+            (USE_GLOBAL_DEFAULT_FEE, None) => return Err(ProgramError::UninitializedAccount),
+            (fee, _) => fee,
+        };
+
+        // 打开 enforce_unique 时，同一 (mint_x, mint_y, fee) 只能登记一个池子
+        if global.is_some_and(|global| global.enforce_unique()) {
+            self.register_pool(fee)?;
+        }
+
         config.set_inner(
             self.instruction_data.seed,
             self.instruction_data.authority,
             self.instruction_data.mint_x,
             self.instruction_data.mint_y,
-            self.instruction_data.fee,
+            fee,
             self.instruction_data.config_bump,
         )?;
 
+        // Token-2022 的 mint 可能带有扩展，所有 X/Y 转账都强制使用 TransferChecked
+        if self.accounts.token_program.key() == &SPL_TOKEN_2022_ID {
+            let (decimals_x, decimals_y) = self.mint_decimals()?;
+            config.set_checked_transfers(decimals_x, decimals_y);
+        }
+        config.set_lp_decimals(self.instruction_data.lp_decimals);
+        // 权重不在 [MIN_WEIGHT_BPS, MAX_WEIGHT_BPS] 内时返回 InvalidInstructionData
+        config.set_weight_x(self.instruction_data.weight_x)?;
+        // 金库地址在这里就已确定（config 的 ATA），记录 bump 后存取款只需一次 create_program_address 验证
+        let token_program = self.accounts.token_program.key();
+        let (_, vault_x_bump) =
+            get_associated_token_address_and_bump(self.accounts.config.key(), &self.instruction_data.mint_x, token_program);
+        let (_, vault_y_bump) =
+            get_associated_token_address_and_bump(self.accounts.config.key(), &self.instruction_data.mint_y, token_program);
+        config.set_vault_bumps(vault_x_bump, vault_y_bump);
+        // 新池子从空储备开始记录快照：创建前就转入金库的代币不影响价格
+        config.set_reserves(0, 0);
+
+        Ok(())
+    }
+
+    /// 读取传入的 mint_x / mint_y 的精度，mint 必须与指令数据中的一致
+    fn mint_decimals(&self) -> Result<(u8, u8), ProgramError> {
+        let mints = self.accounts.mints.ok_or(ProgramError::NotEnoughAccountKeys)?;
+        if mints.mint_x.key() != &self.instruction_data.mint_x || mints.mint_y.key() != &self.instruction_data.mint_y {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        MintInterface::check_with_program(mints.mint_x, self.accounts.token_program)?;
+        MintInterface::check_with_program(mints.mint_y, self.accounts.token_program)?;
+        Ok((MintInterface::get(mints.mint_x)?.decimals(), MintInterface::get(mints.mint_y)?.decimals()))
+    }
+
+    /// 创建 (mint_x, mint_y, fee) 对应的 PoolRegistry PDA 并记录此池子；PDA 已存在说明组合已被占用
+    fn register_pool(&self, fee: u16) -> ProgramResult {
+        let registry = self.accounts.pool_registry.ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+        let (mint_lo, mint_hi) =
+            PoolRegistry::sorted_mints(&self.instruction_data.mint_x, &self.instruction_data.mint_y);
+        let fee_binding = fee.to_le_bytes();
+        let (expected, bump) = find_program_address(
+            &[b"registry", mint_lo.as_ref(), mint_hi.as_ref(), fee_binding.as_ref()],
+            &crate::ID,
+        );
+        if registry.key() != &expected {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        // 只看 owner 和长度：预先转入 lamports 的 registry 仍由 `ProgramAccount::init` 正常创建，
+        // 否则任何人转 1 lamport 就能永久占住这组 mint + 费率
+        if ProgramAccount::is_created(registry, PoolRegistry::LEN) {
+            return Err(AmmError::PoolAlreadyExists.into());
+        }
+
+        let bump_binding = [bump];
+        let registry_seeds = registry_seeds_from_parts(mint_lo, mint_hi, &fee_binding, &bump_binding);
+        ProgramAccount::init::<PoolRegistry>(
+            self.accounts.initializer,
+            registry,
+            &registry_seeds[..],
+            PoolRegistry::LEN,
+        )?;
+
+        let mut data = registry.try_borrow_mut_data()?;
+        unsafe { PoolRegistry::load_mut_unchecked(data.as_mut())? }
+            .set_inner(*self.accounts.config.key(), bump_binding);
+
         Ok(())
     }
 }
@@ -90,6 +200,13 @@ pub struct InitializeAccounts<'a> {
 
     pub system_program: &'a AccountInfo,
     pub token_program: &'a AccountInfo,
+    /// Token-2022 池子必须在固定账户之后传入 mint_x / mint_y，用于记录 TransferChecked 的精度
+    pub mints: Option<PoolMints<'a>>,
+    /// GlobalConfig PDA，总是必须传入（见 `GlobalConfig::load_if_initialized`）；
+    /// InitializeAndSeed 在解析完自己的账户后才补上，所以这里是 Option，缺少时在 process 中报错
+    pub global_config: Option<&'a AccountInfo>,
+    /// 可选的末尾账户（在 GlobalConfig 之后）：GlobalConfig::enforce_unique 打开时必须传入 PoolRegistry PDA
+    pub pool_registry: Option<&'a AccountInfo>,
 }
 
 impl<'a> TryFrom<&'a [AccountInfo]> for InitializeAccounts<'a> {
@@ -103,6 +220,7 @@ impl<'a> TryFrom<&'a [AccountInfo]> for InitializeAccounts<'a> {
             system_program,
             token_program,
             _,
+            rest @ ..
         ] = accounts
         else {
             return Err(ProgramError::NotEnoughAccountKeys);
@@ -117,12 +235,23 @@ impl<'a> TryFrom<&'a [AccountInfo]> for InitializeAccounts<'a> {
         // 同样地，如果传递的 config 账户具有无效的 mint_x 或 mint_y，任何尝试向协议中存入资金的操作都会在代币转移期间失败。
         // 随着经验的积累，您会发现可以省略许多检查，以保持指令的轻量化和优化，依赖系统和下游指令来强制执行约束
 
+        // InitializeAndSeed 只传入前 6 个账户，mints 由它在之后补上，所以这里缺少时不报错，留到 process 检查
+        let (mints, rest) = match rest {
+            [mint_x, mint_y, rest @ ..] if token_program.key() == &SPL_TOKEN_2022_ID => {
+                (Some(PoolMints { mint_x, mint_y }), rest)
+            }
+            _ => (None, rest),
+        };
+
         Ok(Self {
             initializer,
             mint_lp,
             config,
             system_program,
             token_program,
+            mints,
+            global_config: rest.first(),
+            pool_registry: rest.get(1),
         })
     }
 }
@@ -136,33 +265,51 @@ pub struct InitializeInstructionData {
     pub config_bump: [u8; 1],
     pub lp_bump: [u8; 1],
     pub authority: [u8; 32],
+    /// 1 = LP 不可转让（需要 Token-2022），0 = 普通 LP
+    pub soulbound: u8,
+    /// LP mint 的精度，缺省为 LP_DECIMALS
+    pub lp_decimals: u8,
+    /// X 一侧的权重（基点），缺省为 EQUAL_WEIGHT_BPS，例如 8_000 为 80/20 池子
+    pub weight_x: u16,
 }
 
 impl TryFrom<&[u8]> for InitializeInstructionData {
     type Error = ProgramError;
 
     fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
-        const INITIALIZE_DATA_LEN_WITH_AUTHORITY: usize = size_of::<InitializeInstructionData>();
+        const INITIALIZE_DATA_LEN_WITH_WEIGHT: usize = size_of::<InitializeInstructionData>();
+        const INITIALIZE_DATA_LEN_WITH_LP_DECIMALS: usize = INITIALIZE_DATA_LEN_WITH_WEIGHT - size_of::<u16>();
+        const INITIALIZE_DATA_LEN_WITH_SOULBOUND: usize =
+            INITIALIZE_DATA_LEN_WITH_LP_DECIMALS - size_of::<u8>();
+        const INITIALIZE_DATA_LEN_WITH_AUTHORITY: usize =
+            INITIALIZE_DATA_LEN_WITH_SOULBOUND - size_of::<u8>();
         const INITIALIZE_DATA_LEN: usize =
             INITIALIZE_DATA_LEN_WITH_AUTHORITY - size_of::<[u8; 32]>();
 
         match data.len() {
-            INITIALIZE_DATA_LEN_WITH_AUTHORITY => {
-                Ok(unsafe { (data.as_ptr() as *const Self).read_unaligned() })
-            }
-            INITIALIZE_DATA_LEN => {
-                // If the authority is not present, we need to build the buffer and add it at the end before transmuting to the struct
-                let mut raw: MaybeUninit<[u8; INITIALIZE_DATA_LEN_WITH_AUTHORITY]> =
-                    MaybeUninit::uninit();
-                let raw_ptr = raw.as_mut_ptr() as *mut u8;
-                unsafe {
-                    // Copy the provided data
-                    core::ptr::copy_nonoverlapping(data.as_ptr(), raw_ptr, INITIALIZE_DATA_LEN);
-                    // Add the authority to the end of the buffer
-                    core::ptr::write_bytes(raw_ptr.add(INITIALIZE_DATA_LEN), 0, 32);
-                    // Now transmute to the struct
-                    Ok((raw.as_ptr() as *const Self).read_unaligned())
+            INITIALIZE_DATA_LEN
+            | INITIALIZE_DATA_LEN_WITH_AUTHORITY
+            | INITIALIZE_DATA_LEN_WITH_SOULBOUND
+            | INITIALIZE_DATA_LEN_WITH_LP_DECIMALS
+            | INITIALIZE_DATA_LEN_WITH_WEIGHT => {
+                // authority / soulbound / lp_decimals / weight_x 是可选的尾部字段，缺省部分补零后再 transmute 成结构体
+                let mut raw = [0u8; INITIALIZE_DATA_LEN_WITH_WEIGHT];
+                raw[..data.len()].copy_from_slice(data);
+                // lp_decimals 缺省为 LP_DECIMALS 而不是 0
+                if data.len() < INITIALIZE_DATA_LEN_WITH_LP_DECIMALS {
+                    raw[INITIALIZE_DATA_LEN_WITH_SOULBOUND] = LP_DECIMALS;
                 }
+                // weight_x 缺省为 50/50
+                if data.len() < INITIALIZE_DATA_LEN_WITH_WEIGHT {
+                    raw[INITIALIZE_DATA_LEN_WITH_LP_DECIMALS..].copy_from_slice(&EQUAL_WEIGHT_BPS.to_le_bytes());
+                }
+                let instruction_data = unsafe { (raw.as_ptr() as *const Self).read_unaligned() };
+
+                if instruction_data.soulbound > 1 {
+                    return Err(ProgramError::InvalidInstructionData);
+                }
+
+                Ok(instruction_data)
             }
             _ => Err(ProgramError::InvalidInstructionData),
         }
@@ -0,0 +1,204 @@
+use crate::state::Config;
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program_error::ProgramError,
+};
+
+use super::helpers::*;
+use super::{DepositAccounts, Initialize};
+
+/// Initialize 的固定账户数量（末尾的占位账户为 Associated Token Program）
+const INITIALIZE_ACCOUNTS_LEN: usize = 6;
+
+/// 创建池子并在同一条指令中完成首次存款，池子不会停留在“已初始化但没有储备”的状态
+///
+/// 首次存款与独立的 Deposit 走同一条路径：LP 由 `first_deposit_lp` 计算（sqrt(x*y)，锁定 MINIMUM_LIQUIDITY），
+/// 转账和铸造由 `DepositAccounts::deposit_and_mint` 执行。
+///
+/// 账户布局：前 6 个账户与 Initialize 的固定账户完全相同（最后一个为 Associated Token Program），之后依次为
+/// mint_x, mint_y, vault_x, vault_y, user_x_ata, user_y_ata, user_lp_ata，
+/// 以及与 Initialize 相同的末尾账户 GlobalConfig PDA（必须传入）和可选的 PoolRegistry
+///
+/// 指令数据：seed_x (u64) + seed_y (u64) + Initialize 的指令数据
+pub struct InitializeAndSeed<'a> {
+    pub initialize: Initialize<'a>,
+    pub accounts: InitializeAndSeedAccounts<'a>,
+    pub seed_x: u64,
+    pub seed_y: u64,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for InitializeAndSeed<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        if data.len() < 16 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let seed_x = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let seed_y = u64::from_le_bytes(data[8..16].try_into().unwrap());
+        if seed_x == 0 || seed_y == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        if accounts.len() < INITIALIZE_ACCOUNTS_LEN {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        let (initialize_accounts, seed_accounts) = accounts.split_at(INITIALIZE_ACCOUNTS_LEN);
+
+        let accounts = InitializeAndSeedAccounts::try_from(seed_accounts)?;
+        let mut initialize = Initialize::try_from((&data[16..], initialize_accounts))?;
+        initialize.accounts.global_config = accounts.global_config;
+        initialize.accounts.pool_registry = accounts.pool_registry;
+        initialize.accounts.mints = Some(accounts.mints());
+
+        // 传入的 mint 必须与池子配置一致
+        if accounts.mint_x.key() != &initialize.instruction_data.mint_x
+            || accounts.mint_y.key() != &initialize.instruction_data.mint_y
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(Self {
+            initialize,
+            accounts,
+            seed_x,
+            seed_y,
+        })
+    }
+}
+
+impl<'a> InitializeAndSeed<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &6;
+
+    pub fn process(&mut self) -> ProgramResult {
+        //Populate the config account
+        self.initialize.process()?;
+
+        let init = &self.initialize.accounts;
+        let accounts = &self.accounts;
+
+        // 创建金库（config 的 ATA）和用户的 LP ATA
+        AssociatedTokenAccount::init_if_needed(
+            accounts.vault_x,
+            accounts.mint_x,
+            init.initializer,
+            init.config,
+            init.system_program,
+            init.token_program,
+        )?;
+        AssociatedTokenAccount::init_if_needed(
+            accounts.vault_y,
+            accounts.mint_y,
+            init.initializer,
+            init.config,
+            init.system_program,
+            init.token_program,
+        )?;
+        AssociatedTokenAccount::init_if_needed(
+            accounts.user_lp_ata,
+            init.mint_lp,
+            init.initializer,
+            init.initializer,
+            init.system_program,
+            init.token_program,
+        )?;
+
+        // init_if_needed 跳过已存在的账户，这里统一检查地址和归属
+        AssociatedTokenAccount::check(accounts.vault_x, init.config, accounts.mint_x, init.token_program)?;
+        AssociatedTokenAccount::check(accounts.vault_y, init.config, accounts.mint_y, init.token_program)?;
+        TokenAccountInterface::check_no_external_close_authority(accounts.vault_x, init.config.key())?;
+        TokenAccountInterface::check_no_external_close_authority(accounts.vault_y, init.config.key())?;
+        TokenAccountInterface::check_owner_and_mint(
+            accounts.user_x_ata,
+            init.initializer.key(),
+            accounts.mint_x.key(),
+        )?;
+        TokenAccountInterface::check_owner_and_mint(
+            accounts.user_y_ata,
+            init.initializer.key(),
+            accounts.mint_y.key(),
+        )?;
+
+        let config = Config::load_checked(init.config)?;
+
+        let deposit_accounts = DepositAccounts {
+            user: init.initializer,
+            mint_lp: init.mint_lp,
+            vault_x: accounts.vault_x,
+            vault_y: accounts.vault_y,
+            user_x_ata: accounts.user_x_ata,
+            user_y_ata: accounts.user_y_ata,
+            user_lp_ata: accounts.user_lp_ata,
+            config: init.config,
+            token_program: init.token_program,
+            // Initialize::process 已经要求传入 GlobalConfig PDA，这里传同一个账户
+            global_config: init.global_config.ok_or(ProgramError::NotEnoughAccountKeys)?,
+            system_program: init.system_program,
+            // Token-2022 池子在 Initialize 中已打开 require_checked_transfers
+            mints: Some(accounts.mints()),
+            // 新池子的 anti-JIT 冷却期默认关闭
+            position: None,
+        };
+
+        // 新池子的快照从 0 开始，创建前就转入金库的代币不计入储备
+        let reserves = PoolReserves::load(&config, accounts.vault_x, accounts.vault_y)?;
+
+        // 与 Deposit 的首次存款分支完全相同
+        let lp = first_deposit_lp(self.seed_x, self.seed_y)?;
+        deposit_accounts.deposit_and_mint(&config, self.seed_x, self.seed_y, lp)?;
+
+        // 存款前储备为 0，不会累加价格，只把 TWAP 的起始时间记为现在
+        drop(config);
+        reserves.sync(init.config, accounts.vault_x, accounts.vault_y)?;
+        update_price_cumulative(init.config, 0, 0)
+    }
+}
+
+pub struct InitializeAndSeedAccounts<'a> {
+    pub mint_x: &'a AccountInfo,
+    pub mint_y: &'a AccountInfo,
+    pub vault_x: &'a AccountInfo,
+    pub vault_y: &'a AccountInfo,
+    pub user_x_ata: &'a AccountInfo,
+    pub user_y_ata: &'a AccountInfo,
+    pub user_lp_ata: &'a AccountInfo,
+    pub global_config: Option<&'a AccountInfo>,
+    pub pool_registry: Option<&'a AccountInfo>,
+}
+
+impl<'a> InitializeAndSeedAccounts<'a> {
+    #[inline(always)]
+    pub fn mints(&self) -> PoolMints<'a> {
+        PoolMints {
+            mint_x: self.mint_x,
+            mint_y: self.mint_y,
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for InitializeAndSeedAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [mint_x, mint_y, vault_x, vault_y, user_x_ata, user_y_ata, user_lp_ata, rest @ ..] = accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        MintInterface::check(mint_x)?;
+        MintInterface::check(mint_y)?;
+
+        Ok(Self {
+            mint_x,
+            mint_y,
+            vault_x,
+            vault_y,
+            user_x_ata,
+            user_y_ata,
+            user_lp_ata,
+            global_config: rest.first(),
+            pool_registry: rest.get(1),
+        })
+    }
+}
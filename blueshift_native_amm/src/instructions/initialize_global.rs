@@ -0,0 +1,110 @@
+use crate::state::{GlobalConfig, global_seeds_from_parts};
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program_error::ProgramError,
+};
+
+use super::helpers::*;
+
+/// 初始化程序级别的 GlobalConfig PDA（seeds = [b"global"]）
+///
+/// 只能执行一次：账户已存在时 CreateAccount 会失败，这里提前用 check_uninitialized 给出明确的错误。
+/// 只有程序的 upgrade authority 可以执行（由 ProgramData 账户证明），防止部署后被抢先初始化；
+/// 签名的 upgrade authority 成为 superadmin。
+pub struct InitializeGlobal<'a> {
+    pub accounts: InitializeGlobalAccounts<'a>,
+    pub instruction_data: InitializeGlobalInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for InitializeGlobal<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = InitializeGlobalAccounts::try_from(accounts)?;
+        let instruction_data = InitializeGlobalInstructionData::try_from(data)?;
+
+        ProgramAccount::check_uninitialized(accounts.global_config)?;
+
+        let global_seeds = global_seeds_from_parts(&instruction_data.bump);
+        ProgramAccount::init::<GlobalConfig>(
+            accounts.superadmin,
+            accounts.global_config,
+            &global_seeds[..],
+            GlobalConfig::LEN,
+        )?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> InitializeGlobal<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &4;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut data = self.accounts.global_config.try_borrow_mut_data()?;
+        let global = unsafe { GlobalConfig::load_mut_unchecked(data.as_mut())? };
+
+        global.set_inner(
+            *self.accounts.superadmin.key(),
+            self.instruction_data.default_fee,
+            self.instruction_data.bump,
+        )?;
+
+        Ok(())
+    }
+}
+
+pub struct InitializeGlobalAccounts<'a> {
+    pub superadmin: &'a AccountInfo,
+    pub global_config: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub program_data: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for InitializeGlobalAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [superadmin, global_config, system_program, program_data] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SignerAccount::check(superadmin)?;
+        SystemAccount::check_program(system_program)?;
+        ProgramDataAccount::check_upgrade_authority(program_data, superadmin.key())?;
+
+        Ok(Self {
+            superadmin,
+            global_config,
+            system_program,
+            program_data,
+        })
+    }
+}
+
+pub struct InitializeGlobalInstructionData {
+    pub default_fee: u16,
+    pub bump: [u8; 1],
+}
+
+impl<'a> TryFrom<&'a [u8]> for InitializeGlobalInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        // default_fee (2) + bump (1)
+        const INITIALIZE_GLOBAL_DATA_LEN: usize = 3;
+
+        if data.len() != INITIALIZE_GLOBAL_DATA_LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let default_fee = u16::from_le_bytes(data[0..2].try_into().unwrap());
+        let bump = [data[2]];
+
+        Ok(Self { default_fee, bump })
+    }
+}
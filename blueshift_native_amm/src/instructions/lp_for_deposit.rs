@@ -0,0 +1,117 @@
+use crate::curve::Curve;
+use crate::state::{AmmState, Config};
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program::set_return_data,
+    program_error::ProgramError,
+};
+
+use super::helpers::*;
+
+/// 只读：按当前储备计算最多存入 `x`/`y` 时可铸造的 LP 数量，通过 return data 返回
+///
+/// 与 Deposit 使用同一套计算，不会转账或铸造：
+/// - 空池（supply 和两侧储备都为 0）：LP = `first_deposit_lp(x, y)`，x/y 全部存入
+/// - 否则：LP 取两侧按比例计算的较小值，实际存入量为 `deposit_amounts(lp)`（向上取整，不超过 x/y）
+///
+/// 客户端可以直接用返回的 lp 作为 Deposit 的 amount，x/y 作为 max_x/max_y。
+///
+/// return data 布局：lp (u64 LE) + amount_x (u64 LE) + amount_y (u64 LE)
+pub struct LpForDeposit<'a> {
+    pub accounts: LpForDepositAccounts<'a>,
+    pub x: u64,
+    pub y: u64,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for LpForDeposit<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = LpForDepositAccounts::try_from(accounts)?;
+
+        // x (8) + y (8)
+        if data.len() != 16 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let x = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let y = u64::from_le_bytes(data[8..16].try_into().unwrap());
+        if x == 0 || y == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { accounts, x, y })
+    }
+}
+
+impl<'a> LpForDeposit<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &11;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let config = Config::load_checked(self.accounts.config)?;
+        match config.amm_state()? {
+            AmmState::Initialized => {}
+            AmmState::Uninitialized | AmmState::Disabled | AmmState::WithdrawOnly => {
+                return Err(ProgramError::InvalidAccountData);
+            }
+        }
+
+        // 金库和 LP mint 必须属于这个池子（LP 的 mint authority 是 config PDA）
+        TokenAccountInterface::check_owner_and_mint(self.accounts.vault_x, self.accounts.config.key(), config.mint_x())?;
+        TokenAccountInterface::check_owner_and_mint(self.accounts.vault_y, self.accounts.config.key(), config.mint_y())?;
+        let mint_lp = MintInterface::get(self.accounts.mint_lp)?;
+        if mint_lp.mint_authority() != Some(self.accounts.config.key()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // 与 Deposit 一致，按储备快照计算
+        let reserves = PoolReserves::load(&config, self.accounts.vault_x, self.accounts.vault_y)?;
+        let (vault_x, vault_y) = (reserves.x, reserves.y);
+
+        // 与 Deposit 的分支条件一致
+        let (lp, x, y) = match mint_lp.supply() == 0 && vault_x == 0 && vault_y == 0 {
+            true => (first_deposit_lp(self.x, self.y)?, self.x, self.y),
+            false => {
+                let curve = Curve::for_liquidity(vault_x, vault_y, mint_lp.supply())?;
+                let lp = curve.lp_for_amounts(self.x, self.y)?;
+                let (x, y) = match lp {
+                    0 => (0, 0),
+                    lp => curve.deposit_amounts(lp)?,
+                };
+                (lp, x, y)
+            }
+        };
+
+        let mut return_data = [0u8; 24];
+        return_data[0..8].copy_from_slice(&lp.to_le_bytes());
+        return_data[8..16].copy_from_slice(&x.to_le_bytes());
+        return_data[16..24].copy_from_slice(&y.to_le_bytes());
+        set_return_data(&return_data);
+
+        Ok(())
+    }
+}
+
+pub struct LpForDepositAccounts<'a> {
+    pub config: &'a AccountInfo,
+    pub mint_lp: &'a AccountInfo,
+    pub vault_x: &'a AccountInfo,
+    pub vault_y: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for LpForDepositAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [config, mint_lp, vault_x, vault_y, _] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            config,
+            mint_lp,
+            vault_x,
+            vault_y,
+        })
+    }
+}
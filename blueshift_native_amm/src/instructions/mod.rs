@@ -2,10 +2,44 @@ pub mod initialize;
 pub mod deposit;
 pub mod withdraw;
 pub mod swap;
+pub mod initialize_global;
+pub mod set_global_config;
+pub mod initialize_and_seed;
+pub mod quote;
+pub mod set_config;
+pub mod simulate_withdraw;
+pub mod total_value_locked;
+pub mod lp_for_deposit;
+pub mod observe_price;
+pub mod withdraw_single_sided;
+pub mod set_state;
+pub mod deposit_single_sided;
+pub mod flash_swap;
+pub mod quote_swap;
+pub mod swap_route;
+pub mod collect_fees;
+pub mod disable_pool;
 pub mod helpers;
 
 pub use initialize::*;
 pub use deposit::*;
 pub use withdraw::*;
 pub use swap::*;
+pub use initialize_global::*;
+pub use set_global_config::*;
+pub use initialize_and_seed::*;
+pub use quote::*;
+pub use set_config::*;
+pub use simulate_withdraw::*;
+pub use total_value_locked::*;
+pub use lp_for_deposit::*;
+pub use observe_price::*;
+pub use withdraw_single_sided::*;
+pub use set_state::*;
+pub use deposit_single_sided::*;
+pub use flash_swap::*;
+pub use quote_swap::*;
+pub use swap_route::*;
+pub use collect_fees::*;
+pub use disable_pool::*;
 pub use helpers::*;
\ No newline at end of file
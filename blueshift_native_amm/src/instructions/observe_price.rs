@@ -0,0 +1,89 @@
+use crate::state::{AmmState, Config};
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program::set_return_data,
+    program_error::ProgramError,
+};
+
+use super::helpers::*;
+
+/// 先把 TWAP 累加器推进到当前时间，再通过 return data 返回一次价格观测
+///
+/// 外部合约取两次观测即可计算 TWAP，无需反序列化 Config：
+/// `(price_cumulative_2 - price_cumulative_1) / (ts_2 - ts_1)`（累加值可能回绕，用 wrapping_sub）
+///
+/// return data 布局：price_cumulative_x (u128 LE) + price_cumulative_y (u128 LE) + last_update_ts (i64 LE)
+pub struct ObservePrice<'a> {
+    pub accounts: ObservePriceAccounts<'a>,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for ObservePrice<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        if !data.is_empty() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let accounts = ObservePriceAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> ObservePrice<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &12;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let accounts = &self.accounts;
+
+        let (reserve_x, reserve_y) = {
+            let config = Config::load_checked(accounts.config)?;
+            match config.amm_state()? {
+                AmmState::Initialized | AmmState::Disabled | AmmState::WithdrawOnly => {}
+                AmmState::Uninitialized => return Err(ProgramError::InvalidAccountData),
+            }
+
+            // 金库必须属于这个池子，否则可以用伪造的储备污染累加器
+            TokenAccountInterface::check_owner_and_mint(accounts.vault_x, accounts.config.key(), config.mint_x())?;
+            TokenAccountInterface::check_owner_and_mint(accounts.vault_y, accounts.config.key(), config.mint_y())?;
+
+            // 按储备快照累加，直接转入金库的代币不能推动 TWAP
+            let reserves = PoolReserves::load(&config, accounts.vault_x, accounts.vault_y)?;
+            (reserves.x, reserves.y)
+        };
+
+        update_price_cumulative(accounts.config, reserve_x, reserve_y)?;
+
+        let config = Config::load_checked(accounts.config)?;
+        let mut return_data = [0u8; 40];
+        return_data[0..16].copy_from_slice(&config.price_cumulative_x().to_le_bytes());
+        return_data[16..32].copy_from_slice(&config.price_cumulative_y().to_le_bytes());
+        return_data[32..40].copy_from_slice(&config.last_update_ts().to_le_bytes());
+        set_return_data(&return_data);
+
+        Ok(())
+    }
+}
+
+pub struct ObservePriceAccounts<'a> {
+    pub config: &'a AccountInfo,
+    pub vault_x: &'a AccountInfo,
+    pub vault_y: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for ObservePriceAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [config, vault_x, vault_y, _] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            config,
+            vault_x,
+            vault_y,
+        })
+    }
+}
@@ -0,0 +1,120 @@
+use crate::curve::Curve;
+use crate::state::{AmmState, Config};
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program::set_return_data,
+    program_error::ProgramError,
+};
+
+use super::helpers::*;
+
+/// 只读报价：按当前储备计算一次兑换的输出数量和价格影响，通过 return data 返回
+///
+/// return data 布局：amount_out (u64 LE) + price_impact_bps (u16 LE)
+pub struct Quote<'a> {
+    pub accounts: QuoteAccounts<'a>,
+    pub instruction_data: QuoteInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Quote<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = QuoteAccounts::try_from(accounts)?;
+        let instruction_data = QuoteInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> Quote<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &7;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let config = Config::load_checked(self.accounts.config)?;
+        match config.amm_state()? {
+            AmmState::Initialized => {}
+            AmmState::Uninitialized | AmmState::Disabled | AmmState::WithdrawOnly => {
+                return Err(ProgramError::InvalidAccountData);
+            }
+        }
+
+        // 金库必须属于这个池子
+        TokenAccountInterface::check_owner_and_mint(self.accounts.vault_x, self.accounts.config.key(), config.mint_x())?;
+        TokenAccountInterface::check_owner_and_mint(self.accounts.vault_y, self.accounts.config.key(), config.mint_y())?;
+
+        // 与 Swap 一致，按储备快照计价
+        let reserves = PoolReserves::load(&config, self.accounts.vault_x, self.accounts.vault_y)?;
+        let curve = Curve::for_weighted_swap(
+            reserves.x,
+            reserves.y,
+            self.instruction_data.is_x,
+            config.fee(),
+            config.weight_x(),
+        );
+
+        // 与 Swap 一致：协议费部分不进入曲线
+        let (_, amount) = split_protocol_fee(self.instruction_data.amount, config.protocol_fee())?;
+        let amount_out = curve.amount_out(amount)?;
+        let impact = curve.price_impact_bps(amount)?;
+
+        let mut return_data = [0u8; 10];
+        return_data[0..8].copy_from_slice(&amount_out.to_le_bytes());
+        return_data[8..10].copy_from_slice(&impact.to_le_bytes());
+        set_return_data(&return_data);
+
+        Ok(())
+    }
+}
+
+pub struct QuoteAccounts<'a> {
+    pub config: &'a AccountInfo,
+    pub vault_x: &'a AccountInfo,
+    pub vault_y: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for QuoteAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [config, vault_x, vault_y, _] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            config,
+            vault_x,
+            vault_y,
+        })
+    }
+}
+
+pub struct QuoteInstructionData {
+    pub is_x: bool,
+    pub amount: u64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for QuoteInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        // is_x (1) + amount (8)
+        const QUOTE_DATA_LEN: usize = 9;
+
+        if data.len() != QUOTE_DATA_LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let is_x = data[0] == 1;
+        let amount = u64::from_le_bytes(data[1..9].try_into().unwrap());
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { is_x, amount })
+    }
+}
@@ -0,0 +1,129 @@
+use crate::curve::Curve;
+use crate::errors::AmmError;
+use crate::state::{AmmState, Config};
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program::set_return_data,
+    program_error::ProgramError,
+};
+
+use super::helpers::*;
+
+/// 只读报价：不需要任何 token account，返回一次兑换进入金库和离开金库的数量
+///
+/// return data 布局（16 字节，小端）：
+/// - `[0..8]`  deposit (u64)：进入输入侧金库的数量，即 amount 扣除协议费之后的部分
+/// - `[8..16]` withdraw (u64)：从输出侧金库转给用户的数量
+///
+/// 与 `Quote` 相同，这里不修改任何账户，三个账户都可以只读传入
+pub struct QuoteSwap<'a> {
+    pub accounts: QuoteSwapAccounts<'a>,
+    pub instruction_data: QuoteSwapInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for QuoteSwap<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = QuoteSwapAccounts::try_from(accounts)?;
+        let instruction_data = QuoteSwapInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> QuoteSwap<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &17;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let config = Config::load_checked(self.accounts.config)?;
+        match config.amm_state()? {
+            AmmState::Initialized => {}
+            AmmState::Uninitialized | AmmState::Disabled | AmmState::WithdrawOnly => {
+                return Err(ProgramError::InvalidAccountData);
+            }
+        }
+
+        // 金库必须属于这个池子
+        TokenAccountInterface::check_owner_and_mint(self.accounts.vault_x, self.accounts.config.key(), config.mint_x())?;
+        TokenAccountInterface::check_owner_and_mint(self.accounts.vault_y, self.accounts.config.key(), config.mint_y())?;
+
+        // 与 Swap 一致，按储备快照计价
+        let reserves = PoolReserves::load(&config, self.accounts.vault_x, self.accounts.vault_y)?;
+        let curve = Curve::for_weighted_swap(
+            reserves.x,
+            reserves.y,
+            self.instruction_data.is_x,
+            config.fee(),
+            config.weight_x(),
+        );
+
+        // 与 Swap 一致：协议费直接转给 treasury，不进入金库也不进入曲线
+        let (_, deposit) = split_protocol_fee(self.instruction_data.amount, config.protocol_fee())?;
+        let withdraw = curve.amount_out(deposit)?;
+
+        let mut return_data = [0u8; 16];
+        return_data[0..8].copy_from_slice(&deposit.to_le_bytes());
+        return_data[8..16].copy_from_slice(&withdraw.to_le_bytes());
+        set_return_data(&return_data);
+
+        Ok(())
+    }
+}
+
+pub struct QuoteSwapAccounts<'a> {
+    pub config: &'a AccountInfo,
+    pub vault_x: &'a AccountInfo,
+    pub vault_y: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for QuoteSwapAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [config, vault_x, vault_y, ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            config,
+            vault_x,
+            vault_y,
+        })
+    }
+}
+
+pub struct QuoteSwapInstructionData {
+    /// true 时输入 X，否则输入 Y
+    pub is_x: bool,
+    pub amount: u64,
+}
+
+impl<'a> TryFrom<&'a [u8]> for QuoteSwapInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        // is_x (1) + amount (8)
+        const QUOTE_SWAP_DATA_LEN: usize = 9;
+
+        if data.len() != QUOTE_SWAP_DATA_LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let is_x = match data[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+        let amount = u64::from_le_bytes(data[1..9].try_into().unwrap());
+        if amount == 0 {
+            return Err(AmmError::ZeroAmount.into());
+        }
+
+        Ok(Self { is_x, amount })
+    }
+}
@@ -0,0 +1,206 @@
+use crate::errors::AmmError;
+use crate::events::FeeUpdatedEvent;
+use crate::state::{AmmState, Config, ConfigRole, EXIT_FEE_DISABLED, JIT_COOLDOWN_DISABLED, MAX_FEE_BPS};
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::{Pubkey, find_program_address},
+};
+
+use super::helpers::*;
+
+/// 修改池子 Config 中的某一个参数，必须由该参数对应的角色签名（见 `SetConfigInstructionData::role`）
+pub struct SetConfig<'a> {
+    pub accounts: SetConfigAccounts<'a>,
+    pub instruction_data: SetConfigInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for SetConfig<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = SetConfigAccounts::try_from(accounts)?;
+        let instruction_data = SetConfigInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SetConfig<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &8;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut config = Config::load_mut(self.accounts.config)?;
+        config.check_authority(self.accounts.authority, self.instruction_data.role())?;
+
+        match self.instruction_data {
+            SetConfigInstructionData::MaxSwapOut(max_swap_out) => {
+                config.set_max_swap_out(max_swap_out);
+                Ok(())
+            }
+            SetConfigInstructionData::Fee(fee) => {
+                if fee > MAX_FEE_BPS {
+                    return Err(AmmError::FeeTooHigh.into());
+                }
+                let old_fee = config.fee();
+                config.set_fee(fee)?;
+                FeeUpdatedEvent {
+                    pool: self.accounts.config.key(),
+                    user: self.accounts.authority.key(),
+                    old_fee,
+                    new_fee: fee,
+                }
+                .emit();
+                Ok(())
+            }
+            SetConfigInstructionData::State(state) => config.set_state(state),
+            SetConfigInstructionData::FeeAdmin(fee_admin) => {
+                config.set_fee_admin(fee_admin);
+                Ok(())
+            }
+            SetConfigInstructionData::PauseAdmin(pause_admin) => {
+                config.set_pause_admin(pause_admin);
+                Ok(())
+            }
+            SetConfigInstructionData::JitCooldownSlots(slots) => {
+                if slots != JIT_COOLDOWN_DISABLED {
+                    self.check_soulbound_lp()?;
+                }
+                config.set_jit_cooldown_slots(slots);
+                Ok(())
+            }
+            SetConfigInstructionData::ExitFee { bps, window_secs } => {
+                if bps != EXIT_FEE_DISABLED {
+                    self.check_soulbound_lp()?;
+                }
+                config.set_exit_fee(bps, window_secs)
+            }
+            SetConfigInstructionData::ProtocolFee { bps, treasury } => config.set_protocol_fee(bps, treasury),
+            SetConfigInstructionData::Authority(authority) => {
+                config.set_authority(authority);
+                Ok(())
+            }
+        }
+    }
+
+    /// 冷却期和退出费按存款的钱包记录仓位，LP 转到新钱包就能绕过，所以只能在 LP 不可转让（soulbound）的池子上开启
+    fn check_soulbound_lp(&self) -> ProgramResult {
+        let mint_lp = self.accounts.mint_lp.ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let (expected_mint_lp, _) =
+            find_program_address(&[b"mint_lp", self.accounts.config.key().as_ref()], &crate::ID);
+        if mint_lp.key() != &expected_mint_lp {
+            return Err(AmmError::InvalidLpMint.into());
+        }
+        if !MintInterface::is_non_transferable(mint_lp)? {
+            return Err(AmmError::LpNotSoulbound.into());
+        }
+        Ok(())
+    }
+}
+
+pub struct SetConfigAccounts<'a> {
+    pub authority: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    /// 可选的末尾账户：开启 JitCooldownSlots / ExitFee 时必须传入池子的 LP mint
+    pub mint_lp: Option<&'a AccountInfo>,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for SetConfigAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [authority, config, _, rest @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SignerAccount::check(authority)?;
+        ProgramAccount::check_writable(config)?;
+
+        Ok(Self { authority, config, mint_lp: rest.first() })
+    }
+}
+
+/// 指令数据布局：[param: u8][value]
+/// - 0 = MaxSwapOut，value 为 u64 (LE)，0 表示不限制（pause_admin）
+/// - 1 = Fee，value 为 u16 (LE)，不能超过 MAX_FEE_BPS（fee_admin）
+/// - 2 = State，value 为 u8，只能是 Initialized（恢复）或 WithdrawOnly（暂停，只允许取款）；Disabled 由 DisablePool 设置（pause_admin）
+/// - 3 = FeeAdmin，value 为 32 字节公钥（owner）
+/// - 4 = PauseAdmin，value 为 32 字节公钥（owner）
+/// - 5 = JitCooldownSlots，value 为 u64 (LE)，0 表示关闭 anti-JIT 冷却期（fee_admin）
+/// - 6 = ExitFee，value 为 bps: u16 (LE) + window_secs: u64 (LE)，bps 为 0 表示关闭退出费（fee_admin）
+///
+///   5 / 6 设置为非 0 时池子必须使用 soulbound LP，并在账户末尾传入 mint_lp（见 `SetConfig::check_soulbound_lp`）
+/// - 7 = ProtocolFee，value 为 bps: u16 (LE) + treasury: 32 字节公钥，bps 为 0 表示关闭协议费（owner）
+/// - 8 = Authority，value 为新的 owner: 32 字节公钥 + renounce: u8（owner）。
+///   全零公钥表示放弃 owner，此后再也无法修改 owner 级别的参数，因此必须同时设置 renounce = 1 显式确认；
+///   renounce = 1 时公钥必须为全零。已分配的 fee_admin / pause_admin 不受影响
+pub enum SetConfigInstructionData {
+    MaxSwapOut(u64),
+    Fee(u16),
+    State(AmmState),
+    FeeAdmin(Pubkey),
+    PauseAdmin(Pubkey),
+    JitCooldownSlots(u64),
+    ExitFee { bps: u16, window_secs: u64 },
+    ProtocolFee { bps: u16, treasury: Pubkey },
+    Authority(Pubkey),
+}
+
+impl SetConfigInstructionData {
+    /// 修改该参数需要的角色
+    #[inline(always)]
+    pub fn role(&self) -> ConfigRole {
+        match self {
+            Self::MaxSwapOut(_) | Self::State(_) => ConfigRole::PauseAdmin,
+            Self::Fee(_) | Self::JitCooldownSlots(_) | Self::ExitFee { .. } => ConfigRole::FeeAdmin,
+            Self::FeeAdmin(_) | Self::PauseAdmin(_) | Self::ProtocolFee { .. } | Self::Authority(_) => {
+                ConfigRole::Owner
+            }
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for SetConfigInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        match data.split_first() {
+            Some((0, value)) if value.len() == 8 => Ok(Self::MaxSwapOut(u64::from_le_bytes(
+                value.try_into().unwrap(),
+            ))),
+            Some((1, value)) if value.len() == 2 => Ok(Self::Fee(u16::from_le_bytes(
+                value.try_into().unwrap(),
+            ))),
+            Some((2, [state])) => match AmmState::try_from(*state) {
+                // 与 SetState 相同，只在 Initialized 与 WithdrawOnly 之间切换，Disabled 由 DisablePool 设置
+                Ok(state @ (AmmState::Initialized | AmmState::WithdrawOnly)) => Ok(Self::State(state)),
+                _ => Err(ProgramError::InvalidInstructionData),
+            },
+            Some((3, value)) if value.len() == 32 => Ok(Self::FeeAdmin(value.try_into().unwrap())),
+            Some((4, value)) if value.len() == 32 => Ok(Self::PauseAdmin(value.try_into().unwrap())),
+            Some((5, value)) if value.len() == 8 => Ok(Self::JitCooldownSlots(u64::from_le_bytes(
+                value.try_into().unwrap(),
+            ))),
+            Some((6, value)) if value.len() == 10 => Ok(Self::ExitFee {
+                bps: u16::from_le_bytes(value[..2].try_into().unwrap()),
+                window_secs: u64::from_le_bytes(value[2..].try_into().unwrap()),
+            }),
+            Some((7, value)) if value.len() == 34 => Ok(Self::ProtocolFee {
+                bps: u16::from_le_bytes(value[..2].try_into().unwrap()),
+                treasury: value[2..].try_into().unwrap(),
+            }),
+            Some((8, value)) if value.len() == 33 => {
+                let authority: Pubkey = value[..32].try_into().unwrap();
+                match (authority == [0u8; 32], value[32]) {
+                    (false, 0) | (true, 1) => Ok(Self::Authority(authority)),
+                    _ => Err(ProgramError::InvalidInstructionData),
+                }
+            }
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use crate::state::GlobalConfig;
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+use super::helpers::*;
+
+/// 修改 GlobalConfig 中的某一个参数，必须由当前 superadmin 签名
+pub struct SetGlobalConfig<'a> {
+    pub accounts: SetGlobalConfigAccounts<'a>,
+    pub instruction_data: SetGlobalConfigInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for SetGlobalConfig<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = SetGlobalConfigAccounts::try_from(accounts)?;
+        let instruction_data = SetGlobalConfigInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SetGlobalConfig<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &5;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut global = GlobalConfig::load_mut(self.accounts.global_config)?;
+        global.check_superadmin(self.accounts.superadmin)?;
+
+        match self.instruction_data {
+            SetGlobalConfigInstructionData::DefaultFee(fee) => global.set_default_fee(fee),
+            SetGlobalConfigInstructionData::Superadmin(superadmin) => {
+                global.set_superadmin(superadmin)
+            }
+            SetGlobalConfigInstructionData::GraceSecs(grace_secs) => {
+                global.set_grace_secs(grace_secs)
+            }
+            SetGlobalConfigInstructionData::EnforceUnique(enforce_unique) => {
+                global.set_enforce_unique(enforce_unique)
+            }
+            SetGlobalConfigInstructionData::DownstreamProgram { index, program } => {
+                global.set_downstream_program(index, program)
+            }
+        }
+    }
+}
+
+pub struct SetGlobalConfigAccounts<'a> {
+    pub superadmin: &'a AccountInfo,
+    pub global_config: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for SetGlobalConfigAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [superadmin, global_config, _] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SignerAccount::check(superadmin)?;
+        ProgramAccount::check_writable(global_config)?;
+
+        Ok(Self {
+            superadmin,
+            global_config,
+        })
+    }
+}
+
+/// 指令数据布局：[param: u8][value]
+/// - 0 = DefaultFee，value 为 u16 (LE)
+/// - 1 = Superadmin，value 为 32 字节公钥
+/// - 2 = GraceSecs，value 为 u16 (LE)
+/// - 3 = EnforceUnique，value 为 u8（0 或 1）
+/// - 4 = DownstreamProgram，value 为 index: u8 + 32 字节程序地址，全零地址表示清空该槽位
+pub enum SetGlobalConfigInstructionData {
+    DefaultFee(u16),
+    Superadmin(Pubkey),
+    GraceSecs(u16),
+    EnforceUnique(bool),
+    DownstreamProgram { index: u8, program: Pubkey },
+}
+
+impl<'a> TryFrom<&'a [u8]> for SetGlobalConfigInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        match data.split_first() {
+            Some((0, value)) if value.len() == 2 => Ok(Self::DefaultFee(u16::from_le_bytes(
+                value.try_into().unwrap(),
+            ))),
+            Some((1, value)) if value.len() == 32 => {
+                Ok(Self::Superadmin(value.try_into().unwrap()))
+            }
+            Some((2, value)) if value.len() == 2 => Ok(Self::GraceSecs(u16::from_le_bytes(
+                value.try_into().unwrap(),
+            ))),
+            Some((3, [flag @ (0 | 1)])) => Ok(Self::EnforceUnique(*flag == 1)),
+            Some((4, [index, program @ ..])) if program.len() == 32 => Ok(Self::DownstreamProgram {
+                index: *index,
+                program: program.try_into().unwrap(),
+            }),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::errors::AmmError;
+use crate::state::{AmmState, Config, ConfigRole};
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program_error::ProgramError,
+};
+
+use super::helpers::*;
+
+/// 切换池子的运行状态，由 pause_admin 签名（默认即池子的 authority）
+///
+/// 出现事故时可以切到 WithdrawOnly：停止 swap / deposit，但 LP 仍然可以取款；Initialized 恢复正常。
+/// 只在 Initialized 与 WithdrawOnly 之间切换，下线（Disabled）只能由 DisablePool 完成
+pub struct SetState<'a> {
+    pub accounts: SetStateAccounts<'a>,
+    pub instruction_data: SetStateInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for SetState<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = SetStateAccounts::try_from(accounts)?;
+        let instruction_data = SetStateInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SetState<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &14;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let mut config = Config::load_mut(self.accounts.config)?;
+        config.check_authority(self.accounts.authority, ConfigRole::PauseAdmin)?;
+
+        // 尚未初始化的池子不能通过这里“激活”
+        if config.amm_state()? == AmmState::Uninitialized {
+            return Err(AmmError::InvalidAmmState.into());
+        }
+
+        config.set_state(self.instruction_data.state)
+    }
+}
+
+pub struct SetStateAccounts<'a> {
+    pub authority: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for SetStateAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [authority, config, _] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        SignerAccount::check(authority)?;
+        ProgramAccount::check_writable(config)?;
+
+        Ok(Self { authority, config })
+    }
+}
+
+/// 指令数据布局：[state: u8]，只能是 Initialized 或 WithdrawOnly
+pub struct SetStateInstructionData {
+    pub state: AmmState,
+}
+
+impl<'a> TryFrom<&'a [u8]> for SetStateInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        let [state] = data else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+
+        match AmmState::try_from(*state) {
+            Ok(state @ (AmmState::Initialized | AmmState::WithdrawOnly)) => Ok(Self { state }),
+            // Disabled 要求 LP 已全部赎回，只能由 DisablePool 设置
+            Ok(AmmState::Disabled) => Err(AmmError::InvalidStateTransition.into()),
+            _ => Err(AmmError::UnknownAmmState.into()),
+        }
+    }
+}
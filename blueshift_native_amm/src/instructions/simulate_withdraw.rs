@@ -0,0 +1,102 @@
+use crate::curve::Curve;
+use crate::state::{AmmState, Config};
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program::set_return_data,
+    program_error::ProgramError,
+};
+
+use super::helpers::*;
+
+/// 只读模拟取款：按当前储备和 LP supply 计算销毁 `amount` 份 LP 可取回的 x/y，通过 return data 返回
+///
+/// 与 Withdraw 使用同一条 `Curve::for_liquidity` 曲线，不会销毁或转账。
+/// - amount == 0 返回 (0, 0)
+/// - amount == supply 时仍按比例计算：锁定的 MINIMUM_LIQUIDITY 对应的储备留在金库中，不存在“全额取走”
+///
+/// return data 布局：amount_x (u64 LE) + amount_y (u64 LE)
+pub struct SimulateWithdraw<'a> {
+    pub accounts: SimulateWithdrawAccounts<'a>,
+    pub amount: u64,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for SimulateWithdraw<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = SimulateWithdrawAccounts::try_from(accounts)?;
+
+        // amount (8)
+        if data.len() != 8 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let amount = u64::from_le_bytes(data.try_into().unwrap());
+
+        Ok(Self { accounts, amount })
+    }
+}
+
+impl<'a> SimulateWithdraw<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &9;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let config = Config::load_checked(self.accounts.config)?;
+        // 只读视图：已禁用或只允许取款的池子仍可查询
+        match config.amm_state()? {
+            AmmState::Uninitialized => return Err(ProgramError::InvalidAccountData),
+            AmmState::Initialized | AmmState::Disabled | AmmState::WithdrawOnly => {}
+        }
+
+        // 金库和 LP mint 必须属于这个池子（LP 的 mint authority 是 config PDA）
+        TokenAccountInterface::check_owner_and_mint(self.accounts.vault_x, self.accounts.config.key(), config.mint_x())?;
+        TokenAccountInterface::check_owner_and_mint(self.accounts.vault_y, self.accounts.config.key(), config.mint_y())?;
+        let mint_lp = MintInterface::get(self.accounts.mint_lp)?;
+        if mint_lp.mint_authority() != Some(self.accounts.config.key()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // 不能销毁超过已铸造的 LP
+        if self.amount > mint_lp.supply() {
+            return Err(ProgramError::InsufficientFunds);
+        }
+
+        // 与 Withdraw 一致，按储备快照计算
+        let reserves = PoolReserves::load(&config, self.accounts.vault_x, self.accounts.vault_y)?;
+        let (x, y) = match self.amount {
+            0 => (0, 0),
+            amount => Curve::for_liquidity(reserves.x, reserves.y, mint_lp.supply())?.withdraw_amounts(amount)?,
+        };
+
+        let mut return_data = [0u8; 16];
+        return_data[0..8].copy_from_slice(&x.to_le_bytes());
+        return_data[8..16].copy_from_slice(&y.to_le_bytes());
+        set_return_data(&return_data);
+
+        Ok(())
+    }
+}
+
+pub struct SimulateWithdrawAccounts<'a> {
+    pub config: &'a AccountInfo,
+    pub mint_lp: &'a AccountInfo,
+    pub vault_x: &'a AccountInfo,
+    pub vault_y: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for SimulateWithdrawAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [config, mint_lp, vault_x, vault_y, _] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            config,
+            mint_lp,
+            vault_x,
+            vault_y,
+        })
+    }
+}
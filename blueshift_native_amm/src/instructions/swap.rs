@@ -1,21 +1,17 @@
-use crate::state::{AmmState, Config};
-use constant_product_curve::{ConstantProduct, LiquidityPair};
-use core::mem::size_of;
+use super::helpers::*;
+use crate::curve::Curve;
+use crate::errors::AmmError;
+use crate::events::SwapEvent;
+use crate::state::{AmmState, Config, DEFAULT_GRACE_SECS, GlobalConfig};
 use pinocchio::{
     ProgramResult,
     account_info::AccountInfo,
-    instruction::Signer,
     program_error::ProgramError,
-    sysvars::{Sysvar, clock::Clock},
-};
-use pinocchio_token::{
-    instructions::Transfer,
-    state::TokenAccount,
 };
 
 pub struct Swap<'a> {
     pub accounts: SwapAccounts<'a>,
-    pub instruction_data: SwapInstructionData,
+    pub instruction_data: SwapInstructionData<'a>,
 }
 
 impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for Swap<'a> {
@@ -36,122 +32,341 @@ impl<'a> Swap<'a> {
     pub const DISCRIMINATOR: &'a u8 = &3;
 
     pub fn process(&mut self) -> ProgramResult {
-        let config = Config::load(&self.accounts.config)?; //or load_unchecked ?
+        let config = self.accounts.pool.config;
+        with_pool_locked(config, || self.execute())
+    }
 
+    fn execute(&mut self) -> ProgramResult {
         let accounts = &self.accounts;
         let data = &self.instruction_data;
 
-        // （这个检测很重要） 验证用户已签名
-        if !accounts.user.is_signer() {
-            return Err(ProgramError::MissingRequiredSignature);
+        // 过期检查（允许全局配置中的 grace_secs 时钟误差；GlobalConfig 尚未创建时使用 DEFAULT_GRACE_SECS）
+        let global = GlobalConfig::load_if_initialized(accounts.global_config)?;
+        let grace_secs = global.as_ref().map_or(DEFAULT_GRACE_SECS, |global| global.grace_secs());
+        data.expiration_kind.check(data.expiration, grace_secs)?;
+
+        // 输出转交：下游程序必须在白名单中（没有 GlobalConfig 时白名单为空）；没有下游程序时不接受转发数据
+        match accounts.downstream_program {
+            Some(program) if !global.as_ref().is_some_and(|global| global.is_downstream_allowed(program.key())) => {
+                return Err(AmmError::DownstreamProgramNotAllowed.into());
+            }
+            None if !data.downstream_data.is_empty() => return Err(ProgramError::InvalidInstructionData),
+            _ => {}
         }
+        drop(global);
 
-        // 过期检查
-        let clock = Clock::get()?;
-        if clock.unix_timestamp > data.expiration {
-            return Err(ProgramError::InvalidArgument);
+        // wrap_sol 只对 WSOL 池子生效：输入是 WSOL 时先包装用户的 SOL，输出是 WSOL 时最后解包；
+        // 其它池子忽略这个标志
+        let (mint_in, mint_out) = accounts.pool.mints(data.is_x);
+        let wrap_in = data.wrap_sol && mint_in == NATIVE_MINT;
+        let unwrap_out = data.wrap_sol && mint_out == NATIVE_MINT;
+        if wrap_in {
+            SystemAccount::check_program(accounts.system_program)?;
+        }
+        // 解包会关闭 user_out，下游程序就拿不到输出了
+        if unwrap_out && accounts.downstream_program.is_some() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        // 解包时关闭的是用户自己的 WSOL 账户：已有余额会和输出一起被解包，账户本身也不再存在。
+        // 只接受空的 WSOL 账户，避免把用户原有的 WSOL 一并解包
+        if unwrap_out {
+            let (_, user_out) = accounts.pool.user_atas(data.is_x);
+            if TokenAccountInterface::get(user_out)?.amount() != 0 {
+                return Err(AmmError::WsolAccountNotEmpty.into());
+            }
         }
 
-        //验证 AmmState 是否有效
-        let state = config.state();
-        if state != AmmState::Initialized as u8 {
-            return Err(ProgramError::InvalidAccountData);
+        accounts.pool.swap(
+            accounts.user,
+            accounts.token_program,
+            &PoolSwapParams {
+                is_x: data.is_x,
+                exact_out: data.exact_out,
+                amount: data.amount,
+                limit: data.min,
+                wrap_in,
+            },
+        )?;
+
+        // 输出已经到了 user_out（作为中转账户），再由下游程序取走，例如直接存入借贷市场
+        if let Some(program) = accounts.downstream_program {
+            invoke_downstream(program, accounts.downstream_accounts, data.downstream_data)?;
+        }
+        // 输出的 WSOL 解包成 lamports：关闭用户的 WSOL ATA，余额连同租金一起退还给用户
+        if unwrap_out {
+            let (_, user_out) = accounts.pool.user_atas(data.is_x);
+            unwrap_sol(accounts.user, user_out, accounts.token_program)?;
         }
 
-        //反序列化代币信息
-        let vault_x = unsafe { TokenAccount::from_account_info_unchecked(accounts.vault_x)? };
-        let vault_y = unsafe { TokenAccount::from_account_info_unchecked(accounts.vault_y)? };
-        let user_x_ata = unsafe { TokenAccount::from_account_info_unchecked(accounts.user_x_ata)? };
-        let user_y_ata = unsafe { TokenAccount::from_account_info_unchecked(accounts.user_y_ata)? };
+        Ok(())
+    }
+}
 
-        //验证 vault 的 mint 与 config 一致，防止传入伪造 vault
-        if vault_x.mint() != config.mint_x() || vault_y.mint() != config.mint_y() {
-            return Err(ProgramError::InvalidAccountData);
+/// `PoolSwap::swap` 的参数
+pub struct PoolSwapParams {
+    pub is_x: bool,
+    /// exact_out 为 false 时 amount 是输入数量、limit 是最小输出（0 表示不检查）；
+    /// 为 true 时 amount 是期望输出、limit 是最大输入（含协议费）
+    pub exact_out: bool,
+    pub amount: u64,
+    pub limit: u64,
+    /// 输入是 WSOL 时先把用户的 SOL 包装进 user_in
+    pub wrap_in: bool,
+}
+
+/// 一次单池兑换涉及的池子账户，Swap 与 SwapRoute 的每一跳共用同一套账户检查（`validate`）和执行逻辑（`swap`）
+#[derive(Clone, Copy)]
+pub struct PoolSwap<'a> {
+    pub config: &'a AccountInfo,
+    pub vault_x: &'a AccountInfo,
+    pub vault_y: &'a AccountInfo,
+    pub user_x_ata: &'a AccountInfo,
+    pub user_y_ata: &'a AccountInfo,
+    /// require_checked_transfers 的池子必须在 user_y_ata 之后传入 mint_x / mint_y
+    pub mints: Option<PoolMints<'a>>,
+    /// 协议费直接转给 treasury 的池子必须在 mints 之后传入 treasury ATA（见 `Config::requires_treasury_ata`）
+    pub treasury_ata: Option<&'a AccountInfo>,
+}
+
+impl<'a> PoolSwap<'a> {
+    /// 校验一个池子的账户，`rest` 开头依次为可选的 mints 和 treasury ATA，返回剩下的账户
+    ///
+    /// user 的签名和 token_program 由调用方检查
+    pub fn validate(
+        user: &AccountInfo,
+        token_program: &AccountInfo,
+        [config, vault_x, vault_y, user_x_ata, user_y_ata]: [&'a AccountInfo; 5],
+        rest: &'a [AccountInfo],
+    ) -> Result<(Self, &'a [AccountInfo]), ProgramError> {
+        // config 必须是本程序拥有的 Config，fee / mint 等字段才可信
+        let config_data = SwapAccounts::load_config(config)?;
+        let (checked, requires_treasury_ata) =
+            (config_data.require_checked_transfers(), config_data.requires_treasury_ata(config.key()));
+        SwapAccounts::check_data_accounts([config, vault_x, vault_y, user_x_ata, user_y_ata])?;
+        let (mints, rest) = PoolMints::split(checked, rest)?;
+
+        // 用户 ATA 与金库必须互不相同，否则会出现自转账（在逐个检查 owner 之前，给出明确的 DuplicateAccount）
+        config_data.validate_distinct_accounts([user_x_ata, user_y_ata, vault_x, vault_y, config])?;
+
+        // 金库必须是 config 的 ATA：储备按它们的余额计价，输入也转入 vault_in；
+        // 只检查 mint 时，攻击者可以把自己的代币账户当作 vault_in，白拿 vault_out 的输出
+        let bumps = config_data.vault_bumps();
+        if !is_pool_vault(vault_x, config.key(), config_data.mint_x(), token_program, bumps.0)
+            || !is_pool_vault(vault_y, config.key(), config_data.mint_y(), token_program, bumps.1)
+        {
+            return Err(AmmError::InvalidVault.into());
+        }
+
+        TokenAccountInterface::check_with_program(user_x_ata, token_program)?;
+        TokenAccountInterface::check_with_program(user_y_ata, token_program)?;
+        //方向一致性：客户端把两个 ATA 传反时，转账会朝相反的方向进行，给出明确的 SwapDirectionMismatch
+        if TokenAccountInterface::get(user_x_ata)?.mint() == config_data.mint_y()
+            && TokenAccountInterface::get(user_y_ata)?.mint() == config_data.mint_x()
+        {
+            return Err(AmmError::SwapDirectionMismatch.into());
+        }
+        // 用户的两个 ATA：owner 是 user，mint 与池子一致
+        TokenAccountInterface::check_owner_and_mint(user_x_ata, user.key(), config_data.mint_x())?;
+        TokenAccountInterface::check_owner_and_mint(user_y_ata, user.key(), config_data.mint_y())?;
+        TokenAccountInterface::check_not_frozen(user_x_ata)?;
+        TokenAccountInterface::check_not_frozen(user_y_ata)?;
+        drop(config_data);
+        // 没有协议费、或协议费留在金库中的池子账户布局保持不变
+        let (treasury_ata, rest) = match (requires_treasury_ata, rest) {
+            (false, rest) => (None, rest),
+            (true, [treasury_ata, rest @ ..]) => {
+                SwapAccounts::check_data_accounts([treasury_ata])?;
+                (Some(treasury_ata), rest)
+            }
+            (true, []) => return Err(ProgramError::NotEnoughAccountKeys),
+        };
+
+        Ok((
+            Self {
+                config,
+                vault_x,
+                vault_y,
+                user_x_ata,
+                user_y_ata,
+                mints,
+                treasury_ata,
+            },
+            rest,
+        ))
+    }
+
+    /// (扣款 ATA, 收款 ATA)：user_x_ata / user_y_ata 的 mint 已在 validate 中固定
+    #[inline(always)]
+    pub fn user_atas(&self, is_x: bool) -> (&'a AccountInfo, &'a AccountInfo) {
+        match is_x {
+            true => (self.user_x_ata, self.user_y_ata),
+            false => (self.user_y_ata, self.user_x_ata),
+        }
+    }
+
+    /// (输入 mint, 输出 mint)
+    #[inline(always)]
+    pub fn mints(&self, is_x: bool) -> ([u8; 32], [u8; 32]) {
+        // config 的 owner / 长度已在 validate 中检查过；这里只复制两个 mint，不持有借用
+        let config = unsafe { Config::load_unchecked(self.config) };
+        match is_x {
+            true => (*config.mint_x(), *config.mint_y()),
+            false => (*config.mint_y(), *config.mint_x()),
+        }
+    }
+
+    /// 在这个池子中执行一次兑换，返回 (用户支付的总输入（含协议费）, 用户收到的输出)
+    ///
+    /// 协议费、熔断、储备快照、TWAP 和 SwapEvent 对 Swap 与 SwapRoute 的每一跳完全一致
+    pub fn swap(
+        &self,
+        user: &AccountInfo,
+        token_program: &AccountInfo,
+        params: &PoolSwapParams,
+    ) -> Result<(u64, u64), ProgramError> {
+        // config 的 owner / 长度已在 validate 中检查过，这里不再重复
+        // Safety: 下面写 config 账户之前不再使用 config
+        let config = unsafe { Config::load_unchecked(self.config) };
+        let is_x = params.is_x;
+
+        //验证 AmmState 是否有效
+        match config.amm_state()? {
+            AmmState::Initialized => {}
+            AmmState::Uninitialized | AmmState::Disabled | AmmState::WithdrawOnly => {
+                return Err(ProgramError::InvalidAccountData);
+            }
         }
 
-        //todo 这个检查多余吗？
-        //验证 user_x_ata 和 user_y_ata 的 mint 与 config 一致，防止传入伪造 user_x_ata 和 user_y_ata
-        if user_x_ata.mint() != config.mint_x() || user_y_ata.mint() != config.mint_y() {
+        //反序列化代币信息：验证 vault 的 mint 与 config 一致，并拒绝设置了第三方 close_authority 的金库
+        let vault_x = TokenAccountInterface::get(self.vault_x)?;
+        let vault_y = TokenAccountInterface::get(self.vault_y)?;
+        if vault_x.mint() != config.mint_x() || vault_y.mint() != config.mint_y() {
             return Err(ProgramError::InvalidAccountData);
         }
+        TokenAccountInterface::check_no_external_close_authority(self.vault_x, self.config.key())?;
+        TokenAccountInterface::check_no_external_close_authority(self.vault_y, self.config.key())?;
 
-        // Swap Calculations
-        let mut curve = ConstantProduct::init(
-            vault_x.amount(),
-            vault_y.amount(),
-            vault_x.amount(),
-            config.fee(),
-            None,
-        )
-        .map_err(|_| ProgramError::Custom(1))?;
-        let p = match data.is_x {
-            true => LiquidityPair::X,
-            false => LiquidityPair::Y,
+        //is_x 决定从哪个 ATA 扣款、向哪个 ATA 付款
+        let (user_in, user_out) = self.user_atas(is_x);
+        let (vault_in, vault_out) = match is_x {
+            true => (self.vault_x, self.vault_y),
+            false => (self.vault_y, self.vault_x),
         };
-        let swap_result = curve
-            .swap(p, self.instruction_data.amount, self.instruction_data.min)
-            .map_err(|_| ProgramError::Custom(1))?;
-        // Check for correct values
-        if swap_result.deposit == 0 || swap_result.withdraw == 0 {
-            return Err(ProgramError::InvalidArgument);
+        let (mint_in, mint_out) = match is_x {
+            true => (config.mint_x(), config.mint_y()),
+            false => (config.mint_y(), config.mint_x()),
+        };
+
+        //协议费接收账户必须是 treasury 持有的、输入代币的 ATA
+        if let Some(treasury_ata) = self.treasury_ata {
+            TokenAccountInterface::check_owner_and_mint(treasury_ata, config.treasury(), mint_in)?;
         }
 
-        //转账逻辑. 检查is_x值，并将from金额转入金库，将to金额转入用户的代币账户
-        // 构造 Config PDA 签名以从金库转账
-        let config_seeds = config.config_seeds();
-        let signer = Signer::from(&config_seeds);
-        if data.is_x {
-            //x to y
-            Transfer {
-                from: accounts.user_x_ata,
-                to: accounts.vault_x,
-                authority: accounts.user,
-                amount: swap_result.deposit,
-            }
-            .invoke()?;
-            Transfer {
-                from: accounts.vault_y,
-                to: accounts.user_y_ata,
-                authority: accounts.config,
-                amount: swap_result.withdraw,
+        // 交换前的储备，用于定价和更新 TWAP 累加器
+        // 按 config 中的储备快照计价，直接转入金库的代币不会改变价格
+        let mut reserves = PoolReserves::load(config, self.vault_x, self.vault_y)?;
+        reserves.check_not_empty()?;
+        let (reserve_x, reserve_y) = (reserves.x, reserves.y);
+
+        // Swap Calculations（与 Quote 共用同一条曲线）
+        let curve = Curve::for_weighted_swap(reserve_x, reserve_y, is_x, config.fee(), config.weight_x());
+
+        // 协议费先从输入中扣下，剩下的部分才进入曲线（LP 手续费也只对这部分收取）
+        let (protocol_cut, amount_in, amount_out) = if params.exact_out {
+            // exact-out：amount 是期望输出，limit 是愿意支付的最大输入（含协议费）
+            let amount_in_gross = gross_up_protocol_fee(curve.amount_in(params.amount)?, config.protocol_fee())?;
+            if amount_in_gross > params.limit {
+                return Err(AmmError::SlippageExceeded.into());
             }
-            .invoke_signed(&[signer])?;
+            let (protocol_cut, amount_in) = split_protocol_fee(amount_in_gross, config.protocol_fee())?;
+            // 向上取整带来的多余输出留在池子里，用户恰好收到 amount
+            (protocol_cut, amount_in, params.amount)
         } else {
-            //y to x
-            Transfer {
-                from: accounts.user_y_ata,
-                to: accounts.vault_y,
-                authority: accounts.user,
-                amount: swap_result.deposit,
+            let (protocol_cut, amount_in) = split_protocol_fee(params.amount, config.protocol_fee())?;
+            let amount_out = curve.amount_out(amount_in)?;
+            // Check for correct values
+            if amount_out == 0 {
+                return Err(AmmError::ZeroAmount.into());
             }
-            .invoke()?;
-            Transfer {
-                from: accounts.vault_x,
-                to: accounts.user_x_ata,
-                authority: accounts.config,
-                amount: swap_result.withdraw,
+            // 滑点检查
+            if amount_out < params.limit {
+                return Err(AmmError::SlippageExceeded.into());
             }
-            .invoke_signed(&[signer])?;
+            (protocol_cut, amount_in, amount_out)
+        };
+        // 熔断：与滑点无关，限制单次 swap 可以取走的绝对数量
+        config.check_swap_out(amount_out)?;
+        let amount_in_gross = add_u64(amount_in, protocol_cut)?;
+
+        //转账逻辑. 检查is_x值，并将from金额转入金库，将to金额转入用户的代币账户
+        // 构造 Config PDA 签名以从金库转账
+        let config_seeds = config.config_seeds();
+        // 用户的 SOL 先转入 WSOL ATA，覆盖本次的全部输入（含协议费）
+        if params.wrap_in {
+            wrap_sol(user, user_in, token_program, amount_in_gross)?;
+        }
+        //x to y 或 y to x：只从上面校验过的 user_in 扣款，只向 user_out 付款
+        let mints = self.mints.as_ref();
+        transfer_pool_tokens(config, mints, token_program, user_in, vault_in, user, amount_in, is_x, &[])?;
+        // 协议费转给 treasury，或者留在输入侧的金库中累计
+        let accrue_fee = config.accrues_protocol_fee(self.config.key()) && protocol_cut > 0;
+        let fee_destination = if accrue_fee { Some(vault_in) } else { self.treasury_ata };
+        if let (Some(fee_destination), true) = (fee_destination, protocol_cut > 0) {
+            transfer_pool_tokens(config, mints, token_program, user_in, fee_destination, user, protocol_cut, is_x, &[])?;
         }
+        transfer_pool_tokens(
+            config,
+            mints,
+            token_program,
+            vault_out,
+            user_out,
+            self.config,
+            amount_out,
+            !is_x,
+            &config_seeds,
+        )?;
 
-        Ok(())
+        // 事件在 config 释放之后发出，先复制两侧的 mint
+        let (mint_in, mint_out) = (*mint_in, *mint_out);
+        // 之后会写 config 账户，不能再使用 config（见 Config::load_unchecked）
+        if accrue_fee {
+            reserves.accrue_protocol_fee(self.config, is_x, protocol_cut)?;
+        }
+        reserves.sync(self.config, self.vault_x, self.vault_y)?;
+        update_price_cumulative(self.config, reserve_x, reserve_y)?;
+
+        SwapEvent {
+            pool: self.config.key(),
+            user: user.key(),
+            mint_in: &mint_in,
+            mint_out: &mint_out,
+            is_x,
+            amount_in: amount_in_gross,
+            amount_out,
+        }
+        .emit();
+
+        Ok((amount_in_gross, amount_out))
     }
 }
 
 pub struct SwapAccounts<'a> {
     pub user: &'a AccountInfo,
-    pub user_x_ata: &'a AccountInfo,
-    pub user_y_ata: &'a AccountInfo,
-    pub vault_x: &'a AccountInfo,
-    pub vault_y: &'a AccountInfo,
-    pub config: &'a AccountInfo,
     pub token_program: &'a AccountInfo,
+    /// GlobalConfig PDA，InitializeGlobal 之前可以是空账户（见 `GlobalConfig::load_if_initialized`）
+    pub global_config: &'a AccountInfo,
+    /// 只有 wrap_sol 包装输入时才会用到（System Transfer）
+    pub system_program: &'a AccountInfo,
+    /// config、金库、用户 ATA，以及可选的 mints / treasury ATA
+    pub pool: PoolSwap<'a>,
+    /// 可选：接收输出的下游程序（必须在 GlobalConfig 白名单中）
+    pub downstream_program: Option<&'a AccountInfo>,
+    /// 下游程序之后的所有账户，原样转发给下游程序
+    pub downstream_accounts: &'a [AccountInfo],
 }
 
-impl<'a> TryFrom<&'a [AccountInfo]> for SwapAccounts<'a> {
-    type Error = ProgramError;
-
-    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+impl<'a> AccountsValidator<'a> for SwapAccounts<'a> {
+    fn validate(accounts: &'a [AccountInfo]) -> Result<Self, ProgramError> {
         let [
             user,
             user_x_ata,
@@ -160,62 +375,106 @@ impl<'a> TryFrom<&'a [AccountInfo]> for SwapAccounts<'a> {
             vault_y,
             config,
             token_program,
-            _,
+            global_config,
+            system_program,
+            rest @ ..,
         ] = accounts
         else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
-        //todo need check ?
+        // 用户签名；转账 CPI 直接调用传入的 token_program，必须是 SPL Token 或 Token-2022
+        Self::check_signer(user)?;
+        Self::check_token_program(token_program)?;
+        let (pool, rest) =
+            PoolSwap::validate(user, token_program, [config, vault_x, vault_y, user_x_ata, user_y_ata], rest)?;
 
         Ok(Self {
             user,
-            user_x_ata,
-            user_y_ata,
-            vault_x,
-            vault_y,
-            config,
             token_program,
+            global_config,
+            system_program,
+            pool,
+            downstream_program: rest.first(),
+            downstream_accounts: rest.get(1..).unwrap_or(&[]),
         })
     }
 }
 
-pub struct SwapInstructionData {
+impl<'a> TryFrom<&'a [AccountInfo]> for SwapAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        Self::validate(accounts)
+    }
+}
+
+pub struct SwapInstructionData<'a> {
     pub is_x: bool,
+    /// exact_out 为 false 时 amount 是输入数量、min 是最小输出；
+    /// 为 true 时 amount 是期望输出、min 是最大输入
+    pub exact_out: bool,
+    /// 为 true 且池子一侧是 WSOL 时，输入的 SOL 自动包装、输出的 WSOL 自动解包（关闭用户的 WSOL ATA，
+    /// 因此输出是 WSOL 时该账户在 swap 前必须没有余额）
+    pub wrap_sol: bool,
     pub amount: u64,
     pub min: u64,
     pub expiration: i64,
+    /// 可选：expiration 是时间戳还是 slot，缺省为时间戳
+    pub expiration_kind: ExpirationKind,
+    /// 可选：原样转发给下游程序的指令数据，只有传入下游程序时才允许非空
+    pub downstream_data: &'a [u8],
 }
 
-impl<'a> TryFrom<&'a [u8]> for SwapInstructionData {
+impl<'a> TryFrom<&'a [u8]> for SwapInstructionData<'a> {
     type Error = ProgramError;
 
     fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
-        const SWAP_DATA_LEN: usize = size_of::<SwapInstructionData>();
+        // is_x + amount + min + expiration + exact_out + wrap_sol + 对齐填充（与客户端的 32 字节布局保持一致）
+        const SWAP_DATA_LEN: usize = 32;
 
-        //len check
-        if data.len() != SWAP_DATA_LEN {
+        //len check，之后可选地附加 1 字节 expiration_kind，再之后是转发给下游程序的数据
+        if data.len() < SWAP_DATA_LEN || data.len() > SWAP_DATA_LEN + 1 + MAX_DOWNSTREAM_DATA_LEN {
             return Err(ProgramError::InvalidInstructionData);
         }
+        let (expiration_kind, downstream_data) = match data[SWAP_DATA_LEN..].split_first() {
+            Some((kind, downstream_data)) => (ExpirationKind::try_from(*kind)?, downstream_data),
+            None => (ExpirationKind::Timestamp, &[][..]),
+        };
 
         let is_x = data[0] == 1;
         let amount = u64::from_le_bytes(data[1..9].try_into().unwrap());
         let min = u64::from_le_bytes(data[9..17].try_into().unwrap());
         let expiration = i64::from_le_bytes(data[17..25].try_into().unwrap());
+        // 原先的填充字节，旧客户端传 0 即保持 exact-in
+        let exact_out = match data[25] {
+            0 => false,
+            1 => true,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+        let wrap_sol = match data[26] {
+            0 => false,
+            1 => true,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
 
         //todo check ?
         if amount == 0 {
-            return Err(ProgramError::InvalidInstructionData);
+            return Err(AmmError::ZeroAmount.into());
         }
         if min == 0 {
-            return Err(ProgramError::InvalidInstructionData);
+            return Err(AmmError::ZeroAmount.into());
         }
 
         Ok(Self {
             is_x,
+            exact_out,
+            wrap_sol,
             amount,
             min,
             expiration,
+            expiration_kind,
+            downstream_data,
         })
     }
 }
@@ -0,0 +1,217 @@
+use super::helpers::*;
+use super::swap::{PoolSwap, PoolSwapParams};
+use crate::errors::AmmError;
+use crate::state::GlobalConfig;
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program_error::ProgramError,
+};
+
+/// 一条路径最多经过的池子数
+pub const MAX_ROUTE_HOPS: usize = 4;
+
+/// 多跳兑换：在一笔指令中依次经过多个池子，上一跳的输出全部作为下一跳的输入
+///
+/// 每一跳都按 exact-in 的 Swap 执行（账户检查和执行逻辑与单池 Swap 共用 `PoolSwap`），
+/// 中间跳不做滑点检查，只检查最后一跳的输出不低于 min_out。
+///
+/// 账户布局：
+/// `[user, token_program, global_config, _, hop_0.., hop_1.., ..]`
+///
+/// 每一跳依次为：
+/// `[config, vault_x, vault_y, user_x_ata, user_y_ata, (mint_x, mint_y), (treasury_ata)]`
+/// - user_x_ata / user_y_ata 与单池 Swap 相同，按池子的 X/Y 而不是按方向排列
+/// - require_checked_transfers 的池子在 user_y_ata 之后传入 mint_x / mint_y
+/// - 协议费直接转给 treasury 的池子再传入 treasury 持有的、该跳输入代币的 ATA（见 `Config::requires_treasury_ata`）
+/// - 从第二跳开始，输入侧的 user ATA 必须与上一跳输出侧的 user ATA 是同一个账户（RouteMismatch）
+/// - 最后一跳之后不能有多余的账户，跳数必须与指令数据中的 hop_count 一致
+///
+/// 指令数据：`hop_count (u8) + is_x (u8) * hop_count + amount_in (u64) + min_out (u64) + expiration (i64) + (expiration_kind)`
+pub struct SwapRoute<'a> {
+    pub accounts: SwapRouteAccounts<'a>,
+    pub instruction_data: SwapRouteInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for SwapRoute<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = SwapRouteAccounts::try_from(accounts)?;
+        let instruction_data = SwapRouteInstructionData::try_from(data)?;
+
+        // 账户中解析出的跳数必须与数据中的 hop_count 一致
+        if accounts.hop_count != instruction_data.hop_count {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SwapRoute<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &18;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let accounts = &self.accounts;
+        let data = &self.instruction_data;
+
+        // 过期检查对整条路径只做一次
+        let grace_secs = GlobalConfig::grace_secs_or_default(accounts.global_config)?;
+        data.expiration_kind.check(data.expiration, grace_secs)?;
+
+        let mut amount = data.amount_in;
+        let mut prev_out: Option<&AccountInfo> = None;
+        for (hop, &is_x) in accounts.hops().zip(data.path()) {
+            let (user_in, user_out) = hop.user_atas(is_x);
+            // 中间 ATA：本跳扣款的账户必须就是上一跳收款的账户
+            if prev_out.is_some_and(|prev_out| prev_out.key() != user_in.key()) {
+                return Err(AmmError::RouteMismatch.into());
+            }
+            // 每一跳执行期间锁定对应的池子，与单池 Swap 一致
+            // 中间跳不做滑点检查（limit 为 0），只检查最后一跳的输出
+            let params = PoolSwapParams { is_x, exact_out: false, amount, limit: 0, wrap_in: false };
+            let (_, amount_out) =
+                with_pool_locked(hop.config, || hop.swap(accounts.user, accounts.token_program, &params))?;
+            amount = amount_out;
+            prev_out = Some(user_out);
+        }
+
+        // 滑点只针对最终输出
+        if amount < data.min_out {
+            return Err(AmmError::SlippageExceeded.into());
+        }
+
+        Ok(())
+    }
+}
+
+pub struct SwapRouteAccounts<'a> {
+    pub user: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    /// GlobalConfig PDA，InitializeGlobal 之前可以是空账户（见 `GlobalConfig::load_if_initialized`）
+    pub global_config: &'a AccountInfo,
+    /// 前 hop_count 个为 Some
+    pub hops: [Option<PoolSwap<'a>>; MAX_ROUTE_HOPS],
+    pub hop_count: usize,
+}
+
+impl<'a> SwapRouteAccounts<'a> {
+    #[inline(always)]
+    pub fn hops(&self) -> impl Iterator<Item = &PoolSwap<'a>> {
+        self.hops.iter().flatten()
+    }
+}
+
+impl<'a> AccountsValidator<'a> for SwapRouteAccounts<'a> {
+    fn validate(accounts: &'a [AccountInfo]) -> Result<Self, ProgramError> {
+        let [user, token_program, global_config, _, rest @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Self::check_signer(user)?;
+        Self::check_token_program(token_program)?;
+
+        // 每一跳的账户数由它的 config 决定，逐跳解析直到账户用完
+        let mut hops = [None; MAX_ROUTE_HOPS];
+        let (mut hop_count, mut rest) = (0, rest);
+        while !rest.is_empty() {
+            if hop_count == MAX_ROUTE_HOPS {
+                return Err(ProgramError::InvalidArgument);
+            }
+            let [config, vault_x, vault_y, user_x_ata, user_y_ata, hop_rest @ ..] = rest else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+            let (hop, next) =
+                PoolSwap::validate(user, token_program, [config, vault_x, vault_y, user_x_ata, user_y_ata], hop_rest)?;
+            hops[hop_count] = Some(hop);
+            hop_count += 1;
+            rest = next;
+        }
+
+        Ok(Self {
+            user,
+            token_program,
+            global_config,
+            hops,
+            hop_count,
+        })
+    }
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for SwapRouteAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        Self::validate(accounts)
+    }
+}
+
+pub struct SwapRouteInstructionData {
+    pub hop_count: usize,
+    /// 每一跳的方向，前 hop_count 个有效
+    pub is_x: [bool; MAX_ROUTE_HOPS],
+    pub amount_in: u64,
+    pub min_out: u64,
+    pub expiration: i64,
+    /// 可选：expiration 是时间戳还是 slot，缺省为时间戳
+    pub expiration_kind: ExpirationKind,
+}
+
+impl SwapRouteInstructionData {
+    #[inline(always)]
+    pub fn path(&self) -> &[bool] {
+        &self.is_x[..self.hop_count]
+    }
+}
+
+impl<'a> TryFrom<&'a [u8]> for SwapRouteInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        // amount_in + min_out + expiration
+        const ROUTE_TAIL_LEN: usize = 24;
+
+        let Some((&hop_count, rest)) = data.split_first() else {
+            return Err(ProgramError::InvalidInstructionData);
+        };
+        let hop_count = hop_count as usize;
+        if hop_count == 0 || hop_count > MAX_ROUTE_HOPS {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        //len check，之后可选地附加 1 字节 expiration_kind
+        let (rest, expiration_kind) = ExpirationKind::split_suffix(rest, &[hop_count + ROUTE_TAIL_LEN + 1])?;
+        if rest.len() != hop_count + ROUTE_TAIL_LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (path, tail) = rest.split_at(hop_count);
+        let mut is_x = [false; MAX_ROUTE_HOPS];
+        for (dst, src) in is_x.iter_mut().zip(path) {
+            *dst = match src {
+                0 => false,
+                1 => true,
+                _ => return Err(ProgramError::InvalidInstructionData),
+            };
+        }
+        let amount_in = u64::from_le_bytes(tail[0..8].try_into().unwrap());
+        let min_out = u64::from_le_bytes(tail[8..16].try_into().unwrap());
+        let expiration = i64::from_le_bytes(tail[16..24].try_into().unwrap());
+
+        if amount_in == 0 || min_out == 0 {
+            return Err(AmmError::ZeroAmount.into());
+        }
+
+        Ok(Self {
+            hop_count,
+            is_x,
+            amount_in,
+            min_out,
+            expiration,
+            expiration_kind,
+        })
+    }
+}
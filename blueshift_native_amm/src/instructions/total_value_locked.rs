@@ -0,0 +1,90 @@
+use crate::state::{AmmState, Config};
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program::set_return_data,
+    program_error::ProgramError,
+};
+
+use super::helpers::*;
+
+/// 只读 TVL：按调用方提供的价格计算池子的总锁仓价值，通过 return data 返回
+///
+/// 指令数据：price_x (u64 LE) + price_y (u64 LE)
+///
+/// return data 布局：tvl (u128 LE)
+pub struct TotalValueLocked<'a> {
+    pub accounts: TotalValueLockedAccounts<'a>,
+    pub price_x: u64,
+    pub price_y: u64,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for TotalValueLocked<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = TotalValueLockedAccounts::try_from(accounts)?;
+
+        // price_x (8) + price_y (8)
+        if data.len() != 16 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let price_x = u64::from_le_bytes(data[0..8].try_into().unwrap());
+        let price_y = u64::from_le_bytes(data[8..16].try_into().unwrap());
+
+        Ok(Self {
+            accounts,
+            price_x,
+            price_y,
+        })
+    }
+}
+
+impl<'a> TotalValueLocked<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &10;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let config = Config::load_checked(self.accounts.config)?;
+        // 只读视图：已禁用或只允许取款的池子仍可查询
+        match config.amm_state()? {
+            AmmState::Uninitialized => return Err(ProgramError::InvalidAccountData),
+            AmmState::Initialized | AmmState::Disabled | AmmState::WithdrawOnly => {}
+        }
+
+        // 金库必须属于这个池子
+        TokenAccountInterface::check_owner_and_mint(self.accounts.vault_x, self.accounts.config.key(), config.mint_x())?;
+        TokenAccountInterface::check_owner_and_mint(self.accounts.vault_y, self.accounts.config.key(), config.mint_y())?;
+
+        let tvl = total_value_locked(
+            TokenAccountInterface::get(self.accounts.vault_x)?.amount(),
+            TokenAccountInterface::get(self.accounts.vault_y)?.amount(),
+            self.price_x,
+            self.price_y,
+        );
+        set_return_data(&tvl.to_le_bytes());
+
+        Ok(())
+    }
+}
+
+pub struct TotalValueLockedAccounts<'a> {
+    pub config: &'a AccountInfo,
+    pub vault_x: &'a AccountInfo,
+    pub vault_y: &'a AccountInfo,
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for TotalValueLockedAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [config, vault_x, vault_y, _] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        Ok(Self {
+            config,
+            vault_x,
+            vault_y,
+        })
+    }
+}
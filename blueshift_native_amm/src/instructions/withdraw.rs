@@ -1,19 +1,16 @@
-use crate::state::{AmmState, Config};
-use core::mem::size_of;
-use constant_product_curve::ConstantProduct;
+use super::helpers::*;
+use crate::curve::Curve;
+use crate::errors::AmmError;
+use crate::events::WithdrawEvent;
+use crate::state::{AmmState, Config, GlobalConfig};
 use pinocchio::{
     ProgramResult,
     account_info::AccountInfo,
-    instruction::Signer,
     program_error::ProgramError,
-    sysvars::{Sysvar, clock::Clock},
-};
-use pinocchio_token::state::Mint;
-use pinocchio_token::{
-    instructions::{Transfer, Burn},
-    state::TokenAccount,
+    pubkey::find_program_address,
 };
 
+/// 销毁 LP 取回 x/y。取款不收手续费（只有 Swap 收取，启用时另有退出费），数量严格按份额比例向下取整
 pub struct Withdraw<'a> {
     pub accounts: WithdrawAccounts<'a>,
     pub instruction_data: WithdrawInstructionData,
@@ -34,120 +31,183 @@ impl<'a> Withdraw<'a> {
     pub const DISCRIMINATOR: &'a u8 = &2;
 
     pub fn process(&mut self) -> ProgramResult {
-        let config = Config::load(&self.accounts.config)?; //or load_unchecked ?
+        let config = self.accounts.config;
+        with_pool_locked(config, || self.execute())
+    }
+
+    fn execute(&mut self) -> ProgramResult {
+        // config 的 owner / 长度已在 validate 的 check_common 中检查过，这里不再重复
+        // Safety: 下面写 config 账户之前不再使用 config
+        let config = unsafe { Config::load_unchecked(self.accounts.config) };
 
         let accounts = &self.accounts;
         let data = &self.instruction_data;
 
-        // （这个检测很重要） 验证用户已签名
-        if !accounts.user.is_signer() {
-            return Err(ProgramError::MissingRequiredSignature);
-        }
-
-        // 过期检查
-        let clock = Clock::get()?;
-        if clock.unix_timestamp > data.expiration {
-            return Err(ProgramError::InvalidArgument);
-        }
+        // 过期检查（允许全局配置中的 grace_secs 时钟误差）
+        let grace_secs = GlobalConfig::grace_secs_or_default(accounts.global_config)?;
+        data.expiration_kind.check(data.expiration, grace_secs)?;
 
         //验证 AmmState 是否有效
-        let state = config.state();
-        if state != AmmState::Initialized as u8
-            && state != AmmState::WithdrawOnly as u8
-        {
-            return Err(ProgramError::InvalidAccountData);
+        // WithdrawOnly 的池子仍允许取款
+        match config.amm_state()? {
+            AmmState::Initialized | AmmState::WithdrawOnly => {}
+            AmmState::Uninitialized | AmmState::Disabled => {
+                return Err(ProgramError::InvalidAccountData);
+            }
         }
 
-        //todo 这个检查多余吗？ //太费性能了，改为和config里的mint_x和mint_y对比
-        // //检查 vault_x 和 vault_y 的派生是否为关联代币账户（Associated Token Accounts）
-        // let (vault_x, _) = find_program_address(
-        //     &[
-        //         self.accounts.config.key(),
-        //         self.accounts.token_program.key(),
-        //         config.mint_x(),
-        //     ],
-        //     &pinocchio_associated_token_account::ID.to_bytes(),
-        // );
-        // if vault_x.ne(self.accounts.vault_x.key()) {
-        //     return Err(ProgramError::InvalidAccountData);
-        // }
-
-        // //check vault_y
-        // let (vault_y, _) = find_program_address(
-        //     &[
-        //         self.accounts.config.key(),
-        //         self.accounts.token_program.key(),
-        //         config.mint_y(),
-        //     ],
-        //     &pinocchio_associated_token_account::ID.to_bytes(),
-        // );
-        // if vault_y.ne(self.accounts.vault_y.key()) {
-        //     return Err(ProgramError::InvalidAccountData);
-        // }
+        // LP supply 决定取回的份额，mint_lp 必须是这个池子的 PDA，否则用户可以用自己的小 supply mint 取走整个金库
+        let (expected_mint_lp, _) =
+            find_program_address(&[b"mint_lp", accounts.config.key().as_ref()], &crate::ID);
+        if accounts.mint_lp.key() != &expected_mint_lp {
+            return Err(AmmError::InvalidLpMint.into());
+        }
 
         // 反序列化代币信息
-        let mint_lp = unsafe { Mint::from_account_info_unchecked(accounts.mint_lp)? };
-        let vault_x = unsafe { TokenAccount::from_account_info_unchecked(accounts.vault_x)? };
-        let vault_y = unsafe { TokenAccount::from_account_info_unchecked(accounts.vault_y)? };
+        let mint_lp = MintInterface::get(accounts.mint_lp)?;
+        // 份额计算与精度无关（见 LP_DECIMALS），这里只确认 LP mint 的精度与 Initialize 记录的一致
+        if mint_lp.decimals() != config.lp_decimals() {
+            return Err(AmmError::InvalidLpMint.into());
+        }
+        let vault_x = TokenAccountInterface::get(accounts.vault_x)?;
+        let vault_y = TokenAccountInterface::get(accounts.vault_y)?;
 
         // 验证 vault 的 mint 与 config 一致，防止传入伪造 vault
         if vault_x.mint() != config.mint_x() || vault_y.mint() != config.mint_y() {
             return Err(ProgramError::InvalidAccountData);
         }
+        TokenAccountInterface::check_no_external_close_authority(accounts.vault_x, accounts.config.key())?;
+        TokenAccountInterface::check_no_external_close_authority(accounts.vault_y, accounts.config.key())?;
+
+        // 报价后 LP supply 变化过大则拒绝
+        if let Some(expected) = &data.expected_lp_supply {
+            expected.check(mint_lp.supply())?;
+        }
 
         //将金额从金库转移到用户的代币账户，并从用户的代币账户中销毁相应数量的 LP 代币
         //计算应退还的 X, Y 数量
-        let (x, y) = if mint_lp.supply() == data.amount {
-            // 全额提取：直接取走所有余额，防止舍入误差留下“尘埃”
-            (vault_x.amount(), vault_y.amount())
+        // 首次存款锁定的 MINIMUM_LIQUIDITY 计入 supply，所以即使用户销毁全部 LP，
+        // 锁定份额对应的储备也会留在金库中（不再有“全额提取取走所有余额”的捷径）
+        // 按份额比例向下取整，取款不收手续费（只有 Swap 收取）
+        // 份额按 config 中的储备快照计算，直接转入金库的代币不计入
+        let reserves = PoolReserves::load(config, accounts.vault_x, accounts.vault_y)?;
+        let (reserve_x, reserve_y) = (reserves.x, reserves.y);
+        let curve = Curve::for_liquidity(reserve_x, reserve_y, mint_lp.supply())?.with_weight_x(config.weight_x());
+        // anti-JIT：存款后冷却期内取款，不分这段时间的手续费
+        let fee_growth = match PositionAccount::cooldown_liquidity_per_lp(
+            accounts.position,
+            accounts.config,
+            accounts.user,
+            config.jit_cooldown_slots(),
+        )? {
+            Some(then) => Some((then, curve.liquidity_per_lp()?)),
+            None => None,
+        };
+
+        // 退出费：存款后窗口内取款要留下一部分给剩余的 LP，随时间线性衰减
+        let exit_fee_bps = PositionAccount::exit_fee_bps(
+            accounts.position,
+            accounts.config,
+            accounts.user,
+            config.exit_fee_bps(),
+            config.exit_fee_window_secs(),
+        )?;
+
+        // exact-out：按目标数量反推要销毁的 LP，先按相反顺序把退出费和没收的手续费加回去，
+        // 超过 max_lp_in（即 amount）则拒绝；默认按 amount 销毁
+        let lp = if data.exact_out {
+            let mut target_x = gross_up_exit_fee(data.min_x, exit_fee_bps)?;
+            let mut target_y = gross_up_exit_fee(data.min_y, exit_fee_bps)?;
+            if let Some((then, now)) = fee_growth {
+                target_x = gross_up_fee_growth(target_x, then, now)?;
+                target_y = gross_up_fee_growth(target_y, then, now)?;
+            }
+            let lp = curve.lp_for_withdraw(target_x, target_y)?;
+            if lp > data.amount {
+                return Err(AmmError::SlippageExceeded.into());
+            }
+            lp
         } else {
-            let amounts = ConstantProduct::xy_withdraw_amounts_from_l(
-                vault_x.amount(),
-                vault_y.amount(),
-                mint_lp.supply(),
-                data.amount,
-                6, // LP decimals
-            )
-            .map_err(|_| ProgramError::ArithmeticOverflow)?;
-            (amounts.x, amounts.y)
+            data.amount
         };
 
-        // 滑点检查
+        let (mut x, mut y) = curve.withdraw_amounts(lp)?;
+        if let Some((then, now)) = fee_growth {
+            x = forfeit_fee_growth(x, then, now)?;
+            y = forfeit_fee_growth(y, then, now)?;
+        }
+        x = apply_exit_fee(x, exit_fee_bps)?;
+        y = apply_exit_fee(y, exit_fee_bps)?;
+
+        // 滑点检查（exact-out 时即目标数量，上面的向上取整保证满足）
         if x < data.min_x || y < data.min_y {
-            return Err(ProgramError::InvalidArgument);
+            return Err(AmmError::SlippageExceeded.into());
         }
 
          // 销毁用户的 LP 代币 (用户签名)
-         //todo 如果用户的lp代币在burn以后余额为0，那需要关闭用户对应的lp代币账户吗？现在不关的话，以后用户可以自己关闭吗？(不需要使用该crate的program了吧？)
-         Burn {
-            mint: accounts.mint_lp,
-            account: accounts.user_lp_ata,
-            authority: accounts.user,
-            amount: data.amount,
+        burn_tokens(
+            accounts.token_program,
+            accounts.user_lp_ata,
+            accounts.mint_lp,
+            accounts.user,
+            lp,
+        )?;
+
+        // 全部取出时可以顺便关闭 LP ATA，租金退还给用户；还有余额时不关闭（CloseAccount 本身也会失败）
+        // 不关闭的话用户之后也可以自己调用 Token Program 关闭，不需要经过本程序
+        if data.close_lp_ata {
+            let remaining = TokenAccountInterface::get(accounts.user_lp_ata)?.amount();
+            if remaining == 0 {
+                close_token_account(accounts.token_program, accounts.user_lp_ata, accounts.user, accounts.user)?;
+            }
         }
-        .invoke()?;
 
         // 构造 Config PDA 签名以从金库转账
         let config_seeds = config.config_seeds();
-        let signer = Signer::from(&config_seeds);
 
         // 转移 Token X 和 Y (Config PDA 签名)
-        Transfer {
-            from: accounts.vault_x,
-            to: accounts.user_x_ata,
-            authority: accounts.config,
-            amount: x,
-        }
-        // .invoke_signed(&[signer.clone()])?;
-        .invoke_signed(core::slice::from_ref(&signer))?;
-
-        Transfer {
-            from: accounts.vault_y,
-            to: accounts.user_y_ata,
-            authority: accounts.config,
-            amount: y,
+        let mints = accounts.mints.as_ref();
+        transfer_pool_tokens(
+            config,
+            mints,
+            accounts.token_program,
+            accounts.vault_x,
+            accounts.user_x_ata,
+            accounts.config,
+            x,
+            true,
+            &config_seeds,
+        )?;
+
+        transfer_pool_tokens(
+            config,
+            mints,
+            accounts.token_program,
+            accounts.vault_y,
+            accounts.user_y_ata,
+            accounts.config,
+            y,
+            false,
+            &config_seeds,
+        )?;
+
+        // 用取款前的储备更新 TWAP 累加器
+        // 事件在 config 释放之后发出，先复制两侧的 mint
+        let (mint_x, mint_y) = (*config.mint_x(), *config.mint_y());
+        // 之后会写 config 账户，不能再使用 config（见 Config::load_unchecked）
+        reserves.sync(accounts.config, accounts.vault_x, accounts.vault_y)?;
+        update_price_cumulative(accounts.config, reserve_x, reserve_y)?;
+
+        WithdrawEvent {
+            pool: accounts.config.key(),
+            user: accounts.user.key(),
+            mint_x: &mint_x,
+            mint_y: &mint_y,
+            lp,
+            x,
+            y,
         }
-        .invoke_signed(&[signer])?;
+        .emit();
 
 
         Ok(())
@@ -164,12 +224,16 @@ pub struct WithdrawAccounts<'a> {
     pub user_lp_ata: &'a AccountInfo,
     pub config: &'a AccountInfo,
     pub token_program: &'a AccountInfo,
+    /// GlobalConfig PDA，InitializeGlobal 之前可以是空账户（见 `GlobalConfig::load_if_initialized`）
+    pub global_config: &'a AccountInfo,
+    /// require_checked_transfers 的池子必须在固定账户之后传入 mint_x / mint_y
+    pub mints: Option<PoolMints<'a>>,
+    /// 可选的末尾账户：池子启用 anti-JIT 冷却期或退出费时必须传入用户的 LpPosition PDA
+    pub position: Option<&'a AccountInfo>,
 }
 
-impl<'a> TryFrom<&'a [AccountInfo]> for WithdrawAccounts<'a> {
-    type Error = ProgramError;
-
-    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+impl<'a> AccountsValidator<'a> for WithdrawAccounts<'a> {
+    fn validate(accounts: &'a [AccountInfo]) -> Result<Self, ProgramError> {
         let [
             user,
             mint_lp,
@@ -180,13 +244,44 @@ impl<'a> TryFrom<&'a [AccountInfo]> for WithdrawAccounts<'a> {
             user_lp_ata,
             config,
             token_program,
+            global_config,
             _,
+            rest @ ..,
         ] = accounts
         else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
 
-        //todo need check ?
+        // 用户签名；销毁/转账 CPI 直接调用传入的 token_program，必须是 SPL Token 或 Token-2022
+        let config_data = Self::check_common(user, token_program, config)?;
+        Self::check_data_accounts([config, mint_lp, vault_x, vault_y, user_x_ata, user_y_ata, user_lp_ata])?;
+        let (mints, rest) = PoolMints::split(config_data.require_checked_transfers(), rest)?;
+
+        // 用户 ATA、金库、config 与 mint_lp 必须互不相同（在逐个检查 owner 之前，给出明确的 DuplicateAccount）
+        config_data.validate_distinct_accounts([user_x_ata, user_y_ata, user_lp_ata, vault_x, vault_y, config, mint_lp])?;
+
+        MintInterface::check_with_program(mint_lp, token_program)?;
+        TokenAccountInterface::check_with_program(vault_x, token_program)?;
+        TokenAccountInterface::check_with_program(vault_y, token_program)?;
+        TokenAccountInterface::check_with_program(user_x_ata, token_program)?;
+        TokenAccountInterface::check_with_program(user_y_ata, token_program)?;
+        TokenAccountInterface::check_with_program(user_lp_ata, token_program)?;
+
+        // 金库必须是 config 的 ATA：只比较 mint 时，调用方可以传入另一个 owner 为 config、mint 相同的代币账户，
+        // 用它的余额计算份额。使用 Initialize 记录的金库 bump，不需要 find_program_address 搜索
+        let bumps = config_data.vault_bumps();
+        if !is_pool_vault(vault_x, config.key(), config_data.mint_x(), token_program, bumps.0)
+            || !is_pool_vault(vault_y, config.key(), config_data.mint_y(), token_program, bumps.1)
+        {
+            return Err(AmmError::InvalidVault.into());
+        }
+        TokenAccountInterface::check_owner_and_mint(vault_x, config.key(), config_data.mint_x())?;
+        TokenAccountInterface::check_owner_and_mint(vault_y, config.key(), config_data.mint_y())?;
+
+        // 用户的三个 ATA：owner 是 user，mint 与池子一致
+        TokenAccountInterface::check_owner_and_mint(user_x_ata, user.key(), config_data.mint_x())?;
+        TokenAccountInterface::check_owner_and_mint(user_y_ata, user.key(), config_data.mint_y())?;
+        TokenAccountInterface::check_owner_and_mint(user_lp_ata, user.key(), mint_lp.key())?;
 
         Ok(Self {
             user,
@@ -198,43 +293,87 @@ impl<'a> TryFrom<&'a [AccountInfo]> for WithdrawAccounts<'a> {
             user_lp_ata,
             config,
             token_program,
+            global_config,
+            mints,
+            position: rest.first(),
         })
     }
 }
 
+impl<'a> TryFrom<&'a [AccountInfo]> for WithdrawAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        Self::validate(accounts)
+    }
+}
+
 pub struct WithdrawInstructionData {
+    /// 默认模式下是要销毁的 LP；exact-out 时是最多销毁的 LP（max_lp_in）
     pub amount: u64,
+    /// 默认模式下是最少取回的数量；exact-out 时是要取回的目标数量（target_x / target_y）
     pub min_x: u64,
     pub min_y: u64,
     pub expiration: i64,//todo 为什么需要这个字段？
+    /// 可选：expiration 是时间戳还是 slot，缺省为时间戳
+    pub expiration_kind: ExpirationKind,
+    /// 可选：报价时的 LP supply 及容忍度，见 `ExpectedLpSupply`
+    pub expected_lp_supply: Option<ExpectedLpSupply>,
+    /// 可选：burn 之后 LP 余额为 0 时关闭用户的 LP ATA，缺省为 false
+    pub close_lp_ata: bool,
+    /// 可选：按目标数量取款，销毁达到 min_x / min_y 所需的最少 LP，缺省为 false
+    pub exact_out: bool,
 }
 
 impl<'a> TryFrom<&'a [u8]> for WithdrawInstructionData {
     type Error = ProgramError;
 
     fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
-        const WITHDRAW_DATA_LEN: usize = size_of::<WithdrawInstructionData>();
+        // amount + min_x + min_y + expiration，之后可选地附加 ExpectedLpSupply，再可选地附加 expiration_kind，
+        // 再可选地附加 close_lp_ata（此时 expiration_kind 必须显式给出），最后可选地附加 exact_out（此时 close_lp_ata 必须显式给出）
+        // 按字段偏移显式解析（小端），不依赖结构体布局
+        const AMOUNT_OFFSET: usize = 0;
+        const MIN_X_OFFSET: usize = 8;
+        const MIN_Y_OFFSET: usize = 16;
+        const EXPIRATION_OFFSET: usize = 24;
+        const WITHDRAW_DATA_LEN: usize = 32;
 
         //len check
-        if data.len() != WITHDRAW_DATA_LEN {
+        if data.len() < WITHDRAW_DATA_LEN {
             return Err(ProgramError::InvalidInstructionData);
         }
-
-        let amount = u64::from_le_bytes(data[0..8].try_into().unwrap());
-        let min_x = u64::from_le_bytes(data[8..16].try_into().unwrap());
-        let min_y = u64::from_le_bytes(data[16..24].try_into().unwrap());
-        let expiration = i64::from_le_bytes(data[24..32].try_into().unwrap());
-
-        //todo 这些检查多余吗？
-        //确保任何数量，例如 amount、max_y 和 max_x 都大于零，并且订单尚未过期，可以使用 Clock sysvar 进行检查。
+        let rest = &data[WITHDRAW_DATA_LEN..];
+        // 末尾的 bool 标志：`trailing` 为标志之前（含）可选字段的长度组合
+        let split_flag = |rest: &'a [u8], trailing: usize| -> Result<(&'a [u8], bool), ProgramError> {
+            match rest.split_last() {
+                Some((flag, head)) if rest.len() == trailing || rest.len() == ExpectedLpSupply::LEN + trailing => {
+                    match flag {
+                        0 => Ok((head, false)),
+                        1 => Ok((head, true)),
+                        _ => Err(ProgramError::InvalidInstructionData),
+                    }
+                }
+                _ => Ok((rest, false)),
+            }
+        };
+        let (rest, exact_out) = split_flag(rest, 3)?;
+        let (rest, close_lp_ata) = split_flag(rest, 2)?;
+        let (rest, expiration_kind) = ExpirationKind::split_suffix(rest, &[1, ExpectedLpSupply::LEN + 1])?;
+        let expected_lp_supply = ExpectedLpSupply::parse(rest)?;
+
+        let read_u64 = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+        let amount = read_u64(AMOUNT_OFFSET);
+        let min_x = read_u64(MIN_X_OFFSET);
+        let min_y = read_u64(MIN_Y_OFFSET);
+        let expiration = i64::from_le_bytes(data[EXPIRATION_OFFSET..WITHDRAW_DATA_LEN].try_into().unwrap());
+
+        // min_x / min_y 可以为 0：池子极度倾斜时，用户可能接受某一侧取回 0
         if amount == 0 {
-            return Err(ProgramError::InvalidInstructionData);
+            return Err(AmmError::ZeroAmount.into());
         }
-        if min_x == 0 {
-            return Err(ProgramError::InvalidInstructionData);
-        }
-        if min_y == 0 {
-            return Err(ProgramError::InvalidInstructionData);
+        // exact-out 至少要有一侧的目标数量
+        if exact_out && min_x == 0 && min_y == 0 {
+            return Err(AmmError::ZeroAmount.into());
         }
 
         Ok(Self {
@@ -242,6 +381,10 @@ impl<'a> TryFrom<&'a [u8]> for WithdrawInstructionData {
             min_x,
             min_y,
             expiration,
+            expiration_kind,
+            expected_lp_supply,
+            close_lp_ata,
+            exact_out,
         })
     }
 }
\ No newline at end of file
@@ -0,0 +1,296 @@
+use super::helpers::*;
+use crate::curve::Curve;
+use crate::errors::AmmError;
+use crate::events::WithdrawEvent;
+use crate::state::{AmmState, Config, GlobalConfig};
+use pinocchio::{
+    ProgramResult,
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::find_program_address,
+};
+
+/// 单边取款内部兑换允许的最大价格影响（基点），超过说明池子太浅
+pub const MAX_SINGLE_SIDED_IMPACT_BPS: u16 = 1_000;
+
+/// 销毁 LP，只取回 X 或 Y 其中一种
+///
+/// 等价于先按比例取款，再把另一侧在池子里兑换成目标代币（收取 swap 手续费）。
+/// 被兑换的那一侧从未离开金库，因此只有目标代币一笔转账。
+pub struct WithdrawSingleSided<'a> {
+    pub accounts: WithdrawSingleSidedAccounts<'a>,
+    pub instruction_data: WithdrawSingleSidedInstructionData,
+}
+
+impl<'a> TryFrom<(&'a [u8], &'a [AccountInfo])> for WithdrawSingleSided<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = WithdrawSingleSidedAccounts::try_from(accounts)?;
+        let instruction_data = WithdrawSingleSidedInstructionData::try_from(data)?;
+
+        Ok(Self { accounts, instruction_data })
+    }
+}
+
+impl<'a> WithdrawSingleSided<'a> {
+    pub const DISCRIMINATOR: &'a u8 = &13;
+
+    pub fn process(&mut self) -> ProgramResult {
+        let config = self.accounts.config;
+        with_pool_locked(config, || self.execute())
+    }
+
+    fn execute(&mut self) -> ProgramResult {
+        let config = Config::load_checked(self.accounts.config)?;
+
+        let accounts = &self.accounts;
+        let data = &self.instruction_data;
+
+        // 与 DepositSingleSided 相同，只支持 50/50 池子
+        if !config.is_equal_weight() {
+            return Err(AmmError::WeightedPoolUnsupported.into());
+        }
+
+        // 过期检查（允许全局配置中的 grace_secs 时钟误差）
+        let grace_secs = GlobalConfig::grace_secs_or_default(accounts.global_config)?;
+        data.expiration_kind.check(data.expiration, grace_secs)?;
+
+        config.validate_distinct_accounts([
+            accounts.user_out_ata,
+            accounts.user_lp_ata,
+            accounts.vault_x,
+            accounts.vault_y,
+            accounts.config,
+            accounts.mint_lp,
+        ])?;
+
+        // 内部包含一次兑换，只有可以 swap 的池子才允许
+        match config.amm_state()? {
+            AmmState::Initialized => {}
+            AmmState::Uninitialized | AmmState::Disabled | AmmState::WithdrawOnly => {
+                return Err(ProgramError::InvalidAccountData);
+            }
+        }
+
+        // LP supply 参与份额计算，mint_lp 必须是这个池子的 PDA
+        let (expected_mint_lp, _) =
+            find_program_address(&[b"mint_lp", accounts.config.key().as_ref()], &crate::ID);
+        if accounts.mint_lp.key() != &expected_mint_lp {
+            return Err(AmmError::InvalidLpMint.into());
+        }
+
+        let mint_lp = MintInterface::get(accounts.mint_lp)?;
+        if mint_lp.decimals() != config.lp_decimals() {
+            return Err(AmmError::InvalidLpMint.into());
+        }
+        let vault_x = TokenAccountInterface::get(accounts.vault_x)?;
+        let vault_y = TokenAccountInterface::get(accounts.vault_y)?;
+
+        if vault_x.mint() != config.mint_x() || vault_y.mint() != config.mint_y() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        // 与 Withdraw 相同，金库必须是 config 的 ATA，否则可以用另一个余额更大的代币账户抬高份额
+        let bumps = config.vault_bumps();
+        if !is_pool_vault(accounts.vault_x, accounts.config.key(), config.mint_x(), accounts.token_program, bumps.0)
+            || !is_pool_vault(accounts.vault_y, accounts.config.key(), config.mint_y(), accounts.token_program, bumps.1)
+        {
+            return Err(AmmError::InvalidVault.into());
+        }
+        TokenAccountInterface::check_no_external_close_authority(accounts.vault_x, accounts.config.key())?;
+        TokenAccountInterface::check_no_external_close_authority(accounts.vault_y, accounts.config.key())?;
+
+        // 与 Withdraw 相同，按储备快照计算
+        let reserves = PoolReserves::load(&config, accounts.vault_x, accounts.vault_y)?;
+        let (reserve_x, reserve_y) = (reserves.x, reserves.y);
+        let curve = Curve::for_liquidity(reserve_x, reserve_y, mint_lp.supply())?;
+        let (mut amount_out, impact) = curve.single_sided_withdraw(data.amount, data.to_x, config.fee())?;
+
+        // anti-JIT：与 Withdraw 相同，冷却期内不分手续费
+        if let Some(then) = PositionAccount::cooldown_liquidity_per_lp(
+            accounts.position,
+            accounts.config,
+            accounts.user,
+            config.jit_cooldown_slots(),
+        )? {
+            amount_out = forfeit_fee_growth(amount_out, then, curve.liquidity_per_lp()?)?;
+        }
+        // 退出费：与 Withdraw 相同
+        let exit_fee_bps = PositionAccount::exit_fee_bps(
+            accounts.position,
+            accounts.config,
+            accounts.user,
+            config.exit_fee_bps(),
+            config.exit_fee_window_secs(),
+        )?;
+        amount_out = apply_exit_fee(amount_out, exit_fee_bps)?;
+
+        if impact > MAX_SINGLE_SIDED_IMPACT_BPS {
+            return Err(AmmError::PriceImpactTooHigh.into());
+        }
+        // 内部兑换的输出同样受熔断限制
+        config.check_swap_out(amount_out)?;
+        // 滑点检查
+        if amount_out < data.min_out {
+            return Err(AmmError::SlippageExceeded.into());
+        }
+
+        burn_tokens(
+            accounts.token_program,
+            accounts.user_lp_ata,
+            accounts.mint_lp,
+            accounts.user,
+            data.amount,
+        )?;
+
+        let vault_out = match data.to_x {
+            true => accounts.vault_x,
+            false => accounts.vault_y,
+        };
+        let config_seeds = config.config_seeds();
+        transfer_pool_tokens(
+            &config,
+            accounts.mints.as_ref(),
+            accounts.token_program,
+            vault_out,
+            accounts.user_out_ata,
+            accounts.config,
+            amount_out,
+            data.to_x,
+            &config_seeds,
+        )?;
+
+        // 事件在 config 释放之后发出，先复制两侧的 mint
+        let (mint_x, mint_y) = (*config.mint_x(), *config.mint_y());
+        drop(config);
+        reserves.sync(accounts.config, accounts.vault_x, accounts.vault_y)?;
+        update_price_cumulative(accounts.config, reserve_x, reserve_y)?;
+
+        let (x, y) = match data.to_x {
+            true => (amount_out, 0),
+            false => (0, amount_out),
+        };
+        WithdrawEvent {
+            pool: accounts.config.key(),
+            user: accounts.user.key(),
+            mint_x: &mint_x,
+            mint_y: &mint_y,
+            lp: data.amount,
+            x,
+            y,
+        }
+        .emit();
+
+        Ok(())
+    }
+}
+
+pub struct WithdrawSingleSidedAccounts<'a> {
+    pub user: &'a AccountInfo,
+    pub mint_lp: &'a AccountInfo,
+    pub vault_x: &'a AccountInfo,
+    pub vault_y: &'a AccountInfo,
+    /// 用户接收目标代币（X 或 Y）的 ATA
+    pub user_out_ata: &'a AccountInfo,
+    pub user_lp_ata: &'a AccountInfo,
+    pub config: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    /// GlobalConfig PDA，InitializeGlobal 之前可以是空账户（见 `GlobalConfig::load_if_initialized`）
+    pub global_config: &'a AccountInfo,
+    /// require_checked_transfers 的池子必须在固定账户之后传入 mint_x / mint_y
+    pub mints: Option<PoolMints<'a>>,
+    /// 可选的末尾账户：池子启用 anti-JIT 冷却期或退出费时必须传入用户的 LpPosition PDA
+    pub position: Option<&'a AccountInfo>,
+}
+
+impl<'a> AccountsValidator<'a> for WithdrawSingleSidedAccounts<'a> {
+    fn validate(accounts: &'a [AccountInfo]) -> Result<Self, ProgramError> {
+        let [
+            user,
+            mint_lp,
+            vault_x,
+            vault_y,
+            user_out_ata,
+            user_lp_ata,
+            config,
+            token_program,
+            global_config,
+            // 保留位：与 Withdraw 的账户布局一致，客户端传 system_program，这里不读取
+            _,
+            rest @ ..,
+        ] = accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        let checked = Self::check_common(user, token_program, config)?.require_checked_transfers();
+        Self::check_data_accounts([config, mint_lp, vault_x, vault_y, user_out_ata, user_lp_ata])?;
+        let (mints, rest) = PoolMints::split(checked, rest)?;
+
+        Ok(Self {
+            user,
+            mint_lp,
+            vault_x,
+            vault_y,
+            user_out_ata,
+            user_lp_ata,
+            config,
+            token_program,
+            global_config,
+            mints,
+            position: rest.first(),
+        })
+    }
+}
+
+impl<'a> TryFrom<&'a [AccountInfo]> for WithdrawSingleSidedAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        Self::validate(accounts)
+    }
+}
+
+pub struct WithdrawSingleSidedInstructionData {
+    pub to_x: bool,
+    pub amount: u64,
+    pub min_out: u64,
+    pub expiration: i64,
+    /// 可选：expiration 是时间戳还是 slot，缺省为时间戳
+    pub expiration_kind: ExpirationKind,
+}
+
+impl<'a> TryFrom<&'a [u8]> for WithdrawSingleSidedInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        // to_x (1) + amount (8) + min_out (8) + expiration (8)，末尾可选地附加 1 字节 expiration_kind
+        const WITHDRAW_SINGLE_SIDED_DATA_LEN: usize = 25;
+
+        if data.len() != WITHDRAW_SINGLE_SIDED_DATA_LEN && data.len() != WITHDRAW_SINGLE_SIDED_DATA_LEN + 1 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (_, expiration_kind) = ExpirationKind::split_suffix(&data[WITHDRAW_SINGLE_SIDED_DATA_LEN..], &[1])?;
+
+        let to_x = match data[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+        let amount = u64::from_le_bytes(data[1..9].try_into().unwrap());
+        let min_out = u64::from_le_bytes(data[9..17].try_into().unwrap());
+        let expiration = i64::from_le_bytes(data[17..25].try_into().unwrap());
+
+        if amount == 0 {
+            return Err(AmmError::ZeroAmount.into());
+        }
+
+        Ok(Self {
+            to_x,
+            amount,
+            min_out,
+            expiration,
+            expiration_kind,
+        })
+    }
+}
@@ -4,13 +4,15 @@ use pinocchio::{
 };
 entrypoint!(process_instruction);
 
-mod instructions;
+pub mod instructions;
 use instructions::*;
 
-mod state;
+pub mod state;
 // use state::*;
-mod errors;
+pub mod errors;
 // use errors::*;
+pub mod curve;
+pub mod events;
 
 // 22222222222222222222222222222222222222222222
 pub const ID: Pubkey = [
@@ -30,6 +32,41 @@ fn process_instruction(
         Some((Deposit::DISCRIMINATOR, data)) => Deposit::try_from((data, accounts))?.process(),
         Some((Withdraw::DISCRIMINATOR, data)) => Withdraw::try_from((data, accounts))?.process(),
         Some((Swap::DISCRIMINATOR, data)) => Swap::try_from((data, accounts))?.process(),
+        Some((InitializeGlobal::DISCRIMINATOR, data)) => {
+            InitializeGlobal::try_from((data, accounts))?.process()
+        }
+        Some((SetGlobalConfig::DISCRIMINATOR, data)) => {
+            SetGlobalConfig::try_from((data, accounts))?.process()
+        }
+        Some((InitializeAndSeed::DISCRIMINATOR, data)) => {
+            InitializeAndSeed::try_from((data, accounts))?.process()
+        }
+        Some((Quote::DISCRIMINATOR, data)) => Quote::try_from((data, accounts))?.process(),
+        Some((SetConfig::DISCRIMINATOR, data)) => SetConfig::try_from((data, accounts))?.process(),
+        Some((SimulateWithdraw::DISCRIMINATOR, data)) => {
+            SimulateWithdraw::try_from((data, accounts))?.process()
+        }
+        Some((TotalValueLocked::DISCRIMINATOR, data)) => {
+            TotalValueLocked::try_from((data, accounts))?.process()
+        }
+        Some((LpForDeposit::DISCRIMINATOR, data)) => {
+            LpForDeposit::try_from((data, accounts))?.process()
+        }
+        Some((ObservePrice::DISCRIMINATOR, data)) => {
+            ObservePrice::try_from((data, accounts))?.process()
+        }
+        Some((WithdrawSingleSided::DISCRIMINATOR, data)) => {
+            WithdrawSingleSided::try_from((data, accounts))?.process()
+        }
+        Some((SetState::DISCRIMINATOR, data)) => SetState::try_from((data, accounts))?.process(),
+        Some((DepositSingleSided::DISCRIMINATOR, data)) => {
+            DepositSingleSided::try_from((data, accounts))?.process()
+        }
+        Some((FlashSwap::DISCRIMINATOR, data)) => FlashSwap::try_from((data, accounts))?.process(),
+        Some((QuoteSwap::DISCRIMINATOR, data)) => QuoteSwap::try_from((data, accounts))?.process(),
+        Some((SwapRoute::DISCRIMINATOR, data)) => SwapRoute::try_from((data, accounts))?.process(),
+        Some((CollectFees::DISCRIMINATOR, data)) => CollectFees::try_from((data, accounts))?.process(),
+        Some((DisablePool::DISCRIMINATOR, data)) => DisablePool::try_from((data, accounts))?.process(),
         _ => Err(ProgramError::InvalidInstructionData),
     }
 }
@@ -1,13 +1,92 @@
+use crate::errors::AmmError;
+use crate::instructions::helpers::mul_div;
 use core::mem::size_of;
 use pinocchio::{
     account_info::{AccountInfo, Ref, RefMut}, 
     instruction::Seed, 
     program_error::ProgramError, 
-    pubkey::Pubkey
+    pubkey::{Pubkey, create_program_address, find_program_address}
 };
 
+/// LP mint 的缺省精度，Initialize 不传 lp_decimals 时使用
+///
+/// LP 的数量只表示在池子中的份额，与 X/Y 的精度无关：存取款的数量都是
+/// `reserve * lp / supply` 形式的比例，X、Y 各自在自己的最小单位下计算，不需要（也不应该）
+/// 把两侧换算到同一精度。因此 X/Y 精度不同的池子不需要额外的归一化；
+/// 高精度代币组成的池子可以在 Initialize 时选择更高的 LP 精度，让 LP 的最小单位更细。
+/// 存取款只需要保证传入的 LP mint 的精度与 `Config::lp_decimals` 一致。
 pub const LP_DECIMALS: u8 = 6;
 
+/// `Config::validate_distinct_accounts` 的纯函数版本，只比较公钥，便于单独测试
+#[inline(always)]
+pub fn validate_distinct_keys(
+    mint_x: &Pubkey,
+    mint_y: &Pubkey,
+    keys: &[&Pubkey],
+) -> Result<(), ProgramError> {
+    if mint_x == mint_y {
+        return Err(AmmError::DuplicateAccount.into());
+    }
+    for (i, a) in keys.iter().enumerate() {
+        if keys[i + 1..].iter().any(|b| a == b) {
+            return Err(AmmError::DuplicateAccount.into());
+        }
+    }
+    Ok(())
+}
+
+/// 首次存款时永久锁定的 LP 数量，防止首个 LP 的份额通胀攻击
+///
+/// 这部分 LP 不会真正铸造出来，而是在所有份额计算中加到 supply 上（等价于 Uniswap 铸造到 address(0)），
+/// 因此对应的储备永远留在金库中，池子的 LP 价值无法被压到只剩几个最小单位。
+pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+
+/// Initialize 的 fee 传入此值时，池子使用 GlobalConfig 中的默认费率
+pub const USE_GLOBAL_DEFAULT_FEE: u16 = u16::MAX;
+
+/// SetConfig 修改费率时允许的上限（基点，1_000 = 10%），超过时返回 `FeeTooHigh`
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+/// Config::max_swap_out 取此值时不限制单次 swap 的输出
+pub const NO_SWAP_OUT_LIMIT: u64 = 0;
+
+/// Config::jit_cooldown_slots 取此值时不启用 anti-JIT 冷却期
+pub const JIT_COOLDOWN_DISABLED: u64 = 0;
+
+/// Config::exit_fee_bps 取此值时不收取退出费（默认）
+pub const EXIT_FEE_DISABLED: u16 = 0;
+
+/// TWAP 累加器中价格的定点小数位数（Q64.64）
+pub const PRICE_FRACTION_BITS: u32 = 64;
+
+/// X 一侧的缺省权重（基点）：50/50 的恒定乘积池子
+pub const EQUAL_WEIGHT_BPS: u16 = 5_000;
+/// 加权池子中任一侧权重的下限 / 上限（基点），例如 8_000 为 80/20 池子
+///
+/// 权重越极端，兑换公式中的指数越大（最多 9 次方），定点数的舍入误差也被放大，因此不允许任意接近 0 或 10000
+pub const MIN_WEIGHT_BPS: u16 = 1_000;
+pub const MAX_WEIGHT_BPS: u16 = 9_000;
+
+/// 储备之比换算成现货价格：加权池子中以 quote 计价的 base 价格为 (quote / w_quote) / (base / w_base)，
+/// 即储备之比 `ratio`（quote / base，Q64.64）再乘以 w_base / w_quote；50/50 时原样返回
+#[inline(always)]
+pub fn weighted_price(ratio: u128, weight_base: u16) -> u128 {
+    if weight_base == EQUAL_WEIGHT_BPS {
+        return ratio;
+    }
+    let (weight_base, weight_quote) = (weight_base as u64, (10_000 - weight_base) as u64);
+
+    // ratio * w_base / w_quote 先乘后除：高 64 位用 mul_div，余数并入低 64 位再除，结果即整体的向下取整
+    let (hi, lo) = ((ratio >> 64) as u64, ratio as u64);
+    let Ok(quotient_hi) = mul_div(hi, weight_base, weight_quote) else {
+        return u128::MAX;
+    };
+    // remainder < w_quote < 2^14，lo * w_base < 2^78，相加不会溢出 u128
+    let remainder = (hi as u128 * weight_base as u128) % weight_quote as u128;
+    let quotient_lo = ((remainder << 64) + lo as u128 * weight_base as u128) / weight_quote as u128;
+    ((quotient_hi as u128) << 64).saturating_add(quotient_lo)
+}
+
 /// 从配置参数构造 config PDA 的种子数组
 /// 
 /// 用于 initialize 阶段（还没有 Config 实例）或任何需要从原始参数构造种子的场景
@@ -33,14 +112,49 @@ pub fn config_seeds_from_parts<'a>(
 pub struct Config {
     state: u8,
     seed: [u8; 8],
-    authority: Pubkey, //对 AMM 拥有管理控制权的公钥（例如，用于暂停或升级池）。可以通过传递 [0u8; 32] 将其设置为不可变。
+    authority: Pubkey, //池子的 owner，负责重新分配 fee_admin / pause_admin（见 ConfigRole）。可以通过传递 [0u8; 32] 将其设置为不可变。
     mint_x: Pubkey,
     mint_y: Pubkey,
     fee: [u8; 2], //以基点（1 基点 = 0.01%）表示的交换费用，在每次交易中收取并分配给流动性提供者。
     config_bump: [u8; 1], //用于 PDA 派生的 bump 种子，确保配置账户地址有效且唯一。保存此值以提高 PDA 派生效率。
+    max_swap_out: [u8; 8], //单次 swap 输出数量上限（熔断），NO_SWAP_OUT_LIMIT 表示不限制
+    price_cumulative_x: [u8; 16], //X 以 Y 计价（y/x，Q64.64）对时间的累加，允许回绕
+    price_cumulative_y: [u8; 16], //Y 以 X 计价（x/y，Q64.64）对时间的累加，允许回绕
+    last_update_ts: [u8; 8], //上次更新累加器时的 unix 时间戳
+    fee_admin: Pubkey, //可以修改手续费
+    pause_admin: Pubkey, //可以暂停/恢复池子、设置 swap 熔断
+    jit_cooldown_slots: [u8; 8], //存款后多少个 slot 内取款不分手续费（anti-JIT），JIT_COOLDOWN_DISABLED 表示关闭
+    exit_fee_bps: [u8; 2], //存款后立即取款的退出费（基点），在 exit_fee_window_secs 内线性衰减到 0，EXIT_FEE_DISABLED 表示关闭
+    exit_fee_window_secs: [u8; 8], //退出费的衰减窗口（秒）
+    require_checked_transfers: u8, //1 = 所有 X/Y 转账都使用 TransferChecked（Token-2022 池子在 Initialize 时自动打开）
+    decimals_x: u8, //Initialize 时记录的 mint_x 精度，TransferChecked 使用
+    decimals_y: u8, //Initialize 时记录的 mint_y 精度，TransferChecked 使用
+    protocol_fee: [u8; 2], //协议费（基点），swap 时先从输入中扣下，转给 treasury 的 ATA，其余部分再进入曲线计算；0 表示关闭
+    treasury: Pubkey, //接收协议费的 owner，swap 传入的 treasury_ata 必须属于它；设置为池子自己的 config 地址时协议费留在金库中累计（见 fees_x / fees_y）
+    reserve_x: [u8; 8], //储备快照：定价使用快照而不是金库余额（见 PoolReserves），只随存取款 / swap 的实际转账变化，直接转入金库的代币（捐赠）不计入
+    reserve_y: [u8; 8],
+    lp_decimals: u8, //LP mint 的精度，Initialize 时确定，缺省为 LP_DECIMALS
+    fees_x: [u8; 8], //留在金库中、尚未被 CollectFees 取走的协议费（X），不计入储备快照
+    fees_y: [u8; 8],
+    vault_x_bump: u8, //vault_x（config 的 mint_x ATA）的 bump，Initialize 时记录，验证金库时只需一次 create_program_address
+    vault_y_bump: u8, //vault_y（config 的 mint_y ATA）的 bump
+    locked: u8, //1 = 池子正在执行 swap / deposit / withdraw 等资金类指令，期间再次进入本池子的资金类指令返回 Reentrancy
+    weight_x: [u8; 2], //X 一侧的权重（基点），Y 一侧为 10000 - weight_x；Initialize 时确定
+}
+
+/// 池子的管理角色，各 authority-gated 操作只检查对应的角色（最小权限）
+///
+/// Owner 即 Config 中的 `authority`，负责重新分配 FeeAdmin / PauseAdmin。
+/// 某个角色为全零时，该角色对应的操作被永久关闭。
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConfigRole {
+    Owner,
+    FeeAdmin,
+    PauseAdmin,
 }
 
 #[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum AmmState {
     Uninitialized = 0u8,
     Initialized = 1u8,
@@ -48,11 +162,46 @@ pub enum AmmState {
     WithdrawOnly = 3u8,
 }
 
+impl AmmState {
+    /// 状态迁移表（只允许列出的迁移，其余一律拒绝，包括重复设置同一状态）：
+    /// - Uninitialized -> Initialized：Initialize
+    /// - Initialized <-> WithdrawOnly：暂停 swap / deposit 与恢复
+    /// - WithdrawOnly -> Disabled：LP 取完后下线，Disabled 是终态
+    ///
+    /// 任何状态都不能回到 Uninitialized，Initialized 也不能跳过 WithdrawOnly 直接下线
+    #[inline(always)]
+    pub fn can_transition_to(self, new: AmmState) -> bool {
+        matches!(
+            (self, new),
+            (AmmState::Uninitialized, AmmState::Initialized)
+                | (AmmState::Initialized, AmmState::WithdrawOnly)
+                | (AmmState::WithdrawOnly, AmmState::Initialized)
+                | (AmmState::WithdrawOnly, AmmState::Disabled)
+        )
+    }
+}
+
+impl TryFrom<u8> for AmmState {
+    type Error = ProgramError;
+
+    #[inline(always)]
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(AmmState::Uninitialized),
+            1 => Ok(AmmState::Initialized),
+            2 => Ok(AmmState::Disabled),
+            3 => Ok(AmmState::WithdrawOnly),
+            _ => Err(AmmError::InvalidAmmState.into()),
+        }
+    }
+}
+
 impl Config {
     pub const LEN: usize = size_of::<Config>();
 
+    /// 完整校验后加载：owner 必须是本程序、长度必须为 LEN，并登记一次不可变借用
     #[inline(always)]
-    pub fn load(account_info: &AccountInfo) -> Result<Ref<Self>, ProgramError> {
+    pub fn load_checked(account_info: &AccountInfo) -> Result<Ref<'_, Self>, ProgramError> {
         if account_info.data_len() != Self::LEN {
             return Err(ProgramError::InvalidAccountData);
         }
@@ -63,20 +212,27 @@ impl Config {
             Self::from_bytes_unchecked(data)
         }))
     }
+    /// 热路径加载：不检查 owner / 长度，也不登记借用
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that:
+    /// - `account_info` already passed `load_checked` in this instruction (e.g. in
+    ///   `AccountsValidator::validate`), so it is owned by this program and is `LEN` bytes long;
+    /// - no mutable borrow of the account data (`load_mut`, `try_borrow_mut_data`, ...) exists
+    ///   while the returned reference is in use. Handlers stop using it before `PoolReserves::sync`
+    ///   and `update_price_cumulative` write the account.
     #[inline(always)]
-    pub unsafe fn load_unchecked(account_info: &AccountInfo) -> Result<&Self, ProgramError> {
-        if account_info.data_len() != Self::LEN {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        if account_info.owner() != &crate::ID {
-            return Err(ProgramError::InvalidAccountOwner);
-        }
-        unsafe {
-            Ok(Self::from_bytes_unchecked(
-                account_info.borrow_data_unchecked(),
-            ))
-        }
+    pub unsafe fn load_unchecked(account_info: &AccountInfo) -> &Self {
+        debug_assert!(account_info.data_len() == Self::LEN && account_info.owner() == &crate::ID);
+        unsafe { Self::from_bytes_unchecked(account_info.borrow_data_unchecked()) }
     }
+    /// Return a mutable `Config` reference from raw account data, checking only the length.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `data` belongs to a config account owned by this program
+    /// (e.g. it was just created by `Initialize`) and is not aliased while the reference is in use.
     #[inline(always)]
     pub unsafe fn load_mut_unchecked(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
         if data.len() != Self::LEN {
@@ -110,6 +266,11 @@ impl Config {
     pub fn state(&self) -> u8 {
         self.state
     }
+    /// 以枚举形式返回池子状态，handler 应对其做穷尽匹配，新增状态时编译器会提示每个 handler
+    #[inline(always)]
+    pub fn amm_state(&self) -> Result<AmmState, ProgramError> {
+        AmmState::try_from(self.state)
+    }
     #[inline(always)]
     pub fn seed(&self) -> u64 {
         u64::from_le_bytes(self.seed)
@@ -119,6 +280,22 @@ impl Config {
         &self.authority
     }
     #[inline(always)]
+    pub fn fee_admin(&self) -> &Pubkey {
+        &self.fee_admin
+    }
+    #[inline(always)]
+    pub fn pause_admin(&self) -> &Pubkey {
+        &self.pause_admin
+    }
+    #[inline(always)]
+    pub fn role(&self, role: ConfigRole) -> &Pubkey {
+        match role {
+            ConfigRole::Owner => &self.authority,
+            ConfigRole::FeeAdmin => &self.fee_admin,
+            ConfigRole::PauseAdmin => &self.pause_admin,
+        }
+    }
+    #[inline(always)]
     pub fn mint_x(&self) -> &Pubkey {
         &self.mint_x
     }
@@ -134,17 +311,196 @@ impl Config {
     pub fn config_bump(&self) -> [u8; 1] {
         self.config_bump
     }
+    #[inline(always)]
+    pub fn max_swap_out(&self) -> u64 {
+        u64::from_le_bytes(self.max_swap_out)
+    }
+    #[inline(always)]
+    pub fn jit_cooldown_slots(&self) -> u64 {
+        u64::from_le_bytes(self.jit_cooldown_slots)
+    }
+    #[inline(always)]
+    pub fn require_checked_transfers(&self) -> bool {
+        self.require_checked_transfers == 1
+    }
+    #[inline(always)]
+    pub fn decimals_x(&self) -> u8 {
+        self.decimals_x
+    }
+    #[inline(always)]
+    pub fn decimals_y(&self) -> u8 {
+        self.decimals_y
+    }
+    #[inline(always)]
+    pub fn protocol_fee(&self) -> u16 {
+        u16::from_le_bytes(self.protocol_fee)
+    }
+    #[inline(always)]
+    pub fn treasury(&self) -> &Pubkey {
+        &self.treasury
+    }
+    #[inline(always)]
+    pub fn lp_decimals(&self) -> u8 {
+        self.lp_decimals
+    }
+    #[inline(always)]
+    pub fn reserve_x(&self) -> u64 {
+        u64::from_le_bytes(self.reserve_x)
+    }
+    #[inline(always)]
+    pub fn reserve_y(&self) -> u64 {
+        u64::from_le_bytes(self.reserve_y)
+    }
+    #[inline(always)]
+    pub fn fees_x(&self) -> u64 {
+        u64::from_le_bytes(self.fees_x)
+    }
+    #[inline(always)]
+    pub fn fees_y(&self) -> u64 {
+        u64::from_le_bytes(self.fees_y)
+    }
+    /// Initialize 记录的金库 ATA bump (vault_x, vault_y)
+    #[inline(always)]
+    pub fn vault_bumps(&self) -> (u8, u8) {
+        (self.vault_x_bump, self.vault_y_bump)
+    }
+    /// X 一侧的权重（基点）
+    #[inline(always)]
+    pub fn weight_x(&self) -> u16 {
+        u16::from_le_bytes(self.weight_x)
+    }
+    /// 是否为 50/50 池子
+    #[inline(always)]
+    pub fn is_equal_weight(&self) -> bool {
+        self.weight_x() == EQUAL_WEIGHT_BPS
+    }
+    /// 池子是否正在执行资金类指令（见 `with_pool_locked`）
+    #[inline(always)]
+    pub fn is_locked(&self) -> bool {
+        self.locked == 1
+    }
+    /// 重入检查：池子被锁定时返回 `Reentrancy`
+    #[inline(always)]
+    pub fn check_unlocked(&self) -> Result<(), ProgramError> {
+        if self.is_locked() {
+            return Err(AmmError::Reentrancy.into());
+        }
+        Ok(())
+    }
+    /// 协议费是否留在金库中累计：开启了协议费，且 treasury 是池子自己的 config 地址
+    #[inline(always)]
+    pub fn accrues_protocol_fee(&self, config: &Pubkey) -> bool {
+        self.protocol_fee() != 0 && self.treasury() == config
+    }
+    /// swap 是否需要传入 treasury ATA：开启了协议费，且协议费直接转给外部的 treasury
+    #[inline(always)]
+    pub fn requires_treasury_ata(&self, config: &Pubkey) -> bool {
+        self.protocol_fee() != 0 && !self.accrues_protocol_fee(config)
+    }
+    #[inline(always)]
+    pub fn exit_fee_bps(&self) -> u16 {
+        u16::from_le_bytes(self.exit_fee_bps)
+    }
+    #[inline(always)]
+    pub fn exit_fee_window_secs(&self) -> u64 {
+        u64::from_le_bytes(self.exit_fee_window_secs)
+    }
+    /// 是否启用了退出费（费率和窗口都非零）
+    #[inline(always)]
+    pub fn exit_fee_enabled(&self) -> bool {
+        self.exit_fee_bps() != EXIT_FEE_DISABLED && self.exit_fee_window_secs() != 0
+    }
+    /// 是否需要为存款人记录 LpPosition（anti-JIT 冷却期或退出费任一启用）
+    #[inline(always)]
+    pub fn tracks_positions(&self) -> bool {
+        self.jit_cooldown_slots() != JIT_COOLDOWN_DISABLED || self.exit_fee_enabled()
+    }
+    #[inline(always)]
+    pub fn price_cumulative_x(&self) -> u128 {
+        u128::from_le_bytes(self.price_cumulative_x)
+    }
+    #[inline(always)]
+    pub fn price_cumulative_y(&self) -> u128 {
+        u128::from_le_bytes(self.price_cumulative_y)
+    }
+    #[inline(always)]
+    pub fn last_update_ts(&self) -> i64 {
+        i64::from_le_bytes(self.last_update_ts)
+    }
+
+    /// 把 [last_update_ts, now) 这段时间的价格累加进 TWAP 累加器
+    ///
+    /// 必须在储备变化之前、用变化前的储备调用：这段时间内池子的价格就是这组储备给出的价格，
+    /// 同一时间戳内的多次调用不会再累加，因此单个区块内操纵储备无法影响累加值。
+    /// 累加值按 u128 回绕，使用方取两次观测的差值时应使用 wrapping_sub，
+    /// TWAP = (c2 - c1) / (t2 - t1)，结果为 Q64.64 定点数。
+    /// 加权池子的价格按 `weighted_price` 换算
+    #[inline(always)]
+    pub fn update_price_cumulative(&mut self, reserve_x: u64, reserve_y: u64, now: i64) {
+        let elapsed = now.saturating_sub(self.last_update_ts());
+        if elapsed > 0 && reserve_x != 0 && reserve_y != 0 {
+            // reserve < 2^64，左移 64 位不会溢出 u128
+            let price_x = weighted_price(((reserve_y as u128) << PRICE_FRACTION_BITS) / reserve_x as u128, self.weight_x());
+            let price_y =
+                weighted_price(((reserve_x as u128) << PRICE_FRACTION_BITS) / reserve_y as u128, 10_000 - self.weight_x());
+            self.price_cumulative_x = self
+                .price_cumulative_x()
+                .wrapping_add(price_x.wrapping_mul(elapsed as u128))
+                .to_le_bytes();
+            self.price_cumulative_y = self
+                .price_cumulative_y()
+                .wrapping_add(price_y.wrapping_mul(elapsed as u128))
+                .to_le_bytes();
+        }
+        if elapsed > 0 {
+            self.last_update_ts = now.to_le_bytes();
+        }
+    }
+
+    /// 检查单次 swap 的输出是否超过上限
+    #[inline(always)]
+    pub fn check_swap_out(&self, amount_out: u64) -> Result<(), ProgramError> {
+        match self.max_swap_out() {
+            NO_SWAP_OUT_LIMIT => Ok(()),
+            max if amount_out > max => Err(AmmError::OutputCapExceeded.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// 检查一条指令中与代币相关的账户两两不同，并且池子的两个 mint 不同
+    ///
+    /// 用户 ATA 与金库重合会变成自转账；config 与 mint_lp/金库重合会让同一个账户扮演两种角色。
+    /// 各指令只需把自己的代币相关账户（ATA、金库、config、mint_lp 等）一次性传进来
+    #[inline(always)]
+    pub fn validate_distinct_accounts<const N: usize>(
+        &self,
+        accounts: [&AccountInfo; N],
+    ) -> Result<(), ProgramError> {
+        validate_distinct_keys(self.mint_x(), self.mint_y(), &accounts.map(|a| a.key()))
+    }
+
+    /// 检查 authority 是否签名，且与池子配置中保存的 `role` 一致（该角色为全零时对应操作不可用）
+    #[inline(always)]
+    pub fn check_authority(&self, authority: &AccountInfo, role: ConfigRole) -> Result<(), ProgramError> {
+        if !authority.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        match non_zero_key(self.role(role)) {
+            Some(expected) if expected.eq(authority.key()) => Ok(()),
+            _ => Err(AmmError::InvalidAuthority.into()),
+        }
+    }
 
     /// 构造此 Config PDA 的种子数组，用于签名操作
     /// 
     /// 调用方应在栈上持有返回的 seeds，然后构造 Signer：
-    /// ```
+    /// ```ignore
     /// let seeds = config.config_seeds();
     /// let signer = Signer::from(&seeds);
     /// xxx.invoke_signed(&[signer])?;
     /// ```
     #[inline(always)]
-    pub fn config_seeds(&self) -> [Seed; 5] {
+    pub fn config_seeds(&self) -> [Seed<'_>; 5] {
         config_seeds_from_parts(
             &self.seed,
             self.mint_x(),
@@ -155,7 +511,7 @@ impl Config {
 
     //辅助函数
     #[inline(always)]
-    pub fn load_mut(account_info: &AccountInfo) -> Result<RefMut<Self>, ProgramError> {
+    pub fn load_mut(account_info: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
         if account_info.data_len() != Self::LEN {
             return Err(ProgramError::InvalidAccountData);
         }
@@ -167,12 +523,13 @@ impl Config {
             |data| unsafe { Self::from_bytes_unchecked_mut(data) },
         ))
     }
+    /// 按 `AmmState::can_transition_to` 的迁移表修改状态，非法迁移返回 `InvalidStateTransition`
     #[inline(always)]
-    pub fn set_state(&mut self, state: u8) -> Result<(), ProgramError> {
-        if state.ge(&(AmmState::WithdrawOnly as u8)) {
-            return Err(ProgramError::InvalidAccountData);
+    pub fn set_state(&mut self, new: AmmState) -> Result<(), ProgramError> {
+        if !self.amm_state()?.can_transition_to(new) {
+            return Err(AmmError::InvalidStateTransition.into());
         }
-        self.state = state as u8;
+        self.state = new as u8;
         Ok(())
     }
     #[inline(always)]
@@ -192,6 +549,95 @@ impl Config {
         self.authority = authority;
     }
     #[inline(always)]
+    pub fn set_jit_cooldown_slots(&mut self, slots: u64) {
+        self.jit_cooldown_slots = slots.to_le_bytes();
+    }
+    /// 打开 require_checked_transfers 并记录两侧 mint 的精度
+    #[inline(always)]
+    pub fn set_checked_transfers(&mut self, decimals_x: u8, decimals_y: u8) {
+        self.require_checked_transfers = 1;
+        self.decimals_x = decimals_x;
+        self.decimals_y = decimals_y;
+    }
+    /// 设置退出费，bps 不能超过 10_000（100%）
+    #[inline(always)]
+    pub fn set_exit_fee(&mut self, bps: u16, window_secs: u64) -> Result<(), ProgramError> {
+        if bps > 10_000 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        self.exit_fee_bps = bps.to_le_bytes();
+        self.exit_fee_window_secs = window_secs.to_le_bytes();
+        Ok(())
+    }
+    #[inline(always)]
+    pub fn set_lp_decimals(&mut self, lp_decimals: u8) {
+        self.lp_decimals = lp_decimals;
+    }
+    /// 写入储备快照，之后的定价都以快照为准
+    #[inline(always)]
+    pub fn set_reserves(&mut self, reserve_x: u64, reserve_y: u64) {
+        self.reserve_x = reserve_x.to_le_bytes();
+        self.reserve_y = reserve_y.to_le_bytes();
+    }
+    /// 累计留在金库中的协议费，`is_x` 表示哪一侧
+    #[inline(always)]
+    pub fn accrue_fees(&mut self, is_x: bool, amount: u64) -> Result<(), ProgramError> {
+        let fees = match is_x {
+            true => &mut self.fees_x,
+            false => &mut self.fees_y,
+        };
+        let total = u64::from_le_bytes(*fees)
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        *fees = total.to_le_bytes();
+        Ok(())
+    }
+    /// 记录金库 ATA 的 bump，之后验证金库地址时不再需要 find_program_address
+    #[inline(always)]
+    pub fn set_vault_bumps(&mut self, vault_x_bump: u8, vault_y_bump: u8) {
+        self.vault_x_bump = vault_x_bump;
+        self.vault_y_bump = vault_y_bump;
+    }
+    /// 权重必须在 [MIN_WEIGHT_BPS, MAX_WEIGHT_BPS] 内
+    #[inline(always)]
+    pub fn set_weight_x(&mut self, weight_x: u16) -> Result<(), ProgramError> {
+        if !(MIN_WEIGHT_BPS..=MAX_WEIGHT_BPS).contains(&weight_x) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        self.weight_x = weight_x.to_le_bytes();
+        Ok(())
+    }
+    #[inline(always)]
+    pub fn set_locked(&mut self, locked: bool) {
+        self.locked = locked as u8;
+    }
+    /// CollectFees 取走后清零
+    #[inline(always)]
+    pub fn reset_fees(&mut self) {
+        self.fees_x = [0u8; 8];
+        self.fees_y = [0u8; 8];
+    }
+    /// 设置协议费及其接收方；开启协议费（bps > 0）时 treasury 不能为全零
+    ///
+    /// treasury 为池子自己的 config 地址时，协议费留在金库中由 CollectFees 取出
+    #[inline(always)]
+    pub fn set_protocol_fee(&mut self, bps: u16, treasury: Pubkey) -> Result<(), ProgramError> {
+        if bps.ge(&10_000) || (bps != 0 && non_zero_key(&treasury).is_none()) {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        self.protocol_fee = bps.to_le_bytes();
+        self.treasury = treasury;
+        Ok(())
+    }
+    #[inline(always)]
+    pub fn set_fee_admin(&mut self, fee_admin: Pubkey) {
+        self.fee_admin = fee_admin;
+    }
+    #[inline(always)]
+    pub fn set_pause_admin(&mut self, pause_admin: Pubkey) {
+        self.pause_admin = pause_admin;
+    }
+    #[inline(always)]
     pub fn set_mint_x(&mut self, mint_x: Pubkey) {
         self.mint_x = mint_x;
     }
@@ -204,6 +650,10 @@ impl Config {
         self.config_bump = config_bump;
     }
     #[inline(always)]
+    pub fn set_max_swap_out(&mut self, max_swap_out: u64) {
+        self.max_swap_out = max_swap_out.to_le_bytes();
+    }
+    #[inline(always)]
     pub fn set_inner(
         &mut self,
         seed: u64,
@@ -213,23 +663,454 @@ impl Config {
         fee: u16,
         config_bump: [u8; 1],
     ) -> Result<(), ProgramError> {
-        self.set_state(AmmState::Initialized as u8)?;
+        self.set_state(AmmState::Initialized)?;
         self.set_seed(seed);
+        // 三个角色默认都是初始 authority，之后由 owner 分别重新分配
         self.set_authority(authority);
+        self.set_fee_admin(authority);
+        self.set_pause_admin(authority);
         self.set_mint_x(mint_x);
         self.set_mint_y(mint_y);
         self.set_fee(fee)?;
         self.set_config_bump(config_bump);
+        self.set_max_swap_out(NO_SWAP_OUT_LIMIT);
+        self.set_jit_cooldown_slots(JIT_COOLDOWN_DISABLED);
+        self.set_exit_fee(EXIT_FEE_DISABLED, 0)?;
+        // 默认使用普通 Transfer，Token-2022 池子由 Initialize 另外调用 set_checked_transfers
+        self.require_checked_transfers = 0;
+        self.decimals_x = 0;
+        self.decimals_y = 0;
+        self.set_protocol_fee(0, [0u8; 32])?;
+        // 新池子从空储备开始记录快照
+        self.reserve_x = [0u8; 8];
+        self.reserve_y = [0u8; 8];
+        self.set_lp_decimals(LP_DECIMALS);
+        self.reset_fees();
+        // 金库 bump 由 Initialize 另外调用 set_vault_bumps 记录
+        self.vault_x_bump = 0;
+        self.vault_y_bump = 0;
+        self.locked = 0;
+        // 权重由 Initialize 另外调用 set_weight_x 设置
+        self.set_weight_x(EQUAL_WEIGHT_BPS)?;
+        self.price_cumulative_x = [0u8; 16];
+        self.price_cumulative_y = [0u8; 16];
+        self.last_update_ts = [0u8; 8];
         Ok(())
     }
     #[inline(always)]
     pub fn has_authority(&self) -> Option<Pubkey> {
-        let bytes = self.authority();
-        let chunks: &[u64; 4] = unsafe { &*(bytes.as_ptr() as *const [u64; 4]) };
-        if chunks.iter().any(|&x| x != 0) {
-            Some(self.authority)
-        } else {
-            None
+        non_zero_key(self.authority())
+    }
+}
+
+/// 全零的公钥视为“未设置”
+#[inline(always)]
+fn non_zero_key(key: &Pubkey) -> Option<Pubkey> {
+    let chunks: &[u64; 4] = unsafe { &*(key.as_ptr() as *const [u64; 4]) };
+    if chunks.iter().any(|&x| x != 0) {
+        Some(*key)
+    } else {
+        None
+    }
+}
+
+/// 过期检查默认允许的时钟误差（秒）
+pub const DEFAULT_GRACE_SECS: u16 = 5;
+/// grace_secs 的上限，避免实质性地延长用户设置的截止时间
+pub const MAX_GRACE_SECS: u16 = 60;
+
+/// GlobalConfig 中下游程序白名单的槽位数，见 Swap 的输出转交
+pub const MAX_DOWNSTREAM_PROGRAMS: usize = 4;
+
+/// 从 bump 构造 global config PDA 的种子数组（seeds = [b"global", bump]）
+#[inline(always)]
+pub fn global_seeds_from_parts(bump: &[u8; 1]) -> [Seed<'_>; 2] {
+    [Seed::from(b"global"), Seed::from(bump.as_ref())]
+}
+
+/// 程序级别的全局配置，由 superadmin 初始化一次
+///
+/// 保存所有池共享的默认参数（例如默认费率），只有 superadmin 签名才能修改
+#[repr(C)]
+pub struct GlobalConfig {
+    superadmin: Pubkey,    //唯一可以修改全局配置的公钥
+    default_fee: [u8; 2],  //新建池子在未指定费率时使用的默认费率（基点）
+    bump: [u8; 1],
+    grace_secs: [u8; 2],   //过期检查允许的时钟误差（秒）
+    enforce_unique: u8,    //1 = 同一 (mint_x, mint_y, fee) 只允许一个池子，见 PoolRegistry
+    downstream_programs: [Pubkey; MAX_DOWNSTREAM_PROGRAMS], //Swap 可以把输出转交给的下游程序，全零表示空槽
+}
+
+impl GlobalConfig {
+    pub const LEN: usize = size_of::<GlobalConfig>();
+
+    /// 加载并校验 global config：长度、owner 以及 PDA 地址
+    #[inline(always)]
+    pub fn load(account_info: &AccountInfo) -> Result<Ref<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let global = Ref::map(account_info.try_borrow_data()?, |data| unsafe {
+            Self::from_bytes_unchecked(data)
+        });
+
+        let expected = create_program_address(&[b"global", &global.bump], &crate::ID)?;
+        if expected.ne(account_info.key()) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        Ok(global)
+    }
+    /// 加载 global config；InitializeGlobal 还没有执行时返回 None
+    ///
+    /// 传入的账户仍必须是 `[b"global"]` PDA，调用方不能用任意账户冒充“尚未创建”来绕过全局配置。
+    /// 尚未创建的 PDA 可能已被别人预先转入 lamports，所以按 owner 而不是 lamports 判断
+    #[inline(always)]
+    pub fn load_if_initialized(account_info: &AccountInfo) -> Result<Option<Ref<'_, Self>>, ProgramError> {
+        if account_info.owner() == &crate::ID {
+            return Self::load(account_info).map(Some);
+        }
+        let (expected, _) = find_program_address(&[b"global"], &crate::ID);
+        if expected.ne(account_info.key()) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        Ok(None)
+    }
+    /// 过期检查允许的时钟误差；InitializeGlobal 还没有执行时使用 DEFAULT_GRACE_SECS，
+    /// 存取款和兑换不依赖管理员先创建 GlobalConfig
+    #[inline(always)]
+    pub fn grace_secs_or_default(account_info: &AccountInfo) -> Result<u16, ProgramError> {
+        Ok(Self::load_if_initialized(account_info)?.map_or(DEFAULT_GRACE_SECS, |global| global.grace_secs()))
+    }
+    #[inline(always)]
+    pub fn load_mut(account_info: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let global = RefMut::map(account_info.try_borrow_mut_data()?, |data| unsafe {
+            Self::from_bytes_unchecked_mut(data)
+        });
+
+        let expected = create_program_address(&[b"global", &global.bump], &crate::ID)?;
+        if expected.ne(account_info.key()) {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        Ok(global)
+    }
+    /// Return a mutable `GlobalConfig` reference from freshly created account data.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `data` is the data of the `[b"global"]` PDA owned by this program.
+    /// Only the length is checked; `InitializeGlobal` uses this right after creating the account.
+    #[inline(always)]
+    pub unsafe fn load_mut_unchecked(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        unsafe { Ok(Self::from_bytes_unchecked_mut(data)) }
+    }
+    /// Return a `GlobalConfig` from the given bytes.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `bytes` contains a valid representation of `GlobalConfig`.
+    /// `GlobalConfig` has an alignment of 1 byte. This method does not perform a length validation.
+    #[inline(always)]
+    pub unsafe fn from_bytes_unchecked(bytes: &[u8]) -> &Self {
+        unsafe { &*(bytes.as_ptr() as *const GlobalConfig) }
+    }
+    /// Return a mutable `GlobalConfig` reference from the given bytes.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that `bytes` contains a valid representation of `GlobalConfig`.
+    #[inline(always)]
+    pub unsafe fn from_bytes_unchecked_mut(bytes: &mut [u8]) -> &mut Self {
+        unsafe { &mut *(bytes.as_mut_ptr() as *mut GlobalConfig) }
+    }
+
+    #[inline(always)]
+    pub fn superadmin(&self) -> &Pubkey {
+        &self.superadmin
+    }
+    #[inline(always)]
+    pub fn default_fee(&self) -> u16 {
+        u16::from_le_bytes(self.default_fee)
+    }
+    #[inline(always)]
+    pub fn bump(&self) -> [u8; 1] {
+        self.bump
+    }
+    #[inline(always)]
+    pub fn grace_secs(&self) -> u16 {
+        u16::from_le_bytes(self.grace_secs)
+    }
+    #[inline(always)]
+    pub fn enforce_unique(&self) -> bool {
+        self.enforce_unique == 1
+    }
+    #[inline(always)]
+    pub fn downstream_programs(&self) -> &[Pubkey; MAX_DOWNSTREAM_PROGRAMS] {
+        &self.downstream_programs
+    }
+    /// `program` 是否在下游程序白名单中（空槽不匹配任何程序）
+    #[inline(always)]
+    pub fn is_downstream_allowed(&self, program: &Pubkey) -> bool {
+        non_zero_key(program).is_some() && self.downstream_programs.iter().any(|p| p == program)
+    }
+
+    /// 检查 superadmin 是否签名，且与全局配置中保存的一致
+    #[inline(always)]
+    pub fn check_superadmin(&self, superadmin: &AccountInfo) -> Result<(), ProgramError> {
+        if !superadmin.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if superadmin.key().ne(&self.superadmin) {
+            return Err(AmmError::InvalidAuthority.into());
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn set_superadmin(&mut self, superadmin: Pubkey) -> Result<(), ProgramError> {
+        // 全零公钥会让全局配置永久无法修改
+        if superadmin == [0u8; 32] {
+            return Err(ProgramError::InvalidArgument);
+        }
+        self.superadmin = superadmin;
+        Ok(())
+    }
+    #[inline(always)]
+    pub fn set_default_fee(&mut self, fee: u16) -> Result<(), ProgramError> {
+        if fee.ge(&10_000) {
+            return Err(ProgramError::InvalidArgument);
         }
+        self.default_fee = fee.to_le_bytes();
+        Ok(())
     }
+    #[inline(always)]
+    pub fn set_grace_secs(&mut self, grace_secs: u16) -> Result<(), ProgramError> {
+        if grace_secs.gt(&MAX_GRACE_SECS) {
+            return Err(ProgramError::InvalidArgument);
+        }
+        self.grace_secs = grace_secs.to_le_bytes();
+        Ok(())
+    }
+    #[inline(always)]
+    pub fn set_enforce_unique(&mut self, enforce_unique: bool) -> Result<(), ProgramError> {
+        self.enforce_unique = enforce_unique as u8;
+        Ok(())
+    }
+    /// 设置白名单的第 index 个槽位，传入全零公钥即清空该槽位
+    #[inline(always)]
+    pub fn set_downstream_program(&mut self, index: u8, program: Pubkey) -> Result<(), ProgramError> {
+        // 不允许把本程序加入白名单
+        if program == crate::ID {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let slot = self
+            .downstream_programs
+            .get_mut(index as usize)
+            .ok_or(ProgramError::InvalidArgument)?;
+        *slot = program;
+        Ok(())
+    }
+    #[inline(always)]
+    pub fn set_inner(
+        &mut self,
+        superadmin: Pubkey,
+        default_fee: u16,
+        bump: [u8; 1],
+    ) -> Result<(), ProgramError> {
+        self.set_superadmin(superadmin)?;
+        self.set_default_fee(default_fee)?;
+        self.set_grace_secs(DEFAULT_GRACE_SECS)?;
+        self.set_enforce_unique(false)?;
+        self.downstream_programs = [[0u8; 32]; MAX_DOWNSTREAM_PROGRAMS];
+        self.bump = bump;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// PoolRegistry
+// ============================================================================
+//
+// config PDA 的种子包含 seed，所以同一对 mint、同一费率可以创建任意多个池子（seed 不同即可），
+// 这是有意为之的。GlobalConfig::enforce_unique 打开时，Initialize 额外创建一个以
+// (mint_x, mint_y, fee) 为种子的 PoolRegistry PDA，同一组合第二次初始化会失败，
+// 从而保证每个组合只有一个“规范”池子。关闭时不创建也不检查 registry。
+
+/// 每个 (mint_x, mint_y, fee) 组合的唯一登记，记录对应的池子
+#[repr(C)]
+pub struct PoolRegistry {
+    config: Pubkey,  //登记的池子（config PDA）
+    bump: [u8; 1],
+}
+
+impl PoolRegistry {
+    pub const LEN: usize = size_of::<PoolRegistry>();
+
+    /// mint 按字节序排序，(X, Y) 与 (Y, X) 视为同一组合
+    #[inline(always)]
+    pub fn sorted_mints<'a>(mint_x: &'a Pubkey, mint_y: &'a Pubkey) -> (&'a Pubkey, &'a Pubkey) {
+        match mint_x <= mint_y {
+            true => (mint_x, mint_y),
+            false => (mint_y, mint_x),
+        }
+    }
+
+    #[inline(always)]
+    pub fn load(account_info: &AccountInfo) -> Result<Ref<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(Ref::map(account_info.try_borrow_data()?, |data| unsafe {
+            &*(data.as_ptr() as *const PoolRegistry)
+        }))
+    }
+    /// # Safety
+    ///
+    /// The caller must ensure that `data` is a freshly created registry account.
+    #[inline(always)]
+    pub unsafe fn load_mut_unchecked(data: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if data.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        unsafe { Ok(&mut *(data.as_mut_ptr() as *mut PoolRegistry)) }
+    }
+
+    #[inline(always)]
+    pub fn config(&self) -> &Pubkey {
+        &self.config
+    }
+    #[inline(always)]
+    pub fn bump(&self) -> [u8; 1] {
+        self.bump
+    }
+
+    #[inline(always)]
+    pub fn set_inner(&mut self, config: Pubkey, bump: [u8; 1]) {
+        self.config = config;
+        self.bump = bump;
+    }
+}
+
+/// 构造 pool registry PDA 的种子数组（seeds = [b"registry", mint_lo, mint_hi, fee, bump]）
+///
+/// mint_lo/mint_hi 由 `PoolRegistry::sorted_mints` 得到；fee_binding 和 bump 由调用方在栈上持有
+#[inline(always)]
+pub fn registry_seeds_from_parts<'a>(
+    mint_lo: &'a Pubkey,
+    mint_hi: &'a Pubkey,
+    fee_binding: &'a [u8; 2],
+    bump: &'a [u8; 1],
+) -> [Seed<'a>; 5] {
+    [
+        Seed::from(b"registry"),
+        Seed::from(mint_lo.as_ref()),
+        Seed::from(mint_hi.as_ref()),
+        Seed::from(fee_binding.as_ref()),
+        Seed::from(bump.as_ref()),
+    ]
+}
+
+/// 用户在某个池子中的 LP 仓位记录，只在池子启用 anti-JIT 冷却期或退出费时使用
+///
+/// PDA seeds = [b"position", config, owner, bump]。每次存款都会刷新 deposit_slot、
+/// deposit_ts 和 liquidity_per_lp；冷却期内取款时，LP 的价值按存款时的 liquidity_per_lp 计算，
+/// 这段时间累积的手续费留在池子里归其他 LP。退出费按 deposit_ts 起算的衰减窗口收取。
+///
+/// 仓位跟随存款的钱包，LP 转到别的钱包后取款不受限制，所以 SetConfig 只允许在 soulbound LP
+/// （NonTransferable）的池子上开启冷却期和退出费。
+#[repr(C)]
+pub struct LpPosition {
+    deposit_slot: [u8; 8],      //最近一次存款的 slot
+    liquidity_per_lp: [u8; 16], //最近一次存款后的 sqrt(x*y) / supply（Q64.64），见 LiquidityCurve::liquidity_per_lp
+    bump: [u8; 1],
+    deposit_ts: [u8; 8],        //最近一次存款的 unix 时间戳，退出费从这里开始衰减
+}
+
+impl LpPosition {
+    pub const LEN: usize = size_of::<LpPosition>();
+
+    #[inline(always)]
+    pub fn load(account_info: &AccountInfo) -> Result<Ref<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(Ref::map(account_info.try_borrow_data()?, |data| unsafe {
+            &*(data.as_ptr() as *const LpPosition)
+        }))
+    }
+    #[inline(always)]
+    pub fn load_mut(account_info: &AccountInfo) -> Result<RefMut<'_, Self>, ProgramError> {
+        if account_info.data_len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if account_info.owner().ne(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        Ok(RefMut::map(account_info.try_borrow_mut_data()?, |data| unsafe {
+            &mut *(data.as_mut_ptr() as *mut LpPosition)
+        }))
+    }
+
+    #[inline(always)]
+    pub fn deposit_slot(&self) -> u64 {
+        u64::from_le_bytes(self.deposit_slot)
+    }
+    #[inline(always)]
+    pub fn liquidity_per_lp(&self) -> u128 {
+        u128::from_le_bytes(self.liquidity_per_lp)
+    }
+    #[inline(always)]
+    pub fn bump(&self) -> [u8; 1] {
+        self.bump
+    }
+    #[inline(always)]
+    pub fn deposit_ts(&self) -> i64 {
+        i64::from_le_bytes(self.deposit_ts)
+    }
+
+    /// `now_slot` 是否仍在存款后的冷却期内
+    #[inline(always)]
+    pub fn in_cooldown(&self, now_slot: u64, cooldown_slots: u64) -> bool {
+        now_slot < self.deposit_slot().saturating_add(cooldown_slots)
+    }
+
+    #[inline(always)]
+    pub fn set_inner(&mut self, deposit_slot: u64, deposit_ts: i64, liquidity_per_lp: u128, bump: [u8; 1]) {
+        self.deposit_slot = deposit_slot.to_le_bytes();
+        self.deposit_ts = deposit_ts.to_le_bytes();
+        self.liquidity_per_lp = liquidity_per_lp.to_le_bytes();
+        self.bump = bump;
+    }
+}
+
+/// 构造 LP 仓位 PDA 的种子数组（seeds = [b"position", config, owner, bump]）
+#[inline(always)]
+pub fn position_seeds_from_parts<'a>(
+    config: &'a Pubkey,
+    owner: &'a Pubkey,
+    bump: &'a [u8; 1],
+) -> [Seed<'a>; 4] {
+    [
+        Seed::from(b"position"),
+        Seed::from(config.as_ref()),
+        Seed::from(owner.as_ref()),
+        Seed::from(bump.as_ref()),
+    ]
 }
@@ -0,0 +1,1038 @@
+#![allow(dead_code, clippy::too_many_arguments)]
+
+use {
+    blueshift_native_amm::{
+        errors::AmmError,
+        state::{Config, GlobalConfig},
+    },
+    mollusk_svm::Mollusk,
+    mollusk_svm_programs_token::{associated_token, token, token2022},
+    solana_account::Account,
+    solana_sdk::{
+        instruction::{AccountMeta, Instruction},
+        program_option::COption,
+        program_error::ProgramError,
+        program_pack::Pack,
+        pubkey::Pubkey,
+    },
+    spl_associated_token_account::get_associated_token_address_with_program_id,
+    spl_token::state::{Account as TokenAccount, AccountState, Mint},
+};
+
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+// ============================================================================
+// Setup
+// ============================================================================
+
+/// `AmmError` only converts into pinocchio's `ProgramError`; Mollusk checks compare against the solana_sdk one
+pub fn amm_err(error: AmmError) -> ProgramError {
+    ProgramError::Custom(error as u32)
+}
+
+/// `solana_sdk::system_program` is deprecated; the program id is the same one the AMM checks against
+pub mod system_program {
+    use solana_sdk::pubkey::Pubkey;
+
+    pub fn id() -> Pubkey {
+        Pubkey::new_from_array(pinocchio_system::ID)
+    }
+}
+
+pub fn program_id() -> Pubkey {
+    Pubkey::new_from_array(blueshift_native_amm::ID)
+}
+
+/// Setup Mollusk with the AMM program, SPL Token, Token-2022 and the ATA program
+pub fn setup_mollusk() -> Mollusk {
+    let mut mollusk = Mollusk::new(&program_id(), "blueshift_native_amm");
+
+    token::add_program(&mut mollusk);
+    token2022::add_program(&mut mollusk);
+    associated_token::add_program(&mut mollusk);
+
+    mollusk
+}
+
+// ============================================================================
+// Accounts
+// ============================================================================
+
+/// Create a funded system account
+pub fn create_system_account(lamports: u64) -> Account {
+    Account {
+        lamports,
+        data: vec![],
+        owner: system_program::id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Create the system program account (executable)
+pub fn create_system_program_account() -> Account {
+    Account {
+        lamports: LAMPORTS_PER_SOL,
+        data: vec![],
+        owner: solana_sdk::native_loader::id(),
+        executable: true,
+        rent_epoch: 0,
+    }
+}
+
+/// Create a rent-exempt account owned by the AMM program
+pub fn create_program_account(mollusk: &Mollusk, data: Vec<u8>) -> Account {
+    Account {
+        lamports: mollusk.sysvars.rent.minimum_balance(data.len()),
+        data,
+        owner: program_id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Create a Mint account owned by `token_program` (SPL Token or Token-2022 share the base layout)
+pub fn create_mint_account(
+    mint_authority: &Pubkey,
+    decimals: u8,
+    supply: u64,
+    token_program: &Pubkey,
+) -> Account {
+    let mint_state = Mint {
+        mint_authority: COption::Some(*mint_authority),
+        supply,
+        decimals,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    };
+
+    let mut data = vec![0u8; Mint::LEN];
+    Pack::pack(mint_state, &mut data).unwrap();
+
+    Account {
+        lamports: LAMPORTS_PER_SOL,
+        data,
+        owner: *token_program,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Create a Token-2022 Mint with the NonTransferable extension, as Initialize does for soulbound LP
+pub fn create_soulbound_mint_account(mint_authority: &Pubkey, decimals: u8, supply: u64) -> Account {
+    let mut account = create_mint_account(mint_authority, decimals, supply, &spl_token_2022::id());
+    // base Mint padded to Account::LEN, then AccountType::Mint and the NonTransferable TLV (type 9, no data)
+    account.data.resize(165, 0);
+    account.data.push(1);
+    account.data.extend_from_slice(&9u16.to_le_bytes());
+    account.data.extend_from_slice(&0u16.to_le_bytes());
+    account
+}
+
+/// Create a Token Account owned by `token_program`
+pub fn create_token_account(
+    mint: &Pubkey,
+    owner: &Pubkey,
+    amount: u64,
+    token_program: &Pubkey,
+) -> Account {
+    let token_state = TokenAccount {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        delegate: COption::None,
+        state: AccountState::Initialized,
+        is_native: COption::None,
+        delegated_amount: 0,
+        close_authority: COption::None,
+    };
+
+    let mut data = vec![0u8; TokenAccount::LEN];
+    Pack::pack(token_state, &mut data).unwrap();
+
+    Account {
+        lamports: LAMPORTS_PER_SOL,
+        data,
+        owner: *token_program,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// Create a Token Account with a `close_authority` set
+pub fn create_token_account_with_close_authority(
+    mint: &Pubkey,
+    owner: &Pubkey,
+    amount: u64,
+    close_authority: &Pubkey,
+    token_program: &Pubkey,
+) -> Account {
+    let mut account = create_token_account(mint, owner, amount, token_program);
+    let mut token_state = TokenAccount::unpack_from_slice(&account.data).unwrap();
+    token_state.close_authority = COption::Some(*close_authority);
+    Pack::pack(token_state, &mut account.data).unwrap();
+    account
+}
+
+/// Create a wrapped-SOL (SPL Token native mint) account: the balance is backed by lamports above a 1 SOL reserve
+pub fn create_native_token_account(owner: &Pubkey, amount: u64) -> Account {
+    let mut account = create_token_account(&spl_token::native_mint::id(), owner, amount, &spl_token::id());
+    let mut token_state = TokenAccount::unpack_from_slice(&account.data).unwrap();
+    token_state.is_native = COption::Some(LAMPORTS_PER_SOL);
+    Pack::pack(token_state, &mut account.data).unwrap();
+    account.lamports = LAMPORTS_PER_SOL + amount;
+    account
+}
+
+/// The executable account of SPL Token or Token-2022
+pub fn token_program_keyed_account(token_program: &Pubkey) -> (Pubkey, Account) {
+    if *token_program == spl_token_2022::id() {
+        token2022::keyed_account()
+    } else {
+        token::keyed_account()
+    }
+}
+
+/// Read the token amount of a token account
+pub fn token_amount(account: &Account) -> u64 {
+    TokenAccount::unpack_from_slice(&account.data[..TokenAccount::LEN])
+        .unwrap()
+        .amount
+}
+
+/// Read the supply of a mint account
+pub fn mint_supply(account: &Account) -> u64 {
+    Mint::unpack_from_slice(&account.data[..Mint::LEN]).unwrap().supply
+}
+
+// ============================================================================
+// PDAs
+// ============================================================================
+
+pub fn global_config_pda() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"global"], &program_id())
+}
+
+pub fn bpf_loader_upgradeable_id() -> Pubkey {
+    Pubkey::new_from_array(blueshift_native_amm::instructions::helpers::BPF_LOADER_UPGRADEABLE_ID)
+}
+
+/// The program's ProgramData account under the BPF Upgradeable Loader
+pub fn program_data_pda() -> Pubkey {
+    Pubkey::find_program_address(&[program_id().as_ref()], &bpf_loader_upgradeable_id()).0
+}
+
+pub fn config_pda(seed: u64, mint_x: &Pubkey, mint_y: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"config", &seed.to_le_bytes(), mint_x.as_ref(), mint_y.as_ref()],
+        &program_id(),
+    )
+}
+
+/// PoolRegistry PDA：mint 按字节序排序，与 (mint_x, mint_y) 的顺序无关
+pub fn pool_registry_pda(mint_x: &Pubkey, mint_y: &Pubkey, fee: u16) -> (Pubkey, u8) {
+    let (lo, hi) = if mint_x <= mint_y { (mint_x, mint_y) } else { (mint_y, mint_x) };
+    Pubkey::find_program_address(
+        &[b"registry", lo.as_ref(), hi.as_ref(), &fee.to_le_bytes()],
+        &program_id(),
+    )
+}
+
+pub fn mint_lp_pda(config: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"mint_lp", config.as_ref()], &program_id())
+}
+
+pub fn ata(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, mint, token_program)
+}
+
+// ============================================================================
+// Global config
+// ============================================================================
+
+pub fn create_global_config_account(
+    mollusk: &Mollusk,
+    superadmin: &Pubkey,
+    default_fee: u16,
+    bump: u8,
+) -> Account {
+    let mut data = vec![0u8; GlobalConfig::LEN];
+    unsafe { GlobalConfig::from_bytes_unchecked_mut(&mut data) }
+        .set_inner(superadmin.to_bytes(), default_fee, [bump])
+        .unwrap();
+    create_program_account(mollusk, data)
+}
+
+/// ProgramData account in the `UpgradeableLoaderState::ProgramData` layout (no program bytes).
+/// `None` makes the program immutable.
+pub fn create_program_data_account(mollusk: &Mollusk, upgrade_authority: Option<&Pubkey>) -> Account {
+    let mut data = vec![0u8; 4 + 8 + 1 + 32];
+    data[..4].copy_from_slice(&3u32.to_le_bytes());
+    if let Some(upgrade_authority) = upgrade_authority {
+        data[12] = 1;
+        data[13..].copy_from_slice(upgrade_authority.as_ref());
+    }
+    Account {
+        lamports: mollusk.sysvars.rent.minimum_balance(data.len()),
+        data,
+        owner: bpf_loader_upgradeable_id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+/// `superadmin` must be the program's upgrade authority, see `create_program_data_account`
+pub fn initialize_global_ix(superadmin: &Pubkey, default_fee: u16, bump: u8) -> Instruction {
+    let (global_config, _) = global_config_pda();
+
+    let mut data = vec![4u8];
+    data.extend_from_slice(&default_fee.to_le_bytes());
+    data.push(bump);
+
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(*superadmin, true),
+            AccountMeta::new(global_config, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(program_data_pda(), false),
+        ],
+        data,
+    }
+}
+
+/// `param` 0 = default fee (u16), 1 = superadmin (32 bytes), 2 = grace secs (u16)
+pub fn set_global_config_ix(superadmin: &Pubkey, param: u8, value: &[u8]) -> Instruction {
+    let (global_config, _) = global_config_pda();
+
+    let mut data = vec![5u8, param];
+    data.extend_from_slice(value);
+
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*superadmin, true),
+            AccountMeta::new(global_config, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data,
+    }
+}
+
+/// Token-2022 pools require checked transfers and take mint_x / mint_y right after the fixed accounts
+pub fn pool_mint_metas(mint_x: &Pubkey, mint_y: &Pubkey, token_program: &Pubkey) -> Vec<AccountMeta> {
+    if token_program != &spl_token_2022::id() {
+        return vec![];
+    }
+    vec![
+        AccountMeta::new_readonly(*mint_x, false),
+        AccountMeta::new_readonly(*mint_y, false),
+    ]
+}
+
+// ============================================================================
+// Initialize
+// ============================================================================
+
+/// Build an `Initialize` instruction. Pass `USE_GLOBAL_DEFAULT_FEE` as `fee` to pick up
+/// the global default fee. The optional global config is always passed after the mints; drop
+/// the last account for the 6-account layout.
+pub fn initialize_ix(
+    initializer: &Pubkey,
+    seed: u64,
+    fee: u16,
+    mint_x: &Pubkey,
+    mint_y: &Pubkey,
+    authority: Option<&Pubkey>,
+    token_program: &Pubkey,
+) -> Instruction {
+    let (config, config_bump) = config_pda(seed, mint_x, mint_y);
+    let (mint_lp, lp_bump) = mint_lp_pda(&config);
+    let (global_config, _) = global_config_pda();
+
+    let mut data = vec![0u8];
+    data.extend_from_slice(&seed.to_le_bytes());
+    data.extend_from_slice(&fee.to_le_bytes());
+    data.extend_from_slice(mint_x.as_ref());
+    data.extend_from_slice(mint_y.as_ref());
+    data.push(config_bump);
+    data.push(lp_bump);
+    if let Some(authority) = authority {
+        data.extend_from_slice(authority.as_ref());
+    }
+
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(*initializer, true),
+            AccountMeta::new(mint_lp, false),
+            AccountMeta::new(config, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ]
+        .into_iter()
+        .chain(pool_mint_metas(mint_x, mint_y, token_program))
+        .chain([AccountMeta::new_readonly(global_config, false)])
+        .collect(),
+        data,
+    }
+}
+
+/// Build an `Initialize` instruction with the soulbound LP flag set (requires Token-2022)
+pub fn initialize_soulbound_ix(
+    initializer: &Pubkey,
+    seed: u64,
+    fee: u16,
+    mint_x: &Pubkey,
+    mint_y: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    // 全零 authority 与不传 authority 等价
+    let mut ix = initialize_ix(initializer, seed, fee, mint_x, mint_y, Some(&Pubkey::default()), token_program);
+    ix.data.push(1);
+    ix
+}
+
+/// Build an `InitializeAndSeed` instruction: the fixed `Initialize` accounts (with the ATA program in
+/// the placeholder slot) followed by the mints, vaults, user token accounts and the global config.
+pub fn initialize_and_seed_ix(
+    initializer: &Pubkey,
+    seed: u64,
+    fee: u16,
+    mint_x: &Pubkey,
+    mint_y: &Pubkey,
+    seed_x: u64,
+    seed_y: u64,
+    token_program: &Pubkey,
+) -> Instruction {
+    let initialize = initialize_ix(initializer, seed, fee, mint_x, mint_y, None, token_program);
+    let (config, _) = config_pda(seed, mint_x, mint_y);
+    let (mint_lp, _) = mint_lp_pda(&config);
+
+    let mut data = vec![6u8];
+    data.extend_from_slice(&seed_x.to_le_bytes());
+    data.extend_from_slice(&seed_y.to_le_bytes());
+    data.extend_from_slice(&initialize.data[1..]);
+
+    // InitializeAndSeed 从自己的 mint 账户读取精度，不需要 Initialize 的 mint 尾部账户
+    let mut accounts = initialize.accounts;
+    accounts.truncate(6);
+    accounts[5] = AccountMeta::new_readonly(spl_associated_token_account::id(), false);
+    accounts.extend([
+        AccountMeta::new_readonly(*mint_x, false),
+        AccountMeta::new_readonly(*mint_y, false),
+        AccountMeta::new(ata(&config, mint_x, token_program), false),
+        AccountMeta::new(ata(&config, mint_y, token_program), false),
+        AccountMeta::new(ata(initializer, mint_x, token_program), false),
+        AccountMeta::new(ata(initializer, mint_y, token_program), false),
+        AccountMeta::new(ata(initializer, &mint_lp, token_program), false),
+        AccountMeta::new_readonly(global_config_pda().0, false),
+    ]);
+
+    Instruction {
+        program_id: program_id(),
+        accounts,
+        data,
+    }
+}
+
+// ============================================================================
+// Deposit
+// ============================================================================
+
+pub fn deposit_ix(
+    user: &Pubkey,
+    config: &Pubkey,
+    mint_x: &Pubkey,
+    mint_y: &Pubkey,
+    amount: u64,
+    max_x: u64,
+    max_y: u64,
+    token_program: &Pubkey,
+) -> Instruction {
+    let (mint_lp, _) = mint_lp_pda(config);
+
+    let mut data = vec![1u8];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&max_x.to_le_bytes());
+    data.extend_from_slice(&max_y.to_le_bytes());
+    // min_lp_out = 0：不检查 LP 侧的滑点
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.extend_from_slice(&i64::MAX.to_le_bytes());
+
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(mint_lp, false),
+            AccountMeta::new(ata(config, mint_x, token_program), false),
+            AccountMeta::new(ata(config, mint_y, token_program), false),
+            AccountMeta::new(ata(user, mint_x, token_program), false),
+            AccountMeta::new(ata(user, mint_y, token_program), false),
+            AccountMeta::new(ata(user, &mint_lp, token_program), false),
+            AccountMeta::new(*config, false),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new_readonly(global_config_pda().0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+        ]
+        .into_iter()
+        .chain(pool_mint_metas(mint_x, mint_y, token_program))
+        .collect(),
+        data,
+    }
+}
+
+/// 空池的首次存款：按 x/y 建池，附带恰好包含 x/y 的价格区间
+pub fn first_deposit_ix(
+    user: &Pubkey,
+    config: &Pubkey,
+    mint_x: &Pubkey,
+    mint_y: &Pubkey,
+    x: u64,
+    y: u64,
+    token_program: &Pubkey,
+) -> Instruction {
+    let mut ix = deposit_ix(user, config, mint_x, mint_y, 1, x, y, token_program);
+    ix.data.extend(exact_price_band(x, y));
+    ix
+}
+
+/// Deposit 的 `PriceBand` 后缀：x/y（Q64.64）的区间 [min_price, max_price]
+pub fn price_band(min_price: u128, max_price: u128) -> Vec<u8> {
+    [min_price.to_le_bytes(), max_price.to_le_bytes()].concat()
+}
+
+/// 恰好只包含 x/y 本身的价格区间，首次存款按给出的 x/y 建池时使用
+pub fn exact_price_band(x: u64, y: u64) -> Vec<u8> {
+    let price = ((x as u128) << 64) / y as u128;
+    price_band(price, price)
+}
+
+// ============================================================================
+// Withdraw
+// ============================================================================
+
+pub fn withdraw_ix(
+    user: &Pubkey,
+    config: &Pubkey,
+    mint_x: &Pubkey,
+    mint_y: &Pubkey,
+    amount: u64,
+    min_x: u64,
+    min_y: u64,
+    token_program: &Pubkey,
+) -> Instruction {
+    let (mint_lp, _) = mint_lp_pda(config);
+
+    let mut data = vec![2u8];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&min_x.to_le_bytes());
+    data.extend_from_slice(&min_y.to_le_bytes());
+    data.extend_from_slice(&i64::MAX.to_le_bytes());
+
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(mint_lp, false),
+            AccountMeta::new(ata(config, mint_x, token_program), false),
+            AccountMeta::new(ata(config, mint_y, token_program), false),
+            AccountMeta::new(ata(user, mint_x, token_program), false),
+            AccountMeta::new(ata(user, mint_y, token_program), false),
+            AccountMeta::new(ata(user, &mint_lp, token_program), false),
+            AccountMeta::new(*config, false),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new_readonly(global_config_pda().0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ]
+        .into_iter()
+        .chain(pool_mint_metas(mint_x, mint_y, token_program))
+        .collect(),
+        data,
+    }
+}
+
+// ============================================================================
+// Pool fixture
+// ============================================================================
+
+/// An initialized pool whose accounts are built directly, without running `Initialize`/`Deposit`
+pub struct Pool {
+    pub seed: u64,
+    pub mint_x: Pubkey,
+    pub mint_y: Pubkey,
+    pub config: Pubkey,
+    pub config_bump: u8,
+    pub mint_lp: Pubkey,
+    pub vault_x: Pubkey,
+    pub vault_y: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl Pool {
+    pub fn new(seed: u64) -> Self {
+        Self::new_with_token_program(seed, spl_token::id())
+    }
+
+    /// `token_program` is either SPL Token or Token-2022
+    pub fn new_with_token_program(seed: u64, token_program: Pubkey) -> Self {
+        Self::new_with_mints(seed, Pubkey::new_unique(), Pubkey::new_unique(), token_program)
+    }
+
+    /// A pool over existing mints, e.g. the second pool of a multi-hop route
+    pub fn new_with_mints(seed: u64, mint_x: Pubkey, mint_y: Pubkey, token_program: Pubkey) -> Self {
+        let (config, config_bump) = config_pda(seed, &mint_x, &mint_y);
+        let (mint_lp, _) = mint_lp_pda(&config);
+
+        Self {
+            seed,
+            mint_x,
+            mint_y,
+            config,
+            config_bump,
+            mint_lp,
+            vault_x: ata(&config, &mint_x, &token_program),
+            vault_y: ata(&config, &mint_y, &token_program),
+            token_program,
+        }
+    }
+
+    pub fn config_account(&self, mollusk: &Mollusk, fee: u16) -> Account {
+        self.config_account_with_authority(mollusk, fee, &Pubkey::default())
+    }
+
+    /// Config with a pool `authority` (all zeros means immutable)
+    pub fn config_account_with_authority(&self, mollusk: &Mollusk, fee: u16, authority: &Pubkey) -> Account {
+        self.build_config_account(mollusk, fee, authority)
+    }
+
+    fn build_config_account(&self, mollusk: &Mollusk, fee: u16, authority: &Pubkey) -> Account {
+        let mut data = vec![0u8; Config::LEN];
+        let config = unsafe { Config::from_bytes_unchecked_mut(&mut data) };
+        config
+            .set_inner(
+                self.seed,
+                authority.to_bytes(),
+                self.mint_x.to_bytes(),
+                self.mint_y.to_bytes(),
+                fee,
+                [self.config_bump],
+            )
+            .unwrap();
+        // 与 Initialize 一致：Token-2022 池子强制使用 TransferChecked（fixture 的 mint 都是 6 位精度）
+        if self.token_program == spl_token_2022::id() {
+            config.set_checked_transfers(6, 6);
+        }
+        // 与 Initialize 一致：记录金库 ATA 的 bump
+        config.set_vault_bumps(self.vault_bump(&self.mint_x), self.vault_bump(&self.mint_y));
+        create_program_account(mollusk, data)
+    }
+
+    /// Bump of the config's ATA for `mint`
+    pub fn vault_bump(&self, mint: &Pubkey) -> u8 {
+        Pubkey::find_program_address(
+            &[self.config.as_ref(), self.token_program.as_ref(), mint.as_ref()],
+            &spl_associated_token_account::id(),
+        )
+        .1
+    }
+
+    /// Config, LP mint, X/Y mints, vaults, the token program and the global config
+    pub fn accounts(
+        &self,
+        mollusk: &Mollusk,
+        fee: u16,
+        reserve_x: u64,
+        reserve_y: u64,
+        lp_supply: u64,
+    ) -> Vec<(Pubkey, Account)> {
+        let mint_authority = Pubkey::new_unique();
+        let (token_program, token_program_account) = token_program_keyed_account(&self.token_program);
+        let (global_config, global_bump) = global_config_pda();
+
+        let mut accounts = vec![
+            (self.config, self.config_account(mollusk, fee)),
+            (self.mint_lp, create_mint_account(&self.config, 6, lp_supply, &token_program)),
+            (self.mint_x, create_mint_account(&mint_authority, 6, u64::MAX / 2, &token_program)),
+            (self.mint_y, create_mint_account(&mint_authority, 6, u64::MAX / 2, &token_program)),
+            (self.vault_x, create_token_account(&self.mint_x, &self.config, reserve_x, &token_program)),
+            (self.vault_y, create_token_account(&self.mint_y, &self.config, reserve_y, &token_program)),
+            (token_program, token_program_account),
+            (system_program::id(), create_system_program_account()),
+            associated_token::keyed_account(),
+            (global_config, create_global_config_account(mollusk, &Pubkey::new_unique(), fee, global_bump)),
+        ];
+        self.sync_reserves(&mut accounts);
+        accounts
+    }
+
+    /// Replace the LP mint with a soulbound one, keeping its supply (Token-2022 pools only)
+    pub fn make_lp_soulbound(&self, accounts: &mut [(Pubkey, Account)]) {
+        assert_eq!(self.token_program, spl_token_2022::id());
+        let supply = mint_supply(&accounts.iter().find(|(k, _)| k == &self.mint_lp).unwrap().1);
+        set_account(accounts, &self.mint_lp, create_soulbound_mint_account(&self.config, 6, supply));
+    }
+
+    /// Record the vault balances as the config's reserve snapshot, as the program does after every
+    /// deposit / withdraw / swap. Call again after replacing the config account.
+    pub fn sync_reserves(&self, accounts: &mut [(Pubkey, Account)]) {
+        let balance = |key: &Pubkey| token_amount(&accounts.iter().find(|(k, _)| k == key).unwrap().1);
+        let (reserve_x, reserve_y) = (balance(&self.vault_x), balance(&self.vault_y));
+        let config = &mut accounts.iter_mut().find(|(k, _)| k == &self.config).unwrap().1;
+        unsafe { Config::from_bytes_unchecked_mut(&mut config.data) }.set_reserves(reserve_x, reserve_y);
+    }
+
+    /// The user's system account and X/Y/LP token accounts
+    pub fn user_accounts(&self, user: &Pubkey, x: u64, y: u64, lp: u64) -> Vec<(Pubkey, Account)> {
+        vec![
+            (*user, create_system_account(10 * LAMPORTS_PER_SOL)),
+            (self.user_x(user), create_token_account(&self.mint_x, user, x, &self.token_program)),
+            (self.user_y(user), create_token_account(&self.mint_y, user, y, &self.token_program)),
+            (self.user_lp(user), create_token_account(&self.mint_lp, user, lp, &self.token_program)),
+        ]
+    }
+
+    pub fn user_x(&self, user: &Pubkey) -> Pubkey {
+        ata(user, &self.mint_x, &self.token_program)
+    }
+
+    pub fn user_y(&self, user: &Pubkey) -> Pubkey {
+        ata(user, &self.mint_y, &self.token_program)
+    }
+
+    pub fn user_lp(&self, user: &Pubkey) -> Pubkey {
+        ata(user, &self.mint_lp, &self.token_program)
+    }
+
+    /// The user's anti-JIT position PDA
+    pub fn position(&self, user: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"position", self.config.as_ref(), user.as_ref()], &program_id()).0
+    }
+
+    pub fn deposit_ix(&self, user: &Pubkey, amount: u64, max_x: u64, max_y: u64) -> Instruction {
+        deposit_ix(user, &self.config, &self.mint_x, &self.mint_y, amount, max_x, max_y, &self.token_program)
+    }
+
+    /// 空池的首次存款，见 `first_deposit_ix`
+    pub fn first_deposit_ix(&self, user: &Pubkey, x: u64, y: u64) -> Instruction {
+        first_deposit_ix(user, &self.config, &self.mint_x, &self.mint_y, x, y, &self.token_program)
+    }
+
+    pub fn swap_ix(&self, user: &Pubkey, is_x: bool, amount: u64, min: u64) -> Instruction {
+        let mut data = vec![3u8, is_x as u8];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&min.to_le_bytes());
+        data.extend_from_slice(&i64::MAX.to_le_bytes());
+        // SwapInstructionData 的固定部分为 32 字节（包含 bool 之后的对齐填充）
+        data.resize(1 + 32, 0);
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*user, true),
+                AccountMeta::new(self.user_x(user), false),
+                AccountMeta::new(self.user_y(user), false),
+                AccountMeta::new(self.vault_x, false),
+                AccountMeta::new(self.vault_y, false),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new_readonly(self.token_program, false),
+                AccountMeta::new_readonly(global_config_pda().0, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ]
+            .into_iter()
+            .chain(pool_mint_metas(&self.mint_x, &self.mint_y, &self.token_program))
+            .collect(),
+            data,
+        }
+    }
+
+    pub fn quote_ix(&self, is_x: bool, amount: u64) -> Instruction {
+        let mut data = vec![7u8, is_x as u8];
+        data.extend_from_slice(&amount.to_le_bytes());
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(self.config, false),
+                AccountMeta::new_readonly(self.vault_x, false),
+                AccountMeta::new_readonly(self.vault_y, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data,
+        }
+    }
+
+    /// 只传 config 和两个金库的报价，return data 为 deposit (u64) + withdraw (u64)
+    pub fn quote_swap_ix(&self, is_x: bool, amount: u64) -> Instruction {
+        let mut data = vec![17u8, is_x as u8];
+        data.extend_from_slice(&amount.to_le_bytes());
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(self.config, false),
+                AccountMeta::new_readonly(self.vault_x, false),
+                AccountMeta::new_readonly(self.vault_y, false),
+            ],
+            data,
+        }
+    }
+
+    pub fn simulate_withdraw_ix(&self, amount: u64) -> Instruction {
+        let mut data = vec![9u8];
+        data.extend_from_slice(&amount.to_le_bytes());
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(self.config, false),
+                AccountMeta::new_readonly(self.mint_lp, false),
+                AccountMeta::new_readonly(self.vault_x, false),
+                AccountMeta::new_readonly(self.vault_y, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data,
+        }
+    }
+
+    pub fn lp_for_deposit_ix(&self, x: u64, y: u64) -> Instruction {
+        let mut data = vec![11u8];
+        data.extend_from_slice(&x.to_le_bytes());
+        data.extend_from_slice(&y.to_le_bytes());
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(self.config, false),
+                AccountMeta::new_readonly(self.mint_lp, false),
+                AccountMeta::new_readonly(self.vault_x, false),
+                AccountMeta::new_readonly(self.vault_y, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data,
+        }
+    }
+
+    pub fn observe_price_ix(&self) -> Instruction {
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(self.config, false),
+                AccountMeta::new_readonly(self.vault_x, false),
+                AccountMeta::new_readonly(self.vault_y, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data: vec![12u8],
+        }
+    }
+
+    pub fn withdraw_ix(&self, user: &Pubkey, amount: u64, min_x: u64, min_y: u64) -> Instruction {
+        withdraw_ix(user, &self.config, &self.mint_x, &self.mint_y, amount, min_x, min_y, &self.token_program)
+    }
+
+    pub fn withdraw_single_sided_ix(&self, user: &Pubkey, to_x: bool, amount: u64, min_out: u64) -> Instruction {
+        let mut data = vec![13u8, to_x as u8];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&min_out.to_le_bytes());
+        data.extend_from_slice(&i64::MAX.to_le_bytes());
+
+        let user_out = match to_x {
+            true => self.user_x(user),
+            false => self.user_y(user),
+        };
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*user, true),
+                AccountMeta::new(self.mint_lp, false),
+                AccountMeta::new(self.vault_x, false),
+                AccountMeta::new(self.vault_y, false),
+                AccountMeta::new(user_out, false),
+                AccountMeta::new(self.user_lp(user), false),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new_readonly(self.token_program, false),
+                AccountMeta::new_readonly(global_config_pda().0, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ]
+            .into_iter()
+            .chain(pool_mint_metas(&self.mint_x, &self.mint_y, &self.token_program))
+            .collect(),
+            data,
+        }
+    }
+
+    /// 借出 `amount`，回调 `callback_program`（附带 `callback_accounts` 与 `callback_data`）
+    pub fn flash_swap_ix(
+        &self,
+        user: &Pubkey,
+        is_x: bool,
+        amount: u64,
+        callback_program: &Pubkey,
+        callback_accounts: &[AccountMeta],
+        callback_data: &[u8],
+    ) -> Instruction {
+        let mut data = vec![16u8, is_x as u8];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(callback_data);
+
+        let user_out = match is_x {
+            true => self.user_x(user),
+            false => self.user_y(user),
+        };
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*user, true),
+                AccountMeta::new(self.vault_x, false),
+                AccountMeta::new(self.vault_y, false),
+                AccountMeta::new(user_out, false),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new_readonly(self.token_program, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ]
+            .into_iter()
+            .chain(pool_mint_metas(&self.mint_x, &self.mint_y, &self.token_program))
+            .chain([AccountMeta::new_readonly(*callback_program, false)])
+            .chain(callback_accounts.iter().cloned())
+            .collect(),
+            data,
+        }
+    }
+
+    pub fn deposit_single_sided_ix(&self, user: &Pubkey, from_x: bool, amount: u64, min_lp_out: u64) -> Instruction {
+        let mut data = vec![15u8, from_x as u8];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&min_lp_out.to_le_bytes());
+        data.extend_from_slice(&i64::MAX.to_le_bytes());
+
+        let user_in = match from_x {
+            true => self.user_x(user),
+            false => self.user_y(user),
+        };
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new(*user, true),
+                AccountMeta::new(self.mint_lp, false),
+                AccountMeta::new(self.vault_x, false),
+                AccountMeta::new(self.vault_y, false),
+                AccountMeta::new(user_in, false),
+                AccountMeta::new(self.user_lp(user), false),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new_readonly(self.token_program, false),
+                AccountMeta::new_readonly(global_config_pda().0, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            ]
+            .into_iter()
+            .chain(pool_mint_metas(&self.mint_x, &self.mint_y, &self.token_program))
+            .collect(),
+            data,
+        }
+    }
+
+    pub fn set_state_ix(&self, authority: &Pubkey, state: u8) -> Instruction {
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(*authority, true),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ],
+            data: vec![14u8, state],
+        }
+    }
+
+    /// `CollectFees` to the owner-chosen `destination_x` / `destination_y` token accounts
+    pub fn collect_fees_ix(&self, authority: &Pubkey, destination_x: &Pubkey, destination_y: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(*authority, true),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new(self.vault_x, false),
+                AccountMeta::new(self.vault_y, false),
+                AccountMeta::new(*destination_x, false),
+                AccountMeta::new(*destination_y, false),
+                AccountMeta::new_readonly(self.token_program, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+            ]
+            .into_iter()
+            .chain(pool_mint_metas(&self.mint_x, &self.mint_y, &self.token_program))
+            .collect(),
+            data: vec![19u8],
+        }
+    }
+
+    /// `DisablePool`：LP 全部赎回后由 owner 把池子切到 Disabled
+    pub fn disable_pool_ix(&self, authority: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(*authority, true),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new_readonly(self.mint_lp, false),
+            ],
+            data: vec![20u8],
+        }
+    }
+
+    /// `param` 0 = max swap out (u64), 1 = fee (u16), 2 = state (u8), 3 = fee admin, 4 = pause admin,
+    /// 5 = JIT cooldown slots (u64), 6 = exit fee (bps: u16 + window secs: u64),
+    /// 7 = protocol fee (bps: u16 + treasury pubkey). The LP mint is always appended, enabling 5 / 6 requires
+    /// a soulbound LP (see `make_lp_soulbound`)
+    pub fn set_config_ix(&self, authority: &Pubkey, param: u8, value: &[u8]) -> Instruction {
+        let mut data = vec![8u8, param];
+        data.extend_from_slice(value);
+
+        Instruction {
+            program_id: program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(*authority, true),
+                AccountMeta::new(self.config, false),
+                AccountMeta::new_readonly(system_program::id(), false),
+                AccountMeta::new_readonly(self.mint_lp, false),
+            ],
+            data,
+        }
+    }
+}
+
+/// Build a `SwapRoute` instruction through `hops` (pool, is_x), in order
+pub fn swap_route_ix(user: &Pubkey, hops: &[(&Pool, bool)], amount_in: u64, min_out: u64) -> Instruction {
+    let mut data = vec![18u8, hops.len() as u8];
+    data.extend(hops.iter().map(|(_, is_x)| *is_x as u8));
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&min_out.to_le_bytes());
+    data.extend_from_slice(&i64::MAX.to_le_bytes());
+
+    let mut accounts = vec![
+        AccountMeta::new(*user, true),
+        AccountMeta::new_readonly(hops[0].0.token_program, false),
+        AccountMeta::new_readonly(global_config_pda().0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    for (pool, _) in hops {
+        accounts.extend([
+            AccountMeta::new(pool.config, false),
+            AccountMeta::new(pool.vault_x, false),
+            AccountMeta::new(pool.vault_y, false),
+            AccountMeta::new(pool.user_x(user), false),
+            AccountMeta::new(pool.user_y(user), false),
+        ]);
+        accounts.extend(pool_mint_metas(&pool.mint_x, &pool.mint_y, &pool.token_program));
+    }
+
+    Instruction {
+        program_id: program_id(),
+        accounts,
+        data,
+    }
+}
+
+/// Replace the account stored under `key`
+pub fn set_account(accounts: &mut [(Pubkey, Account)], key: &Pubkey, account: Account) {
+    accounts.iter_mut().find(|(k, _)| k == key).unwrap().1 = account;
+}
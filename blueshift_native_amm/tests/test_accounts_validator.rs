@@ -0,0 +1,96 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    solana_account::Account,
+    solana_sdk::{instruction::Instruction, program_error::ProgramError, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+
+/// deposit/withdraw/swap 中 (user, config, token_program) 在账户列表中的下标
+struct Layout {
+    config: usize,
+    token_program: usize,
+}
+
+const DEPOSIT: Layout = Layout { config: 7, token_program: 8 };
+const WITHDRAW: Layout = Layout { config: 7, token_program: 8 };
+const SWAP: Layout = Layout { config: 5, token_program: 6 };
+
+fn setup(mollusk: &Mollusk, pool: &Pool, user: &Pubkey) -> Vec<(Pubkey, Account)> {
+    let mut accounts = pool.accounts(mollusk, FEE, 1_000_000, 1_000_000, 1_000_000);
+    accounts.extend(pool.user_accounts(user, 1_000_000, 1_000_000, 100_000));
+    accounts
+}
+
+/// 每个 handler 的公共检查必须给出与之前手写校验相同的错误
+fn assert_common_checks(mollusk: &Mollusk, pool: &Pool, user: &Pubkey, ix: Instruction, layout: Layout) {
+    let accounts = setup(mollusk, pool, user);
+
+    // user 未签名
+    let mut unsigned = ix.clone();
+    unsigned.accounts[0].is_signer = false;
+    mollusk.process_and_validate_instruction(
+        &unsigned,
+        &accounts,
+        &[Check::err(ProgramError::MissingRequiredSignature)],
+    );
+
+    // token_program 不是 SPL Token / Token-2022
+    let mut wrong_program = ix.clone();
+    wrong_program.accounts[layout.token_program].pubkey = system_program::id();
+    mollusk.process_and_validate_instruction(
+        &wrong_program,
+        &accounts,
+        &[Check::err(ProgramError::IncorrectProgramId)],
+    );
+
+    // config 不是本程序的账户
+    let mut wrong_config = ix.clone();
+    wrong_config.accounts[layout.config].pubkey = *user;
+    mollusk.process_and_validate_instruction(
+        &wrong_config,
+        &accounts,
+        &[Check::err(ProgramError::InvalidAccountData)],
+    );
+
+    // 账户数量不足
+    let mut truncated = ix;
+    truncated.accounts.pop();
+    mollusk.process_and_validate_instruction(
+        &truncated,
+        &accounts,
+        &[Check::err(ProgramError::NotEnoughAccountKeys)],
+    );
+}
+
+#[test]
+fn test_deposit_accounts_validation() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(111);
+    let user = Pubkey::new_unique();
+    let ix = pool.deposit_ix(&user, 1_000, 10_000, 10_000);
+    assert_common_checks(&mollusk, &pool, &user, ix, DEPOSIT);
+}
+
+#[test]
+fn test_withdraw_accounts_validation() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(112);
+    let user = Pubkey::new_unique();
+    let ix = pool.withdraw_ix(&user, 1_000, 1, 1);
+    assert_common_checks(&mollusk, &pool, &user, ix, WITHDRAW);
+}
+
+#[test]
+fn test_swap_accounts_validation() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(113);
+    let user = Pubkey::new_unique();
+    let ix = pool.swap_ix(&user, true, 10_000, 1);
+    assert_common_checks(&mollusk, &pool, &user, ix, SWAP);
+}
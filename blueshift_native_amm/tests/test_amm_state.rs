@@ -0,0 +1,105 @@
+use blueshift_native_amm::{
+    errors::AmmError,
+    state::{AmmState, Config},
+};
+use pinocchio::program_error::ProgramError;
+
+#[test]
+fn test_every_state_byte_round_trips() {
+    for state in [
+        AmmState::Uninitialized,
+        AmmState::Initialized,
+        AmmState::Disabled,
+        AmmState::WithdrawOnly,
+    ] {
+        assert_eq!(AmmState::try_from(state as u8), Ok(state));
+    }
+}
+
+#[test]
+fn test_invalid_state_byte_is_rejected() {
+    for byte in [4u8, 5, 0x7f, u8::MAX] {
+        assert_eq!(
+            AmmState::try_from(byte),
+            Err(ProgramError::from(AmmError::InvalidAmmState))
+        );
+    }
+}
+
+#[test]
+fn test_config_amm_state_reads_state_byte() {
+    let mut data = vec![0u8; Config::LEN];
+
+    data[0] = AmmState::WithdrawOnly as u8;
+    let config = unsafe { Config::from_bytes_unchecked(&data) };
+    assert_eq!(config.amm_state(), Ok(AmmState::WithdrawOnly));
+
+    data[0] = 9;
+    let config = unsafe { Config::from_bytes_unchecked(&data) };
+    assert_eq!(
+        config.amm_state(),
+        Err(ProgramError::from(AmmError::InvalidAmmState))
+    );
+}
+
+const ALL_STATES: [AmmState; 4] = [
+    AmmState::Uninitialized,
+    AmmState::Initialized,
+    AmmState::Disabled,
+    AmmState::WithdrawOnly,
+];
+
+const ALLOWED_TRANSITIONS: [(AmmState, AmmState); 4] = [
+    (AmmState::Uninitialized, AmmState::Initialized),
+    (AmmState::Initialized, AmmState::WithdrawOnly),
+    (AmmState::WithdrawOnly, AmmState::Initialized),
+    (AmmState::WithdrawOnly, AmmState::Disabled),
+];
+
+#[test]
+fn test_transition_table() {
+    for (from, to) in ALLOWED_TRANSITIONS {
+        assert!(from.can_transition_to(to), "{from:?} -> {to:?}");
+    }
+}
+
+/// 表外的 12 个组合全部被拒绝：回到 Uninitialized、离开 Disabled、Initialized 直接下线、重复设置同一状态
+#[test]
+fn test_every_disallowed_transition_is_rejected() {
+    let mut disallowed = 0;
+    for from in ALL_STATES {
+        for to in ALL_STATES {
+            if ALLOWED_TRANSITIONS.contains(&(from, to)) {
+                continue;
+            }
+            assert!(!from.can_transition_to(to), "{from:?} -> {to:?}");
+            disallowed += 1;
+        }
+    }
+    assert_eq!(disallowed, ALL_STATES.len() * ALL_STATES.len() - ALLOWED_TRANSITIONS.len());
+
+    // Disabled 是终态
+    assert!(ALL_STATES.iter().all(|&to| !AmmState::Disabled.can_transition_to(to)));
+}
+
+#[test]
+fn test_config_set_state_enforces_transitions() {
+    for from in ALL_STATES {
+        for to in ALL_STATES {
+            let mut data = vec![0u8; Config::LEN];
+            data[0] = from as u8;
+            let config = unsafe { Config::from_bytes_unchecked_mut(&mut data) };
+
+            if from.can_transition_to(to) {
+                assert_eq!(config.set_state(to), Ok(()));
+                assert_eq!(config.amm_state(), Ok(to));
+            } else {
+                assert_eq!(
+                    config.set_state(to),
+                    Err(ProgramError::from(AmmError::InvalidStateTransition))
+                );
+                assert_eq!(config.amm_state(), Ok(from));
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{errors::AmmError, state::Config},
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    solana_account::Account,
+    solana_sdk::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+
+/// 池子 + 持有 X/Y 的用户；`decimals_y` 是 mint_y 实际的精度（fixture 的 Config 记录的是 6）
+fn pool_accounts(mollusk: &Mollusk, pool: &Pool, user: &Pubkey, decimals_y: u8) -> Vec<(Pubkey, Account)> {
+    let mut accounts = pool.accounts(mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    set_account(
+        &mut accounts,
+        &pool.mint_y,
+        create_mint_account(&Pubkey::new_unique(), decimals_y, u64::MAX / 2, &pool.token_program),
+    );
+    accounts.extend(pool.user_accounts(user, RESERVE, RESERVE, 0));
+    accounts
+}
+
+/// checked 模式（Token-2022）：存款、swap、取款都通过 TransferChecked 完成
+#[test]
+fn test_checked_mode_transfers_succeed() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new_with_token_program(221, spl_token_2022::id());
+    let user = Pubkey::new_unique();
+    let accounts = pool_accounts(&mollusk, &pool, &user, 6);
+
+    mollusk.process_and_validate_instruction_chain(
+        &[
+            (&pool.deposit_ix(&user, 10_000, RESERVE, RESERVE), &[Check::success()]),
+            (&pool.swap_ix(&user, true, 10_000, 1), &[Check::success()]),
+            (&pool.withdraw_ix(&user, 10_000, 1, 1), &[Check::success()]),
+        ],
+        &accounts,
+    );
+}
+
+/// 普通 SPL Token 池子仍使用 Transfer，不需要传入 mint
+#[test]
+fn test_unchecked_mode_transfers_succeed() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(222);
+    let user = Pubkey::new_unique();
+    let accounts = pool_accounts(&mollusk, &pool, &user, 6);
+
+    mollusk.process_and_validate_instruction_chain(
+        &[
+            (&pool.deposit_ix(&user, 10_000, RESERVE, RESERVE), &[Check::success()]),
+            (&pool.swap_ix(&user, true, 10_000, 1), &[Check::success()]),
+            (&pool.withdraw_ix(&user, 10_000, 1, 1), &[Check::success()]),
+        ],
+        &accounts,
+    );
+}
+
+/// checked 模式下 Config 记录的精度与 mint 不一致时，程序在 CPI 之前以 MintDecimalsMismatch 拒绝
+#[test]
+fn test_checked_mode_catches_decimals_mismatch() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new_with_token_program(223, spl_token_2022::id());
+    let user = Pubkey::new_unique();
+    let accounts = pool_accounts(&mollusk, &pool, &user, 9);
+
+    mollusk.process_and_validate_instruction(
+        &pool.swap_ix(&user, true, 10_000, 1),
+        &accounts,
+        &[Check::err(amm_err(AmmError::MintDecimalsMismatch))],
+    );
+}
+
+/// checked 模式缺少 mint 账户时拒绝
+#[test]
+fn test_checked_mode_requires_mints() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new_with_token_program(224, spl_token_2022::id());
+    let user = Pubkey::new_unique();
+    let accounts = pool_accounts(&mollusk, &pool, &user, 6);
+
+    let mut ix = pool.swap_ix(&user, true, 10_000, 1);
+    ix.accounts.truncate(ix.accounts.len() - 2);
+    mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::err(ProgramError::NotEnoughAccountKeys)]);
+}
+
+/// Initialize 对 Token-2022 池子自动打开 require_checked_transfers，并记录 mint 的精度
+#[test]
+fn test_initialize_enables_checked_transfers_for_token_2022() {
+    for (token_program, checked) in [(spl_token_2022::id(), true), (spl_token::id(), false)] {
+        let mollusk = setup_mollusk();
+        let (token_program, token_program_account) = token_program_keyed_account(&token_program);
+        let initializer = Pubkey::new_unique();
+        let mint_x = Pubkey::new_unique();
+        let mint_y = Pubkey::new_unique();
+        let (global_config, global_bump) = global_config_pda();
+        let (config, _) = config_pda(225, &mint_x, &mint_y);
+        let (mint_lp, _) = mint_lp_pda(&config);
+
+        let accounts = vec![
+            (initializer, create_system_account(10 * LAMPORTS_PER_SOL)),
+            (mint_lp, Account::default()),
+            (config, Account::default()),
+            (system_program::id(), create_system_program_account()),
+            (token_program, token_program_account),
+            (global_config, create_global_config_account(&mollusk, &Pubkey::new_unique(), FEE, global_bump)),
+            (mint_x, create_mint_account(&initializer, 6, 0, &token_program)),
+            (mint_y, create_mint_account(&initializer, 9, 0, &token_program)),
+        ];
+        let result = mollusk.process_and_validate_instruction(
+            &initialize_ix(&initializer, 225, FEE, &mint_x, &mint_y, None, &token_program),
+            &accounts,
+            &[Check::success()],
+        );
+
+        let data = result.get_account(&config).unwrap().data.clone();
+        let config = unsafe { Config::from_bytes_unchecked(&data) };
+        assert_eq!(config.require_checked_transfers(), checked);
+        if checked {
+            assert_eq!((config.decimals_x(), config.decimals_y()), (6, 9));
+        }
+    }
+}
@@ -0,0 +1,109 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::errors::AmmError,
+    common::*,
+    mollusk_svm::{result::{Check, ProgramResult}, Mollusk},
+    solana_account::Account,
+    solana_sdk::{instruction::Instruction, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+const AMOUNT_IN: u64 = 10_000;
+/// 1% 协议费：10_000 的输入中 100 留在金库中累计
+const PROTOCOL_FEE: u16 = 100;
+const PROTOCOL_CUT: u64 = 100;
+
+/// 池子 authority 为 `authority`，user 持有足够的 X，authority 持有 X / Y 两个空 ATA 作为收款账户
+fn setup(seed: u64, authority: &Pubkey, user: &Pubkey) -> (Mollusk, Pool, Vec<(Pubkey, Account)>) {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(seed);
+
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    set_account(&mut accounts, &pool.config, pool.config_account_with_authority(&mollusk, FEE, authority));
+    pool.sync_reserves(&mut accounts);
+    accounts.extend(pool.user_accounts(authority, 0, 0, 0));
+    accounts.extend(pool.user_accounts(user, RESERVE, 0, 0));
+    (mollusk, pool, accounts)
+}
+
+/// treasury 设置为池子自己的 config：协议费留在金库中
+fn accrue_protocol_fee_ix(pool: &Pool, authority: &Pubkey) -> Instruction {
+    let mut value = PROTOCOL_FEE.to_le_bytes().to_vec();
+    value.extend_from_slice(pool.config.as_ref());
+    pool.set_config_ix(authority, 7, &value)
+}
+
+#[test]
+fn test_swap_accrues_fee_and_collect_fees_pays_it_out() {
+    let (authority, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (mollusk, pool, accounts) = setup(318, &authority, &user);
+
+    // 不需要 treasury ATA，协议费连同输入一起进入金库
+    let result = mollusk.process_instruction_chain(
+        &[accrue_protocol_fee_ix(&pool, &authority), pool.swap_ix(&user, true, AMOUNT_IN, 1)],
+        &accounts,
+    );
+    assert_eq!(result.program_result, ProgramResult::Success);
+    assert_eq!(token_amount(result.get_account(&pool.vault_x).unwrap()), RESERVE + AMOUNT_IN);
+
+    let result = mollusk.process_and_validate_instruction(
+        &pool.collect_fees_ix(&authority, &pool.user_x(&authority), &pool.user_y(&authority)),
+        &result.resulting_accounts,
+        &[Check::success()],
+    );
+    assert_eq!(token_amount(result.get_account(&pool.user_x(&authority)).unwrap()), PROTOCOL_CUT);
+    assert_eq!(token_amount(result.get_account(&pool.user_y(&authority)).unwrap()), 0);
+    assert_eq!(token_amount(result.get_account(&pool.vault_x).unwrap()), RESERVE + AMOUNT_IN - PROTOCOL_CUT);
+
+    // 累计值已清零，再次调用不再转出
+    let again = mollusk.process_and_validate_instruction(
+        &pool.collect_fees_ix(&authority, &pool.user_x(&authority), &pool.user_y(&authority)),
+        &result.resulting_accounts,
+        &[Check::success()],
+    );
+    assert_eq!(token_amount(again.get_account(&pool.user_x(&authority)).unwrap()), PROTOCOL_CUT);
+}
+
+/// 记账的协议费超过金库中可取出的部分时，按可取出的部分封顶
+#[test]
+fn test_collect_fees_caps_at_vault_surplus() {
+    let (authority, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (mollusk, pool, accounts) = setup(319, &authority, &user);
+
+    let result = mollusk.process_instruction_chain(
+        &[accrue_protocol_fee_ix(&pool, &authority), pool.swap_ix(&user, true, AMOUNT_IN, 1)],
+        &accounts,
+    );
+    assert_eq!(result.program_result, ProgramResult::Success);
+
+    // 金库中只剩 40 个超出快照的 X
+    let mut accounts = result.resulting_accounts;
+    let vault_x = create_token_account(&pool.mint_x, &pool.config, RESERVE + AMOUNT_IN - PROTOCOL_CUT + 40, &pool.token_program);
+    set_account(&mut accounts, &pool.vault_x, vault_x);
+
+    let result = mollusk.process_and_validate_instruction(
+        &pool.collect_fees_ix(&authority, &pool.user_x(&authority), &pool.user_y(&authority)),
+        &accounts,
+        &[Check::success()],
+    );
+    assert_eq!(token_amount(result.get_account(&pool.user_x(&authority)).unwrap()), 40);
+    assert_eq!(token_amount(result.get_account(&pool.vault_x).unwrap()), RESERVE + AMOUNT_IN - PROTOCOL_CUT);
+}
+
+#[test]
+fn test_collect_fees_requires_owner() {
+    let (authority, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (mollusk, pool, mut accounts) = setup(320, &authority, &user);
+    let intruder = Pubkey::new_unique();
+    accounts.extend(pool.user_accounts(&intruder, 0, 0, 0));
+
+    mollusk.process_and_validate_instruction(
+        &pool.collect_fees_ix(&intruder, &pool.user_x(&intruder), &pool.user_y(&intruder)),
+        &accounts,
+        &[Check::err(amm_err(AmmError::InvalidAuthority))],
+    );
+}
@@ -0,0 +1,135 @@
+#![cfg(feature = "test-sbf")]
+
+//! 每条指令的 compute unit 预算。超出预算说明有性能回退；实际消耗会打印出来，便于查看余量：
+//!
+//! cargo test-sbf --features test-sbf --test test_compute_units -- --nocapture
+
+mod common;
+
+use {
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    solana_account::Account,
+    solana_sdk::{instruction::Instruction, pubkey::Pubkey},
+    spl_associated_token_account::instruction::create_associated_token_account_idempotent,
+};
+
+const FEE: u16 = 30;
+
+// SPL Token 路径的预算
+const INITIALIZE_BUDGET: u64 = 50_000;
+const DEPOSIT_BUDGET: u64 = 60_000;
+const WITHDRAW_BUDGET: u64 = 30_000;
+const SWAP_BUDGET: u64 = 30_000;
+const QUOTE_BUDGET: u64 = 10_000;
+
+// Token-2022 路径的预算（Token-2022 程序本身更重）
+const SWAP_BUDGET_2022: u64 = 40_000;
+const QUOTE_BUDGET_2022: u64 = 10_000;
+
+fn assert_budget(
+    name: &str,
+    mollusk: &Mollusk,
+    ix: &Instruction,
+    accounts: &[(Pubkey, Account)],
+    budget: u64,
+) {
+    let result = mollusk.process_and_validate_instruction(ix, accounts, &[Check::success()]);
+    let used = result.compute_units_consumed;
+    println!("{name:<16} {used:>7} CU (budget {budget}, headroom {})", budget as i64 - used as i64);
+    assert!(used <= budget, "{name} used {used} CU, budget is {budget}");
+}
+
+#[test]
+fn test_initialize_compute_units() {
+    let mollusk = setup_mollusk();
+    let (token_program, token_program_account) = token_program_keyed_account(&spl_token::id());
+    let initializer = Pubkey::new_unique();
+    let mint_x = Pubkey::new_unique();
+    let mint_y = Pubkey::new_unique();
+    let (global_config, global_bump) = global_config_pda();
+    let (config, _) = config_pda(1, &mint_x, &mint_y);
+    let (mint_lp, _) = mint_lp_pda(&config);
+
+    let accounts = vec![
+        (initializer, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (mint_lp, Account::default()),
+        (config, Account::default()),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (global_config, create_global_config_account(&mollusk, &Pubkey::new_unique(), FEE, global_bump)),
+    ];
+    assert_budget(
+        "initialize",
+        &mollusk,
+        &initialize_ix(&initializer, 1, FEE, &mint_x, &mint_y, None, &token_program),
+        &accounts,
+        INITIALIZE_BUDGET,
+    );
+}
+
+#[test]
+fn test_deposit_withdraw_swap_quote_compute_units() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(51);
+    let user = Pubkey::new_unique();
+
+    let mut accounts = pool.accounts(&mollusk, FEE, 1_000_000_000, 1_000_000_000, 1_000_000_000);
+    accounts.extend(pool.user_accounts(&user, 1_000_000, 1_000_000, 100_000));
+
+    assert_budget("deposit", &mollusk, &pool.deposit_ix(&user, 10_000, 20_000, 20_000), &accounts, DEPOSIT_BUDGET);
+    assert_budget("withdraw", &mollusk, &pool.withdraw_ix(&user, 10_000, 1, 1), &accounts, WITHDRAW_BUDGET);
+    assert_budget("swap", &mollusk, &pool.swap_ix(&user, true, 10_000, 1), &accounts, SWAP_BUDGET);
+    assert_budget("quote", &mollusk, &pool.quote_ix(true, 10_000), &accounts, QUOTE_BUDGET);
+}
+
+/// Initialize 记录的金库 bump 让存取款省去两次 find_program_address。
+/// 作为对照，ATA 程序的 CreateIdempotent 对已存在的金库只做一次 find_program_address 和归属检查，
+/// 两个金库各执行一次即为不记录 bump 时验证金库的开销
+#[test]
+fn test_cached_vault_bumps_save_compute_units() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(54);
+    let user = Pubkey::new_unique();
+
+    let mut accounts = pool.accounts(&mollusk, FEE, 1_000_000_000, 1_000_000_000, 1_000_000_000);
+    accounts.extend(pool.user_accounts(&user, 1_000_000, 1_000_000, 100_000));
+
+    let search: u64 = [pool.mint_x, pool.mint_y]
+        .iter()
+        .map(|mint| {
+            let ix = create_associated_token_account_idempotent(&user, &pool.config, mint, &pool.token_program);
+            mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::success()]).compute_units_consumed
+        })
+        .sum();
+
+    for (name, ix, budget) in [
+        ("deposit", pool.deposit_ix(&user, 10_000, 20_000, 20_000), DEPOSIT_BUDGET),
+        ("withdraw", pool.withdraw_ix(&user, 10_000, 1, 1), WITHDRAW_BUDGET),
+    ] {
+        let cached = mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::success()]).compute_units_consumed;
+        println!("{name:<16} {cached:>7} CU with cached bumps, ~{:>7} CU with two vault searches", cached + search);
+        assert!(cached <= budget, "{name} used {cached} CU, budget is {budget}");
+    }
+}
+
+#[test]
+fn test_quote_compute_units_token_2022() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new_with_token_program(52, spl_token_2022::id());
+    let accounts = pool.accounts(&mollusk, FEE, 1_000_000_000, 1_000_000_000, 1_000_000_000);
+
+    assert_budget("quote (2022)", &mollusk, &pool.quote_ix(true, 10_000), &accounts, QUOTE_BUDGET_2022);
+}
+
+#[test]
+fn test_swap_compute_units_token_2022() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new_with_token_program(53, spl_token_2022::id());
+    let user = Pubkey::new_unique();
+
+    let mut accounts = pool.accounts(&mollusk, FEE, 1_000_000_000, 1_000_000_000, 1_000_000_000);
+    accounts.extend(pool.user_accounts(&user, 1_000_000, 1_000_000, 0));
+
+    assert_budget("swap (2022)", &mollusk, &pool.swap_ix(&user, true, 10_000, 1), &accounts, SWAP_BUDGET_2022);
+}
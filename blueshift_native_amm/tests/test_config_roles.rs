@@ -0,0 +1,145 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{
+        errors::AmmError,
+        state::{AmmState, Config, MAX_FEE_BPS},
+    },
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    solana_account::Account,
+    solana_sdk::pubkey::Pubkey,
+};
+
+const FEE: u16 = 30;
+
+/// owner 已把 fee_admin / pause_admin 分别交给两个不同的账户
+struct Roles {
+    owner: Pubkey,
+    fee_admin: Pubkey,
+    pause_admin: Pubkey,
+}
+
+fn setup(mollusk: &Mollusk, pool: &Pool) -> (Roles, Vec<(Pubkey, Account)>) {
+    let roles = Roles {
+        owner: Pubkey::new_unique(),
+        fee_admin: Pubkey::new_unique(),
+        pause_admin: Pubkey::new_unique(),
+    };
+    let mut accounts = pool.accounts(mollusk, FEE, 1_000_000, 1_000_000, 1_000_000);
+    set_account(&mut accounts, &pool.config, pool.config_account_with_authority(mollusk, FEE, &roles.owner));
+    pool.sync_reserves(&mut accounts);
+    for key in [roles.owner, roles.fee_admin, roles.pause_admin] {
+        accounts.push((key, create_system_account(LAMPORTS_PER_SOL)));
+    }
+
+    let result = mollusk.process_and_validate_instruction_chain(
+        &[
+            (&pool.set_config_ix(&roles.owner, 3, roles.fee_admin.as_ref()), &[Check::success()]),
+            (&pool.set_config_ix(&roles.owner, 4, roles.pause_admin.as_ref()), &[Check::success()]),
+        ],
+        &accounts,
+    );
+    (roles, result.resulting_accounts)
+}
+
+fn config(accounts: &[(Pubkey, Account)], pool: &Pool) -> (u8, u16) {
+    let data = &accounts.iter().find(|(k, _)| k == &pool.config).unwrap().1.data;
+    let config = unsafe { Config::from_bytes_unchecked(data) };
+    (config.state(), config.fee())
+}
+
+#[test]
+fn test_pause_admin_can_pause_but_not_change_fee() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(131);
+    let (roles, accounts) = setup(&mollusk, &pool);
+
+    let result = mollusk.process_and_validate_instruction(
+        &pool.set_config_ix(&roles.pause_admin, 2, &[AmmState::WithdrawOnly as u8]),
+        &accounts,
+        &[Check::success()],
+    );
+    assert_eq!(config(&result.resulting_accounts, &pool).0, AmmState::WithdrawOnly as u8);
+
+    mollusk.process_and_validate_instruction(
+        &pool.set_config_ix(&roles.pause_admin, 1, &100u16.to_le_bytes()),
+        &accounts,
+        &[Check::err(amm_err(AmmError::InvalidAuthority))],
+    );
+}
+
+#[test]
+fn test_fee_admin_can_change_fee_but_not_pause() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(132);
+    let (roles, accounts) = setup(&mollusk, &pool);
+
+    let result = mollusk.process_and_validate_instruction(
+        &pool.set_config_ix(&roles.fee_admin, 1, &100u16.to_le_bytes()),
+        &accounts,
+        &[Check::success()],
+    );
+    assert_eq!(config(&result.resulting_accounts, &pool).1, 100);
+
+    mollusk.process_and_validate_instruction(
+        &pool.set_config_ix(&roles.fee_admin, 2, &[AmmState::WithdrawOnly as u8]),
+        &accounts,
+        &[Check::err(amm_err(AmmError::InvalidAuthority))],
+    );
+}
+
+/// 重新分配之后 owner 自己不再持有 fee/pause 权限，但仍可以再次分配
+#[test]
+fn test_owner_reassigns_roles() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(133);
+    let (roles, accounts) = setup(&mollusk, &pool);
+
+    mollusk.process_and_validate_instruction(
+        &pool.set_config_ix(&roles.owner, 1, &100u16.to_le_bytes()),
+        &accounts,
+        &[Check::err(amm_err(AmmError::InvalidAuthority))],
+    );
+    // 只有 owner 可以重新分配角色
+    mollusk.process_and_validate_instruction(
+        &pool.set_config_ix(&roles.fee_admin, 4, roles.fee_admin.as_ref()),
+        &accounts,
+        &[Check::err(amm_err(AmmError::InvalidAuthority))],
+    );
+
+    mollusk.process_and_validate_instruction_chain(
+        &[
+            (&pool.set_config_ix(&roles.owner, 3, roles.owner.as_ref()), &[Check::success()]),
+            (&pool.set_config_ix(&roles.owner, 1, &100u16.to_le_bytes()), &[Check::success()]),
+            (
+                &pool.set_config_ix(&roles.fee_admin, 1, &200u16.to_le_bytes()),
+                &[Check::err(amm_err(AmmError::InvalidAuthority))],
+            ),
+        ],
+        &accounts,
+    );
+}
+
+/// 费率最多改到 MAX_FEE_BPS，更高的值被 FeeTooHigh 拒绝且不修改 config
+#[test]
+fn test_fee_change_is_capped() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(327);
+    let (roles, accounts) = setup(&mollusk, &pool);
+
+    let result = mollusk.process_and_validate_instruction(
+        &pool.set_config_ix(&roles.fee_admin, 1, &MAX_FEE_BPS.to_le_bytes()),
+        &accounts,
+        &[Check::success()],
+    );
+    assert_eq!(config(&result.resulting_accounts, &pool).1, MAX_FEE_BPS);
+
+    mollusk.process_and_validate_instruction(
+        &pool.set_config_ix(&roles.fee_admin, 1, &(MAX_FEE_BPS + 1).to_le_bytes()),
+        &accounts,
+        &[Check::err(amm_err(AmmError::FeeTooHigh))],
+    );
+}
@@ -0,0 +1,234 @@
+use blueshift_native_amm::{
+    curve::{weighted, Curve},
+    instructions::helpers::{compute_swap, compute_swap_exact_out, single_sided_swap_amount, deposit_amounts_from_lp, withdraw_amounts_from_lp},
+    state::{EQUAL_WEIGHT_BPS, MINIMUM_LIQUIDITY, weighted_price},
+};
+
+const VAULT_X: u64 = 3_000_000;
+const VAULT_Y: u64 = 1_000_000;
+
+#[test]
+fn test_for_swap_orders_reserves_by_direction() {
+    let x_to_y = Curve::for_swap(VAULT_X, VAULT_Y, true, 30);
+    assert_eq!((x_to_y.reserve_in, x_to_y.reserve_out, x_to_y.fee_bps), (VAULT_X, VAULT_Y, 30));
+
+    let y_to_x = Curve::for_swap(VAULT_X, VAULT_Y, false, 30);
+    assert_eq!((y_to_x.reserve_in, y_to_x.reserve_out, y_to_x.fee_bps), (VAULT_Y, VAULT_X, 30));
+
+    assert_eq!(x_to_y.amount_out(10_000).unwrap(), compute_swap(VAULT_X, VAULT_Y, 10_000, 30).unwrap());
+    assert_eq!(y_to_x.amount_out(10_000).unwrap(), compute_swap(VAULT_Y, VAULT_X, 10_000, 30).unwrap());
+}
+
+#[test]
+fn test_amount_in_is_minimal_input_for_amount_out() {
+    for is_x in [true, false] {
+        let curve = Curve::for_swap(VAULT_X, VAULT_Y, is_x, 30);
+        for amount_out in [1, 997, 10_000, 123_456, 500_000] {
+            let amount_in = curve.amount_in(amount_out).unwrap();
+            // 足够得到 amount_out，少 1 则不够
+            assert!(curve.amount_out(amount_in).unwrap() >= amount_out);
+            assert!(curve.amount_out(amount_in - 1).unwrap() < amount_out);
+        }
+    }
+}
+
+#[test]
+fn test_amount_in_rejects_draining_reserve_out() {
+    assert!(compute_swap_exact_out(VAULT_X, VAULT_Y, VAULT_Y, 30).is_err());
+    assert!(compute_swap_exact_out(VAULT_X, VAULT_Y, VAULT_Y + 1, 30).is_err());
+    assert!(compute_swap_exact_out(VAULT_X, VAULT_Y, VAULT_Y - 1, 30).is_ok());
+}
+
+#[test]
+fn test_for_liquidity_uses_locked_supply() {
+    let supply = 1_000_000;
+    let curve = Curve::for_liquidity(VAULT_X, VAULT_Y, supply).unwrap();
+    assert_eq!((curve.reserve_x, curve.reserve_y), (VAULT_X, VAULT_Y));
+    assert_eq!(curve.lp_supply, supply + MINIMUM_LIQUIDITY);
+
+    let locked = supply + MINIMUM_LIQUIDITY;
+    assert_eq!(
+        curve.deposit_amounts(12_345).unwrap(),
+        deposit_amounts_from_lp(VAULT_X, VAULT_Y, locked, 12_345).unwrap()
+    );
+    assert_eq!(
+        curve.withdraw_amounts(12_345).unwrap(),
+        withdraw_amounts_from_lp(VAULT_X, VAULT_Y, locked, 12_345).unwrap()
+    );
+}
+
+#[test]
+fn test_for_liquidity_rejects_supply_overflow() {
+    assert!(Curve::for_liquidity(VAULT_X, VAULT_Y, u64::MAX).is_err());
+}
+
+#[test]
+fn test_lp_for_withdraw_round_trips_with_withdraw_amounts() {
+    let curve = Curve::for_liquidity(VAULT_X, VAULT_Y, 777_777).unwrap();
+
+    for (x, y) in [(10_000, 0), (0, 1), (1, 1_000), (999_999, 3), (123_457, 41_153)] {
+        let lp = curve.lp_for_withdraw(x, y).unwrap();
+        let (got_x, got_y) = curve.withdraw_amounts(lp).unwrap();
+        assert!(got_x >= x && got_y >= y, "lp {lp} returns ({got_x}, {got_y}) < ({x}, {y})");
+
+        // 少一份 LP 就达不到其中一侧
+        let (less_x, less_y) = curve.withdraw_amounts(lp - 1).unwrap();
+        assert!(less_x < x || less_y < y);
+    }
+}
+
+#[test]
+fn test_lp_for_amounts_round_trips_with_deposit_amounts() {
+    let curve = Curve::for_liquidity(VAULT_X, VAULT_Y, 777_777).unwrap();
+
+    for (x, y) in [(10_000, 10_000), (1, 1_000_000), (999_999, 3), (123_457, 41_153)] {
+        let lp = curve.lp_for_amounts(x, y).unwrap();
+        let (need_x, need_y) = curve.deposit_amounts(lp).unwrap();
+        assert!(need_x <= x && need_y <= y, "lp {lp} needs ({need_x}, {need_y}) > ({x}, {y})");
+
+        // 多一份 LP 就会超出其中一侧
+        let (more_x, more_y) = curve.deposit_amounts(lp + 1).unwrap();
+        assert!(more_x > x || more_y > y);
+    }
+}
+
+#[test]
+fn test_single_sided_swap_amount_is_about_half() {
+    // 无手续费、a 远小于储备时约为一半，手续费越高需要换出的越多
+    let half = single_sided_swap_amount(VAULT_X, 10_000, 0).unwrap();
+    assert!((4_990..=5_000).contains(&half), "{half}");
+    assert!(single_sided_swap_amount(VAULT_X, 10_000, 30).unwrap() >= half);
+    // 无手续费时的精确解 R(sqrt(1 + a/R) - 1)：a = 3R 时为 R
+    assert!(single_sided_swap_amount(VAULT_X, 3 * VAULT_X, 0).unwrap().abs_diff(VAULT_X) <= 1);
+}
+
+#[test]
+fn test_single_sided_deposit_matches_swap_then_deposit() {
+    let curve = Curve::for_liquidity(VAULT_X, VAULT_Y, 1_000_000).unwrap();
+    let amount = 30_000;
+    let (lp, swap_out, impact) = curve.single_sided_deposit(amount, true, 30).unwrap();
+
+    let swapped = single_sided_swap_amount(VAULT_X, amount, 30).unwrap();
+    assert_eq!(swap_out, compute_swap(VAULT_X, VAULT_Y, swapped, 30).unwrap());
+    let after = Curve::for_liquidity(VAULT_X + swapped, VAULT_Y - swap_out, 1_000_000).unwrap();
+    assert_eq!(lp, after.lp_for_amounts(amount - swapped, swap_out).unwrap());
+    assert!(impact > 0);
+
+    // 按比例分配几乎没有剩余：两侧算出的 LP 相差很小
+    let by_x = (amount - swapped) as u128 * after.lp_supply as u128 / after.reserve_x as u128;
+    let by_y = swap_out as u128 * after.lp_supply as u128 / after.reserve_y as u128;
+    assert!(by_x.abs_diff(by_y) * 1_000 <= by_x, "x {by_x} y {by_y}");
+
+    // 少于按现价（3:1）拆成两半、没有手续费和价格影响时的份额
+    assert!(lp < curve.lp_for_amounts(amount / 2, amount / 2 / 3).unwrap());
+}
+
+/// 80/20 池子：X 储备 4_000_000（权重 80%），Y 储备 1_000_000（权重 20%），现货价格为 1
+const WEIGHTED_X: u64 = 4_000_000;
+const WEIGHTED_Y: u64 = 1_000_000;
+
+/// 加权公式的浮点参考值：out = R_out · (1 - (R_in / (R_in + a'))^(w_in / w_out))
+fn weighted_out_f64(reserve_in: u64, reserve_out: u64, amount_in: u64, weight_in: u16) -> f64 {
+    let after_fee = (amount_in as u128 * 9_970 / 10_000) as f64;
+    let exponent = weight_in as f64 / (10_000 - weight_in) as f64;
+    reserve_out as f64 * (1.0 - (reserve_in as f64 / (reserve_in as f64 + after_fee)).powf(exponent))
+}
+
+#[test]
+fn test_weighted_swap_at_equal_weight_is_constant_product() {
+    // 50/50 的 SwapCurve 仍然走精确的整数公式
+    for is_x in [true, false] {
+        let curve = Curve::for_weighted_swap(VAULT_X, VAULT_Y, is_x, 30, EQUAL_WEIGHT_BPS);
+        let plain = Curve::for_swap(VAULT_X, VAULT_Y, is_x, 30);
+        for amount in [1, 997, 10_000, 123_456] {
+            assert_eq!(curve.amount_out(amount).unwrap(), plain.amount_out(amount).unwrap());
+        }
+    }
+    // 定点数实现在 50/50 时也与 x * y = k 一致，只会略少
+    for amount in [1, 997, 10_000, 123_456, 2_000_000] {
+        let exact = compute_swap(VAULT_X, VAULT_Y, amount, 30).unwrap();
+        let approx = weighted::compute_swap(VAULT_X, VAULT_Y, amount, 30, EQUAL_WEIGHT_BPS).unwrap();
+        assert!(approx <= exact && exact - approx <= 1, "{approx} {exact}");
+    }
+}
+
+#[test]
+fn test_weighted_swap_matches_closed_form() {
+    for (is_x, weight_in) in [(true, 8_000), (false, 2_000)] {
+        let curve = Curve::for_weighted_swap(WEIGHTED_X, WEIGHTED_Y, is_x, 30, 8_000);
+        assert_eq!(curve.weight_in, weight_in);
+        for amount in [1, 997, 10_000, 123_456, 2_000_000] {
+            let out = curve.amount_out(amount).unwrap();
+            let expected = weighted_out_f64(curve.reserve_in, curve.reserve_out, amount, weight_in);
+            // 向下取整，不会多给
+            assert!(out as f64 <= expected && expected - (out as f64) < 2.0, "{out} {expected}");
+        }
+        for amount_out in [1, 997, 10_000, 123_456] {
+            let amount_in = curve.amount_in(amount_out).unwrap();
+            let exponent = (10_000 - weight_in) as f64 / weight_in as f64;
+            let ratio = curve.reserve_out as f64 / (curve.reserve_out - amount_out) as f64;
+            let expected = curve.reserve_in as f64 * (ratio.powf(exponent) - 1.0) / 0.997;
+            // 向上取整，不会少收
+            assert!(amount_in as f64 >= expected && (amount_in as f64) - expected < 3.0, "{amount_in} {expected}");
+        }
+    }
+}
+
+#[test]
+fn test_80_20_quote_against_50_50() {
+    let amount = 100_000;
+    let weighted = Curve::for_weighted_swap(WEIGHTED_X, WEIGHTED_Y, true, 30, 8_000);
+    // 同样价值（现价 1 下总计 5_000_000）的 50/50 池子
+    let equal_value = Curve::for_swap(2_500_000, 2_500_000, true, 30);
+    // Y 一侧深度相同的 50/50 池子
+    let same_y = Curve::for_swap(WEIGHTED_Y, WEIGHTED_Y, true, 30);
+
+    // 现价相同：小额兑换几乎只差手续费
+    assert_eq!(weighted.amount_out(100).unwrap(), equal_value.amount_out(100).unwrap());
+
+    // 80/20 池子 Y 一侧只占 20% 的价值，价格影响比同样价值的 50/50 池子大，
+    // 但比只有同样 Y 储备的 50/50 池子小
+    let out = weighted.amount_out(amount).unwrap();
+    assert!(out < equal_value.amount_out(amount).unwrap());
+    assert!(out > same_y.amount_out(amount).unwrap());
+    assert!(weighted.price_impact_bps(amount).unwrap() > equal_value.price_impact_bps(amount).unwrap());
+}
+
+#[test]
+fn test_weighted_swap_rejects_out_of_range_weights() {
+    for weight in [0, 999, 9_001, 10_000] {
+        assert!(weighted::compute_swap(WEIGHTED_X, WEIGHTED_Y, 10_000, 30, weight).is_err());
+    }
+    // 取空输出一侧
+    let curve = Curve::for_weighted_swap(WEIGHTED_X, WEIGHTED_Y, true, 30, 8_000);
+    assert!(curve.amount_in(WEIGHTED_Y).is_err());
+}
+
+#[test]
+fn test_weighted_liquidity_per_lp() {
+    // x^0.8 · y^0.2 = 4_000_000^0.8 · 1_000_000^0.2 ≈ 3_031_433.13
+    assert_eq!(weighted::geometric_mean(WEIGHTED_X, WEIGHTED_Y, 8_000).unwrap(), 3_031_433);
+
+    let curve = Curve::for_liquidity(WEIGHTED_X, WEIGHTED_Y, 1_000_000).unwrap();
+    let weighted = Curve::for_liquidity(WEIGHTED_X, WEIGHTED_Y, 1_000_000).unwrap().with_weight_x(8_000);
+    // 50/50 的 sqrt(x*y) = 2_000_000，80/20 的不变量更大
+    assert!(weighted.liquidity_per_lp().unwrap() > curve.liquidity_per_lp().unwrap());
+}
+
+/// weighted_price 先乘后除：与按 ratio * w_base / w_quote 直接计算（不溢出时）的结果一致，
+/// 小于 w_quote 的 ratio 不会被先除成 0
+#[test]
+fn test_weighted_price_multiplies_before_dividing() {
+    for weight_base in [1_000u16, 2_000, 3_333, 8_000, 9_000] {
+        let weight_quote = (10_000 - weight_base) as u128;
+        for ratio in [0u128, 1, 999, 1 << 64, (3 << 64) + 12_345, u64::MAX as u128 * 7_919, 1 << 100] {
+            let expected = ratio * weight_base as u128 / weight_quote;
+            assert_eq!(weighted_price(ratio, weight_base), expected, "{ratio} @ {weight_base}");
+        }
+    }
+    assert_eq!(weighted_price(1, 8_000), 4);
+    assert_eq!(weighted_price(12_345, EQUAL_WEIGHT_BPS), 12_345);
+    // 结果超过 u128 时饱和
+    assert_eq!(weighted_price(u128::MAX, 9_000), u128::MAX);
+    assert_eq!(weighted_price(u128::MAX, 1_000), u128::MAX / 9);
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    common::*,
+    mollusk_svm::result::Check,
+    solana_account::Account,
+    solana_sdk::pubkey::Pubkey,
+};
+
+const FEE: u16 = 30;
+
+#[test]
+fn test_deposit_creates_missing_user_lp_ata() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(81);
+    let user = Pubkey::new_unique();
+
+    let mut accounts = pool.accounts(&mollusk, FEE, 1_000_000, 1_000_000, 1_000_000);
+    accounts.extend(pool.user_accounts(&user, 1_000_000, 1_000_000, 0));
+    // 首次存款的用户还没有 LP ATA
+    set_account(&mut accounts, &pool.user_lp(&user), Account::default());
+
+    let result = mollusk.process_and_validate_instruction(
+        &pool.deposit_ix(&user, 10_000, 20_000, 20_000),
+        &accounts,
+        &[Check::success()],
+    );
+
+    let user_lp = result.get_account(&pool.user_lp(&user)).unwrap();
+    assert_eq!(user_lp.owner, pool.token_program);
+    assert_eq!(token_amount(user_lp), 10_000);
+}
@@ -0,0 +1,65 @@
+use blueshift_native_amm::{errors::AmmError, instructions::{DepositInstructionData, ExpirationKind}};
+use pinocchio::program_error::ProgramError;
+
+fn data(amount: u64, max_x: u64, max_y: u64, min_lp_out: u64, expiration: i64) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&max_x.to_le_bytes());
+    data.extend_from_slice(&max_y.to_le_bytes());
+    data.extend_from_slice(&min_lp_out.to_le_bytes());
+    data.extend_from_slice(&expiration.to_le_bytes());
+    data
+}
+
+#[test]
+fn test_fields_are_read_from_fixed_offsets() {
+    let parsed = DepositInstructionData::try_from(data(1, 2, 3, 4, -5).as_slice()).unwrap();
+    assert_eq!(
+        (parsed.amount, parsed.max_x, parsed.max_y, parsed.min_lp_out, parsed.expiration),
+        (1, 2, 3, 4, -5)
+    );
+    assert!(parsed.expected_lp_supply.is_none());
+    assert!(parsed.price_band.is_none());
+}
+
+/// 解析不读取时钟：早已过期的 expiration 也能解析，OrderExpired 在 Deposit::process 中返回
+#[test]
+fn test_parsing_does_not_check_expiration() {
+    for expiration in [i64::MIN, -1, 0] {
+        let parsed = DepositInstructionData::try_from(data(1, 1, 1, 0, expiration).as_slice()).unwrap();
+        assert_eq!(parsed.expiration, expiration);
+    }
+}
+
+#[test]
+fn test_zero_amounts_are_rejected() {
+    for bytes in [data(0, 1, 1, 0, 0), data(1, 0, 1, 0, 0), data(1, 1, 0, 0, 0)] {
+        assert_eq!(
+            DepositInstructionData::try_from(bytes.as_slice()).err(),
+            Some(ProgramError::from(AmmError::ZeroAmount))
+        );
+    }
+    assert_eq!(
+        DepositInstructionData::try_from(&data(1, 1, 1, 0, 0)[..39]).err(),
+        Some(ProgramError::InvalidInstructionData)
+    );
+}
+
+/// 追加 expiration_kind 后固定字段的解析结果不变；长度不属于任何已知可选字段组合时拒绝
+#[test]
+fn test_longer_buffer_parses_the_same_fixed_fields() {
+    let mut bytes = data(1, 2, 3, 4, 5);
+    bytes.push(ExpirationKind::Slot as u8);
+    let parsed = DepositInstructionData::try_from(bytes.as_slice()).unwrap();
+    assert_eq!(
+        (parsed.amount, parsed.max_x, parsed.max_y, parsed.min_lp_out, parsed.expiration),
+        (1, 2, 3, 4, 5)
+    );
+    assert_eq!(parsed.expiration_kind, ExpirationKind::Slot);
+
+    bytes.extend_from_slice(&[0; 3]);
+    assert_eq!(
+        DepositInstructionData::try_from(bytes.as_slice()).err(),
+        Some(ProgramError::InvalidInstructionData)
+    );
+}
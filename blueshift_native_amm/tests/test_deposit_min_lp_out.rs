@@ -0,0 +1,80 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{curve::Curve, errors::AmmError},
+    common::*,
+    mollusk_svm::result::Check,
+    solana_sdk::{instruction::Instruction, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+/// LP supply 远大于储备：每 1 个 x/y 对应约 1_000 份 LP，向上取整的影响很明显
+const RESERVE: u64 = 1_000;
+const LP_SUPPLY: u64 = 1_000_000;
+const AMOUNT: u64 = 1_500;
+
+fn deposit_with_min_lp_out(pool: &Pool, user: &Pubkey, min_lp_out: u64) -> Instruction {
+    let mut ix = pool.deposit_ix(user, AMOUNT, RESERVE, RESERVE);
+    // discriminator + amount + max_x + max_y 之后是 min_lp_out
+    ix.data[25..33].copy_from_slice(&min_lp_out.to_le_bytes());
+    ix
+}
+
+/// 按实际存入的 x/y 反算的 LP（向下取整）
+fn expected_lp() -> u64 {
+    let curve = Curve::for_liquidity(RESERVE, RESERVE, LP_SUPPLY).unwrap();
+    let (x, y) = curve.deposit_amounts(AMOUNT).unwrap();
+    curve.lp_for_amounts(x, y).unwrap()
+}
+
+#[test]
+fn test_deposit_mints_lp_for_actual_contribution() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(241);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, LP_SUPPLY);
+    accounts.extend(pool.user_accounts(&user, RESERVE, RESERVE, 0));
+
+    let result = mollusk.process_and_validate_instruction(
+        &pool.deposit_ix(&user, AMOUNT, RESERVE, RESERVE),
+        &accounts,
+        &[Check::success()],
+    );
+
+    // 向上取整多存入的部分按比例计入 LP，而不是只铸造请求的 amount
+    let minted = token_amount(result.get_account(&pool.user_lp(&user)).unwrap());
+    assert_eq!(minted, expected_lp());
+    assert!(minted >= AMOUNT);
+}
+
+#[test]
+fn test_deposit_accepts_min_lp_out_at_actual_mint() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(242);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, LP_SUPPLY);
+    accounts.extend(pool.user_accounts(&user, RESERVE, RESERVE, 0));
+
+    mollusk.process_and_validate_instruction(
+        &deposit_with_min_lp_out(&pool, &user, expected_lp()),
+        &accounts,
+        &[Check::success()],
+    );
+}
+
+#[test]
+fn test_deposit_rejects_lp_below_min_lp_out() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(243);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, LP_SUPPLY);
+    accounts.extend(pool.user_accounts(&user, RESERVE, RESERVE, 0));
+
+    mollusk.process_and_validate_instruction(
+        &deposit_with_min_lp_out(&pool, &user, expected_lp() + 1),
+        &accounts,
+        &[Check::err(amm_err(AmmError::LpSlippageExceeded))],
+    );
+}
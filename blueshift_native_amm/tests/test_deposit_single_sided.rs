@@ -0,0 +1,113 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{curve::Curve, errors::AmmError},
+    common::*,
+    mollusk_svm::result::Check,
+    solana_sdk::pubkey::Pubkey,
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+const AMOUNT: u64 = 20_000;
+
+fn expected(from_x: bool) -> u64 {
+    Curve::for_liquidity(RESERVE, RESERVE, RESERVE).unwrap().single_sided_deposit(AMOUNT, from_x, FEE).unwrap().0
+}
+
+/// 只转入一侧，兑换出的代币留在金库里；LP 与曲线计算一致
+#[test]
+fn test_single_sided_deposit_mints_lp_for_one_token() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(271);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, AMOUNT, 0, 0));
+
+    let result = mollusk.process_and_validate_instruction(
+        &pool.deposit_single_sided_ix(&user, true, AMOUNT, 1),
+        &accounts,
+        &[Check::success()],
+    );
+
+    assert_eq!(token_amount(result.get_account(&pool.user_x(&user)).unwrap()), 0);
+    assert_eq!(token_amount(result.get_account(&pool.vault_x).unwrap()), RESERVE + AMOUNT);
+    assert_eq!(token_amount(result.get_account(&pool.vault_y).unwrap()), RESERVE);
+    assert_eq!(token_amount(result.get_account(&pool.user_lp(&user)).unwrap()), expected(true));
+}
+
+/// 存入后立刻按比例取出，拿回的总价值少于存入的数量（内部兑换收取了手续费）
+#[test]
+fn test_single_sided_deposit_pays_swap_fee() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(272);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, 0, AMOUNT, 0));
+
+    let deposited = mollusk.process_and_validate_instruction(
+        &pool.deposit_single_sided_ix(&user, false, AMOUNT, 1),
+        &accounts,
+        &[Check::success()],
+    );
+    let lp = token_amount(deposited.get_account(&pool.user_lp(&user)).unwrap());
+    assert_eq!(lp, expected(false));
+
+    let withdrawn = mollusk.process_and_validate_instruction(
+        &pool.withdraw_ix(&user, lp, 0, 0),
+        &deposited.resulting_accounts,
+        &[Check::success()],
+    );
+    let x = token_amount(withdrawn.get_account(&pool.user_x(&user)).unwrap());
+    let y = token_amount(withdrawn.get_account(&pool.user_y(&user)).unwrap());
+    assert!(x + y < AMOUNT, "x {x} y {y}");
+}
+
+#[test]
+fn test_single_sided_deposit_enforces_min_lp_out() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(273);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, AMOUNT, 0, 0));
+
+    mollusk.process_and_validate_instruction(
+        &pool.deposit_single_sided_ix(&user, true, AMOUNT, expected(true) + 1),
+        &accounts,
+        &[Check::err(amm_err(AmmError::LpSlippageExceeded))],
+    );
+}
+
+/// 内部兑换的价格影响超过上限时拒绝，与单边取款相同
+#[test]
+fn test_single_sided_deposit_rejects_high_price_impact() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(274);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, RESERVE, 0, 0));
+
+    mollusk.process_and_validate_instruction(
+        &pool.deposit_single_sided_ix(&user, true, RESERVE, 1),
+        &accounts,
+        &[Check::err(amm_err(AmmError::PriceImpactTooHigh))],
+    );
+}
+
+/// 空池子没有价格，单边存款不能作为首次存款
+#[test]
+fn test_single_sided_deposit_rejects_empty_pool() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(275);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, 0, 0, 0);
+    accounts.extend(pool.user_accounts(&user, AMOUNT, 0, 0));
+
+    mollusk.process_and_validate_instruction(
+        &pool.deposit_single_sided_ix(&user, true, AMOUNT, 1),
+        &accounts,
+        &[Check::err(amm_err(AmmError::InvalidAmmState))],
+    );
+}
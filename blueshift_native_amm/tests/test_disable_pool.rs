@@ -0,0 +1,130 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{
+        errors::AmmError,
+        state::{AmmState, Config},
+    },
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    solana_account::Account,
+    solana_sdk::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+const LP: u64 = 10_000;
+
+/// 池子 authority 为 `authority`，全部 LP 都在 user 手里
+fn setup(mollusk: &Mollusk, pool: &Pool, authority: &Pubkey, user: &Pubkey) -> Vec<(Pubkey, Account)> {
+    let mut accounts = pool.accounts(mollusk, FEE, RESERVE, RESERVE, LP);
+    set_account(&mut accounts, &pool.config, pool.config_account_with_authority(mollusk, FEE, authority));
+    pool.sync_reserves(&mut accounts);
+    accounts.push((*authority, create_system_account(LAMPORTS_PER_SOL)));
+    accounts.extend(pool.user_accounts(user, RESERVE, RESERVE, LP));
+    accounts
+}
+
+/// 还有 LP 未赎回时拒绝下线
+#[test]
+fn test_disable_pool_rejects_outstanding_lp() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(321);
+    let (authority, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = setup(&mollusk, &pool, &authority, &user);
+
+    mollusk.process_and_validate_instruction_chain(
+        &[
+            (&pool.disable_pool_ix(&authority), &[Check::err(amm_err(AmmError::PoolNotEmpty))]),
+            // 取走一部分后仍然不行
+            (&pool.withdraw_ix(&user, LP / 2, 1, 1), &[Check::success()]),
+            (&pool.disable_pool_ix(&authority), &[Check::err(amm_err(AmmError::PoolNotEmpty))]),
+        ],
+        &accounts,
+    );
+}
+
+/// WithdrawOnly -> LP 全部取出 -> Disabled，之后存款、swap、取款都被拒绝
+#[test]
+fn test_disable_pool_after_full_withdrawal() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(322);
+    let (authority, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = setup(&mollusk, &pool, &authority, &user);
+
+    let result = mollusk.process_and_validate_instruction_chain(
+        &[
+            (&pool.set_state_ix(&authority, AmmState::WithdrawOnly as u8), &[Check::success()]),
+            (&pool.withdraw_ix(&user, LP, 1, 1), &[Check::success()]),
+            (&pool.disable_pool_ix(&authority), &[Check::success()]),
+        ],
+        &accounts,
+    );
+    let accounts = result.resulting_accounts;
+    let config = &accounts.iter().find(|(k, _)| k == &pool.config).unwrap().1.data;
+    assert_eq!(unsafe { Config::from_bytes_unchecked(config) }.state(), AmmState::Disabled as u8);
+
+    for ix in [
+        pool.deposit_ix(&user, 1_000, RESERVE, RESERVE),
+        pool.swap_ix(&user, true, 1_000, 1),
+        pool.withdraw_ix(&user, 1, 0, 0),
+    ] {
+        mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::err(ProgramError::InvalidAccountData)]);
+    }
+}
+
+/// Disabled 是终态：SetState / SetConfig 都不能再把池子切回 Initialized 或 WithdrawOnly
+#[test]
+fn test_disabled_pool_cannot_be_reenabled() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(335);
+    let (authority, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = setup(&mollusk, &pool, &authority, &user);
+
+    let result = mollusk.process_and_validate_instruction_chain(
+        &[
+            (&pool.set_state_ix(&authority, AmmState::WithdrawOnly as u8), &[Check::success()]),
+            (&pool.withdraw_ix(&user, LP, 1, 1), &[Check::success()]),
+            (&pool.disable_pool_ix(&authority), &[Check::success()]),
+        ],
+        &accounts,
+    );
+    let accounts = result.resulting_accounts;
+
+    for state in [AmmState::Initialized, AmmState::WithdrawOnly] {
+        for ix in [
+            pool.set_state_ix(&authority, state as u8),
+            pool.set_config_ix(&authority, 2, &[state as u8]),
+        ] {
+            mollusk.process_and_validate_instruction(
+                &ix,
+                &accounts,
+                &[Check::err(amm_err(AmmError::InvalidStateTransition))],
+            );
+        }
+    }
+    // 重复下线同样被拒绝
+    mollusk.process_and_validate_instruction(
+        &pool.disable_pool_ix(&authority),
+        &accounts,
+        &[Check::err(amm_err(AmmError::InvalidStateTransition))],
+    );
+}
+
+/// 只有 owner 可以下线池子
+#[test]
+fn test_disable_pool_requires_owner() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(323);
+    let (authority, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut accounts = setup(&mollusk, &pool, &authority, &user);
+    set_account(&mut accounts, &pool.mint_lp, create_mint_account(&pool.config, 6, 0, &pool.token_program));
+
+    mollusk.process_and_validate_instruction(
+        &pool.disable_pool_ix(&user),
+        &accounts,
+        &[Check::err(amm_err(AmmError::InvalidAuthority))],
+    );
+}
@@ -0,0 +1,53 @@
+use blueshift_native_amm::{errors::AmmError, state::validate_distinct_keys};
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+
+const MINT_X: Pubkey = [1; 32];
+const MINT_Y: Pubkey = [2; 32];
+const USER_X: Pubkey = [3; 32];
+const USER_Y: Pubkey = [4; 32];
+const USER_LP: Pubkey = [5; 32];
+const VAULT_X: Pubkey = [6; 32];
+const VAULT_Y: Pubkey = [7; 32];
+const CONFIG: Pubkey = [8; 32];
+const MINT_LP: Pubkey = [9; 32];
+
+/// 与 deposit/withdraw 传入的账户顺序一致
+fn accounts() -> [Pubkey; 7] {
+    [USER_X, USER_Y, USER_LP, VAULT_X, VAULT_Y, CONFIG, MINT_LP]
+}
+
+fn check(mint_x: &Pubkey, mint_y: &Pubkey, keys: &[Pubkey]) -> Result<(), ProgramError> {
+    let refs: Vec<&Pubkey> = keys.iter().collect();
+    validate_distinct_keys(mint_x, mint_y, &refs)
+}
+
+#[test]
+fn test_distinct_accounts_pass() {
+    assert!(check(&MINT_X, &MINT_Y, &accounts()).is_ok());
+}
+
+#[test]
+fn test_rejects_same_mints() {
+    assert_eq!(
+        check(&MINT_X, &MINT_X, &accounts()),
+        Err(ProgramError::from(AmmError::DuplicateAccount))
+    );
+}
+
+#[test]
+fn test_rejects_every_collision_pair() {
+    let names = ["user_x", "user_y", "user_lp", "vault_x", "vault_y", "config", "mint_lp"];
+    for i in 0..names.len() {
+        for j in i + 1..names.len() {
+            let mut keys = accounts();
+            keys[j] = keys[i];
+            assert_eq!(
+                check(&MINT_X, &MINT_Y, &keys),
+                Err(ProgramError::from(AmmError::DuplicateAccount)),
+                "{} == {} was accepted",
+                names[i],
+                names[j],
+            );
+        }
+    }
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::errors::AmmError,
+    common::*,
+    mollusk_svm::result::Check,
+    solana_sdk::pubkey::Pubkey,
+};
+
+const FEE: u16 = 30;
+
+#[test]
+fn test_swap_rejects_user_x_ata_equal_to_vault_x() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(41);
+    let user = Pubkey::new_unique();
+
+    let mut accounts = pool.accounts(&mollusk, FEE, 1_000_000, 1_000_000, 1_000_000);
+    accounts.extend(pool.user_accounts(&user, 1_000_000, 1_000_000, 0));
+
+    let mut ix = pool.swap_ix(&user, true, 10_000, 1);
+    ix.accounts[1].pubkey = pool.vault_x;
+
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &accounts,
+        &[Check::err(amm_err(AmmError::DuplicateAccount))],
+    );
+
+    // 没有发生任何转账
+    assert_eq!(token_amount(result.get_account(&pool.vault_x).unwrap()), 1_000_000);
+    assert_eq!(token_amount(result.get_account(&pool.vault_y).unwrap()), 1_000_000);
+}
+
+#[test]
+fn test_deposit_rejects_user_y_ata_equal_to_vault_y() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(42);
+    let user = Pubkey::new_unique();
+
+    let mut accounts = pool.accounts(&mollusk, FEE, 1_000_000, 1_000_000, 1_000_000);
+    accounts.extend(pool.user_accounts(&user, 1_000_000, 1_000_000, 0));
+
+    let mut ix = pool.deposit_ix(&user, 1_000, 10_000, 10_000);
+    ix.accounts[5].pubkey = pool.vault_y;
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &accounts,
+        &[Check::err(amm_err(AmmError::DuplicateAccount))],
+    );
+}
+
+#[test]
+fn test_deposit_rejects_mint_lp_equal_to_config() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(43);
+    let user = Pubkey::new_unique();
+
+    let mut accounts = pool.accounts(&mollusk, FEE, 1_000_000, 1_000_000, 1_000_000);
+    accounts.extend(pool.user_accounts(&user, 1_000_000, 1_000_000, 0));
+
+    let mut ix = pool.deposit_ix(&user, 1_000, 10_000, 10_000);
+    ix.accounts[1].pubkey = pool.config;
+
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &accounts,
+        &[Check::err(amm_err(AmmError::DuplicateAccount))],
+    );
+
+    // 在任何 CPI 之前被拒绝
+    assert_eq!(token_amount(result.get_account(&pool.user_x(&user)).unwrap()), 1_000_000);
+    assert_eq!(token_amount(result.get_account(&pool.vault_x).unwrap()), 1_000_000);
+}
+
+#[test]
+fn test_withdraw_rejects_user_lp_ata_equal_to_vault_x() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(44);
+    let user = Pubkey::new_unique();
+
+    let mut accounts = pool.accounts(&mollusk, FEE, 1_000_000, 1_000_000, 1_000_000);
+    accounts.extend(pool.user_accounts(&user, 0, 0, 10_000));
+
+    let mut ix = pool.withdraw_ix(&user, 1_000, 1, 1);
+    ix.accounts[6].pubkey = pool.vault_x;
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &accounts,
+        &[Check::err(amm_err(AmmError::DuplicateAccount))],
+    );
+}
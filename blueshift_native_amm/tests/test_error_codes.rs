@@ -0,0 +1,61 @@
+use blueshift_native_amm::errors::{AmmError, ERROR_CODES};
+
+/// 穷尽匹配：新增变体而没有更新这里会编译失败，从而提醒同步 ERROR_CODES
+fn expected_index(e: AmmError) -> usize {
+    match e {
+        AmmError::InvalidAmmState => 0,
+        AmmError::InvalidAuthority => 1,
+        AmmError::InsufficientInitialLiquidity => 2,
+        AmmError::InvalidVault => 3,
+        AmmError::OrderExpired => 4,
+        AmmError::DuplicateAccount => 5,
+        AmmError::OutputCapExceeded => 6,
+        AmmError::LpSupplyChanged => 7,
+        AmmError::PoolAlreadyExists => 8,
+        AmmError::PriceImpactTooHigh => 9,
+        AmmError::SwapDirectionMismatch => 10,
+        AmmError::CurveOverflow => 11,
+        AmmError::DownstreamProgramNotAllowed => 12,
+        AmmError::LpSlippageExceeded => 13,
+        AmmError::UnknownAmmState => 14,
+        AmmError::SlippageExceeded => 15,
+        AmmError::ZeroAmount => 16,
+        AmmError::InvalidLpMint => 17,
+        AmmError::FlashSwapNotRepaid => 18,
+        AmmError::ReserveMismatch => 19,
+        AmmError::AccountFrozen => 20,
+        AmmError::RouteMismatch => 21,
+        AmmError::InitialPriceOutOfRange => 22,
+        AmmError::EmptyPool => 23,
+        AmmError::MintDecimalsMismatch => 24,
+        AmmError::PoolNotEmpty => 25,
+        AmmError::FeeTooHigh => 26,
+        AmmError::InvalidStateTransition => 27,
+        AmmError::Reentrancy => 28,
+        AmmError::WeightedPoolUnsupported => 29,
+        AmmError::LpNotSoulbound => 30,
+        AmmError::WsolAccountNotEmpty => 31,
+    }
+}
+
+#[test]
+fn test_error_table_covers_every_variant() {
+    for (i, (code, name)) in ERROR_CODES.iter().enumerate() {
+        let e = AmmError::from_code(*code).unwrap_or_else(|| panic!("code {code} has no variant"));
+        assert_eq!(expected_index(e), i);
+        assert_eq!(e as u32, *code);
+        assert_eq!(e.name(), *name);
+    }
+
+    // 表中的条目数与变体数一致（最后一个变体的下标 + 1）
+    assert_eq!(ERROR_CODES.len(), expected_index(AmmError::WsolAccountNotEmpty) + 1);
+    assert_eq!(AmmError::from_code(ERROR_CODES.len() as u32), None);
+}
+
+#[test]
+fn test_error_codes_are_stable() {
+    // 已发布的错误码不能改变，客户端依赖这些数值
+    assert_eq!(AmmError::InvalidAmmState as u32, 0);
+    assert_eq!(AmmError::DuplicateAccount as u32, 5);
+    assert_eq!(AmmError::OutputCapExceeded as u32, 6);
+}
@@ -0,0 +1,90 @@
+use {
+    blueshift_native_amm::events::{DepositEvent, FeeUpdatedEvent, SwapEvent, WithdrawEvent, EVENT_VERSION},
+    solana_sdk::{hash::hash, pubkey::Pubkey},
+};
+
+/// Anchor 的事件 discriminator：sha256("event:<名称>")[..8]
+fn anchor_discriminator(name: &str) -> [u8; 8] {
+    hash(format!("event:{name}").as_bytes()).to_bytes()[..8].try_into().unwrap()
+}
+
+#[test]
+fn test_event_discriminators_match_anchor() {
+    assert_eq!(DepositEvent::DISCRIMINATOR, anchor_discriminator("DepositEvent"));
+    assert_eq!(WithdrawEvent::DISCRIMINATOR, anchor_discriminator("WithdrawEvent"));
+    assert_eq!(SwapEvent::DISCRIMINATOR, anchor_discriminator("SwapEvent"));
+    assert_eq!(FeeUpdatedEvent::DISCRIMINATOR, anchor_discriminator("FeeUpdatedEvent"));
+}
+
+#[test]
+fn test_swap_event_decodes_as_anchor_event() {
+    let pool = Pubkey::new_unique().to_bytes();
+    let user = Pubkey::new_unique().to_bytes();
+    let mint_in = Pubkey::new_unique().to_bytes();
+    let mint_out = Pubkey::new_unique().to_bytes();
+    let data = SwapEvent {
+        pool: &pool,
+        user: &user,
+        mint_in: &mint_in,
+        mint_out: &mint_out,
+        is_x: true,
+        amount_in: 10_000,
+        amount_out: 9_871,
+    }
+    .to_bytes(1_700_000_000);
+
+    // 按 Anchor/Borsh 的布局逐个字段解析
+    let (discriminator, fields) = data.split_at(8);
+    assert_eq!(discriminator, anchor_discriminator("SwapEvent"));
+    assert_eq!(fields[0], EVENT_VERSION);
+    assert_eq!(&fields[1..33], pool);
+    assert_eq!(&fields[33..65], user);
+    assert_eq!(&fields[65..97], mint_in);
+    assert_eq!(&fields[97..129], mint_out);
+    assert_eq!(fields[129], 1);
+    assert_eq!(u64::from_le_bytes(fields[130..138].try_into().unwrap()), 10_000);
+    assert_eq!(u64::from_le_bytes(fields[138..146].try_into().unwrap()), 9_871);
+    assert_eq!(i64::from_le_bytes(fields[146..154].try_into().unwrap()), 1_700_000_000);
+    assert_eq!(fields.len(), 154);
+}
+
+#[test]
+fn test_deposit_and_withdraw_events_share_layout() {
+    let pool = Pubkey::new_unique().to_bytes();
+    let user = Pubkey::new_unique().to_bytes();
+    let mint_x = Pubkey::new_unique().to_bytes();
+    let mint_y = Pubkey::new_unique().to_bytes();
+    let deposit = DepositEvent { pool: &pool, user: &user, mint_x: &mint_x, mint_y: &mint_y, lp: 1, x: 2, y: 3 }.to_bytes(-4);
+    let withdraw = WithdrawEvent { pool: &pool, user: &user, mint_x: &mint_x, mint_y: &mint_y, lp: 1, x: 2, y: 3 }.to_bytes(-4);
+
+    // 除 discriminator 外字节完全相同
+    assert_eq!(deposit[8..], withdraw[8..]);
+
+    let fields = &deposit[8..];
+    assert_eq!(fields[0], EVENT_VERSION);
+    assert_eq!(&fields[1..33], pool);
+    assert_eq!(&fields[33..65], user);
+    assert_eq!(&fields[65..97], mint_x);
+    assert_eq!(&fields[97..129], mint_y);
+    assert_eq!(u64::from_le_bytes(fields[129..137].try_into().unwrap()), 1);
+    assert_eq!(u64::from_le_bytes(fields[137..145].try_into().unwrap()), 2);
+    assert_eq!(u64::from_le_bytes(fields[145..153].try_into().unwrap()), 3);
+    assert_eq!(i64::from_le_bytes(fields[153..161].try_into().unwrap()), -4);
+    assert_eq!(fields.len(), 161);
+}
+
+#[test]
+fn test_fee_updated_event_layout() {
+    let pool = Pubkey::new_unique().to_bytes();
+    let user = Pubkey::new_unique().to_bytes();
+    let data = FeeUpdatedEvent { pool: &pool, user: &user, old_fee: 30, new_fee: 100 }.to_bytes(7);
+
+    let fields = &data[8..];
+    assert_eq!(fields[0], EVENT_VERSION);
+    assert_eq!(&fields[1..33], pool);
+    assert_eq!(&fields[33..65], user);
+    assert_eq!(u16::from_le_bytes(fields[65..67].try_into().unwrap()), 30);
+    assert_eq!(u16::from_le_bytes(fields[67..69].try_into().unwrap()), 100);
+    assert_eq!(i64::from_le_bytes(fields[69..77].try_into().unwrap()), 7);
+    assert_eq!(data.len(), FeeUpdatedEvent::LEN);
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    solana_account::Account,
+    solana_sdk::{instruction::Instruction, program_error::ProgramError, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+
+/// 把 vault_x 标记为可执行账户后执行 `ix`，应在账户校验阶段直接返回 InvalidAccountData
+fn assert_executable_vault_rejected(mollusk: &Mollusk, pool: &Pool, user: &Pubkey, ix: Instruction) {
+    let mut accounts = pool.accounts(mollusk, FEE, 1_000_000, 1_000_000, 1_000_000);
+    accounts.extend(pool.user_accounts(user, 1_000_000, 1_000_000, 100_000));
+
+    let mut vault_x: Account = accounts.iter().find(|(k, _)| k == &pool.vault_x).unwrap().1.clone();
+    vault_x.executable = true;
+    set_account(&mut accounts, &pool.vault_x, vault_x);
+
+    mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::err(ProgramError::InvalidAccountData)]);
+}
+
+#[test]
+fn test_swap_rejects_executable_vault() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(161);
+    let user = Pubkey::new_unique();
+    assert_executable_vault_rejected(&mollusk, &pool, &user, pool.swap_ix(&user, true, 1_000, 1));
+}
+
+#[test]
+fn test_deposit_rejects_executable_vault() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(162);
+    let user = Pubkey::new_unique();
+    assert_executable_vault_rejected(&mollusk, &pool, &user, pool.deposit_ix(&user, 1_000, 10_000, 10_000));
+}
@@ -0,0 +1,126 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{errors::AmmError, instructions::helpers::apply_exit_fee},
+    common::*,
+    mollusk_svm::result::Check,
+    solana_account::Account,
+    solana_sdk::{instruction::AccountMeta, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+const EXIT_FEE_BPS: u16 = 200;
+const WINDOW_SECS: u64 = 3_600;
+const DEPOSIT_TS: i64 = 1_700_000_000;
+
+struct RoundTrip {
+    /// 用户取回的 (x, y)
+    withdrawn: (u64, u64),
+    /// 取款后金库中剩余的 (x, y)，全部归原有的 LP
+    vaults: (u64, u64),
+}
+
+/// 存入与池子等量的流动性，在存款后 `elapsed` 秒全部取出
+fn round_trip(seed: u64, exit_fee_bps: u16, elapsed: i64) -> RoundTrip {
+    let mut mollusk = setup_mollusk();
+    mollusk.sysvars.clock.unix_timestamp = DEPOSIT_TS;
+
+    let pool = Pool::new_with_token_program(seed, spl_token_2022::id());
+    let admin = Pubkey::new_unique();
+    let user = Pubkey::new_unique();
+
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    pool.make_lp_soulbound(&mut accounts);
+    set_account(&mut accounts, &pool.config, pool.config_account_with_authority(&mollusk, FEE, &admin));
+    pool.sync_reserves(&mut accounts);
+    accounts.push((admin, create_system_account(LAMPORTS_PER_SOL)));
+    accounts.push((pool.position(&user), Account::default()));
+    accounts.extend(pool.user_accounts(&user, RESERVE, RESERVE, 0));
+
+    let mut exit_fee = exit_fee_bps.to_le_bytes().to_vec();
+    exit_fee.extend_from_slice(&WINDOW_SECS.to_le_bytes());
+    let mut deposit = pool.deposit_ix(&user, RESERVE, RESERVE, RESERVE);
+    deposit.accounts.push(AccountMeta::new(pool.position(&user), false));
+    let mut withdraw = pool.withdraw_ix(&user, RESERVE, 0, 0);
+    withdraw.accounts.push(AccountMeta::new(pool.position(&user), false));
+
+    let result = mollusk.process_and_validate_instruction_chain(
+        &[
+            (&pool.set_config_ix(&admin, 6, &exit_fee), &[Check::success()]),
+            (&deposit, &[Check::success()]),
+        ],
+        &accounts,
+    );
+
+    mollusk.sysvars.clock.unix_timestamp = DEPOSIT_TS + elapsed;
+    let result = mollusk.process_and_validate_instruction(&withdraw, &result.resulting_accounts, &[Check::success()]);
+    let balance = |key: &Pubkey| token_amount(result.get_account(key).unwrap());
+
+    RoundTrip {
+        withdrawn: (balance(&pool.user_x(&user)), balance(&pool.user_y(&user))),
+        vaults: (balance(&pool.vault_x), balance(&pool.vault_y)),
+    }
+}
+
+/// 存款后立即取款，按最高退出费扣除
+#[test]
+fn test_immediate_withdraw_pays_max_exit_fee() {
+    let free = round_trip(191, 0, 0);
+    let charged = round_trip(192, EXIT_FEE_BPS, 0);
+
+    assert_eq!(charged.withdrawn.0, apply_exit_fee(free.withdrawn.0, EXIT_FEE_BPS).unwrap());
+    assert_eq!(charged.withdrawn.1, apply_exit_fee(free.withdrawn.1, EXIT_FEE_BPS).unwrap());
+}
+
+/// 窗口结束后取款不再收取退出费
+#[test]
+fn test_withdraw_after_window_pays_no_exit_fee() {
+    let free = round_trip(193, 0, WINDOW_SECS as i64);
+    let charged = round_trip(194, EXIT_FEE_BPS, WINDOW_SECS as i64);
+
+    assert_eq!(charged.withdrawn, free.withdrawn);
+}
+
+/// 窗口中途取款，退出费衰减到一半左右
+#[test]
+fn test_exit_fee_decays_within_window() {
+    let free = round_trip(195, 0, 0);
+    let half = round_trip(196, EXIT_FEE_BPS, WINDOW_SECS as i64 / 2);
+
+    assert_eq!(half.withdrawn.0, apply_exit_fee(free.withdrawn.0, EXIT_FEE_BPS / 2).unwrap());
+}
+
+/// 退出费留在金库里，归剩余的 LP
+#[test]
+fn test_exit_fee_stays_with_remaining_lps() {
+    let free = round_trip(197, 0, 0);
+    let charged = round_trip(198, EXIT_FEE_BPS, 0);
+
+    assert_eq!(charged.vaults.0 - free.vaults.0, free.withdrawn.0 - charged.withdrawn.0);
+    assert_eq!(charged.vaults.1 - free.vaults.1, free.withdrawn.1 - charged.withdrawn.1);
+    assert!(charged.vaults.0 > free.vaults.0);
+}
+
+/// LP 可以转让的池子不能开启退出费：转到新钱包后取款就不再受限
+#[test]
+fn test_exit_fee_requires_soulbound_lp() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(344);
+    let admin = Pubkey::new_unique();
+
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    set_account(&mut accounts, &pool.config, pool.config_account_with_authority(&mollusk, FEE, &admin));
+    pool.sync_reserves(&mut accounts);
+    accounts.push((admin, create_system_account(LAMPORTS_PER_SOL)));
+
+    let mut exit_fee = EXIT_FEE_BPS.to_le_bytes().to_vec();
+    exit_fee.extend_from_slice(&WINDOW_SECS.to_le_bytes());
+    mollusk.process_and_validate_instruction(
+        &pool.set_config_ix(&admin, 6, &exit_fee),
+        &accounts,
+        &[Check::err(amm_err(AmmError::LpNotSoulbound))],
+    );
+}
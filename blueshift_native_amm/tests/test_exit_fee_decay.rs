@@ -0,0 +1,32 @@
+use blueshift_native_amm::instructions::helpers::{apply_exit_fee, decayed_exit_fee_bps};
+
+#[test]
+fn test_exit_fee_decays_linearly_to_zero() {
+    assert_eq!(decayed_exit_fee_bps(200, 100, 0), 200);
+    assert_eq!(decayed_exit_fee_bps(200, 100, 50), 100);
+    assert_eq!(decayed_exit_fee_bps(200, 100, 99), 2);
+    assert_eq!(decayed_exit_fee_bps(200, 100, 100), 0);
+    assert_eq!(decayed_exit_fee_bps(200, 100, 1_000), 0);
+}
+
+#[test]
+fn test_exit_fee_rounds_up_inside_window() {
+    // 窗口最后一秒仍有退出费
+    assert_eq!(decayed_exit_fee_bps(1, 3_600, 3_599), 1);
+}
+
+#[test]
+fn test_exit_fee_disabled_or_clock_skew() {
+    assert_eq!(decayed_exit_fee_bps(200, 0, 0), 0);
+    assert_eq!(decayed_exit_fee_bps(0, 100, 0), 0);
+    assert_eq!(decayed_exit_fee_bps(200, 100, -5), 200);
+}
+
+#[test]
+fn test_apply_exit_fee() {
+    assert_eq!(apply_exit_fee(10_000, 0).unwrap(), 10_000);
+    assert_eq!(apply_exit_fee(10_000, 200).unwrap(), 9_800);
+    // 向下取整，零头留给池子
+    assert_eq!(apply_exit_fee(99, 100).unwrap(), 98);
+    assert_eq!(apply_exit_fee(10_000, 10_000).unwrap(), 0);
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::errors::AmmError,
+    common::*,
+    mollusk_svm::{result::{Check, ProgramResult}, Mollusk},
+    solana_account::Account,
+    solana_sdk::{instruction::Instruction, program_error::ProgramError, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+// 报价时看到的 LP supply
+const QUOTED_SUPPLY: u64 = RESERVE;
+// 并发的存款让 supply 增加 10%
+const CONCURRENT_LP: u64 = 100_000;
+
+/// 在 deposit/withdraw 指令数据末尾附加 expected_lp_supply + tolerance_bps
+fn with_expected_supply(mut ix: Instruction, supply: u64, tolerance_bps: u16) -> Instruction {
+    ix.data.extend_from_slice(&supply.to_le_bytes());
+    ix.data.extend_from_slice(&tolerance_bps.to_le_bytes());
+    ix
+}
+
+fn pool_accounts(mollusk: &Mollusk, pool: &Pool, alice: &Pubkey, bob: &Pubkey) -> Vec<(Pubkey, Account)> {
+    let mut accounts = pool.accounts(mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(alice, RESERVE, RESERVE, 50_000));
+    accounts.extend(pool.user_accounts(bob, RESERVE, RESERVE, 0));
+    accounts
+}
+
+#[test]
+fn test_deposit_rejects_supply_change_beyond_tolerance() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(82);
+    let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = pool_accounts(&mollusk, &pool, &alice, &bob);
+
+    // 容忍 0.5%，实际变化 10%
+    mollusk.process_and_validate_instruction_chain(
+        &[
+            (&pool.deposit_ix(&bob, CONCURRENT_LP, RESERVE, RESERVE), &[Check::success()]),
+            (
+                &with_expected_supply(pool.deposit_ix(&alice, 10_000, RESERVE, RESERVE), QUOTED_SUPPLY, 50),
+                &[Check::err(amm_err(AmmError::LpSupplyChanged))],
+            ),
+        ],
+        &accounts,
+    );
+}
+
+#[test]
+fn test_deposit_allows_supply_change_within_tolerance() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(83);
+    let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = pool_accounts(&mollusk, &pool, &alice, &bob);
+
+    // 容忍 10%，恰好等于实际变化
+    let result = mollusk.process_instruction_chain(
+        &[
+            pool.deposit_ix(&bob, CONCURRENT_LP, RESERVE, RESERVE),
+            with_expected_supply(pool.deposit_ix(&alice, 10_000, RESERVE, RESERVE), QUOTED_SUPPLY, 1_000),
+        ],
+        &accounts,
+    );
+    assert_eq!(result.program_result, ProgramResult::Success);
+    assert_eq!(token_amount(result.get_account(&pool.user_lp(&alice)).unwrap()), 60_000);
+}
+
+#[test]
+fn test_withdraw_rejects_supply_change_beyond_tolerance() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(84);
+    let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = pool_accounts(&mollusk, &pool, &alice, &bob);
+
+    mollusk.process_and_validate_instruction_chain(
+        &[
+            (&pool.deposit_ix(&bob, CONCURRENT_LP, RESERVE, RESERVE), &[Check::success()]),
+            (
+                &with_expected_supply(pool.withdraw_ix(&alice, 10_000, 1, 1), QUOTED_SUPPLY, 50),
+                &[Check::err(amm_err(AmmError::LpSupplyChanged))],
+            ),
+        ],
+        &accounts,
+    );
+}
+
+#[test]
+fn test_withdraw_with_unchanged_supply_succeeds() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(85);
+    let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = pool_accounts(&mollusk, &pool, &alice, &bob);
+
+    // 容忍度为 0 时要求 supply 完全一致
+    mollusk.process_and_validate_instruction(
+        &with_expected_supply(pool.withdraw_ix(&alice, 10_000, 1, 1), QUOTED_SUPPLY, 0),
+        &accounts,
+        &[Check::success()],
+    );
+}
+
+#[test]
+fn test_rejects_truncated_expected_supply() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(86);
+    let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = pool_accounts(&mollusk, &pool, &alice, &bob);
+
+    let mut ix = pool.deposit_ix(&alice, 10_000, RESERVE, RESERVE);
+    ix.data.extend_from_slice(&QUOTED_SUPPLY.to_le_bytes());
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &accounts,
+        &[Check::err(ProgramError::InvalidInstructionData)],
+    );
+}
@@ -0,0 +1,112 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{
+        errors::AmmError,
+        state::{GlobalConfig, DEFAULT_GRACE_SECS, MAX_GRACE_SECS},
+    },
+    common::*,
+    mollusk_svm::result::Check,
+    solana_account::Account,
+    solana_sdk::{instruction::Instruction, program_error::ProgramError, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+const NOW: i64 = 1_700_000_000;
+
+fn deposit_with_expiration(expiration: i64, check: Check) {
+    let mut mollusk = setup_mollusk();
+    mollusk.sysvars.clock.unix_timestamp = NOW;
+
+    let pool = Pool::new(21);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, 1_000_000, 1_000_000, 1_000_000);
+    accounts.extend(pool.user_accounts(&user, 1_000_000, 1_000_000, 0));
+
+    let mut ix = pool.deposit_ix(&user, 1_000, 10_000, 10_000);
+    let len = ix.data.len();
+    ix.data[len - 8..].copy_from_slice(&expiration.to_le_bytes());
+
+    mollusk.process_and_validate_instruction(&ix, &accounts, &[check]);
+}
+
+#[test]
+fn test_deadline_within_grace_window_passes() {
+    deposit_with_expiration(NOW - DEFAULT_GRACE_SECS as i64, Check::success());
+}
+
+#[test]
+fn test_deadline_exactly_now_passes() {
+    deposit_with_expiration(NOW, Check::success());
+}
+
+#[test]
+fn test_deadline_past_grace_window_is_rejected() {
+    deposit_with_expiration(
+        NOW - DEFAULT_GRACE_SECS as i64 - 1,
+        Check::err(amm_err(AmmError::OrderExpired)),
+    );
+}
+
+#[test]
+fn test_superadmin_sets_grace_secs_within_bound() {
+    let mollusk = setup_mollusk();
+    let superadmin = Pubkey::new_unique();
+    let (global_config, bump) = global_config_pda();
+
+    let accounts = vec![
+        (superadmin, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (global_config, create_global_config_account(&mollusk, &superadmin, FEE, bump)),
+        (system_program::id(), create_system_program_account()),
+    ];
+    let result = mollusk.process_and_validate_instruction(
+        &set_global_config_ix(&superadmin, 2, &MAX_GRACE_SECS.to_le_bytes()),
+        &accounts,
+        &[Check::success()],
+    );
+    let global_account = result.get_account(&global_config).unwrap();
+    let global = unsafe { GlobalConfig::from_bytes_unchecked(&global_account.data) };
+    assert_eq!(global.grace_secs(), MAX_GRACE_SECS);
+
+    mollusk.process_and_validate_instruction(
+        &set_global_config_ix(&superadmin, 2, &(MAX_GRACE_SECS + 1).to_le_bytes()),
+        &accounts,
+        &[Check::err(ProgramError::InvalidArgument)],
+    );
+}
+
+/// InitializeGlobal 还没有执行：GlobalConfig PDA 是空账户，存取款和兑换使用 DEFAULT_GRACE_SECS，照常可用
+#[test]
+fn test_pool_is_usable_before_global_config_exists() {
+    let mut mollusk = setup_mollusk();
+    mollusk.sysvars.clock.unix_timestamp = NOW;
+
+    let pool = Pool::new(340);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, 1_000_000, 1_000_000, 1_000_000);
+    accounts.extend(pool.user_accounts(&user, 1_000_000, 1_000_000, 10_000));
+    set_account(&mut accounts, &global_config_pda().0, Account::default());
+
+    let expire = |mut ix: Instruction, expiration: i64| {
+        let len = ix.data.len();
+        ix.data[len - 8..].copy_from_slice(&expiration.to_le_bytes());
+        ix
+    };
+    let late = NOW - DEFAULT_GRACE_SECS as i64 - 1;
+
+    mollusk.process_and_validate_instruction(
+        &expire(pool.withdraw_ix(&user, 1_000, 1, 1), late),
+        &accounts,
+        &[Check::err(amm_err(AmmError::OrderExpired))],
+    );
+    mollusk.process_and_validate_instruction_chain(
+        &[
+            (&pool.withdraw_ix(&user, 1_000, 1, 1), &[Check::success()]),
+            (&pool.deposit_ix(&user, 1_000, 10_000, 10_000), &[Check::success()]),
+            (&pool.swap_ix(&user, true, 1_000, 1), &[Check::success()]),
+        ],
+        &accounts,
+    );
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{errors::AmmError, state::MINIMUM_LIQUIDITY},
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    solana_account::Account,
+    solana_sdk::pubkey::Pubkey,
+};
+
+const FEE: u16 = 30;
+const AMOUNT: u64 = 1_000_000;
+/// 1.0 的 Q64.64 表示
+const ONE: u128 = 1 << 64;
+
+/// 空池子 + 持有 AMOUNT 个 x/y 的用户
+fn empty_pool(mollusk: &Mollusk, pool: &Pool, user: &Pubkey) -> Vec<(Pubkey, Account)> {
+    let mut accounts = pool.accounts(mollusk, FEE, 0, 0, 0);
+    accounts.extend(pool.user_accounts(user, AMOUNT, AMOUNT, 0));
+    accounts
+}
+
+/// 价格 x/y 在 [0.9, 1.1] 内：按 sqrt(x*y) 铸造 LP，锁定 MINIMUM_LIQUIDITY
+#[test]
+fn test_balanced_first_deposit_within_band_succeeds() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(308);
+    let user = Pubkey::new_unique();
+    let accounts = empty_pool(&mollusk, &pool, &user);
+
+    let mut ix = pool.deposit_ix(&user, 1, AMOUNT, AMOUNT);
+    ix.data.extend(price_band(ONE / 10 * 9, ONE / 10 * 11));
+    let result = mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::success()]);
+
+    // sqrt(AMOUNT * AMOUNT) = AMOUNT
+    assert_eq!(token_amount(result.get_account(&pool.user_lp(&user)).unwrap()), AMOUNT - MINIMUM_LIQUIDITY);
+    assert_eq!(token_amount(result.get_account(&pool.vault_x).unwrap()), AMOUNT);
+    assert_eq!(token_amount(result.get_account(&pool.vault_y).unwrap()), AMOUNT);
+}
+
+/// x/y = 100 远超区间上限，手误或恶意的初始价格被拒绝
+#[test]
+fn test_skewed_first_deposit_outside_band_is_rejected() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(309);
+    let user = Pubkey::new_unique();
+    let accounts = empty_pool(&mollusk, &pool, &user);
+
+    let mut ix = pool.deposit_ix(&user, 1, AMOUNT, AMOUNT / 100);
+    ix.data.extend(price_band(ONE / 10 * 9, ONE / 10 * 11));
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &accounts,
+        &[Check::err(amm_err(AmmError::InitialPriceOutOfRange))],
+    );
+}
+
+/// 首次存款必须给出价格区间
+#[test]
+fn test_first_deposit_without_band_is_rejected() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(310);
+    let user = Pubkey::new_unique();
+    let accounts = empty_pool(&mollusk, &pool, &user);
+
+    mollusk.process_and_validate_instruction(
+        &pool.deposit_ix(&user, 1, AMOUNT, AMOUNT),
+        &accounts,
+        &[Check::err(amm_err(AmmError::InitialPriceOutOfRange))],
+    );
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::errors::AmmError,
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    solana_account::Account,
+    solana_sdk::{instruction::{AccountMeta, Instruction}, program_error::ProgramError, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+const AMOUNT: u64 = 100_000;
+/// 100_000 * 30 / 10_000
+const FLASH_FEE: u64 = 300;
+/// 用户事先持有的 X，用来支付手续费
+const USER_X: u64 = 1_000;
+
+fn setup(seed: u64, user: &Pubkey) -> (Mollusk, Pool, Vec<(Pubkey, Account)>) {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(seed);
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(user, USER_X, 0, 0));
+    (mollusk, pool, accounts)
+}
+
+/// 借出 X，回调用 SPL Token 的 Transfer 把 `repay` 从用户的 X ATA 还回金库（模拟套利后还款）
+fn flash_swap_repaying(pool: &Pool, user: &Pubkey, repay: u64) -> Instruction {
+    let mut transfer = vec![3u8];
+    transfer.extend_from_slice(&repay.to_le_bytes());
+    pool.flash_swap_ix(
+        user,
+        true,
+        AMOUNT,
+        &pool.token_program,
+        &[
+            AccountMeta::new(pool.user_x(user), false),
+            AccountMeta::new(pool.vault_x, false),
+            AccountMeta::new_readonly(*user, true),
+        ],
+        &transfer,
+    )
+}
+
+#[test]
+fn test_flash_swap_repaid_with_fee_succeeds() {
+    let user = Pubkey::new_unique();
+    let (mollusk, pool, accounts) = setup(281, &user);
+
+    let result = mollusk.process_and_validate_instruction(
+        &flash_swap_repaying(&pool, &user, AMOUNT + FLASH_FEE),
+        &accounts,
+        &[Check::success()],
+    );
+
+    // 手续费留在金库中归 LP
+    assert_eq!(token_amount(result.get_account(&pool.vault_x).unwrap()), RESERVE + FLASH_FEE);
+    assert_eq!(token_amount(result.get_account(&pool.vault_y).unwrap()), RESERVE);
+    assert_eq!(token_amount(result.get_account(&pool.user_x(&user)).unwrap()), USER_X - FLASH_FEE);
+}
+
+#[test]
+fn test_flash_swap_without_fee_is_rejected() {
+    let user = Pubkey::new_unique();
+    let (mollusk, pool, accounts) = setup(282, &user);
+
+    mollusk.process_and_validate_instruction(
+        &flash_swap_repaying(&pool, &user, AMOUNT + FLASH_FEE - 1),
+        &accounts,
+        &[Check::err(amm_err(AmmError::FlashSwapNotRepaid))],
+    );
+}
+
+#[test]
+fn test_flash_swap_cannot_call_back_into_amm() {
+    let user = Pubkey::new_unique();
+    let (mollusk, pool, accounts) = setup(283, &user);
+
+    // 借出期间以被掏空的储备在本池子里 swap
+    let inner = pool.swap_ix(&user, false, 1, 1);
+    mollusk.process_and_validate_instruction(
+        &pool.flash_swap_ix(&user, true, AMOUNT, &program_id(), &inner.accounts, &inner.data),
+        &accounts,
+        &[Check::err(amm_err(AmmError::DownstreamProgramNotAllowed))],
+    );
+}
+
+#[test]
+fn test_flash_swap_rejects_amount_above_reserve() {
+    let user = Pubkey::new_unique();
+    let (mollusk, pool, accounts) = setup(284, &user);
+
+    let mut ix = flash_swap_repaying(&pool, &user, RESERVE + 1);
+    ix.data[2..10].copy_from_slice(&(RESERVE + 1).to_le_bytes());
+    mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::err(ProgramError::InsufficientFunds)]);
+}
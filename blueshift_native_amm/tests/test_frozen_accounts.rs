@@ -0,0 +1,55 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::errors::AmmError,
+    common::*,
+    mollusk_svm::result::Check,
+    solana_account::Account,
+    solana_sdk::pubkey::Pubkey,
+    spl_token::state::AccountState,
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+
+/// Token Account 布局中 state 字段的偏移（mint 32 + owner 32 + amount 8 + delegate 36）
+const STATE_OFFSET: usize = 108;
+
+fn freeze(accounts: &mut [(Pubkey, Account)], key: &Pubkey) {
+    accounts.iter_mut().find(|(k, _)| k == key).unwrap().1.data[STATE_OFFSET] = AccountState::Frozen as u8;
+}
+
+#[test]
+fn test_swap_rejects_frozen_user_ata() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(298);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, 10_000, 0, 0));
+    // 输出侧被冻结：没有这个检查时会在最后一笔转账 CPI 中失败
+    freeze(&mut accounts, &pool.user_y(&user));
+
+    mollusk.process_and_validate_instruction(
+        &pool.swap_ix(&user, true, 10_000, 1),
+        &accounts,
+        &[Check::err(amm_err(AmmError::AccountFrozen))],
+    );
+}
+
+#[test]
+fn test_deposit_rejects_frozen_user_ata() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(299);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, RESERVE, RESERVE, 0));
+    freeze(&mut accounts, &pool.user_x(&user));
+
+    mollusk.process_and_validate_instruction(
+        &pool.deposit_ix(&user, 10_000, RESERVE, RESERVE),
+        &accounts,
+        &[Check::err(amm_err(AmmError::AccountFrozen))],
+    );
+}
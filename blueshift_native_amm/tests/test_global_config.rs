@@ -0,0 +1,236 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{
+        errors::AmmError,
+        state::{Config, GlobalConfig, USE_GLOBAL_DEFAULT_FEE},
+    },
+    common::*,
+    mollusk_svm::result::Check,
+    solana_account::Account,
+    solana_sdk::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+#[test]
+fn test_superadmin_initializes_and_updates_global_config() {
+    let mollusk = setup_mollusk();
+    let superadmin = Pubkey::new_unique();
+    let (global_config, bump) = global_config_pda();
+
+    // Initialize
+    let accounts = vec![
+        (superadmin, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (global_config, Account::default()),
+        (system_program::id(), create_system_program_account()),
+        (program_data_pda(), create_program_data_account(&mollusk, Some(&superadmin))),
+    ];
+    let result = mollusk.process_and_validate_instruction(
+        &initialize_global_ix(&superadmin, 30, bump),
+        &accounts,
+        &[Check::success()],
+    );
+
+    let global_account = result.get_account(&global_config).unwrap();
+    let global = unsafe { GlobalConfig::from_bytes_unchecked(&global_account.data) };
+    assert_eq!(global.superadmin(), &superadmin.to_bytes());
+    assert_eq!(global.default_fee(), 30);
+
+    // Update the default fee
+    let accounts = vec![
+        (superadmin, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (global_config, global_account.clone()),
+        (system_program::id(), create_system_program_account()),
+    ];
+    let result = mollusk.process_and_validate_instruction(
+        &set_global_config_ix(&superadmin, 0, &50u16.to_le_bytes()),
+        &accounts,
+        &[Check::success()],
+    );
+
+    let global_account = result.get_account(&global_config).unwrap();
+    let global = unsafe { GlobalConfig::from_bytes_unchecked(&global_account.data) };
+    assert_eq!(global.default_fee(), 50);
+}
+
+#[test]
+fn test_global_config_cannot_be_initialized_twice() {
+    let mollusk = setup_mollusk();
+    let superadmin = Pubkey::new_unique();
+    let (global_config, bump) = global_config_pda();
+
+    let accounts = vec![
+        (superadmin, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (global_config, create_global_config_account(&mollusk, &superadmin, 30, bump)),
+        (system_program::id(), create_system_program_account()),
+        (program_data_pda(), create_program_data_account(&mollusk, Some(&superadmin))),
+    ];
+    mollusk.process_and_validate_instruction(
+        &initialize_global_ix(&superadmin, 30, bump),
+        &accounts,
+        &[Check::err(ProgramError::AccountAlreadyInitialized)],
+    );
+}
+
+/// 部署后、初始化前的窗口内，只有 upgrade authority 可以成为 superadmin
+#[test]
+fn test_only_upgrade_authority_can_initialize_global_config() {
+    let mollusk = setup_mollusk();
+    let upgrade_authority = Pubkey::new_unique();
+    let attacker = Pubkey::new_unique();
+    let (global_config, bump) = global_config_pda();
+
+    let accounts = |program_data: Account| {
+        vec![
+            (attacker, create_system_account(10 * LAMPORTS_PER_SOL)),
+            (global_config, Account::default()),
+            (system_program::id(), create_system_program_account()),
+            (program_data_pda(), program_data),
+        ]
+    };
+
+    // 签名者不是 upgrade authority
+    mollusk.process_and_validate_instruction(
+        &initialize_global_ix(&attacker, 30, bump),
+        &accounts(create_program_data_account(&mollusk, Some(&upgrade_authority))),
+        &[Check::err(amm_err(AmmError::InvalidAuthority))],
+    );
+
+    // 程序已不可升级：没有人可以初始化
+    mollusk.process_and_validate_instruction(
+        &initialize_global_ix(&attacker, 30, bump),
+        &accounts(create_program_data_account(&mollusk, None)),
+        &[Check::err(amm_err(AmmError::InvalidAuthority))],
+    );
+
+    // 伪造的 ProgramData：布局正确但不归 BPF Upgradeable Loader 所有
+    let mut forged = create_program_data_account(&mollusk, Some(&attacker));
+    forged.owner = system_program::id();
+    mollusk.process_and_validate_instruction(
+        &initialize_global_ix(&attacker, 30, bump),
+        &accounts(forged),
+        &[Check::err(ProgramError::InvalidAccountOwner)],
+    );
+
+    // ProgramData 不是本程序的 PDA
+    let mut ix = initialize_global_ix(&attacker, 30, bump);
+    let fake = Pubkey::new_unique();
+    ix.accounts[3].pubkey = fake;
+    let mut fake_accounts = accounts(Account::default());
+    fake_accounts[3] = (fake, create_program_data_account(&mollusk, Some(&attacker)));
+    mollusk.process_and_validate_instruction(&ix, &fake_accounts, &[Check::err(ProgramError::InvalidSeeds)]);
+}
+
+#[test]
+fn test_non_superadmin_is_rejected() {
+    let mollusk = setup_mollusk();
+    let superadmin = Pubkey::new_unique();
+    let attacker = Pubkey::new_unique();
+    let (global_config, bump) = global_config_pda();
+
+    let accounts = vec![
+        (attacker, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (global_config, create_global_config_account(&mollusk, &superadmin, 30, bump)),
+        (system_program::id(), create_system_program_account()),
+    ];
+    mollusk.process_and_validate_instruction(
+        &set_global_config_ix(&attacker, 0, &0u16.to_le_bytes()),
+        &accounts,
+        &[Check::err(amm_err(AmmError::InvalidAuthority))],
+    );
+}
+
+#[test]
+fn test_pool_picks_up_global_default_fee() {
+    let mollusk = setup_mollusk();
+    let (token_program, token_program_account) = mollusk_svm_programs_token::token::keyed_account();
+
+    let superadmin = Pubkey::new_unique();
+    let initializer = Pubkey::new_unique();
+    let mint_x = Pubkey::new_unique();
+    let mint_y = Pubkey::new_unique();
+    let seed = 1u64;
+
+    let (global_config, global_bump) = global_config_pda();
+    let (config, _) = config_pda(seed, &mint_x, &mint_y);
+    let (mint_lp, _) = mint_lp_pda(&config);
+
+    let accounts = vec![
+        (initializer, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (mint_lp, Account::default()),
+        (config, Account::default()),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (global_config, create_global_config_account(&mollusk, &superadmin, 25, global_bump)),
+    ];
+    let result = mollusk.process_and_validate_instruction(
+        &initialize_ix(
+            &initializer,
+            seed,
+            USE_GLOBAL_DEFAULT_FEE,
+            &mint_x,
+            &mint_y,
+            None,
+            &token_program,
+        ),
+        &accounts,
+        &[Check::success()],
+    );
+
+    let config_account = result.get_account(&config).unwrap();
+    let config_data = unsafe { Config::from_bytes_unchecked(&config_account.data) };
+    assert_eq!(config_data.fee(), 25);
+}
+
+/// GlobalConfig PDA 总是必须传入；InitializeGlobal 之前它是空账户，只能给出具体费率
+#[test]
+fn test_global_config_pda_is_required_even_before_initialize_global() {
+    let mollusk = setup_mollusk();
+    let (token_program, token_program_account) = mollusk_svm_programs_token::token::keyed_account();
+
+    let initializer = Pubkey::new_unique();
+    let mint_x = Pubkey::new_unique();
+    let mint_y = Pubkey::new_unique();
+    let seed = 2u64;
+
+    let (config, _) = config_pda(seed, &mint_x, &mint_y);
+    let (mint_lp, _) = mint_lp_pda(&config);
+    let (global_config, _) = global_config_pda();
+
+    // InitializeGlobal 还没有执行：GlobalConfig PDA 是空账户
+    let accounts = vec![
+        (initializer, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (mint_lp, Account::default()),
+        (config, Account::default()),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (global_config, Account::default()),
+    ];
+    let ix = |fee| initialize_ix(&initializer, seed, fee, &mint_x, &mint_y, None, &token_program);
+
+    // 省略 GlobalConfig 账户一律拒绝，即使给出了具体费率
+    let mut without_global = ix(30);
+    without_global.accounts.pop();
+    mollusk.process_and_validate_instruction(
+        &without_global,
+        &accounts,
+        &[Check::err(ProgramError::NotEnoughAccountKeys)],
+    );
+
+    // 其它账户不能冒充尚未创建的 GlobalConfig PDA
+    let mut spoofed = ix(30);
+    spoofed.accounts.last_mut().unwrap().pubkey = initializer;
+    mollusk.process_and_validate_instruction(&spoofed, &accounts, &[Check::err(ProgramError::InvalidSeeds)]);
+
+    // 默认费率需要已经创建的 GlobalConfig
+    mollusk.process_and_validate_instruction(
+        &ix(USE_GLOBAL_DEFAULT_FEE),
+        &accounts,
+        &[Check::err(ProgramError::UninitializedAccount)],
+    );
+
+    let result = mollusk.process_and_validate_instruction(&ix(30), &accounts, &[Check::success()]);
+    let config_account = result.get_account(&config).unwrap();
+    assert_eq!(unsafe { Config::from_bytes_unchecked(&config_account.data) }.fee(), 30);
+}
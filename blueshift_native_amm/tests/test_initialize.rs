@@ -0,0 +1,168 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::state::{Config, LP_DECIMALS},
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    mollusk_svm_programs_token::{token, token2022},
+    solana_account::Account,
+    solana_sdk::{program_error::ProgramError, program_option::COption, program_pack::Pack, pubkey::Pubkey},
+    spl_token::state::Mint,
+};
+
+const SEED: u64 = 13;
+const FEE: u16 = 30;
+
+/// 初始化所需的账户，`mint_lp_account` 为 LP mint 地址上已有的账户
+fn initialize_accounts(
+    mollusk: &Mollusk,
+    initializer: &Pubkey,
+    mint_x: &Pubkey,
+    mint_y: &Pubkey,
+    mint_lp_account: Account,
+    (token_program, token_program_account): (Pubkey, Account),
+) -> Vec<(Pubkey, Account)> {
+    let (global_config, global_bump) = global_config_pda();
+    let (config, _) = config_pda(SEED, mint_x, mint_y);
+    let (mint_lp, _) = mint_lp_pda(&config);
+
+    vec![
+        (*initializer, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (mint_lp, mint_lp_account),
+        (config, Account::default()),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (global_config, create_global_config_account(mollusk, &Pubkey::new_unique(), FEE, global_bump)),
+    ]
+}
+
+#[test]
+fn test_initialize_creates_lp_mint() {
+    let mollusk = setup_mollusk();
+    let initializer = Pubkey::new_unique();
+    let mint_x = Pubkey::new_unique();
+    let mint_y = Pubkey::new_unique();
+
+    let accounts =
+        initialize_accounts(&mollusk, &initializer, &mint_x, &mint_y, Account::default(), token::keyed_account());
+    let result = mollusk.process_and_validate_instruction(
+        &initialize_ix(&initializer, SEED, FEE, &mint_x, &mint_y, None, &spl_token::id()),
+        &accounts,
+        &[Check::success()],
+    );
+
+    let (config, _) = config_pda(SEED, &mint_x, &mint_y);
+    assert_lp_mint(result.get_account(&mint_lp_pda(&config).0).unwrap(), &config, &spl_token::id(), LP_DECIMALS);
+}
+
+/// 高精度代币组成的池子可以选择更高的 LP 精度，存取款按 config 中记录的精度检查 LP mint
+#[test]
+fn test_initialize_with_lp_decimals() {
+    let mollusk = setup_mollusk();
+    let initializer = Pubkey::new_unique();
+    let mint_x = Pubkey::new_unique();
+    let mint_y = Pubkey::new_unique();
+
+    let accounts =
+        initialize_accounts(&mollusk, &initializer, &mint_x, &mint_y, Account::default(), token::keyed_account());
+    // authority（全零）+ soulbound = 0 + lp_decimals = 9
+    let mut ix = initialize_ix(&initializer, SEED, FEE, &mint_x, &mint_y, Some(&Pubkey::default()), &spl_token::id());
+    ix.data.extend_from_slice(&[0, 9]);
+    let result = mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::success()]);
+
+    let (config, _) = config_pda(SEED, &mint_x, &mint_y);
+    assert_lp_mint(result.get_account(&mint_lp_pda(&config).0).unwrap(), &config, &spl_token::id(), 9);
+    let config_account = result.get_account(&config).unwrap();
+    assert_eq!(unsafe { Config::from_bytes_unchecked(&config_account.data) }.lp_decimals(), 9);
+}
+
+/// 80/20 池子：weight_x 跟在 lp_decimals 之后，超出 [MIN_WEIGHT_BPS, MAX_WEIGHT_BPS] 时拒绝
+#[test]
+fn test_initialize_with_weight() {
+    let mollusk = setup_mollusk();
+    let initializer = Pubkey::new_unique();
+    let mint_x = Pubkey::new_unique();
+    let mint_y = Pubkey::new_unique();
+
+    let accounts =
+        initialize_accounts(&mollusk, &initializer, &mint_x, &mint_y, Account::default(), token::keyed_account());
+    let weighted_ix = |weight_x: u16| {
+        // authority（全零）+ soulbound = 0 + lp_decimals + weight_x
+        let mut ix = initialize_ix(&initializer, SEED, FEE, &mint_x, &mint_y, Some(&Pubkey::default()), &spl_token::id());
+        ix.data.extend_from_slice(&[0, LP_DECIMALS]);
+        ix.data.extend_from_slice(&weight_x.to_le_bytes());
+        ix
+    };
+
+    let result = mollusk.process_and_validate_instruction(&weighted_ix(8_000), &accounts, &[Check::success()]);
+    let (config, _) = config_pda(SEED, &mint_x, &mint_y);
+    let config_account = result.get_account(&config).unwrap();
+    assert_eq!(unsafe { Config::from_bytes_unchecked(&config_account.data) }.weight_x(), 8_000);
+
+    for weight_x in [0, 500, 9_500] {
+        mollusk.process_and_validate_instruction(
+            &weighted_ix(weight_x),
+            &accounts,
+            &[Check::err(ProgramError::InvalidInstructionData)],
+        );
+    }
+}
+
+/// Token-2022 池子的 LP mint 同样由 Token-2022 初始化
+#[test]
+fn test_initialize_creates_token_2022_lp_mint() {
+    let mollusk = setup_mollusk();
+    let initializer = Pubkey::new_unique();
+    let mint_x = Pubkey::new_unique();
+    let mint_y = Pubkey::new_unique();
+    let (token_program, token_program_account) = token2022::keyed_account();
+
+    let accounts = initialize_accounts(
+        &mollusk,
+        &initializer,
+        &mint_x,
+        &mint_y,
+        Account::default(),
+        (token_program, token_program_account),
+    );
+    let result = mollusk.process_and_validate_instruction(
+        &initialize_ix(&initializer, SEED, FEE, &mint_x, &mint_y, None, &token_program),
+        &accounts,
+        &[Check::success()],
+    );
+
+    let (config, _) = config_pda(SEED, &mint_x, &mint_y);
+    assert_lp_mint(result.get_account(&mint_lp_pda(&config).0).unwrap(), &config, &token_program, LP_DECIMALS);
+}
+
+/// LP mint：Mint::LEN 字节，`decimals` 位小数，config PDA 为 mint authority，没有 freeze authority
+fn assert_lp_mint(account: &Account, config: &Pubkey, token_program: &Pubkey, decimals: u8) {
+    assert_eq!(&account.owner, token_program);
+    assert_eq!(account.data.len(), Mint::LEN);
+    let mint = Mint::unpack(&account.data).unwrap();
+    assert!(mint.is_initialized);
+    assert_eq!(mint.decimals, decimals);
+    assert_eq!(mint.mint_authority, COption::Some(*config));
+    assert_eq!(mint.freeze_authority, COption::None);
+    assert_eq!(mint.supply, 0);
+}
+
+#[test]
+fn test_initialize_rejects_existing_lp_mint() {
+    let mollusk = setup_mollusk();
+    let initializer = Pubkey::new_unique();
+    let mint_x = Pubkey::new_unique();
+    let mint_y = Pubkey::new_unique();
+    let (config, _) = config_pda(SEED, &mint_x, &mint_y);
+
+    // LP mint 地址上已经有一个 mint，不应落到 CreateAccount 里失败
+    let existing = create_mint_account(&config, 6, 0, &spl_token::id());
+    let accounts = initialize_accounts(&mollusk, &initializer, &mint_x, &mint_y, existing, token::keyed_account());
+    mollusk.process_and_validate_instruction(
+        &initialize_ix(&initializer, SEED, FEE, &mint_x, &mint_y, None, &spl_token::id()),
+        &accounts,
+        &[Check::err(ProgramError::AccountAlreadyInitialized)],
+    );
+}
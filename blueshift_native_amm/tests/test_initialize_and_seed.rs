@@ -0,0 +1,131 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{errors::AmmError, state::MINIMUM_LIQUIDITY},
+    common::*,
+    mollusk_svm::result::{Check, ProgramResult},
+    mollusk_svm_programs_token::{associated_token, token},
+    solana_account::Account,
+    solana_sdk::pubkey::Pubkey,
+};
+
+const SEED: u64 = 7;
+const FEE: u16 = 30;
+
+#[test]
+fn test_initialize_and_seed_matches_initialize_then_deposit() {
+    let mollusk = setup_mollusk();
+    let (token_program, token_program_account) = token::keyed_account();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+
+    let user = Pubkey::new_unique();
+    let superadmin = Pubkey::new_unique();
+    let mint_x = Pubkey::new_unique();
+    let mint_y = Pubkey::new_unique();
+    let (seed_x, seed_y) = (4_000_000u64, 1_000_000u64);
+
+    let (global_config, global_bump) = global_config_pda();
+    let (config, _) = config_pda(SEED, &mint_x, &mint_y);
+    let (mint_lp, _) = mint_lp_pda(&config);
+    let vault_x = ata(&config, &mint_x, &token_program);
+    let vault_y = ata(&config, &mint_y, &token_program);
+    let user_x_ata = ata(&user, &mint_x, &token_program);
+    let user_y_ata = ata(&user, &mint_y, &token_program);
+    let user_lp_ata = ata(&user, &mint_lp, &token_program);
+
+    let base_accounts = vec![
+        (user, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (mint_lp, Account::default()),
+        (config, Account::default()),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (global_config, create_global_config_account(&mollusk, &superadmin, FEE, global_bump)),
+        (ata_program, ata_program_account),
+        (mint_x, create_mint_account(&superadmin, 6, 1_000_000_000, &token_program)),
+        (mint_y, create_mint_account(&superadmin, 6, 1_000_000_000, &token_program)),
+        (user_x_ata, create_token_account(&mint_x, &user, seed_x, &token_program)),
+        (user_y_ata, create_token_account(&mint_y, &user, seed_y, &token_program)),
+    ];
+
+    // Initialize + Deposit：金库和用户 LP ATA 需要事先存在
+    let mut accounts = base_accounts.clone();
+    accounts.extend([
+        (vault_x, create_token_account(&mint_x, &config, 0, &token_program)),
+        (vault_y, create_token_account(&mint_y, &config, 0, &token_program)),
+        (user_lp_ata, create_token_account(&mint_lp, &user, 0, &token_program)),
+    ]);
+    let result = mollusk.process_instruction_chain(
+        &[
+            initialize_ix(&user, SEED, FEE, &mint_x, &mint_y, None, &token_program),
+            first_deposit_ix(&user, &config, &mint_x, &mint_y, seed_x, seed_y, &token_program),
+        ],
+        &accounts,
+    );
+    assert_eq!(result.program_result, ProgramResult::Success);
+    let two_step_lp = token_amount(result.get_account(&user_lp_ata).unwrap());
+    let two_step_supply = mint_supply(result.get_account(&mint_lp).unwrap());
+
+    // InitializeAndSeed：金库和用户 LP ATA 在指令内创建
+    let mut accounts = base_accounts;
+    accounts.extend([
+        (vault_x, Account::default()),
+        (vault_y, Account::default()),
+        (user_lp_ata, Account::default()),
+    ]);
+    let result = mollusk.process_and_validate_instruction(
+        &initialize_and_seed_ix(&user, SEED, FEE, &mint_x, &mint_y, seed_x, seed_y, &token_program),
+        &accounts,
+        &[Check::success()],
+    );
+    let seeded_lp = token_amount(result.get_account(&user_lp_ata).unwrap());
+    let seeded_supply = mint_supply(result.get_account(&mint_lp).unwrap());
+
+    // sqrt(4_000_000 * 1_000_000) = 2_000_000
+    assert_eq!(seeded_lp, 2_000_000 - MINIMUM_LIQUIDITY);
+    assert_eq!(seeded_lp, two_step_lp);
+    assert_eq!(seeded_supply, two_step_supply);
+    assert_eq!(token_amount(result.get_account(&vault_x).unwrap()), seed_x);
+    assert_eq!(token_amount(result.get_account(&vault_y).unwrap()), seed_y);
+}
+
+#[test]
+fn test_initialize_and_seed_rejects_dust_seed() {
+    let mollusk = setup_mollusk();
+    let (token_program, token_program_account) = token::keyed_account();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+
+    let user = Pubkey::new_unique();
+    let superadmin = Pubkey::new_unique();
+    let mint_x = Pubkey::new_unique();
+    let mint_y = Pubkey::new_unique();
+    // sqrt(1_000 * 1_000) = 1_000，全部被锁定，用户拿不到 LP
+    let (seed_x, seed_y) = (1_000u64, 1_000u64);
+
+    let (global_config, global_bump) = global_config_pda();
+    let (config, _) = config_pda(SEED, &mint_x, &mint_y);
+    let (mint_lp, _) = mint_lp_pda(&config);
+
+    let accounts = vec![
+        (user, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (mint_lp, Account::default()),
+        (config, Account::default()),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (global_config, create_global_config_account(&mollusk, &superadmin, FEE, global_bump)),
+        (ata_program, ata_program_account),
+        (mint_x, create_mint_account(&superadmin, 6, 1_000_000_000, &token_program)),
+        (mint_y, create_mint_account(&superadmin, 6, 1_000_000_000, &token_program)),
+        (ata(&config, &mint_x, &token_program), Account::default()),
+        (ata(&config, &mint_y, &token_program), Account::default()),
+        (ata(&user, &mint_x, &token_program), create_token_account(&mint_x, &user, seed_x, &token_program)),
+        (ata(&user, &mint_y, &token_program), create_token_account(&mint_y, &user, seed_y, &token_program)),
+        (ata(&user, &mint_lp, &token_program), Account::default()),
+    ];
+    mollusk.process_and_validate_instruction(
+        &initialize_and_seed_ix(&user, SEED, FEE, &mint_x, &mint_y, seed_x, seed_y, &token_program),
+        &accounts,
+        &[Check::err(amm_err(AmmError::InsufficientInitialLiquidity))],
+    );
+}
@@ -0,0 +1,152 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::errors::AmmError,
+    common::*,
+    mollusk_svm::result::Check,
+    solana_account::Account,
+    solana_sdk::{instruction::AccountMeta, program_error::ProgramError, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+const COOLDOWN: u64 = 10;
+const DEPOSIT_SLOT: u64 = 100;
+
+/// JIT 流程：存入与池子等量的流动性 -> 大额 swap -> 在 `withdraw_slot` 全部取出
+///
+/// 返回 (存入的 sqrt(x*y), 取回的 sqrt(x*y))
+fn jit_round_trip(seed: u64, cooldown: u64, withdraw_slot: u64) -> (u128, u128) {
+    jit_round_trip_with_position(seed, cooldown, withdraw_slot, Account::default())
+}
+
+/// 同 `jit_round_trip`，但仓位 PDA 以 `position` 的状态开始
+fn jit_round_trip_with_position(seed: u64, cooldown: u64, withdraw_slot: u64, position: Account) -> (u128, u128) {
+    let mut mollusk = setup_mollusk();
+    mollusk.sysvars.clock.slot = DEPOSIT_SLOT;
+
+    let pool = Pool::new_with_token_program(seed, spl_token_2022::id());
+    let admin = Pubkey::new_unique();
+    let jit = Pubkey::new_unique();
+    let trader = Pubkey::new_unique();
+
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    pool.make_lp_soulbound(&mut accounts);
+    set_account(&mut accounts, &pool.config, pool.config_account_with_authority(&mollusk, FEE, &admin));
+    pool.sync_reserves(&mut accounts);
+    accounts.push((admin, create_system_account(LAMPORTS_PER_SOL)));
+    accounts.push((pool.position(&jit), position));
+    accounts.extend(pool.user_accounts(&jit, 2 * RESERVE, 2 * RESERVE, 0));
+    accounts.extend(pool.user_accounts(&trader, RESERVE, 0, 0));
+
+    let mut deposit = pool.deposit_ix(&jit, RESERVE, 2 * RESERVE, 2 * RESERVE);
+    deposit.accounts.push(AccountMeta::new(pool.position(&jit), false));
+    let mut withdraw = pool.withdraw_ix(&jit, RESERVE, 0, 0);
+    withdraw.accounts.push(AccountMeta::new(pool.position(&jit), false));
+
+    let result = mollusk.process_and_validate_instruction_chain(
+        &[
+            (&pool.set_config_ix(&admin, 5, &cooldown.to_le_bytes()), &[Check::success()]),
+            (&deposit, &[Check::success()]),
+            (&pool.swap_ix(&trader, true, RESERVE / 2, 1), &[Check::success()]),
+        ],
+        &accounts,
+    );
+    let balance = |accounts: &[(Pubkey, Account)], key: &Pubkey| {
+        token_amount(&accounts.iter().find(|(k, _)| k == key).unwrap().1) as u128
+    };
+    let deposited_x = 2 * RESERVE as u128 - balance(&result.resulting_accounts, &pool.user_x(&jit));
+    let deposited_y = 2 * RESERVE as u128 - balance(&result.resulting_accounts, &pool.user_y(&jit));
+
+    mollusk.sysvars.clock.slot = withdraw_slot;
+    let result = mollusk.process_and_validate_instruction(&withdraw, &result.resulting_accounts, &[Check::success()]);
+    let withdrawn_x = balance(&result.resulting_accounts, &pool.user_x(&jit)) - (2 * RESERVE as u128 - deposited_x);
+    let withdrawn_y = balance(&result.resulting_accounts, &pool.user_y(&jit)) - (2 * RESERVE as u128 - deposited_y);
+
+    (
+        (deposited_x * deposited_y).isqrt(),
+        (withdrawn_x * withdrawn_y).isqrt(),
+    )
+}
+
+/// 冷却期内存入又取出的流动性拿不到这笔 swap 的手续费
+#[test]
+fn test_jit_within_cooldown_forfeits_fees() {
+    let (deposited, withdrawn) = jit_round_trip(151, COOLDOWN, DEPOSIT_SLOT);
+    assert!(withdrawn <= deposited, "deposited {deposited} withdrawn {withdrawn}");
+}
+
+/// 冷却期过后正常分得手续费
+#[test]
+fn test_lp_after_cooldown_earns_fees() {
+    let (deposited, withdrawn) = jit_round_trip(152, COOLDOWN, DEPOSIT_SLOT + COOLDOWN);
+    assert!(withdrawn > deposited, "deposited {deposited} withdrawn {withdrawn}");
+}
+
+/// 默认关闭：同一个 slot 内取出也能分得手续费
+#[test]
+fn test_cooldown_disabled_by_default() {
+    let (deposited, withdrawn) = jit_round_trip(153, 0, DEPOSIT_SLOT);
+    assert!(withdrawn > deposited, "deposited {deposited} withdrawn {withdrawn}");
+}
+
+/// 有人预先向仓位 PDA 转入 1 lamport，既不能让存款创建仓位失败，也不能让它被当成已存在的仓位
+#[test]
+fn test_prefunded_position_cannot_grief_deposit() {
+    let prefunded = create_system_account(1);
+    let (deposited, withdrawn) = jit_round_trip_with_position(341, COOLDOWN, DEPOSIT_SLOT, prefunded);
+    assert!(withdrawn <= deposited, "deposited {deposited} withdrawn {withdrawn}");
+}
+
+/// 启用冷却期后存款必须带上仓位账户
+#[test]
+fn test_deposit_requires_position_when_enabled() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new_with_token_program(154, spl_token_2022::id());
+    let admin = Pubkey::new_unique();
+    let user = Pubkey::new_unique();
+
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    pool.make_lp_soulbound(&mut accounts);
+    set_account(&mut accounts, &pool.config, pool.config_account_with_authority(&mollusk, FEE, &admin));
+    pool.sync_reserves(&mut accounts);
+    accounts.push((admin, create_system_account(LAMPORTS_PER_SOL)));
+    accounts.extend(pool.user_accounts(&user, RESERVE, RESERVE, 0));
+
+    mollusk.process_and_validate_instruction_chain(
+        &[
+            (&pool.set_config_ix(&admin, 5, &COOLDOWN.to_le_bytes()), &[Check::success()]),
+            (
+                &pool.deposit_ix(&user, 1_000, RESERVE, RESERVE),
+                &[Check::err(ProgramError::NotEnoughAccountKeys)],
+            ),
+        ],
+        &accounts,
+    );
+}
+
+/// 仓位跟随钱包，LP 可以转让时冷却期能被绕过，只能在 soulbound LP 的池子上开启；关闭不受限制
+#[test]
+fn test_cooldown_requires_soulbound_lp() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(343);
+    let admin = Pubkey::new_unique();
+
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    set_account(&mut accounts, &pool.config, pool.config_account_with_authority(&mollusk, FEE, &admin));
+    pool.sync_reserves(&mut accounts);
+    accounts.push((admin, create_system_account(LAMPORTS_PER_SOL)));
+
+    mollusk.process_and_validate_instruction_chain(
+        &[
+            (
+                &pool.set_config_ix(&admin, 5, &COOLDOWN.to_le_bytes()),
+                &[Check::err(amm_err(AmmError::LpNotSoulbound))],
+            ),
+            (&pool.set_config_ix(&admin, 5, &0u64.to_le_bytes()), &[Check::success()]),
+        ],
+        &accounts,
+    );
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    common::*,
+    mollusk_svm::result::ProgramResult,
+    mollusk_svm_programs_token::{associated_token, token},
+    solana_account::Account,
+    solana_sdk::pubkey::Pubkey,
+};
+
+const SEED: u64 = 3;
+// 池子配置了手续费，但存款/取款不应收取
+const FEE: u16 = 30;
+
+/// 存入一份 LP 后立即全部取出，每一侧最多损失 1 个最小单位（舍入误差），不应有隐藏手续费
+#[test]
+fn test_deposit_then_withdraw_is_fee_free() {
+    let mollusk = setup_mollusk();
+    let (token_program, token_program_account) = token::keyed_account();
+
+    let seeder = Pubkey::new_unique();
+    let lp_user = Pubkey::new_unique();
+    let superadmin = Pubkey::new_unique();
+    let mint_x = Pubkey::new_unique();
+    let mint_y = Pubkey::new_unique();
+
+    // 故意选择不能整除的数量来覆盖舍入
+    let (seed_x, seed_y) = (3_000_007u64, 1_999_993u64);
+    let (user_x, user_y) = (1_000_000u64, 1_000_000u64);
+    let lp = 123_457u64;
+
+    let (global_config, global_bump) = global_config_pda();
+    let (config, _) = config_pda(SEED, &mint_x, &mint_y);
+    let (mint_lp, _) = mint_lp_pda(&config);
+
+    let accounts = vec![
+        (seeder, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (lp_user, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (mint_lp, Account::default()),
+        (config, Account::default()),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        associated_token::keyed_account(),
+        (global_config, create_global_config_account(&mollusk, &superadmin, FEE, global_bump)),
+        (mint_x, create_mint_account(&superadmin, 6, 1_000_000_000, &token_program)),
+        (mint_y, create_mint_account(&superadmin, 6, 1_000_000_000, &token_program)),
+        (ata(&config, &mint_x, &token_program), create_token_account(&mint_x, &config, 0, &token_program)),
+        (ata(&config, &mint_y, &token_program), create_token_account(&mint_y, &config, 0, &token_program)),
+        (ata(&seeder, &mint_x, &token_program), create_token_account(&mint_x, &seeder, seed_x, &token_program)),
+        (ata(&seeder, &mint_y, &token_program), create_token_account(&mint_y, &seeder, seed_y, &token_program)),
+        (ata(&seeder, &mint_lp, &token_program), create_token_account(&mint_lp, &seeder, 0, &token_program)),
+        (ata(&lp_user, &mint_x, &token_program), create_token_account(&mint_x, &lp_user, user_x, &token_program)),
+        (ata(&lp_user, &mint_y, &token_program), create_token_account(&mint_y, &lp_user, user_y, &token_program)),
+        (ata(&lp_user, &mint_lp, &token_program), create_token_account(&mint_lp, &lp_user, 0, &token_program)),
+    ];
+
+    let result = mollusk.process_instruction_chain(
+        &[
+            initialize_ix(&seeder, SEED, FEE, &mint_x, &mint_y, None, &token_program),
+            first_deposit_ix(&seeder, &config, &mint_x, &mint_y, seed_x, seed_y, &token_program),
+            deposit_ix(&lp_user, &config, &mint_x, &mint_y, lp, user_x, user_y, &token_program),
+            withdraw_ix(&lp_user, &config, &mint_x, &mint_y, lp, 1, 1, &token_program),
+        ],
+        &accounts,
+    );
+    assert_eq!(result.program_result, ProgramResult::Success);
+
+    let x_after = token_amount(result.get_account(&ata(&lp_user, &mint_x, &token_program)).unwrap());
+    let y_after = token_amount(result.get_account(&ata(&lp_user, &mint_y, &token_program)).unwrap());
+    let lp_after = token_amount(result.get_account(&ata(&lp_user, &mint_lp, &token_program)).unwrap());
+
+    assert_eq!(lp_after, 0);
+    assert!(user_x - x_after <= 1, "lost {} x", user_x - x_after);
+    assert!(user_y - y_after <= 1, "lost {} y", user_y - y_after);
+}
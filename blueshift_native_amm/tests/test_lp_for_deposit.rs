@@ -0,0 +1,76 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::state::MINIMUM_LIQUIDITY,
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    solana_sdk::pubkey::Pubkey,
+};
+
+const FEE: u16 = 30;
+
+/// 返回 (lp, amount_x, amount_y)
+fn lp_for_deposit(mollusk: &Mollusk, pool: &Pool, reserves: (u64, u64, u64), x: u64, y: u64) -> (u64, u64, u64) {
+    let accounts = pool.accounts(mollusk, FEE, reserves.0, reserves.1, reserves.2);
+    let result = mollusk.process_and_validate_instruction(
+        &pool.lp_for_deposit_ix(x, y),
+        &accounts,
+        &[Check::success()],
+    );
+
+    assert_eq!(result.return_data.len(), 24);
+    (
+        u64::from_le_bytes(result.return_data[0..8].try_into().unwrap()),
+        u64::from_le_bytes(result.return_data[8..16].try_into().unwrap()),
+        u64::from_le_bytes(result.return_data[16..24].try_into().unwrap()),
+    )
+}
+
+/// 真实存款：用户持有 x/y，返回 (铸造的 LP, 实际存入的 x, 实际存入的 y)
+fn deposit(mollusk: &Mollusk, pool: &Pool, reserves: (u64, u64, u64), lp: u64, x: u64, y: u64) -> (u64, u64, u64) {
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(mollusk, FEE, reserves.0, reserves.1, reserves.2);
+    accounts.extend(pool.user_accounts(&user, x, y, 0));
+
+    // 非首次存款时价格区间被忽略
+    let mut ix = pool.deposit_ix(&user, lp, x, y);
+    ix.data.extend(exact_price_band(x, y));
+    let result = mollusk.process_and_validate_instruction(
+        &ix,
+        &accounts,
+        &[Check::success()],
+    );
+    (
+        token_amount(result.get_account(&pool.user_lp(&user)).unwrap()),
+        x - token_amount(result.get_account(&pool.user_x(&user)).unwrap()),
+        y - token_amount(result.get_account(&pool.user_y(&user)).unwrap()),
+    )
+}
+
+#[test]
+fn test_lp_for_deposit_matches_real_deposit() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(91);
+    let reserves = (3_000_007, 1_999_993, 1_000_000);
+    // y 多给了，x 是限制方
+    let (x, y) = (123_457, 500_000);
+
+    let (lp, used_x, used_y) = lp_for_deposit(&mollusk, &pool, reserves, x, y);
+    assert!(lp > 0);
+    assert!(used_x <= x && used_y <= y);
+    assert_eq!((lp, used_x, used_y), deposit(&mollusk, &pool, reserves, lp, x, y));
+}
+
+#[test]
+fn test_lp_for_deposit_on_empty_pool_uses_sqrt() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(92);
+    let (x, y) = (4_000_000, 1_000_000);
+
+    let (lp, used_x, used_y) = lp_for_deposit(&mollusk, &pool, (0, 0, 0), x, y);
+    // sqrt(4_000_000 * 1_000_000) = 2_000_000
+    assert_eq!((lp, used_x, used_y), (2_000_000 - MINIMUM_LIQUIDITY, x, y));
+    assert_eq!((lp, used_x, used_y), deposit(&mollusk, &pool, (0, 0, 0), 1, x, y));
+}
@@ -0,0 +1,44 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{errors::AmmError, state::MINIMUM_LIQUIDITY},
+    common::*,
+    mollusk_svm::result::Check,
+    solana_sdk::pubkey::Pubkey,
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+const AMOUNT: u64 = 20_000;
+
+fn deposit_into_supply(seed: u64, supply: u64, check: Check) {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(seed);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, supply);
+    accounts.extend(pool.user_accounts(&user, RESERVE, RESERVE, 0));
+
+    mollusk.process_and_validate_instruction(
+        &pool.deposit_ix(&user, AMOUNT, RESERVE, RESERVE),
+        &accounts,
+        &[check],
+    );
+}
+
+/// 铸造后 supply 超过 u64::MAX：在 MintTo 之前给出 CurveOverflow
+#[test]
+fn test_deposit_overflowing_lp_supply_is_rejected() {
+    deposit_into_supply(
+        201,
+        u64::MAX - AMOUNT / 2,
+        Check::err(amm_err(AmmError::CurveOverflow)),
+    );
+}
+
+/// 恰好放得下时正常铸造
+#[test]
+fn test_deposit_up_to_max_lp_supply_passes() {
+    deposit_into_supply(202, u64::MAX - MINIMUM_LIQUIDITY - AMOUNT, Check::success());
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{errors::AmmError, state::MINIMUM_LIQUIDITY},
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    solana_account::Account,
+    solana_sdk::pubkey::Pubkey,
+};
+
+const FEE: u16 = 30;
+
+/// 空池子 + 持有 `amount` 个 x/y 的用户
+fn empty_pool(mollusk: &Mollusk, pool: &Pool, user: &Pubkey, amount: u64) -> Vec<(Pubkey, Account)> {
+    let mut accounts = pool.accounts(mollusk, FEE, 0, 0, 0);
+    accounts.extend(pool.user_accounts(user, amount, amount, 0));
+    accounts
+}
+
+/// sqrt(x * y) 正好等于锁定的 MINIMUM_LIQUIDITY 时，用户一份 LP 都拿不到，首次存款被拒绝
+#[test]
+fn test_tiny_first_deposit_cannot_cover_locked_minimum() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(256);
+    let user = Pubkey::new_unique();
+    let accounts = empty_pool(&mollusk, &pool, &user, MINIMUM_LIQUIDITY);
+
+    mollusk.process_and_validate_instruction(
+        &pool.first_deposit_ix(&user, MINIMUM_LIQUIDITY, MINIMUM_LIQUIDITY),
+        &accounts,
+        &[Check::err(amm_err(AmmError::InsufficientInitialLiquidity))],
+    );
+}
+
+/// 刚好超过锁定部分时，用户得到 sqrt(x * y) - MINIMUM_LIQUIDITY，锁定的部分不会铸造给任何人
+#[test]
+fn test_first_deposit_locks_minimum_liquidity() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(257);
+    let user = Pubkey::new_unique();
+    let amount = MINIMUM_LIQUIDITY + 1;
+    let accounts = empty_pool(&mollusk, &pool, &user, amount);
+
+    let result = mollusk.process_and_validate_instruction(
+        &pool.first_deposit_ix(&user, amount, amount),
+        &accounts,
+        &[Check::success()],
+    );
+    assert_eq!(token_amount(result.get_account(&pool.user_lp(&user)).unwrap()), 1);
+    assert_eq!(mint_supply(result.get_account(&pool.mint_lp).unwrap()), 1);
+}
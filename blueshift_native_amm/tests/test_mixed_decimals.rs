@@ -0,0 +1,98 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::state::LP_DECIMALS,
+    common::*,
+    mollusk_svm::{result::{Check, ProgramResult}, Mollusk},
+    solana_account::Account,
+    solana_sdk::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+// X 为 6 位精度，Y 为 9 位精度：1 X ≈ 2 Y
+const RESERVE_X: u64 = 1_000_000_000; // 1_000 X
+const RESERVE_Y: u64 = 2_000_000_000_000; // 2_000 Y
+const LP_SUPPLY: u64 = 1_000_000_000;
+
+fn mixed_pool_accounts(mollusk: &Mollusk, pool: &Pool) -> Vec<(Pubkey, Account)> {
+    let mut accounts = pool.accounts(mollusk, FEE, RESERVE_X, RESERVE_Y, LP_SUPPLY);
+    let authority = Pubkey::new_unique();
+    set_account(&mut accounts, &pool.mint_y, create_mint_account(&authority, 9, u64::MAX / 2, &pool.token_program));
+    accounts
+}
+
+/// 先存后取，两侧都最多损失 1 个最小单位，LP 份额按比例换算
+#[test]
+fn test_mixed_decimals_deposit_withdraw_conserves_value() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(87);
+    let user = Pubkey::new_unique();
+    let (user_x, user_y) = (10_000_000u64, 30_000_000_000u64);
+    let lp = 7_777_777u64;
+
+    let mut accounts = mixed_pool_accounts(&mollusk, &pool);
+    accounts.extend(pool.user_accounts(&user, user_x, user_y, 0));
+
+    let result = mollusk.process_instruction_chain(
+        &[
+            pool.deposit_ix(&user, lp, user_x, user_y),
+            pool.withdraw_ix(&user, lp, 1, 1),
+        ],
+        &accounts,
+    );
+    assert_eq!(result.program_result, ProgramResult::Success);
+
+    let x_after = token_amount(result.get_account(&pool.user_x(&user)).unwrap());
+    let y_after = token_amount(result.get_account(&pool.user_y(&user)).unwrap());
+    assert!(user_x - x_after <= 1, "lost {} x", user_x - x_after);
+    assert!(user_y - y_after <= 1, "lost {} y", user_y - y_after);
+}
+
+/// 存入的两侧数量与储备同比例，与精度无关
+#[test]
+fn test_mixed_decimals_deposit_is_proportional() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(88);
+    let user = Pubkey::new_unique();
+    let (user_x, user_y) = (10_000_000u64, 30_000_000_000u64);
+    // 1% 的份额
+    let lp = LP_SUPPLY / 100;
+
+    let mut accounts = mixed_pool_accounts(&mollusk, &pool);
+    accounts.extend(pool.user_accounts(&user, user_x, user_y, 0));
+
+    let result = mollusk.process_and_validate_instruction(
+        &pool.deposit_ix(&user, lp, user_x, user_y),
+        &accounts,
+        &[Check::success()],
+    );
+
+    let deposited_x = user_x - token_amount(result.get_account(&pool.user_x(&user)).unwrap());
+    let deposited_y = user_y - token_amount(result.get_account(&pool.user_y(&user)).unwrap());
+    // LP supply 计入锁定的 MINIMUM_LIQUIDITY，所以比 1% 略少（向上取整）
+    assert!((RESERVE_X / 100 - RESERVE_X / 100_000..=RESERVE_X / 100).contains(&deposited_x));
+    assert!((RESERVE_Y / 100 - RESERVE_Y / 100_000..=RESERVE_Y / 100).contains(&deposited_y));
+}
+
+#[test]
+fn test_deposit_rejects_lp_mint_with_wrong_decimals() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(89);
+    let user = Pubkey::new_unique();
+
+    let mut accounts = mixed_pool_accounts(&mollusk, &pool);
+    set_account(
+        &mut accounts,
+        &pool.mint_lp,
+        create_mint_account(&pool.config, LP_DECIMALS + 3, LP_SUPPLY, &pool.token_program),
+    );
+    accounts.extend(pool.user_accounts(&user, 10_000_000, 30_000_000_000, 0));
+
+    mollusk.process_and_validate_instruction(
+        &pool.deposit_ix(&user, 1_000, 10_000_000, 30_000_000_000),
+        &accounts,
+        &[Check::err(ProgramError::InvalidAccountData)],
+    );
+}
@@ -0,0 +1,59 @@
+use blueshift_native_amm::instructions::helpers::{add_u64, mul_div, mul_div_ceil, mul_div_round, sqrt_u128, sub_u64};
+use pinocchio::program_error::ProgramError;
+
+#[test]
+fn test_mul_div_round_rounds_half_up() {
+    // 5 / 2 = 2.5 -> 3
+    assert_eq!(mul_div_round(5, 1, 2), Ok(3));
+    // 7 / 3 = 2.33 -> 2，8 / 3 = 2.67 -> 3
+    assert_eq!(mul_div_round(7, 1, 3), Ok(2));
+    assert_eq!(mul_div_round(8, 1, 3), Ok(3));
+    // 整除时三种取整结果相同
+    assert_eq!(mul_div_round(6, 5, 3), Ok(10));
+    assert_eq!(mul_div(6, 5, 3), Ok(10));
+    assert_eq!(mul_div_ceil(6, 5, 3), Ok(10));
+    assert_eq!(mul_div_round(0, 5, 3), Ok(0));
+}
+
+#[test]
+fn test_mul_div_round_is_between_floor_and_ceil() {
+    for (a, b, c) in [(1, 1, 10_000), (9_999, 30, 10_000), (123_456_789, 997, 1_000), (u64::MAX, 1, 3)] {
+        let round = mul_div_round(a, b, c).unwrap();
+        assert!(mul_div(a, b, c).unwrap() <= round && round <= mul_div_ceil(a, b, c).unwrap());
+    }
+}
+
+#[test]
+fn test_mul_div_round_overflow_matches_floor_and_ceil() {
+    // 除数为 0
+    assert_eq!(mul_div_round(1, 1, 0), Err(ProgramError::ArithmeticOverflow));
+    assert_eq!(mul_div(1, 1, 0), Err(ProgramError::ArithmeticOverflow));
+    assert_eq!(mul_div_ceil(1, 1, 0), Err(ProgramError::ArithmeticOverflow));
+
+    // 结果超过 u64
+    assert_eq!(mul_div_round(u64::MAX, 2, 1), Err(ProgramError::ArithmeticOverflow));
+    assert_eq!(mul_div(u64::MAX, 2, 1), Err(ProgramError::ArithmeticOverflow));
+    assert_eq!(mul_div_ceil(u64::MAX, 2, 1), Err(ProgramError::ArithmeticOverflow));
+
+    // 最大的中间值不会在 u128 中溢出
+    assert_eq!(mul_div_round(u64::MAX, u64::MAX, u64::MAX), Ok(u64::MAX));
+    // 舍入后恰好超过 u64::MAX
+    assert_eq!(mul_div_round(u64::MAX, 3, 2), Err(ProgramError::ArithmeticOverflow));
+}
+
+#[test]
+fn test_add_sub_u64_report_overflow_and_underflow() {
+    assert_eq!(add_u64(u64::MAX - 1, 1), Ok(u64::MAX));
+    assert_eq!(add_u64(u64::MAX, 1), Err(ProgramError::ArithmeticOverflow));
+    assert_eq!(sub_u64(5, 5), Ok(0));
+    assert_eq!(sub_u64(5, 6), Err(ProgramError::InsufficientFunds));
+}
+
+#[test]
+fn test_sqrt_u128_floors_and_handles_max() {
+    for (n, root) in [(0, 0), (1, 1), (2, 1), (3, 1), (4, 2), (99, 9), (100, 10), (u64::MAX as u128, u32::MAX as u128)] {
+        assert_eq!(sqrt_u128(n), root);
+    }
+    // 初始猜测 x.div_ceil(2) 不会在 u128::MAX 上溢出
+    assert_eq!(sqrt_u128(u128::MAX), u64::MAX as u128);
+}
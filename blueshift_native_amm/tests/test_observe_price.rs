@@ -0,0 +1,105 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::state::PRICE_FRACTION_BITS,
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    solana_account::Account,
+    solana_sdk::pubkey::Pubkey,
+};
+
+const FEE: u16 = 30;
+
+struct Observation {
+    price_cumulative_x: u128,
+    price_cumulative_y: u128,
+    ts: i64,
+}
+
+fn observe(mollusk: &mut Mollusk, pool: &Pool, accounts: &mut Vec<(Pubkey, Account)>, now: i64) -> Observation {
+    mollusk.sysvars.clock.unix_timestamp = now;
+    let result = mollusk.process_and_validate_instruction(&pool.observe_price_ix(), accounts, &[Check::success()]);
+    *accounts = result.resulting_accounts;
+
+    assert_eq!(result.return_data.len(), 40);
+    Observation {
+        price_cumulative_x: u128::from_le_bytes(result.return_data[0..16].try_into().unwrap()),
+        price_cumulative_y: u128::from_le_bytes(result.return_data[16..32].try_into().unwrap()),
+        ts: i64::from_le_bytes(result.return_data[32..40].try_into().unwrap()),
+    }
+}
+
+/// 两次观测之间的平均价格（Q64.64）
+fn twap(a: &Observation, b: &Observation) -> (u128, u128) {
+    let elapsed = (b.ts - a.ts) as u128;
+    (
+        b.price_cumulative_x.wrapping_sub(a.price_cumulative_x) / elapsed,
+        b.price_cumulative_y.wrapping_sub(a.price_cumulative_y) / elapsed,
+    )
+}
+
+#[test]
+fn test_twap_over_constant_reserves_equals_spot_price() {
+    let mut mollusk = setup_mollusk();
+    let pool = Pool::new(121);
+    let mut accounts = pool.accounts(&mollusk, FEE, 1_000_000, 4_000_000, 2_000_000);
+
+    let first = observe(&mut mollusk, &pool, &mut accounts, 1_000);
+    let second = observe(&mut mollusk, &pool, &mut accounts, 1_600);
+    assert_eq!(second.ts, 1_600);
+
+    // 1 X = 4 Y，1 Y = 0.25 X
+    assert_eq!(twap(&first, &second), (4 << PRICE_FRACTION_BITS, 1 << (PRICE_FRACTION_BITS - 2)));
+}
+
+/// 同一时间戳内重复观测不会再累加
+#[test]
+fn test_repeated_observation_in_same_second_is_stable() {
+    let mut mollusk = setup_mollusk();
+    let pool = Pool::new(122);
+    let mut accounts = pool.accounts(&mollusk, FEE, 1_000_000, 4_000_000, 2_000_000);
+
+    let first = observe(&mut mollusk, &pool, &mut accounts, 1_000);
+    let second = observe(&mut mollusk, &pool, &mut accounts, 1_000);
+    assert_eq!(first.price_cumulative_x, second.price_cumulative_x);
+    assert_eq!(first.price_cumulative_y, second.price_cumulative_y);
+}
+
+/// swap 改变价格后，TWAP 落在前后两个现价之间，并按时间加权
+#[test]
+fn test_twap_across_swap_is_time_weighted() {
+    let mut mollusk = setup_mollusk();
+    let pool = Pool::new(123);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, 1_000_000, 1_000_000, 1_000_000);
+    accounts.extend(pool.user_accounts(&user, 1_000_000, 1_000_000, 0));
+
+    let first = observe(&mut mollusk, &pool, &mut accounts, 1_000);
+
+    // 100 秒后用 X 换 Y，X 变便宜
+    mollusk.sysvars.clock.unix_timestamp = 1_100;
+    let result = mollusk.process_and_validate_instruction(
+        &pool.swap_ix(&user, true, 1_000_000, 1),
+        &accounts,
+        &[Check::success()],
+    );
+    accounts = result.resulting_accounts;
+
+    // 再过 100 秒观测
+    let second = observe(&mut mollusk, &pool, &mut accounts, 1_200);
+    let (avg_x, _) = twap(&first, &second);
+
+    let one = 1u128 << PRICE_FRACTION_BITS;
+    let spot_after = pool_spot_price_x(&pool, &accounts);
+    assert!(spot_after < one);
+    // 前 100 秒价格为 1，后 100 秒为 spot_after，平均值是两者的中点（允许 1 个单位的舍入）
+    let expected = (one + spot_after) / 2;
+    assert!(avg_x.abs_diff(expected) <= 1, "avg {avg_x} expected {expected}");
+}
+
+fn pool_spot_price_x(pool: &Pool, accounts: &[(Pubkey, Account)]) -> u128 {
+    let amount = |key: &Pubkey| token_amount(&accounts.iter().find(|(k, _)| k == key).unwrap().1) as u128;
+    (amount(&pool.vault_y) << PRICE_FRACTION_BITS) / amount(&pool.vault_x)
+}
@@ -0,0 +1,215 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{errors::AmmError, state::GlobalConfig},
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    mollusk_svm_programs_token::token,
+    solana_account::Account,
+    solana_sdk::{
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    },
+};
+
+const FEE: u16 = 30;
+
+fn global_config_account(mollusk: &Mollusk, superadmin: &Pubkey, enforce_unique: bool) -> Account {
+    let (_, global_bump) = global_config_pda();
+    let mut account = create_global_config_account(mollusk, superadmin, FEE, global_bump);
+    unsafe { GlobalConfig::from_bytes_unchecked_mut(&mut account.data) }
+        .set_enforce_unique(enforce_unique)
+        .unwrap();
+    account
+}
+
+/// 两个 seed 不同、mint 和费率相同的池子所需的账户
+fn accounts(
+    mollusk: &Mollusk,
+    initializer: &Pubkey,
+    mint_x: &Pubkey,
+    mint_y: &Pubkey,
+    seeds: [u64; 2],
+    enforce_unique: bool,
+) -> Vec<(Pubkey, Account)> {
+    let (token_program, token_program_account) = token::keyed_account();
+    let (global_config, _) = global_config_pda();
+    let (registry, _) = pool_registry_pda(mint_x, mint_y, FEE);
+
+    let mut accounts = vec![
+        (*initializer, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (global_config, global_config_account(mollusk, &Pubkey::new_unique(), enforce_unique)),
+        (registry, Account::default()),
+    ];
+    for seed in seeds {
+        let (config, _) = config_pda(seed, mint_x, mint_y);
+        let (config_swapped, _) = config_pda(seed, mint_y, mint_x);
+        accounts.push((config, Account::default()));
+        accounts.push((mint_lp_pda(&config).0, Account::default()));
+        accounts.push((config_swapped, Account::default()));
+        accounts.push((mint_lp_pda(&config_swapped).0, Account::default()));
+    }
+    accounts
+}
+
+fn with_registry(mut ix: Instruction, mint_x: &Pubkey, mint_y: &Pubkey) -> Instruction {
+    ix.accounts.push(AccountMeta::new(pool_registry_pda(mint_x, mint_y, FEE).0, false));
+    ix
+}
+
+#[test]
+fn test_enforced_unique_rejects_second_pool_for_same_pair_and_fee() {
+    let mollusk = setup_mollusk();
+    let initializer = Pubkey::new_unique();
+    let (mint_x, mint_y) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = accounts(&mollusk, &initializer, &mint_x, &mint_y, [101, 102], true);
+
+    let first = with_registry(initialize_ix(&initializer, 101, FEE, &mint_x, &mint_y, None, &spl_token::id()), &mint_x, &mint_y);
+    let second = with_registry(initialize_ix(&initializer, 102, FEE, &mint_x, &mint_y, None, &spl_token::id()), &mint_x, &mint_y);
+
+    let result = mollusk.process_and_validate_instruction_chain(
+        &[
+            (&first, &[Check::success()]),
+            (&second, &[Check::err(amm_err(AmmError::PoolAlreadyExists))]),
+        ],
+        &accounts,
+    );
+
+    // registry 记录的是第一个池子
+    let registry = result.get_account(&pool_registry_pda(&mint_x, &mint_y, FEE).0).unwrap();
+    assert_eq!(&registry.data[..32], config_pda(101, &mint_x, &mint_y).0.as_ref());
+}
+
+#[test]
+fn test_enforced_unique_treats_swapped_mints_as_same_pair() {
+    let mollusk = setup_mollusk();
+    let initializer = Pubkey::new_unique();
+    let (mint_x, mint_y) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = accounts(&mollusk, &initializer, &mint_x, &mint_y, [103, 104], true);
+
+    let first = with_registry(initialize_ix(&initializer, 103, FEE, &mint_x, &mint_y, None, &spl_token::id()), &mint_x, &mint_y);
+    let swapped = with_registry(initialize_ix(&initializer, 104, FEE, &mint_y, &mint_x, None, &spl_token::id()), &mint_y, &mint_x);
+
+    mollusk.process_and_validate_instruction_chain(
+        &[
+            (&first, &[Check::success()]),
+            (&swapped, &[Check::err(amm_err(AmmError::PoolAlreadyExists))]),
+        ],
+        &accounts,
+    );
+}
+
+/// 预先向 registry PDA 转入 lamports 不能占住这组 mint + 费率
+#[test]
+fn test_prefunded_registry_does_not_block_pool_creation() {
+    let mollusk = setup_mollusk();
+    let initializer = Pubkey::new_unique();
+    let (mint_x, mint_y) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut accounts = accounts(&mollusk, &initializer, &mint_x, &mint_y, [111, 112], true);
+    let (registry, _) = pool_registry_pda(&mint_x, &mint_y, FEE);
+    set_account(&mut accounts, &registry, create_system_account(1));
+
+    let first = with_registry(initialize_ix(&initializer, 111, FEE, &mint_x, &mint_y, None, &spl_token::id()), &mint_x, &mint_y);
+    let second = with_registry(initialize_ix(&initializer, 112, FEE, &mint_x, &mint_y, None, &spl_token::id()), &mint_x, &mint_y);
+
+    let result = mollusk.process_and_validate_instruction_chain(
+        &[
+            (&first, &[Check::success()]),
+            (&second, &[Check::err(amm_err(AmmError::PoolAlreadyExists))]),
+        ],
+        &accounts,
+    );
+
+    let registry = result.get_account(&registry).unwrap();
+    assert_eq!(registry.owner, program_id());
+    assert_eq!(&registry.data[..32], config_pda(111, &mint_x, &mint_y).0.as_ref());
+}
+
+#[test]
+fn test_enforced_unique_requires_registry_account() {
+    let mollusk = setup_mollusk();
+    let initializer = Pubkey::new_unique();
+    let (mint_x, mint_y) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = accounts(&mollusk, &initializer, &mint_x, &mint_y, [105, 106], true);
+
+    mollusk.process_and_validate_instruction(
+        &initialize_ix(&initializer, 105, FEE, &mint_x, &mint_y, None, &spl_token::id()),
+        &accounts,
+        &[Check::err(ProgramError::NotEnoughAccountKeys)],
+    );
+}
+
+#[test]
+fn test_permissive_mode_allows_seed_differentiated_pools() {
+    let mollusk = setup_mollusk();
+    let initializer = Pubkey::new_unique();
+    let (mint_x, mint_y) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = accounts(&mollusk, &initializer, &mint_x, &mint_y, [107, 108], false);
+
+    // 关闭时不需要 registry，同一组合可以有多个池子
+    let result = mollusk.process_and_validate_instruction_chain(
+        &[
+            (&initialize_ix(&initializer, 107, FEE, &mint_x, &mint_y, None, &spl_token::id()), &[Check::success()]),
+            (&initialize_ix(&initializer, 108, FEE, &mint_x, &mint_y, None, &spl_token::id()), &[Check::success()]),
+        ],
+        &accounts,
+    );
+
+    // 也不会创建 registry
+    let registry = result.get_account(&pool_registry_pda(&mint_x, &mint_y, FEE).0).unwrap();
+    assert_eq!(registry.lamports, 0);
+}
+
+#[test]
+fn test_superadmin_toggles_enforce_unique() {
+    let mollusk = setup_mollusk();
+    let superadmin = Pubkey::new_unique();
+    let (global_config, _) = global_config_pda();
+
+    let accounts = vec![
+        (superadmin, create_system_account(LAMPORTS_PER_SOL)),
+        (global_config, global_config_account(&mollusk, &superadmin, false)),
+        (system_program::id(), create_system_program_account()),
+    ];
+
+    let result = mollusk.process_and_validate_instruction(
+        &set_global_config_ix(&superadmin, 3, &[1]),
+        &accounts,
+        &[Check::success()],
+    );
+    let mut data = result.get_account(&global_config).unwrap().data.clone();
+    assert!(unsafe { GlobalConfig::from_bytes_unchecked_mut(&mut data) }.enforce_unique());
+
+    mollusk.process_and_validate_instruction(
+        &set_global_config_ix(&superadmin, 3, &[2]),
+        &accounts,
+        &[Check::err(ProgramError::InvalidInstructionData)],
+    );
+}
+
+/// enforce_unique 打开后，省略 GlobalConfig（以及 registry）并给出具体费率也不能创建重复的池子
+#[test]
+fn test_enforced_unique_cannot_be_skipped_by_omitting_global_config() {
+    let mollusk = setup_mollusk();
+    let initializer = Pubkey::new_unique();
+    let (mint_x, mint_y) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = accounts(&mollusk, &initializer, &mint_x, &mint_y, [109, 110], true);
+
+    let first = with_registry(initialize_ix(&initializer, 109, FEE, &mint_x, &mint_y, None, &spl_token::id()), &mint_x, &mint_y);
+    // initialize_ix 把 GlobalConfig 放在最后
+    let mut duplicate = initialize_ix(&initializer, 110, FEE, &mint_x, &mint_y, None, &spl_token::id());
+    duplicate.accounts.pop();
+
+    mollusk.process_and_validate_instruction_chain(
+        &[
+            (&first, &[Check::success()]),
+            (&duplicate, &[Check::err(ProgramError::NotEnoughAccountKeys)]),
+        ],
+        &accounts,
+    );
+}
@@ -0,0 +1,48 @@
+use blueshift_native_amm::instructions::helpers::{compute_swap, price_impact_bps};
+
+// 储备 1_000_000 / 1_000_000
+
+#[test]
+fn test_small_swap_without_fee() {
+    // out = 1_000_000 * 1_000 / 1_001_000 = 999
+    assert_eq!(compute_swap(1_000_000, 1_000_000, 1_000, 0).unwrap(), 999);
+    // 999 / 1000 = 99.90% -> 10 bps
+    assert_eq!(price_impact_bps(1_000_000, 1_000_000, 1_000, 0).unwrap(), 10);
+}
+
+#[test]
+fn test_small_swap_with_fee() {
+    // after fee = 997, out = 1_000_000 * 997 / 1_000_997 = 996
+    assert_eq!(compute_swap(1_000_000, 1_000_000, 1_000, 30).unwrap(), 996);
+    // 996 / 1000 = 99.60% -> 40 bps（其中 30 bps 是手续费）
+    assert_eq!(price_impact_bps(1_000_000, 1_000_000, 1_000, 30).unwrap(), 40);
+}
+
+#[test]
+fn test_large_swap_without_fee() {
+    // out = 1_000_000 * 1_000_000 / 2_000_000 = 500_000
+    assert_eq!(compute_swap(1_000_000, 1_000_000, 1_000_000, 0).unwrap(), 500_000);
+    assert_eq!(price_impact_bps(1_000_000, 1_000_000, 1_000_000, 0).unwrap(), 5_000);
+}
+
+#[test]
+fn test_large_swap_with_fee() {
+    // after fee = 997_000, out = 1_000_000 * 997_000 / 1_997_000 = 499_248
+    assert_eq!(compute_swap(1_000_000, 1_000_000, 1_000_000, 30).unwrap(), 499_248);
+    // 499_248 / 1_000_000 = 49.9248% -> 4992 bps executed -> 5008 bps impact
+    assert_eq!(price_impact_bps(1_000_000, 1_000_000, 1_000_000, 30).unwrap(), 5_008);
+}
+
+#[test]
+fn test_impact_accounts_for_spot_price() {
+    // 现货价格 1 x = 4 y；out = 4_000_000 * 1_000 / 1_001_000 = 3_996
+    assert_eq!(compute_swap(1_000_000, 4_000_000, 1_000, 0).unwrap(), 3_996);
+    // 3_996 / (1_000 * 4) = 99.90% -> 10 bps
+    assert_eq!(price_impact_bps(1_000_000, 4_000_000, 1_000, 0).unwrap(), 10);
+}
+
+#[test]
+fn test_empty_pool_is_rejected() {
+    assert!(compute_swap(0, 1_000_000, 1_000, 30).is_err());
+    assert!(price_impact_bps(1_000_000, 0, 1_000, 30).is_err());
+}
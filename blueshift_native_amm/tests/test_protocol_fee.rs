@@ -0,0 +1,137 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    common::*,
+    mollusk_svm::{result::{Check, ProgramResult}, Mollusk},
+    solana_account::Account,
+    solana_sdk::{instruction::{AccountMeta, Instruction}, program_error::ProgramError, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+const AMOUNT_IN: u64 = 10_000;
+/// 1% 协议费：10_000 的输入中 100 归 treasury，9_900 进入曲线
+const PROTOCOL_FEE: u16 = 100;
+const PROTOCOL_CUT: u64 = 100;
+
+/// 池子 authority 为 `authority`，user 持有足够的 X，treasury 持有 X / Y 两个空 ATA
+fn setup(seed: u64, authority: &Pubkey, user: &Pubkey, treasury: &Pubkey) -> (Mollusk, Pool, Vec<(Pubkey, Account)>) {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(seed);
+
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    set_account(&mut accounts, &pool.config, pool.config_account_with_authority(&mollusk, FEE, authority));
+    pool.sync_reserves(&mut accounts);
+    accounts.push((*authority, create_system_account(LAMPORTS_PER_SOL)));
+    accounts.extend(pool.user_accounts(user, RESERVE, 0, 0));
+    accounts.extend(pool.user_accounts(treasury, 0, 0, 0));
+    (mollusk, pool, accounts)
+}
+
+fn set_protocol_fee_ix(pool: &Pool, authority: &Pubkey, bps: u16, treasury: &Pubkey) -> Instruction {
+    let mut value = bps.to_le_bytes().to_vec();
+    value.extend_from_slice(treasury.as_ref());
+    pool.set_config_ix(authority, 7, &value)
+}
+
+/// X -> Y，协议费池子在固定账户（以及 mints）之后传入 treasury ATA
+fn swap_with_treasury(pool: &Pool, user: &Pubkey, treasury_ata: &Pubkey) -> Instruction {
+    let mut ix = pool.swap_ix(user, true, AMOUNT_IN, 1);
+    ix.accounts.push(AccountMeta::new(*treasury_ata, false));
+    ix
+}
+
+#[test]
+fn test_protocol_fee_is_carved_off_before_curve() {
+    let (authority, user, treasury) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let (mollusk, pool, accounts) = setup(231, &authority, &user, &treasury);
+
+    let result = mollusk.process_instruction_chain(
+        &[
+            set_protocol_fee_ix(&pool, &authority, PROTOCOL_FEE, &treasury),
+            swap_with_treasury(&pool, &user, &pool.user_x(&treasury)),
+        ],
+        &accounts,
+    );
+    assert_eq!(result.program_result, ProgramResult::Success);
+    assert_eq!(token_amount(result.get_account(&pool.user_x(&treasury)).unwrap()), PROTOCOL_CUT);
+    assert_eq!(token_amount(result.get_account(&pool.user_x(&user)).unwrap()), RESERVE - AMOUNT_IN);
+    assert_eq!(token_amount(result.get_account(&pool.vault_x).unwrap()), RESERVE + AMOUNT_IN - PROTOCOL_CUT);
+
+    // 输出与没有协议费的池子直接兑换 9_900 完全相同
+    let (reference_user, reference_authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (_, reference, reference_accounts) = setup(232, &reference_authority, &reference_user, &treasury);
+    let expected = mollusk.process_instruction(
+        &reference.swap_ix(&reference_user, true, AMOUNT_IN - PROTOCOL_CUT, 1),
+        &reference_accounts,
+    );
+    assert_eq!(expected.program_result, ProgramResult::Success);
+    assert_eq!(
+        token_amount(result.get_account(&pool.user_y(&user)).unwrap()),
+        token_amount(expected.get_account(&reference.user_y(&reference_user)).unwrap()),
+    );
+}
+
+#[test]
+fn test_protocol_fee_rejects_treasury_with_output_mint() {
+    let (authority, user, treasury) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let (mollusk, pool, accounts) = setup(233, &authority, &user, &treasury);
+
+    // X -> Y 的协议费以 X 收取，treasury 的 Y ATA 不被接受
+    mollusk.process_and_validate_instruction_chain(
+        &[
+            (&set_protocol_fee_ix(&pool, &authority, PROTOCOL_FEE, &treasury), &[Check::success()]),
+            (
+                &swap_with_treasury(&pool, &user, &pool.user_y(&treasury)),
+                &[Check::err(ProgramError::InvalidAccountData)],
+            ),
+        ],
+        &accounts,
+    );
+}
+
+#[test]
+fn test_protocol_fee_rejects_treasury_ata_of_other_owner() {
+    let (authority, user, treasury) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let (mollusk, pool, accounts) = setup(234, &authority, &user, &treasury);
+
+    // 用户不能把协议费转回自己的 X ATA
+    mollusk.process_and_validate_instruction_chain(
+        &[
+            (&set_protocol_fee_ix(&pool, &authority, PROTOCOL_FEE, &treasury), &[Check::success()]),
+            (
+                &swap_with_treasury(&pool, &user, &pool.user_x(&user)),
+                &[Check::err(ProgramError::InvalidAccountOwner)],
+            ),
+        ],
+        &accounts,
+    );
+}
+
+#[test]
+fn test_protocol_fee_requires_treasury_account() {
+    let (authority, user, treasury) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let (mollusk, pool, accounts) = setup(235, &authority, &user, &treasury);
+
+    mollusk.process_and_validate_instruction_chain(
+        &[
+            (&set_protocol_fee_ix(&pool, &authority, PROTOCOL_FEE, &treasury), &[Check::success()]),
+            (&pool.swap_ix(&user, true, AMOUNT_IN, 1), &[Check::err(ProgramError::NotEnoughAccountKeys)]),
+        ],
+        &accounts,
+    );
+}
+
+#[test]
+fn test_protocol_fee_requires_treasury_owner() {
+    let (authority, user, treasury) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let (mollusk, pool, accounts) = setup(236, &authority, &user, &treasury);
+
+    mollusk.process_and_validate_instruction(
+        &set_protocol_fee_ix(&pool, &authority, PROTOCOL_FEE, &Pubkey::default()),
+        &accounts,
+        &[Check::err(ProgramError::InvalidInstructionData)],
+    );
+}
@@ -0,0 +1,27 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    common::*,
+    mollusk_svm::result::Check,
+};
+
+#[test]
+fn test_quote_returns_amount_out_and_price_impact() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(31);
+    let accounts = pool.accounts(&mollusk, 30, 1_000_000, 1_000_000, 1_000_000);
+
+    let result = mollusk.process_and_validate_instruction(
+        &pool.quote_ix(true, 1_000_000),
+        &accounts,
+        &[Check::success()],
+    );
+
+    assert_eq!(result.return_data.len(), 10);
+    let amount_out = u64::from_le_bytes(result.return_data[0..8].try_into().unwrap());
+    let impact = u16::from_le_bytes(result.return_data[8..10].try_into().unwrap());
+    assert_eq!(amount_out, 499_248);
+    assert_eq!(impact, 5_008);
+}
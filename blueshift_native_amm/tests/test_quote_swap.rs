@@ -0,0 +1,68 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::errors::AmmError,
+    common::*,
+    mollusk_svm::result::Check,
+    solana_sdk::pubkey::Pubkey,
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+const AMOUNT: u64 = 10_000;
+
+fn decode(return_data: &[u8]) -> (u64, u64) {
+    assert_eq!(return_data.len(), 16);
+    (
+        u64::from_le_bytes(return_data[0..8].try_into().unwrap()),
+        u64::from_le_bytes(return_data[8..16].try_into().unwrap()),
+    )
+}
+
+#[test]
+fn test_quote_swap_matches_swap_without_token_accounts() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(288);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+
+    let quote = mollusk.process_and_validate_instruction(
+        &pool.quote_swap_ix(true, AMOUNT),
+        &accounts,
+        &[Check::success()],
+    );
+    let (deposit, withdraw) = decode(&quote.return_data);
+    assert_eq!(deposit, AMOUNT);
+
+    // 报价不修改任何账户
+    for (key, account) in &accounts {
+        if let Some(after) = quote.get_account(key) {
+            assert_eq!(after, account);
+        }
+    }
+
+    // 实际兑换的结果与报价一致
+    accounts.extend(pool.user_accounts(&user, AMOUNT, 0, 0));
+    let swap = mollusk.process_and_validate_instruction(
+        &pool.swap_ix(&user, true, AMOUNT, 1),
+        &accounts,
+        &[Check::success()],
+    );
+    assert_eq!(token_amount(swap.get_account(&pool.user_y(&user)).unwrap()), withdraw);
+    assert_eq!(token_amount(swap.get_account(&pool.vault_x).unwrap()), RESERVE + deposit);
+}
+
+#[test]
+fn test_quote_swap_rejects_zero_amount() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(289);
+    let accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+
+    mollusk.process_and_validate_instruction(
+        &pool.quote_swap_ix(false, 0),
+        &accounts,
+        &[Check::err(amm_err(AmmError::ZeroAmount))],
+    );
+}
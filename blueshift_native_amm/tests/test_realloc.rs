@@ -0,0 +1,50 @@
+use blueshift_native_amm::instructions::helpers::{ProgramAccount, MAX_PERMITTED_DATA_INCREASE};
+use pinocchio::program_error::ProgramError;
+
+#[test]
+fn test_growth_within_limit_is_allowed() {
+    assert!(ProgramAccount::check_realloc_growth(100, 100 + MAX_PERMITTED_DATA_INCREASE).is_ok());
+    assert!(ProgramAccount::check_realloc_growth(0, 1).is_ok());
+}
+
+#[test]
+fn test_shrinking_is_always_allowed() {
+    assert!(ProgramAccount::check_realloc_growth(MAX_PERMITTED_DATA_INCREASE * 3, 0).is_ok());
+}
+
+/// 超限增长在 resize 和转移 lamports 之前就被拒绝
+#[test]
+fn test_over_limit_growth_is_rejected() {
+    assert_eq!(
+        ProgramAccount::check_realloc_growth(100, 101 + MAX_PERMITTED_DATA_INCREASE),
+        Err(ProgramError::InvalidRealloc)
+    );
+}
+
+/// 增长时补足新大小的租金；已经足够时不再转入
+#[test]
+fn test_grow_tops_up_to_new_minimum() {
+    assert_eq!(ProgramAccount::realloc_target_lamports(1_000, 1_000, 1_500, false), 1_500);
+    assert_eq!(ProgramAccount::realloc_target_lamports(1_000, 1_000, 1_500, true), 1_500);
+    assert_eq!(ProgramAccount::realloc_target_lamports(2_000, 1_000, 1_500, true), 2_000);
+}
+
+/// 缩小且 reclaim：释放出的租金退还，账户恰好保留新大小的租金
+#[test]
+fn test_shrink_with_reclaim_returns_released_rent() {
+    assert_eq!(ProgramAccount::realloc_target_lamports(1_500, 1_500, 1_000, true), 1_000);
+}
+
+/// 缩小但不 reclaim：多余的 lamports 留在账户中
+#[test]
+fn test_shrink_without_reclaim_keeps_lamports() {
+    assert_eq!(ProgramAccount::realloc_target_lamports(1_500, 1_500, 1_000, false), 1_500);
+}
+
+/// 账户中超出原租金的 lamports 不属于释放出的租金，reclaim 时不会被取走
+#[test]
+fn test_reclaim_keeps_extra_lamports() {
+    assert_eq!(ProgramAccount::realloc_target_lamports(1_700, 1_500, 1_000, true), 1_200);
+    // 原本就没有缴足旧大小的租金时，只退到新大小的租金为止
+    assert_eq!(ProgramAccount::realloc_target_lamports(1_200, 1_500, 1_000, true), 1_000);
+}
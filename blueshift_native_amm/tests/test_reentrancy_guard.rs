@@ -0,0 +1,109 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{errors::AmmError, state::Config},
+    common::*,
+    mollusk_svm::result::Check,
+    solana_account::Account,
+    solana_sdk::{instruction::AccountMeta, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+const AMOUNT_IN: u64 = 10_000;
+const HANDOFF: u64 = 9_000;
+
+fn locked(account: &mut Account) {
+    unsafe { Config::from_bytes_unchecked_mut(&mut account.data) }.set_locked(true);
+}
+
+fn is_locked(accounts: &[(Pubkey, Account)], config: &Pubkey) -> bool {
+    let data = &accounts.iter().find(|(k, _)| k == config).unwrap().1.data;
+    unsafe { Config::from_bytes_unchecked(data) }.is_locked()
+}
+
+/// 下游程序 / 闪电兑换回调再次调用本池子时，看到的是调用期间的 config：locked = 1。
+/// 这里直接构造这样的 config，模拟重入的那一次调用
+#[test]
+fn test_reentrant_call_is_rejected() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(328);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, RESERVE, RESERVE, RESERVE));
+    let mut config = pool.config_account(&mollusk, FEE);
+    locked(&mut config);
+    set_account(&mut accounts, &pool.config, config);
+    pool.sync_reserves(&mut accounts);
+
+    for ix in [
+        pool.swap_ix(&user, true, AMOUNT_IN, 1),
+        pool.deposit_ix(&user, 1_000, RESERVE, RESERVE),
+        pool.withdraw_ix(&user, 1_000, 0, 0),
+        pool.deposit_single_sided_ix(&user, true, AMOUNT_IN, 1),
+        pool.withdraw_single_sided_ix(&user, true, 1_000, 1),
+    ] {
+        mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::err(amm_err(AmmError::Reentrancy))]);
+    }
+}
+
+/// 下游程序返回后锁被释放，同一个池子可以继续交易
+#[test]
+fn test_lock_is_released_after_downstream_call() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(329);
+    let (superadmin, user, market) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let (global_config, bump) = global_config_pda();
+
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    set_account(&mut accounts, &global_config, create_global_config_account(&mollusk, &superadmin, FEE, bump));
+    accounts.push((superadmin, create_system_account(LAMPORTS_PER_SOL)));
+    accounts.extend(pool.user_accounts(&user, RESERVE, 0, 0));
+    accounts.push((market, create_token_account(&pool.mint_y, &Pubkey::new_unique(), 0, &pool.token_program)));
+
+    // X -> Y，输出由下游程序（SPL Token 的 Transfer）转入 market
+    let mut swap = pool.swap_ix(&user, true, AMOUNT_IN, HANDOFF);
+    swap.data.push(0);
+    swap.data.push(3);
+    swap.data.extend_from_slice(&HANDOFF.to_le_bytes());
+    swap.accounts.extend([
+        AccountMeta::new_readonly(pool.token_program, false),
+        AccountMeta::new(pool.user_y(&user), false),
+        AccountMeta::new(market, false),
+        AccountMeta::new_readonly(user, true),
+    ]);
+    let mut allow = vec![0u8];
+    allow.extend_from_slice(pool.token_program.as_ref());
+
+    let result = mollusk.process_and_validate_instruction_chain(
+        &[
+            (&set_global_config_ix(&superadmin, 4, &allow), &[Check::success()]),
+            (&swap, &[Check::success()]),
+            (&pool.swap_ix(&user, true, AMOUNT_IN, 1), &[Check::success()]),
+        ],
+        &accounts,
+    );
+    assert!(!is_locked(&result.resulting_accounts, &pool.config));
+}
+
+/// 锁覆盖整条指令：swap / deposit / withdraw 成功返回后池子都已解锁，下一条指令可以继续进入
+#[test]
+fn test_lock_is_released_after_each_instruction() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(336);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, RESERVE, RESERVE, RESERVE));
+
+    for ix in [
+        pool.swap_ix(&user, true, AMOUNT_IN, 1),
+        pool.deposit_ix(&user, 1_000, RESERVE, RESERVE),
+        pool.withdraw_ix(&user, 1_000, 0, 0),
+    ] {
+        let result = mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::success()]);
+        assert!(!is_locked(&result.resulting_accounts, &pool.config));
+        accounts = result.resulting_accounts;
+    }
+}
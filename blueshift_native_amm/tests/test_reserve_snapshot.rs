@@ -0,0 +1,104 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{errors::AmmError, state::Config},
+    common::*,
+    mollusk_svm::{result::{Check, ProgramResult}, Mollusk},
+    solana_account::Account,
+    solana_sdk::pubkey::Pubkey,
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+const AMOUNT: u64 = 10_000;
+const DONATION: u64 = 500_000;
+
+/// 储备快照等于金库余额的池子
+fn synced_pool(mollusk: &Mollusk, seed: u64) -> (Pool, Vec<(Pubkey, Account)>) {
+    let pool = Pool::new(seed);
+    let accounts = pool.accounts(mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    (pool, accounts)
+}
+
+/// 直接向 vault_x 转入代币，不经过本程序
+fn set_vault_x(pool: &Pool, accounts: &mut [(Pubkey, Account)], amount: u64) {
+    set_account(accounts, &pool.vault_x, create_token_account(&pool.mint_x, &pool.config, amount, &pool.token_program));
+}
+
+fn reserves(accounts: &[(Pubkey, Account)], pool: &Pool) -> (u64, u64) {
+    let data = &accounts.iter().find(|(k, _)| k == &pool.config).unwrap().1.data;
+    let config = unsafe { Config::from_bytes_unchecked(data) };
+    (config.reserve_x(), config.reserve_y())
+}
+
+#[test]
+fn test_donation_does_not_change_quote() {
+    let mollusk = setup_mollusk();
+    let (pool, mut accounts) = synced_pool(&mollusk, 294);
+
+    let before = mollusk.process_instruction(&pool.quote_swap_ix(false, AMOUNT), &accounts);
+    assert_eq!(before.program_result, ProgramResult::Success);
+
+    // 捐赠 X 会让按金库余额计算的 Y -> X 报价变好，快照计价不受影响
+    set_vault_x(&pool, &mut accounts, RESERVE + DONATION);
+    let after = mollusk.process_instruction(&pool.quote_swap_ix(false, AMOUNT), &accounts);
+    assert_eq!(after.program_result, ProgramResult::Success);
+    assert_eq!(after.return_data, before.return_data);
+}
+
+#[test]
+fn test_swap_prices_from_snapshot_and_keeps_donation_out() {
+    let mollusk = setup_mollusk();
+    let (pool, mut accounts) = synced_pool(&mollusk, 295);
+    let expected = mollusk.process_instruction(&pool.quote_swap_ix(true, AMOUNT), &accounts);
+    let amount_out = u64::from_le_bytes(expected.return_data[8..16].try_into().unwrap());
+
+    let user = Pubkey::new_unique();
+    set_vault_x(&pool, &mut accounts, RESERVE + DONATION);
+    accounts.extend(pool.user_accounts(&user, AMOUNT, 0, 0));
+
+    let result = mollusk.process_and_validate_instruction(
+        &pool.swap_ix(&user, true, AMOUNT, 1),
+        &accounts,
+        &[Check::success()],
+    );
+    assert_eq!(token_amount(result.get_account(&pool.user_y(&user)).unwrap()), amount_out);
+    // 捐赠仍在金库里，但不计入快照
+    assert_eq!(token_amount(result.get_account(&pool.vault_x).unwrap()), RESERVE + DONATION + AMOUNT);
+    assert_eq!(reserves(&result.resulting_accounts, &pool), (RESERVE + AMOUNT, RESERVE - amount_out));
+}
+
+#[test]
+fn test_vault_below_snapshot_is_rejected() {
+    let mollusk = setup_mollusk();
+    let (pool, mut accounts) = synced_pool(&mollusk, 296);
+    let user = Pubkey::new_unique();
+    set_vault_x(&pool, &mut accounts, RESERVE - 1);
+    accounts.extend(pool.user_accounts(&user, AMOUNT, 0, 0));
+
+    mollusk.process_and_validate_instruction(
+        &pool.swap_ix(&user, true, AMOUNT, 1),
+        &accounts,
+        &[Check::err(amm_err(AmmError::ReserveMismatch))],
+    );
+}
+
+/// 快照为空时金库里的代币全部视为捐赠（例如创建池子之前就转入金库），池子没有价格
+#[test]
+fn test_tokens_outside_snapshot_do_not_price_the_pool() {
+    let mollusk = setup_mollusk();
+    let (pool, mut accounts) = synced_pool(&mollusk, 297);
+    let user = Pubkey::new_unique();
+    let config = &mut accounts.iter_mut().find(|(k, _)| k == &pool.config).unwrap().1;
+    unsafe { Config::from_bytes_unchecked_mut(&mut config.data) }.set_reserves(0, 0);
+    accounts.extend(pool.user_accounts(&user, AMOUNT, 0, 0));
+
+    mollusk.process_and_validate_instruction(
+        &pool.swap_ix(&user, true, AMOUNT, 1),
+        &accounts,
+        &[Check::err(amm_err(AmmError::EmptyPool))],
+    );
+    assert_eq!(reserves(&accounts, &pool), (0, 0));
+}
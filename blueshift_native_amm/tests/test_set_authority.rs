@@ -0,0 +1,117 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{errors::AmmError, state::Config},
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    solana_account::Account,
+    solana_sdk::{instruction::Instruction, program_error::ProgramError, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+
+fn setup(mollusk: &Mollusk, pool: &Pool, owner: &Pubkey) -> Vec<(Pubkey, Account)> {
+    let mut accounts = pool.accounts(mollusk, FEE, 1_000_000, 1_000_000, 1_000_000);
+    set_account(&mut accounts, &pool.config, pool.config_account_with_authority(mollusk, FEE, owner));
+    pool.sync_reserves(&mut accounts);
+    accounts.push((*owner, create_system_account(LAMPORTS_PER_SOL)));
+    accounts
+}
+
+fn set_authority_ix(pool: &Pool, signer: &Pubkey, authority: &Pubkey, renounce: bool) -> Instruction {
+    let mut value = authority.to_bytes().to_vec();
+    value.push(renounce as u8);
+    pool.set_config_ix(signer, 8, &value)
+}
+
+fn authority(accounts: &[(Pubkey, Account)], pool: &Pool) -> Pubkey {
+    let data = &accounts.iter().find(|(k, _)| k == &pool.config).unwrap().1.data;
+    Pubkey::new_from_array(*unsafe { Config::from_bytes_unchecked(data) }.authority())
+}
+
+#[test]
+fn test_owner_can_rotate_authority() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(290);
+    let (owner, new_owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut accounts = setup(&mollusk, &pool, &owner);
+    accounts.push((new_owner, create_system_account(LAMPORTS_PER_SOL)));
+
+    let result = mollusk.process_and_validate_instruction(
+        &set_authority_ix(&pool, &owner, &new_owner, false),
+        &accounts,
+        &[Check::success()],
+    );
+    assert_eq!(authority(&result.resulting_accounts, &pool), new_owner);
+
+    // 新 owner 可以执行 owner 级别的操作，旧 owner 不再可以
+    mollusk.process_and_validate_instruction_chain(
+        &[
+            (
+                &pool.set_config_ix(&owner, 3, new_owner.as_ref()),
+                &[Check::err(amm_err(AmmError::InvalidAuthority))],
+            ),
+            (&pool.set_config_ix(&new_owner, 3, owner.as_ref()), &[Check::success()]),
+        ],
+        &result.resulting_accounts,
+    );
+}
+
+#[test]
+fn test_owner_can_renounce_authority() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(291);
+    let owner = Pubkey::new_unique();
+    let accounts = setup(&mollusk, &pool, &owner);
+
+    let result = mollusk.process_and_validate_instruction(
+        &set_authority_ix(&pool, &owner, &Pubkey::default(), true),
+        &accounts,
+        &[Check::success()],
+    );
+    assert_eq!(authority(&result.resulting_accounts, &pool), Pubkey::default());
+
+    // 放弃之后 owner 不能再修改，包括把自己设回去
+    mollusk.process_and_validate_instruction(
+        &set_authority_ix(&pool, &owner, &owner, false),
+        &result.resulting_accounts,
+        &[Check::err(amm_err(AmmError::InvalidAuthority))],
+    );
+}
+
+#[test]
+fn test_zero_authority_requires_renounce_flag() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(292);
+    let owner = Pubkey::new_unique();
+    let accounts = setup(&mollusk, &pool, &owner);
+
+    mollusk.process_and_validate_instruction(
+        &set_authority_ix(&pool, &owner, &Pubkey::default(), false),
+        &accounts,
+        &[Check::err(ProgramError::InvalidInstructionData)],
+    );
+    // renounce 时公钥必须为全零
+    mollusk.process_and_validate_instruction(
+        &set_authority_ix(&pool, &owner, &Pubkey::new_unique(), true),
+        &accounts,
+        &[Check::err(ProgramError::InvalidInstructionData)],
+    );
+}
+
+#[test]
+fn test_non_authority_cannot_rotate_authority() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(293);
+    let (owner, attacker) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let mut accounts = setup(&mollusk, &pool, &owner);
+    accounts.push((attacker, create_system_account(LAMPORTS_PER_SOL)));
+
+    mollusk.process_and_validate_instruction(
+        &set_authority_ix(&pool, &attacker, &attacker, false),
+        &accounts,
+        &[Check::err(amm_err(AmmError::InvalidAuthority))],
+    );
+}
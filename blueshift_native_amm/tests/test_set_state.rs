@@ -0,0 +1,128 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{
+        errors::AmmError,
+        state::{AmmState, Config},
+    },
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    solana_account::Account,
+    solana_sdk::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+
+/// 池子 authority 为 `authority`，user 持有 X 和一部分 LP
+fn setup(mollusk: &Mollusk, pool: &Pool, authority: &Pubkey, user: &Pubkey) -> Vec<(Pubkey, Account)> {
+    let mut accounts = pool.accounts(mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    set_account(&mut accounts, &pool.config, pool.config_account_with_authority(mollusk, FEE, authority));
+    pool.sync_reserves(&mut accounts);
+    accounts.push((*authority, create_system_account(LAMPORTS_PER_SOL)));
+    accounts.extend(pool.user_accounts(user, RESERVE, 0, 10_000));
+    accounts
+}
+
+fn state(accounts: &[(Pubkey, Account)], pool: &Pool) -> u8 {
+    let data = &accounts.iter().find(|(k, _)| k == &pool.config).unwrap().1.data;
+    unsafe { Config::from_bytes_unchecked(data) }.state()
+}
+
+#[test]
+fn test_withdraw_only_blocks_swap_but_allows_withdraw() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(251);
+    let (authority, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = setup(&mollusk, &pool, &authority, &user);
+
+    let result = mollusk.process_and_validate_instruction(
+        &pool.set_state_ix(&authority, AmmState::WithdrawOnly as u8),
+        &accounts,
+        &[Check::success()],
+    );
+    let accounts = result.resulting_accounts;
+    assert_eq!(state(&accounts, &pool), AmmState::WithdrawOnly as u8);
+
+    mollusk.process_and_validate_instruction(
+        &pool.swap_ix(&user, true, 1_000, 1),
+        &accounts,
+        &[Check::err(ProgramError::InvalidAccountData)],
+    );
+    mollusk.process_and_validate_instruction(&pool.withdraw_ix(&user, 10_000, 1, 1), &accounts, &[Check::success()]);
+}
+
+#[test]
+fn test_set_state_resumes_swaps() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(252);
+    let (authority, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = setup(&mollusk, &pool, &authority, &user);
+
+    mollusk.process_and_validate_instruction_chain(
+        &[
+            (&pool.set_state_ix(&authority, AmmState::WithdrawOnly as u8), &[Check::success()]),
+            (&pool.swap_ix(&user, true, 1_000, 1), &[Check::err(ProgramError::InvalidAccountData)]),
+            (&pool.set_state_ix(&authority, AmmState::Initialized as u8), &[Check::success()]),
+            (&pool.swap_ix(&user, true, 1_000, 1), &[Check::success()]),
+        ],
+        &accounts,
+    );
+}
+
+#[test]
+fn test_set_state_rejects_unknown_state() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(253);
+    let (authority, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = setup(&mollusk, &pool, &authority, &user);
+
+    // Uninitialized 不能通过 SetState 写入
+    for byte in [AmmState::Uninitialized as u8, 4, u8::MAX] {
+        mollusk.process_and_validate_instruction(
+            &pool.set_state_ix(&authority, byte),
+            &accounts,
+            &[Check::err(amm_err(AmmError::UnknownAmmState))],
+        );
+    }
+}
+
+/// 下线只能走 DisablePool（要求 LP 已全部赎回），SetState 不能直接切到 Disabled
+#[test]
+fn test_set_state_cannot_disable_pool() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(334);
+    let (authority, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = setup(&mollusk, &pool, &authority, &user);
+
+    mollusk.process_and_validate_instruction_chain(
+        &[
+            (
+                &pool.set_state_ix(&authority, AmmState::Disabled as u8),
+                &[Check::err(amm_err(AmmError::InvalidStateTransition))],
+            ),
+            (&pool.set_state_ix(&authority, AmmState::WithdrawOnly as u8), &[Check::success()]),
+            (
+                &pool.set_state_ix(&authority, AmmState::Disabled as u8),
+                &[Check::err(amm_err(AmmError::InvalidStateTransition))],
+            ),
+        ],
+        &accounts,
+    );
+}
+
+#[test]
+fn test_set_state_requires_pause_admin() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(254);
+    let (authority, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let accounts = setup(&mollusk, &pool, &authority, &user);
+
+    mollusk.process_and_validate_instruction(
+        &pool.set_state_ix(&user, AmmState::WithdrawOnly as u8),
+        &accounts,
+        &[Check::err(amm_err(AmmError::InvalidAuthority))],
+    );
+}
@@ -0,0 +1,26 @@
+mod common;
+
+use {
+    blueshift_native_amm::state::Config,
+    common::*,
+    solana_sdk::pubkey::Pubkey,
+};
+
+#[test]
+fn test_config_seeds_reproduce_pda() {
+    let mint_x = Pubkey::new_unique();
+    let mint_y = Pubkey::new_unique();
+    let seed = 0x0102_0304_0506_07ffu64;
+    let (config, bump) = config_pda(seed, &mint_x, &mint_y);
+
+    let mut data = vec![0u8; Config::LEN];
+    unsafe { Config::from_bytes_unchecked_mut(&mut data) }
+        .set_inner(seed, [0u8; 32], mint_x.to_bytes(), mint_y.to_bytes(), 30, [bump])
+        .unwrap();
+
+    let state = unsafe { Config::from_bytes_unchecked(&data) };
+    let seeds = state.config_seeds();
+    let seeds: Vec<&[u8]> = seeds.iter().map(|seed| &**seed).collect();
+
+    assert_eq!(Pubkey::create_program_address(&seeds, &program_id()).unwrap(), config);
+}
@@ -0,0 +1,75 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    solana_sdk::pubkey::Pubkey,
+};
+
+const FEE: u16 = 30;
+const RESERVE_X: u64 = 3_000_007;
+const RESERVE_Y: u64 = 1_999_993;
+const LP_SUPPLY: u64 = 1_000_000;
+
+fn simulate(mollusk: &Mollusk, pool: &Pool, amount: u64) -> (u64, u64) {
+    let accounts = pool.accounts(mollusk, FEE, RESERVE_X, RESERVE_Y, LP_SUPPLY);
+    let result = mollusk.process_and_validate_instruction(
+        &pool.simulate_withdraw_ix(amount),
+        &accounts,
+        &[Check::success()],
+    );
+
+    assert_eq!(result.return_data.len(), 16);
+    (
+        u64::from_le_bytes(result.return_data[0..8].try_into().unwrap()),
+        u64::from_le_bytes(result.return_data[8..16].try_into().unwrap()),
+    )
+}
+
+/// 真实取款后用户实际收到的 x/y（用户持有全部 LP）
+fn withdraw(mollusk: &Mollusk, pool: &Pool, amount: u64) -> (u64, u64) {
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(mollusk, FEE, RESERVE_X, RESERVE_Y, LP_SUPPLY);
+    accounts.extend(pool.user_accounts(&user, 0, 0, LP_SUPPLY));
+
+    let result = mollusk.process_and_validate_instruction(
+        &pool.withdraw_ix(&user, amount, 1, 1),
+        &accounts,
+        &[Check::success()],
+    );
+    (
+        token_amount(result.get_account(&pool.user_x(&user)).unwrap()),
+        token_amount(result.get_account(&pool.user_y(&user)).unwrap()),
+    )
+}
+
+#[test]
+fn test_simulated_withdraw_matches_real_withdraw() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(71);
+
+    let simulated = simulate(&mollusk, &pool, 123_457);
+    assert!(simulated.0 > 0 && simulated.1 > 0);
+    assert_eq!(simulated, withdraw(&mollusk, &pool, 123_457));
+}
+
+#[test]
+fn test_simulated_full_withdraw_leaves_locked_liquidity() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(72);
+
+    // 销毁全部已铸造的 LP：锁定的 MINIMUM_LIQUIDITY 对应的储备仍留在金库中
+    let simulated = simulate(&mollusk, &pool, LP_SUPPLY);
+    assert!(simulated.0 < RESERVE_X && simulated.1 < RESERVE_Y);
+    assert_eq!(simulated, withdraw(&mollusk, &pool, LP_SUPPLY));
+}
+
+#[test]
+fn test_simulated_zero_withdraw_returns_zero() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(73);
+
+    assert_eq!(simulate(&mollusk, &pool, 0), (0, 0));
+}
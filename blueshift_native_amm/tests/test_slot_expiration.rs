@@ -0,0 +1,92 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::errors::AmmError,
+    common::*,
+    mollusk_svm::result::Check,
+    solana_sdk::{instruction::Instruction, program_error::ProgramError, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+const SLOT: u64 = 5_000;
+const SLOT_KIND: u8 = 1;
+
+// 时间戳远在过去：若仍按时间戳解释 expiration，交易必然过期
+const NOW: i64 = 1_700_000_000;
+
+fn process_with_slot_deadline(seed: u64, build: impl Fn(&Pool, &Pubkey) -> Instruction, check: Check) {
+    let mut mollusk = setup_mollusk();
+    mollusk.sysvars.clock.slot = SLOT;
+    mollusk.sysvars.clock.unix_timestamp = NOW;
+
+    let pool = Pool::new(seed);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, 1_000_000, 1_000_000, 1_000_000);
+    accounts.extend(pool.user_accounts(&user, 1_000_000, 1_000_000, 0));
+
+    mollusk.process_and_validate_instruction(&build(&pool, &user), &accounts, &[check]);
+}
+
+fn deposit_with_slot_deadline(pool: &Pool, user: &Pubkey, deadline: u64) -> Instruction {
+    let mut ix = pool.deposit_ix(user, 1_000, 10_000, 10_000);
+    let len = ix.data.len();
+    ix.data[len - 8..].copy_from_slice(&deadline.to_le_bytes());
+    ix.data.push(SLOT_KIND);
+    ix
+}
+
+fn swap_with_slot_deadline(pool: &Pool, user: &Pubkey, deadline: u64) -> Instruction {
+    let mut ix = pool.swap_ix(user, true, 1_000, 1);
+    // discriminator + is_x + amount + min 之后是 expiration
+    ix.data[18..26].copy_from_slice(&deadline.to_le_bytes());
+    ix.data.push(SLOT_KIND);
+    ix
+}
+
+#[test]
+fn test_deposit_before_slot_deadline_passes() {
+    process_with_slot_deadline(171, |p, u| deposit_with_slot_deadline(p, u, SLOT + 10), Check::success());
+}
+
+#[test]
+fn test_deposit_at_slot_deadline_passes() {
+    process_with_slot_deadline(171, |p, u| deposit_with_slot_deadline(p, u, SLOT), Check::success());
+}
+
+#[test]
+fn test_deposit_after_slot_deadline_is_rejected() {
+    process_with_slot_deadline(
+        171,
+        |p, u| deposit_with_slot_deadline(p, u, SLOT - 1),
+        Check::err(amm_err(AmmError::OrderExpired)),
+    );
+}
+
+#[test]
+fn test_swap_before_slot_deadline_passes() {
+    process_with_slot_deadline(172, |p, u| swap_with_slot_deadline(p, u, SLOT + 10), Check::success());
+}
+
+#[test]
+fn test_swap_after_slot_deadline_is_rejected() {
+    process_with_slot_deadline(
+        172,
+        |p, u| swap_with_slot_deadline(p, u, SLOT - 1),
+        Check::err(amm_err(AmmError::OrderExpired)),
+    );
+}
+
+#[test]
+fn test_unknown_expiration_kind_is_rejected() {
+    process_with_slot_deadline(
+        173,
+        |p, u| {
+            let mut ix = deposit_with_slot_deadline(p, u, SLOT + 10);
+            *ix.data.last_mut().unwrap() = 2;
+            ix
+        },
+        Check::err(ProgramError::InvalidInstructionData),
+    );
+}
@@ -0,0 +1,128 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::state::LP_DECIMALS,
+    common::*,
+    mollusk_svm::result::{Check, ProgramResult},
+    mollusk_svm_programs_token::{associated_token, token, token2022},
+    solana_account::Account,
+    solana_sdk::{program_error::ProgramError, pubkey::Pubkey},
+    spl_associated_token_account::instruction::create_associated_token_account,
+    spl_token_2022::error::TokenError,
+};
+
+const SEED: u64 = 11;
+const FEE: u16 = 30;
+
+/// soulbound 池子：LP 可以正常存入/取出，但不能在用户之间转让
+#[test]
+fn test_soulbound_lp_cannot_be_transferred() {
+    let mollusk = setup_mollusk();
+    let (token_program, token_program_account) = token2022::keyed_account();
+    let (ata_program, ata_program_account) = associated_token::keyed_account();
+
+    let user = Pubkey::new_unique();
+    let recipient = Pubkey::new_unique();
+    let superadmin = Pubkey::new_unique();
+    let mint_x = Pubkey::new_unique();
+    let mint_y = Pubkey::new_unique();
+    let (x, y) = (4_000_000u64, 1_000_000u64);
+
+    let (global_config, global_bump) = global_config_pda();
+    let (config, _) = config_pda(SEED, &mint_x, &mint_y);
+    let (mint_lp, _) = mint_lp_pda(&config);
+    let user_lp = ata(&user, &mint_lp, &token_program);
+    let recipient_lp = ata(&recipient, &mint_lp, &token_program);
+
+    let accounts = vec![
+        (user, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (recipient, create_system_account(LAMPORTS_PER_SOL)),
+        (mint_lp, Account::default()),
+        (config, Account::default()),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (ata_program, ata_program_account),
+        (global_config, create_global_config_account(&mollusk, &superadmin, FEE, global_bump)),
+        (mint_x, create_mint_account(&superadmin, 6, 1_000_000_000, &token_program)),
+        (mint_y, create_mint_account(&superadmin, 6, 1_000_000_000, &token_program)),
+        (ata(&config, &mint_x, &token_program), create_token_account(&mint_x, &config, 0, &token_program)),
+        (ata(&config, &mint_y, &token_program), create_token_account(&mint_y, &config, 0, &token_program)),
+        (ata(&user, &mint_x, &token_program), create_token_account(&mint_x, &user, x, &token_program)),
+        (ata(&user, &mint_y, &token_program), create_token_account(&mint_y, &user, y, &token_program)),
+        (user_lp, Account::default()),
+        (recipient_lp, Account::default()),
+    ];
+
+    // 初始化 soulbound 池子，通过 ATA program 创建 LP 账户（Token-2022 会自动加上 NonTransferableAccount 扩展），再存款
+    let result = mollusk.process_instruction_chain(
+        &[
+            initialize_soulbound_ix(&user, SEED, FEE, &mint_x, &mint_y, &token_program),
+            create_associated_token_account(&user, &user, &mint_lp, &token_program),
+            create_associated_token_account(&user, &recipient, &mint_lp, &token_program),
+            deposit_ix(&user, &config, &mint_x, &mint_y, 1, x, y, &token_program),
+        ],
+        &accounts,
+    );
+    assert_eq!(result.program_result, ProgramResult::Success);
+    let accounts = result.resulting_accounts;
+
+    let lp = token_amount(&accounts.iter().find(|(key, _)| key == &user_lp).unwrap().1);
+    assert!(lp > 0);
+
+    // LP 转让被 Token-2022 拒绝
+    let transfer = spl_token_2022::instruction::transfer_checked(
+        &token_program,
+        &user_lp,
+        &mint_lp,
+        &recipient_lp,
+        &user,
+        &[],
+        lp,
+        LP_DECIMALS,
+    )
+    .unwrap();
+    mollusk.process_and_validate_instruction(
+        &transfer,
+        &accounts,
+        &[Check::err(ProgramError::Custom(TokenError::NonTransferable as u32))],
+    );
+
+    // 取款（销毁 LP）不受影响
+    let result = mollusk.process_and_validate_instruction(
+        &withdraw_ix(&user, &config, &mint_x, &mint_y, lp, 1, 1, &token_program),
+        &accounts,
+        &[Check::success()],
+    );
+    assert_eq!(token_amount(result.get_account(&user_lp).unwrap()), 0);
+}
+
+#[test]
+fn test_soulbound_requires_token_2022() {
+    let mollusk = setup_mollusk();
+    let (token_program, token_program_account) = token::keyed_account();
+
+    let user = Pubkey::new_unique();
+    let superadmin = Pubkey::new_unique();
+    let mint_x = Pubkey::new_unique();
+    let mint_y = Pubkey::new_unique();
+
+    let (global_config, global_bump) = global_config_pda();
+    let (config, _) = config_pda(SEED, &mint_x, &mint_y);
+    let (mint_lp, _) = mint_lp_pda(&config);
+
+    let accounts = vec![
+        (user, create_system_account(10 * LAMPORTS_PER_SOL)),
+        (mint_lp, Account::default()),
+        (config, Account::default()),
+        (system_program::id(), create_system_program_account()),
+        (token_program, token_program_account),
+        (global_config, create_global_config_account(&mollusk, &superadmin, FEE, global_bump)),
+    ];
+    mollusk.process_and_validate_instruction(
+        &initialize_soulbound_ix(&user, SEED, FEE, &mint_x, &mint_y, &token_program),
+        &accounts,
+        &[Check::err(ProgramError::IncorrectProgramId)],
+    );
+}
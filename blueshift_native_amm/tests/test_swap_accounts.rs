@@ -0,0 +1,81 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::errors::AmmError,
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    solana_account::Account,
+    solana_sdk::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+
+fn setup(seed: u64, user: &Pubkey, other: &Pubkey) -> (Mollusk, Pool, Vec<(Pubkey, Account)>) {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(seed);
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(user, RESERVE, RESERVE, 0));
+    accounts.extend(pool.user_accounts(other, RESERVE, RESERVE, 0));
+    (mollusk, pool, accounts)
+}
+
+/// 输出 ATA 不属于签名的 user
+#[test]
+fn test_swap_rejects_user_ata_of_other_owner() {
+    let (user, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (mollusk, pool, accounts) = setup(276, &user, &other);
+
+    let mut ix = pool.swap_ix(&user, true, 10_000, 1);
+    ix.accounts[2].pubkey = pool.user_y(&other);
+    mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::err(ProgramError::InvalidAccountOwner)]);
+}
+
+/// user_y_ata 位置传入 user 自己的另一个 X 代币账户（不是传反，而是两个都是 X）
+#[test]
+fn test_swap_rejects_user_ata_of_wrong_mint() {
+    let (user, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (mollusk, pool, mut accounts) = setup(277, &user, &other);
+
+    // 再给 user 一个 X 代币账户，地址不同以免触发 DuplicateAccount
+    let second_x = Pubkey::new_unique();
+    let template = accounts.iter().find(|(key, _)| key == &pool.user_x(&user)).unwrap().1.clone();
+    accounts.push((second_x, template));
+
+    let mut ix = pool.swap_ix(&user, true, 10_000, 1);
+    ix.accounts[2].pubkey = second_x;
+    mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::err(ProgramError::InvalidAccountData)]);
+}
+
+/// config 的数据完全相同，但由其他程序拥有：fee / mint 字段不可信
+#[test]
+fn test_swap_rejects_config_owned_by_other_program() {
+    let (user, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (mollusk, pool, mut accounts) = setup(278, &user, &other);
+
+    let mut spoofed = accounts.iter().find(|(key, _)| key == &pool.config).unwrap().1.clone();
+    spoofed.owner = Pubkey::new_unique();
+    set_account(&mut accounts, &pool.config, spoofed);
+
+    mollusk.process_and_validate_instruction(
+        &pool.swap_ix(&user, true, 10_000, 1),
+        &accounts,
+        &[Check::err(ProgramError::InvalidAccountOwner)],
+    );
+}
+
+/// 攻击者把自己持有的 X 代币账户（余额不少于储备）当作 vault_x：输入会留在自己手里，输出却由真正的 vault_y 支付
+#[test]
+fn test_swap_rejects_foreign_vault_in() {
+    let (user, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (mollusk, pool, mut accounts) = setup(337, &user, &other);
+
+    let fake_vault = Pubkey::new_unique();
+    accounts.push((fake_vault, create_token_account(&pool.mint_x, &user, 2 * RESERVE, &pool.token_program)));
+
+    let mut ix = pool.swap_ix(&user, true, 10_000, 1);
+    ix.accounts[3].pubkey = fake_vault;
+    mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::err(amm_err(AmmError::InvalidVault))]);
+}
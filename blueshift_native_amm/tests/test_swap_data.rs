@@ -0,0 +1,70 @@
+use blueshift_native_amm::{errors::AmmError, instructions::{ExpirationKind, SwapInstructionData}};
+use pinocchio::program_error::ProgramError;
+
+fn data(is_x: bool, amount: u64, min: u64, expiration: i64) -> Vec<u8> {
+    let mut data = vec![is_x as u8];
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&min.to_le_bytes());
+    data.extend_from_slice(&expiration.to_le_bytes());
+    // exact_out + wrap_sol + 对齐填充
+    data.resize(32, 0);
+    data
+}
+
+fn fixed_fields(parsed: &SwapInstructionData) -> (bool, bool, bool, u64, u64, i64) {
+    (parsed.is_x, parsed.exact_out, parsed.wrap_sol, parsed.amount, parsed.min, parsed.expiration)
+}
+
+#[test]
+fn test_fields_are_read_from_fixed_offsets() {
+    let bytes = data(true, 1, 2, -3);
+    let parsed = SwapInstructionData::try_from(bytes.as_slice()).unwrap();
+    assert_eq!(fixed_fields(&parsed), (true, false, false, 1, 2, -3));
+    assert!(parsed.downstream_data.is_empty());
+}
+
+/// 固定字段之后可以追加 expiration_kind 和下游数据，固定字段的解析结果不变
+#[test]
+fn test_longer_buffer_parses_the_same_fixed_fields() {
+    let bytes = data(false, 10, 20, 30);
+    let expected = fixed_fields(&SwapInstructionData::try_from(bytes.as_slice()).unwrap());
+
+    let mut longer = bytes.clone();
+    longer.push(ExpirationKind::Slot as u8);
+    longer.extend_from_slice(&[7, 8, 9]);
+    let parsed = SwapInstructionData::try_from(longer.as_slice()).unwrap();
+    assert_eq!(fixed_fields(&parsed), expected);
+    assert_eq!(parsed.downstream_data, &[7, 8, 9]);
+}
+
+#[test]
+fn test_short_buffer_is_rejected() {
+    let bytes = data(true, 1, 1, 0);
+    for len in [0, 25, 31] {
+        assert_eq!(
+            SwapInstructionData::try_from(&bytes[..len]).err(),
+            Some(ProgramError::InvalidInstructionData)
+        );
+    }
+}
+
+/// 追加的字节不是被静默忽略的：未知的 expiration_kind 仍然被拒绝
+#[test]
+fn test_unknown_expiration_kind_is_rejected() {
+    let mut bytes = data(true, 1, 1, 0);
+    bytes.push(2);
+    assert_eq!(
+        SwapInstructionData::try_from(bytes.as_slice()).err(),
+        Some(ProgramError::InvalidInstructionData)
+    );
+}
+
+#[test]
+fn test_zero_amounts_are_rejected() {
+    for bytes in [data(true, 0, 1, 0), data(true, 1, 0, 0)] {
+        assert_eq!(
+            SwapInstructionData::try_from(bytes.as_slice()).err(),
+            Some(ProgramError::from(AmmError::ZeroAmount))
+        );
+    }
+}
@@ -0,0 +1,41 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::errors::AmmError,
+    common::*,
+    mollusk_svm::result::Check,
+    solana_sdk::pubkey::Pubkey,
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+
+fn swap_with_reversed_atas(seed: u64, is_x: bool) {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(seed);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, RESERVE, RESERVE, 0));
+
+    // 交换 user_x_ata 与 user_y_ata 的位置：若不校验方向，is_x 的扣款会落在 Y 上
+    let mut ix = pool.swap_ix(&user, is_x, 10_000, 1);
+    ix.accounts.swap(1, 2);
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &accounts,
+        &[Check::err(amm_err(AmmError::SwapDirectionMismatch))],
+    );
+}
+
+#[test]
+fn test_x_to_y_with_reversed_atas_is_rejected() {
+    swap_with_reversed_atas(181, true);
+}
+
+#[test]
+fn test_y_to_x_with_reversed_atas_is_rejected() {
+    swap_with_reversed_atas(182, false);
+}
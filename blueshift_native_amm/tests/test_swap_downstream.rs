@@ -0,0 +1,96 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::errors::AmmError,
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    solana_account::Account,
+    solana_sdk::{instruction::{AccountMeta, Instruction}, program_error::ProgramError, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+const AMOUNT_IN: u64 = 10_000;
+/// 约 9_870 的输出中转交给下游的数量
+const HANDOFF: u64 = 9_000;
+
+/// 池子 + 用户 + 一个“借贷市场”的 Y 代币账户；superadmin 已知，便于设置白名单
+fn setup(seed: u64, superadmin: &Pubkey, user: &Pubkey, market: &Pubkey) -> (Mollusk, Pool, Vec<(Pubkey, Account)>) {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(seed);
+    let (global_config, bump) = global_config_pda();
+
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    set_account(&mut accounts, &global_config, create_global_config_account(&mollusk, superadmin, FEE, bump));
+    accounts.push((*superadmin, create_system_account(LAMPORTS_PER_SOL)));
+    accounts.extend(pool.user_accounts(user, RESERVE, 0, 0));
+    accounts.push((*market, create_token_account(&pool.mint_y, &Pubkey::new_unique(), 0, &pool.token_program)));
+    (mollusk, pool, accounts)
+}
+
+/// X -> Y，输出先落在 user_y，再由下游程序（这里用 SPL Token 的 Transfer 模拟存入借贷市场）取走
+fn swap_with_downstream(pool: &Pool, user: &Pubkey, market: &Pubkey, downstream: &Pubkey) -> Instruction {
+    let mut ix = pool.swap_ix(user, true, AMOUNT_IN, HANDOFF);
+    // expiration_kind = Timestamp，之后是转发给下游的指令数据：Transfer { amount }
+    ix.data.push(0);
+    ix.data.push(3);
+    ix.data.extend_from_slice(&HANDOFF.to_le_bytes());
+    ix.accounts.extend([
+        AccountMeta::new_readonly(*downstream, false),
+        AccountMeta::new(pool.user_y(user), false),
+        AccountMeta::new(*market, false),
+        AccountMeta::new_readonly(*user, true),
+    ]);
+    ix
+}
+
+fn allow_downstream_ix(superadmin: &Pubkey, program: &Pubkey) -> Instruction {
+    let mut value = vec![0u8];
+    value.extend_from_slice(program.as_ref());
+    set_global_config_ix(superadmin, 4, &value)
+}
+
+#[test]
+fn test_swap_output_is_handed_to_downstream_program() {
+    let (superadmin, user, market) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let (mollusk, pool, accounts) = setup(211, &superadmin, &user, &market);
+
+    let result = mollusk.process_and_validate_instruction_chain(
+        &[
+            (&allow_downstream_ix(&superadmin, &pool.token_program), &[Check::success()]),
+            (&swap_with_downstream(&pool, &user, &market, &pool.token_program), &[Check::success()]),
+        ],
+        &accounts,
+    );
+
+    let market_y = token_amount(result.get_account(&market).unwrap());
+    let user_y = token_amount(result.get_account(&pool.user_y(&user)).unwrap());
+    let vault_y = token_amount(result.get_account(&pool.vault_y).unwrap());
+    assert_eq!(market_y, HANDOFF);
+    // 中转账户只留下转交之后的零头
+    assert_eq!(market_y + user_y, RESERVE - vault_y);
+}
+
+#[test]
+fn test_downstream_program_must_be_whitelisted() {
+    let (superadmin, user, market) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let (mollusk, pool, accounts) = setup(212, &superadmin, &user, &market);
+
+    mollusk.process_and_validate_instruction(
+        &swap_with_downstream(&pool, &user, &market, &pool.token_program),
+        &accounts,
+        &[Check::err(amm_err(AmmError::DownstreamProgramNotAllowed))],
+    );
+}
+
+#[test]
+fn test_downstream_data_without_program_is_rejected() {
+    let (superadmin, user, market) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let (mollusk, pool, accounts) = setup(213, &superadmin, &user, &market);
+
+    let mut ix = pool.swap_ix(&user, true, AMOUNT_IN, HANDOFF);
+    ix.data.extend_from_slice(&[0, 3]);
+    mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::err(ProgramError::InvalidInstructionData)]);
+}
@@ -0,0 +1,41 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::errors::AmmError,
+    common::*,
+    mollusk_svm::result::Check,
+    solana_sdk::pubkey::Pubkey,
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+
+fn swap_on_pool(seed: u64, reserve_x: u64, reserve_y: u64, is_x: bool) {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(seed);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, reserve_x, reserve_y, 0);
+    accounts.extend(pool.user_accounts(&user, RESERVE, RESERVE, 0));
+
+    mollusk.process_and_validate_instruction(
+        &pool.swap_ix(&user, is_x, 10_000, 1),
+        &accounts,
+        &[Check::err(amm_err(AmmError::EmptyPool))],
+    );
+}
+
+/// 初始化后还没有存款的池子：给出 EmptyPool，而不是曲线内部的错误
+#[test]
+fn test_swap_on_unfunded_pool_is_rejected() {
+    swap_on_pool(314, 0, 0, true);
+}
+
+/// 只有一侧为 0 也不能 swap（两个方向都检查）
+#[test]
+fn test_swap_with_one_empty_side_is_rejected() {
+    swap_on_pool(315, RESERVE, 0, true);
+    swap_on_pool(316, 0, RESERVE, true);
+    swap_on_pool(317, RESERVE, 0, false);
+}
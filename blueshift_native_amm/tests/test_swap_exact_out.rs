@@ -0,0 +1,88 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{curve::Curve, errors::AmmError},
+    common::*,
+    mollusk_svm::result::Check,
+    solana_sdk::{instruction::Instruction, program_error::ProgramError, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+const AMOUNT_OUT: u64 = 10_000;
+
+/// X -> Y，amount 为期望输出，min 为最大输入
+fn swap_exact_out_ix(pool: &Pool, user: &Pubkey, amount_out: u64, max_in: u64) -> Instruction {
+    let mut ix = pool.swap_ix(user, true, amount_out, max_in);
+    // discriminator + is_x + amount + min + expiration 之后是 exact_out
+    ix.data[26] = 1;
+    ix
+}
+
+fn required_in() -> u64 {
+    Curve::for_swap(RESERVE, RESERVE, true, FEE).amount_in(AMOUNT_OUT).unwrap()
+}
+
+#[test]
+fn test_swap_exact_out_receives_exact_amount() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(261);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, RESERVE, 0, 0));
+
+    let result = mollusk.process_and_validate_instruction(
+        &swap_exact_out_ix(&pool, &user, AMOUNT_OUT, required_in()),
+        &accounts,
+        &[Check::success()],
+    );
+
+    assert_eq!(token_amount(result.get_account(&pool.user_y(&user)).unwrap()), AMOUNT_OUT);
+    assert_eq!(token_amount(result.get_account(&pool.user_x(&user)).unwrap()), RESERVE - required_in());
+    assert_eq!(token_amount(result.get_account(&pool.vault_x).unwrap()), RESERVE + required_in());
+}
+
+#[test]
+fn test_swap_exact_out_rejects_input_above_max() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(262);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, RESERVE, 0, 0));
+
+    mollusk.process_and_validate_instruction(
+        &swap_exact_out_ix(&pool, &user, AMOUNT_OUT, required_in() - 1),
+        &accounts,
+        &[Check::err(amm_err(AmmError::SlippageExceeded))],
+    );
+}
+
+#[test]
+fn test_swap_exact_out_rejects_draining_vault() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(263);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, RESERVE, 0, 0));
+
+    mollusk.process_and_validate_instruction(
+        &swap_exact_out_ix(&pool, &user, RESERVE, u64::MAX),
+        &accounts,
+        &[Check::err(ProgramError::InvalidArgument)],
+    );
+}
+
+#[test]
+fn test_swap_rejects_unknown_exact_out_flag() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(264);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, RESERVE, 0, 0));
+
+    let mut ix = swap_exact_out_ix(&pool, &user, AMOUNT_OUT, required_in());
+    ix.data[26] = 2;
+    mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::err(ProgramError::InvalidInstructionData)]);
+}
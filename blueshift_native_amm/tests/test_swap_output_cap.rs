@@ -0,0 +1,81 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::errors::AmmError,
+    common::*,
+    mollusk_svm::{result::{Check, ProgramResult}, Mollusk},
+    solana_account::Account,
+    solana_sdk::pubkey::Pubkey,
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+const MAX_SWAP_OUT: u64 = 50_000;
+
+/// 池子 authority 为 `authority`，user 持有足够的 X
+fn capped_pool_accounts(mollusk: &Mollusk, pool: &Pool, authority: &Pubkey, user: &Pubkey) -> Vec<(Pubkey, Account)> {
+    let mut accounts = pool.accounts(mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    set_account(&mut accounts, &pool.config, pool.config_account_with_authority(mollusk, FEE, authority));
+    pool.sync_reserves(&mut accounts);
+    accounts.push((*authority, create_system_account(LAMPORTS_PER_SOL)));
+    accounts.extend(pool.user_accounts(user, RESERVE, 0, 0));
+    accounts
+}
+
+#[test]
+fn test_output_cap_allows_small_swap() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(61);
+    let authority = Pubkey::new_unique();
+    let user = Pubkey::new_unique();
+    let accounts = capped_pool_accounts(&mollusk, &pool, &authority, &user);
+
+    // 约 9_870 的输出，低于上限
+    let result = mollusk.process_instruction_chain(
+        &[
+            pool.set_config_ix(&authority, 0, &MAX_SWAP_OUT.to_le_bytes()),
+            pool.swap_ix(&user, true, 10_000, 1),
+        ],
+        &accounts,
+    );
+    assert_eq!(result.program_result, ProgramResult::Success);
+    assert!(token_amount(result.get_account(&pool.user_y(&user)).unwrap()) > 0);
+}
+
+#[test]
+fn test_output_cap_rejects_large_swap() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(62);
+    let authority = Pubkey::new_unique();
+    let user = Pubkey::new_unique();
+    let accounts = capped_pool_accounts(&mollusk, &pool, &authority, &user);
+
+    // 约 166_000 的输出，超过上限；滑点参数本身允许这笔交易
+    mollusk.process_and_validate_instruction_chain(
+        &[
+            (&pool.set_config_ix(&authority, 0, &MAX_SWAP_OUT.to_le_bytes()), &[Check::success()]),
+            (
+                &pool.swap_ix(&user, true, 200_000, 1),
+                &[Check::err(amm_err(AmmError::OutputCapExceeded))],
+            ),
+        ],
+        &accounts,
+    );
+}
+
+#[test]
+fn test_only_authority_can_set_output_cap() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(63);
+    let authority = Pubkey::new_unique();
+    let user = Pubkey::new_unique();
+    let accounts = capped_pool_accounts(&mollusk, &pool, &authority, &user);
+
+    mollusk.process_and_validate_instruction(
+        &pool.set_config_ix(&user, 0, &MAX_SWAP_OUT.to_le_bytes()),
+        &accounts,
+        &[Check::err(amm_err(AmmError::InvalidAuthority))],
+    );
+}
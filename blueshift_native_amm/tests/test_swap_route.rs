@@ -0,0 +1,127 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::errors::AmmError,
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    solana_account::Account,
+    solana_sdk::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+const AMOUNT: u64 = 10_000;
+
+/// 两个池子 A/B 和 B/C 共用中间代币 B；user 持有 AMOUNT 个 A
+fn setup(mollusk: &Mollusk, seed: u64, user: &Pubkey) -> (Pool, Pool, Vec<(Pubkey, Account)>) {
+    let first = Pool::new(seed);
+    let second = Pool::new_with_mints(seed + 1, first.mint_y, Pubkey::new_unique(), first.token_program);
+
+    // 两个池子共享的 mint、global config、user 和中间 ATA 只保留第一份
+    let mut accounts = first.accounts(mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(first.user_accounts(user, AMOUNT, 0, 0));
+    for (key, account) in second
+        .accounts(mollusk, FEE, RESERVE, RESERVE, RESERVE)
+        .into_iter()
+        .chain(second.user_accounts(user, 0, 0, 0))
+    {
+        if !accounts.iter().any(|(k, _)| *k == key) {
+            accounts.push((key, account));
+        }
+    }
+    (first, second, accounts)
+}
+
+#[test]
+fn test_swap_route_matches_sequential_swaps() {
+    let mollusk = setup_mollusk();
+    let user = Pubkey::new_unique();
+    let (first, second, accounts) = setup(&mollusk, 300, &user);
+
+    // 逐个池子 swap 的结果作为基准
+    let hop = mollusk.process_and_validate_instruction(
+        &first.swap_ix(&user, true, AMOUNT, 1),
+        &accounts,
+        &[Check::success()],
+    );
+    let middle = token_amount(hop.get_account(&first.user_y(&user)).unwrap());
+    let hop = mollusk.process_and_validate_instruction(
+        &second.swap_ix(&user, true, middle, 1),
+        &hop.resulting_accounts,
+        &[Check::success()],
+    );
+    let expected = token_amount(hop.get_account(&second.user_y(&user)).unwrap());
+
+    let result = mollusk.process_and_validate_instruction(
+        &swap_route_ix(&user, &[(&first, true), (&second, true)], AMOUNT, expected),
+        &accounts,
+        &[Check::success()],
+    );
+    assert_eq!(token_amount(result.get_account(&first.user_x(&user)).unwrap()), 0);
+    // 中间代币全部进入第二个池子
+    assert_eq!(token_amount(result.get_account(&first.user_y(&user)).unwrap()), 0);
+    assert_eq!(token_amount(result.get_account(&second.vault_x).unwrap()), RESERVE + middle);
+    assert_eq!(token_amount(result.get_account(&second.user_y(&user)).unwrap()), expected);
+}
+
+#[test]
+fn test_swap_route_checks_slippage_on_final_output_only() {
+    let mollusk = setup_mollusk();
+    let user = Pubkey::new_unique();
+    let (first, second, accounts) = setup(&mollusk, 302, &user);
+
+    // min_out 远大于第一跳的输出，只要最终输出达不到就失败
+    mollusk.process_and_validate_instruction(
+        &swap_route_ix(&user, &[(&first, true), (&second, true)], AMOUNT, AMOUNT),
+        &accounts,
+        &[Check::err(amm_err(AmmError::SlippageExceeded))],
+    );
+}
+
+#[test]
+fn test_swap_route_rejects_disconnected_hops() {
+    let mollusk = setup_mollusk();
+    let user = Pubkey::new_unique();
+    let (first, second, accounts) = setup(&mollusk, 304, &user);
+
+    // 第二跳方向相反：它从 C 的 ATA 扣款，而第一跳的输出在 B 的 ATA
+    mollusk.process_and_validate_instruction(
+        &swap_route_ix(&user, &[(&first, true), (&second, false)], AMOUNT, 1),
+        &accounts,
+        &[Check::err(amm_err(AmmError::RouteMismatch))],
+    );
+}
+
+#[test]
+fn test_swap_route_hop_count_must_match_accounts() {
+    let mollusk = setup_mollusk();
+    let user = Pubkey::new_unique();
+    let (first, second, accounts) = setup(&mollusk, 306, &user);
+
+    let mut ix = swap_route_ix(&user, &[(&first, true), (&second, true)], AMOUNT, 1);
+    ix.data[1] = 1;
+    ix.data.remove(3);
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &accounts,
+        &[Check::err(ProgramError::InvalidInstructionData)],
+    );
+}
+
+/// 第二跳的 vault_x 换成 user 自己持有的 B 代币账户：中间代币会留在 user 手里，输出却由真正的 vault_y 支付
+#[test]
+fn test_swap_route_rejects_foreign_hop_vault() {
+    let mollusk = setup_mollusk();
+    let user = Pubkey::new_unique();
+    let (first, second, mut accounts) = setup(&mollusk, 338, &user);
+
+    let fake_vault = Pubkey::new_unique();
+    accounts.push((fake_vault, create_token_account(&second.mint_x, &user, 2 * RESERVE, &second.token_program)));
+
+    let mut ix = swap_route_ix(&user, &[(&first, true), (&second, true)], AMOUNT, 1);
+    let vault = ix.accounts.iter_mut().find(|meta| meta.pubkey == second.vault_x).unwrap();
+    vault.pubkey = fake_vault;
+    mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::err(amm_err(AmmError::InvalidVault))]);
+}
@@ -0,0 +1,117 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{errors::AmmError, instructions::NATIVE_MINT},
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    solana_account::Account,
+    solana_sdk::{instruction::Instruction, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+const AMOUNT: u64 = 10_000;
+
+/// SwapInstructionData 中 wrap_sol 的位置（指令数据第 0 字节是 discriminator）
+const WRAP_SOL_OFFSET: usize = 1 + 26;
+
+fn with_wrap_sol(mut ix: Instruction) -> Instruction {
+    ix.data[WRAP_SOL_OFFSET] = 1;
+    ix
+}
+
+fn lamports(accounts: &[(Pubkey, Account)], key: &Pubkey) -> u64 {
+    accounts.iter().find(|(k, _)| k == key).unwrap().1.lamports
+}
+
+/// X 侧是 WSOL 的池子，用户持有一个余额为 `user_wsol` 的 WSOL ATA 和 RESERVE 的 Y
+fn wsol_pool(mollusk: &Mollusk, seed: u64, user: &Pubkey, user_wsol: u64) -> (Pool, Vec<(Pubkey, Account)>) {
+    let pool = Pool::new_with_mints(seed, spl_token::native_mint::id(), Pubkey::new_unique(), spl_token::id());
+    let mut accounts = pool.accounts(mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    set_account(&mut accounts, &pool.mint_x, create_mint_account(&Pubkey::default(), 9, 0, &spl_token::id()));
+    set_account(&mut accounts, &pool.vault_x, create_native_token_account(&pool.config, RESERVE));
+    accounts.extend(pool.user_accounts(user, 0, RESERVE, 0));
+    set_account(&mut accounts, &pool.user_x(user), create_native_token_account(user, user_wsol));
+    (pool, accounts)
+}
+
+#[test]
+fn test_native_mint_matches_spl_token() {
+    assert_eq!(NATIVE_MINT, spl_token::native_mint::id().to_bytes());
+}
+
+/// 输入是 WSOL：用户的 SOL 直接被包装后转入金库，WSOL ATA 不需要预先有余额
+#[test]
+fn test_wrap_sol_input() {
+    let mollusk = setup_mollusk();
+    let user = Pubkey::new_unique();
+    let (pool, accounts) = wsol_pool(&mollusk, 324, &user, 0);
+
+    let result = mollusk.process_and_validate_instruction(
+        &with_wrap_sol(pool.swap_ix(&user, true, AMOUNT, 1)),
+        &accounts,
+        &[Check::success()],
+    );
+
+    assert_eq!(lamports(&result.resulting_accounts, &user), lamports(&accounts, &user) - AMOUNT);
+    assert_eq!(token_amount(result.get_account(&pool.user_x(&user)).unwrap()), 0);
+    assert_eq!(token_amount(result.get_account(&pool.vault_x).unwrap()), RESERVE + AMOUNT);
+    assert!(token_amount(result.get_account(&pool.user_y(&user)).unwrap()) > RESERVE);
+}
+
+/// 输出是 WSOL：用户的 WSOL ATA 被关闭，输出连同租金以 lamports 退还
+#[test]
+fn test_unwrap_sol_output() {
+    let mollusk = setup_mollusk();
+    let user = Pubkey::new_unique();
+    let (pool, accounts) = wsol_pool(&mollusk, 325, &user, 0);
+
+    let result = mollusk.process_and_validate_instruction(
+        &with_wrap_sol(pool.swap_ix(&user, false, AMOUNT, 1)),
+        &accounts,
+        &[Check::success()],
+    );
+
+    let amount_out = RESERVE - token_amount(result.get_account(&pool.vault_x).unwrap());
+    assert!(amount_out > 0);
+    assert_eq!(result.get_account(&pool.user_x(&user)).unwrap().lamports, 0);
+    assert_eq!(
+        lamports(&result.resulting_accounts, &user),
+        lamports(&accounts, &user) + lamports(&accounts, &pool.user_x(&user)) + amount_out
+    );
+}
+
+/// 解包会关闭用户的 WSOL ATA：账户里原有的 WSOL 不能被顺带解包，必须先清空
+#[test]
+fn test_unwrap_sol_output_rejects_non_empty_wsol_account() {
+    let mollusk = setup_mollusk();
+    let user = Pubkey::new_unique();
+    let (pool, accounts) = wsol_pool(&mollusk, 345, &user, AMOUNT);
+
+    mollusk.process_and_validate_instruction(
+        &with_wrap_sol(pool.swap_ix(&user, false, AMOUNT, 1)),
+        &accounts,
+        &[Check::err(amm_err(AmmError::WsolAccountNotEmpty))],
+    );
+}
+
+/// 没有 WSOL 的池子忽略 wrap_sol
+#[test]
+fn test_wrap_sol_ignored_for_non_wsol_pool() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(326);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, RESERVE, 0, 0));
+
+    let result = mollusk.process_and_validate_instruction(
+        &with_wrap_sol(pool.swap_ix(&user, true, AMOUNT, 1)),
+        &accounts,
+        &[Check::success()],
+    );
+
+    assert_eq!(lamports(&result.resulting_accounts, &user), lamports(&accounts, &user));
+    assert_eq!(token_amount(result.get_account(&pool.user_x(&user)).unwrap()), RESERVE - AMOUNT);
+}
@@ -0,0 +1,26 @@
+use blueshift_native_amm::instructions::helpers::total_value_locked;
+
+#[test]
+fn test_tvl_with_known_reserves_and_prices() {
+    // 3_000 X @ 2 + 1_000 Y @ 5 = 11_000
+    assert_eq!(total_value_locked(3_000, 1_000, 2, 5), 11_000);
+}
+
+#[test]
+fn test_tvl_ignores_zero_prices() {
+    assert_eq!(total_value_locked(3_000, 1_000, 0, 5), 5_000);
+    assert_eq!(total_value_locked(3_000, 1_000, 2, 0), 6_000);
+    assert_eq!(total_value_locked(3_000, 1_000, 0, 0), 0);
+}
+
+#[test]
+fn test_tvl_single_side_at_u64_max_does_not_overflow() {
+    let max = u64::MAX as u128;
+    assert_eq!(total_value_locked(u64::MAX, 0, u64::MAX, 0), max * max);
+}
+
+#[test]
+fn test_tvl_saturates_near_u128_limit() {
+    // 两侧各约 2^128，相加超过 u128::MAX
+    assert_eq!(total_value_locked(u64::MAX, u64::MAX, u64::MAX, u64::MAX), u128::MAX);
+}
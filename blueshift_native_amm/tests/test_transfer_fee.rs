@@ -0,0 +1,69 @@
+use blueshift_native_amm::instructions::helpers::TransferFee;
+use pinocchio::program_error::ProgramError;
+
+const OLDER: TransferFee = TransferFee { epoch: 0, maximum_fee: 1_000, basis_points: 100 };
+const NEWER: TransferFee = TransferFee { epoch: 10, maximum_fee: 50, basis_points: 500 };
+
+fn push_fee(data: &mut Vec<u8>, fee: &TransferFee) {
+    data.extend_from_slice(&fee.epoch.to_le_bytes());
+    data.extend_from_slice(&fee.maximum_fee.to_le_bytes());
+    data.extend_from_slice(&fee.basis_points.to_le_bytes());
+}
+
+/// Token-2022 Mint：基础数据填充到 165 字节，account type = Mint，之后是扩展 TLV
+fn mint_data(extensions: &[(u16, Vec<u8>)]) -> Vec<u8> {
+    let mut data = vec![0u8; 165];
+    data.push(1);
+    for (extension_type, value) in extensions {
+        data.extend_from_slice(&extension_type.to_le_bytes());
+        data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+        data.extend_from_slice(value);
+    }
+    data
+}
+
+fn transfer_fee_config() -> Vec<u8> {
+    let mut value = vec![0u8; 32 + 32 + 8];
+    push_fee(&mut value, &OLDER);
+    push_fee(&mut value, &NEWER);
+    value
+}
+
+#[test]
+fn test_base_mint_has_no_transfer_fee() {
+    assert_eq!(TransferFee::from_mint_data(&[0u8; 82], 0), Ok(None));
+    // NonTransferable (9) 等其他扩展不影响转账数量
+    assert_eq!(TransferFee::from_mint_data(&mint_data(&[(9, vec![])]), 0), Ok(None));
+}
+
+#[test]
+fn test_transfer_fee_is_selected_by_epoch() {
+    let data = mint_data(&[(9, vec![]), (1, transfer_fee_config())]);
+    assert_eq!(TransferFee::from_mint_data(&data, 9), Ok(Some(OLDER)));
+    assert_eq!(TransferFee::from_mint_data(&data, 10), Ok(Some(NEWER)));
+    assert_eq!(TransferFee::from_mint_data(&data, 11), Ok(Some(NEWER)));
+}
+
+#[test]
+fn test_malformed_transfer_fee_config_is_rejected() {
+    let data = mint_data(&[(1, vec![0u8; 10])]);
+    assert_eq!(TransferFee::from_mint_data(&data, 0), Err(ProgramError::InvalidAccountData));
+
+    // TLV 长度超出账户数据
+    let mut data = mint_data(&[(1, transfer_fee_config())]);
+    data.truncate(data.len() - 1);
+    assert_eq!(TransferFee::from_mint_data(&data, 0), Err(ProgramError::InvalidAccountData));
+}
+
+#[test]
+fn test_fee_rounds_up_and_is_capped() {
+    // 1% 向上取整
+    assert_eq!(OLDER.fee(1), Ok(1));
+    assert_eq!(OLDER.fee(10_000), Ok(100));
+    assert_eq!(OLDER.fee(10_001), Ok(101));
+    // 不超过 maximum_fee
+    assert_eq!(OLDER.fee(1_000_000_000), Ok(1_000));
+    assert_eq!(NEWER.fee(10_000), Ok(50));
+    assert_eq!(OLDER.fee(0), Ok(0));
+    assert_eq!(TransferFee { basis_points: 0, ..OLDER }.fee(10_000), Ok(0));
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::errors::AmmError,
+    common::*,
+    mollusk_svm::result::Check,
+    solana_sdk::pubkey::Pubkey,
+};
+
+const FEE: u16 = 30;
+
+#[test]
+fn test_deposit_rejects_vault_with_third_party_close_authority() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(11);
+    let user = Pubkey::new_unique();
+    let attacker = Pubkey::new_unique();
+
+    let mut accounts = pool.accounts(&mollusk, FEE, 1_000_000, 1_000_000, 1_000_000);
+    accounts.extend(pool.user_accounts(&user, 1_000_000, 1_000_000, 0));
+    set_account(
+        &mut accounts,
+        &pool.vault_x,
+        create_token_account_with_close_authority(&pool.mint_x, &pool.config, 1_000_000, &attacker, &pool.token_program),
+    );
+
+    mollusk.process_and_validate_instruction(
+        &pool.deposit_ix(&user, 1_000, 10_000, 10_000),
+        &accounts,
+        &[Check::err(amm_err(AmmError::InvalidVault))],
+    );
+}
+
+#[test]
+fn test_deposit_accepts_vault_with_config_close_authority() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(12);
+    let user = Pubkey::new_unique();
+
+    let mut accounts = pool.accounts(&mollusk, FEE, 1_000_000, 1_000_000, 1_000_000);
+    accounts.extend(pool.user_accounts(&user, 1_000_000, 1_000_000, 0));
+    set_account(
+        &mut accounts,
+        &pool.vault_x,
+        create_token_account_with_close_authority(&pool.mint_x, &pool.config, 1_000_000, &pool.config, &pool.token_program),
+    );
+
+    mollusk.process_and_validate_instruction(
+        &pool.deposit_ix(&user, 1_000, 10_000, 10_000),
+        &accounts,
+        &[Check::success()],
+    );
+}
+
+#[test]
+fn test_withdraw_rejects_vault_with_third_party_close_authority() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(13);
+    let user = Pubkey::new_unique();
+    let attacker = Pubkey::new_unique();
+
+    let mut accounts = pool.accounts(&mollusk, FEE, 1_000_000, 1_000_000, 1_000_000);
+    accounts.extend(pool.user_accounts(&user, 0, 0, 10_000));
+    set_account(
+        &mut accounts,
+        &pool.vault_y,
+        create_token_account_with_close_authority(&pool.mint_y, &pool.config, 1_000_000, &attacker, &pool.token_program),
+    );
+
+    mollusk.process_and_validate_instruction(
+        &pool.withdraw_ix(&user, 10_000, 1, 1),
+        &accounts,
+        &[Check::err(amm_err(AmmError::InvalidVault))],
+    );
+}
@@ -0,0 +1,89 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{curve::Curve, errors::AmmError, state::Config},
+    common::*,
+    mollusk_svm::{result::Check, Mollusk},
+    solana_account::Account,
+    solana_sdk::pubkey::Pubkey,
+};
+
+const FEE: u16 = 30;
+const AMOUNT_IN: u64 = 100_000;
+
+/// 80/20 池子：X 储备 4_000_000，Y 储备 1_000_000，现货价格为 1
+fn weighted_pool(mollusk: &Mollusk, pool: &Pool) -> Vec<(Pubkey, Account)> {
+    let mut accounts = pool.accounts(mollusk, FEE, 4_000_000, 1_000_000, 1_000_000);
+    let mut config = pool.config_account(mollusk, FEE);
+    unsafe { Config::from_bytes_unchecked_mut(&mut config.data) }.set_weight_x(8_000).unwrap();
+    set_account(&mut accounts, &pool.config, config);
+    pool.sync_reserves(&mut accounts);
+    accounts
+}
+
+fn quote(mollusk: &Mollusk, pool: &Pool, accounts: &[(Pubkey, Account)]) -> (u64, u16) {
+    let result = mollusk.process_and_validate_instruction(&pool.quote_ix(true, AMOUNT_IN), accounts, &[Check::success()]);
+    (
+        u64::from_le_bytes(result.return_data[0..8].try_into().unwrap()),
+        u16::from_le_bytes(result.return_data[8..10].try_into().unwrap()),
+    )
+}
+
+/// 与同样价值（现价 1 下总计 5_000_000）的 50/50 池子比较：80/20 池子的价格影响更大
+#[test]
+fn test_80_20_quote_against_50_50() {
+    let mollusk = setup_mollusk();
+    let weighted = Pool::new(330);
+    let equal = Pool::new(331);
+
+    let (weighted_out, weighted_impact) = quote(&mollusk, &weighted, &weighted_pool(&mollusk, &weighted));
+    let (equal_out, equal_impact) =
+        quote(&mollusk, &equal, &equal.accounts(&mollusk, FEE, 2_500_000, 2_500_000, 1_000_000));
+
+    let curve = Curve::for_weighted_swap(4_000_000, 1_000_000, true, FEE, 8_000);
+    assert_eq!(weighted_out, curve.amount_out(AMOUNT_IN).unwrap());
+    assert_eq!(weighted_impact, curve.price_impact_bps(AMOUNT_IN).unwrap());
+    assert!(weighted_out < equal_out);
+    assert!(weighted_impact > equal_impact);
+}
+
+/// Swap 与 Quote 使用同一条加权曲线
+#[test]
+fn test_weighted_swap_matches_quote() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(332);
+    let user = Pubkey::new_unique();
+    let mut accounts = weighted_pool(&mollusk, &pool);
+    let (quoted, _) = quote(&mollusk, &pool, &accounts);
+    accounts.extend(pool.user_accounts(&user, AMOUNT_IN, 0, 0));
+
+    let result = mollusk.process_and_validate_instruction(
+        &pool.swap_ix(&user, true, AMOUNT_IN, quoted),
+        &accounts,
+        &[Check::success()],
+    );
+    assert_eq!(token_amount(result.get_account(&pool.user_y(&user)).unwrap()), quoted);
+}
+
+/// 单边存取款的内部兑换按 x * y = k 求解，加权池子拒绝
+#[test]
+fn test_weighted_pool_rejects_single_sided() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(333);
+    let user = Pubkey::new_unique();
+    let mut accounts = weighted_pool(&mollusk, &pool);
+    accounts.extend(pool.user_accounts(&user, AMOUNT_IN, 0, 1_000));
+
+    for ix in [
+        pool.deposit_single_sided_ix(&user, true, AMOUNT_IN, 1),
+        pool.withdraw_single_sided_ix(&user, true, 1_000, 1),
+    ] {
+        mollusk.process_and_validate_instruction(
+            &ix,
+            &accounts,
+            &[Check::err(amm_err(AmmError::WeightedPoolUnsupported))],
+        );
+    }
+}
@@ -0,0 +1,82 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    common::*,
+    mollusk_svm::result::Check,
+    solana_sdk::{instruction::Instruction, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+const USER_LP: u64 = 10_000;
+
+/// expiration_kind = Timestamp，之后是 close_lp_ata 标志
+fn withdraw_and_close(pool: &Pool, user: &Pubkey, amount: u64) -> Instruction {
+    let mut ix = pool.withdraw_ix(user, amount, 1, 1);
+    ix.data.extend_from_slice(&[0, 1]);
+    ix
+}
+
+#[test]
+fn test_full_withdraw_closes_lp_ata() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(285);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, 0, 0, USER_LP));
+
+    let lp_rent = accounts.iter().find(|(key, _)| key == &pool.user_lp(&user)).unwrap().1.lamports;
+    let user_lamports = accounts.iter().find(|(key, _)| key == &user).unwrap().1.lamports;
+
+    let result = mollusk.process_and_validate_instruction(
+        &withdraw_and_close(&pool, &user, USER_LP),
+        &accounts,
+        &[Check::success()],
+    );
+
+    // LP ATA 被关闭，租金退还给用户
+    assert_eq!(result.get_account(&pool.user_lp(&user)).unwrap().lamports, 0);
+    assert_eq!(result.get_account(&user).unwrap().lamports, user_lamports + lp_rent);
+    assert!(token_amount(result.get_account(&pool.user_x(&user)).unwrap()) > 0);
+}
+
+#[test]
+fn test_partial_withdraw_keeps_lp_ata() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(286);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, 0, 0, USER_LP));
+
+    // 还有余额时忽略 close_lp_ata，取款照常完成
+    let result = mollusk.process_and_validate_instruction(
+        &withdraw_and_close(&pool, &user, USER_LP / 2),
+        &accounts,
+        &[Check::success()],
+    );
+
+    let lp_ata = result.get_account(&pool.user_lp(&user)).unwrap();
+    assert!(lp_ata.lamports > 0);
+    assert_eq!(token_amount(lp_ata), USER_LP / 2);
+}
+
+#[test]
+fn test_full_withdraw_without_flag_keeps_lp_ata() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(287);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, 0, 0, USER_LP));
+
+    let result = mollusk.process_and_validate_instruction(
+        &pool.withdraw_ix(&user, USER_LP, 1, 1),
+        &accounts,
+        &[Check::success()],
+    );
+
+    let lp_ata = result.get_account(&pool.user_lp(&user)).unwrap();
+    assert!(lp_ata.lamports > 0);
+    assert_eq!(token_amount(lp_ata), 0);
+}
@@ -0,0 +1,118 @@
+use blueshift_native_amm::{errors::AmmError, instructions::WithdrawInstructionData};
+use pinocchio::program_error::ProgramError;
+
+fn data(amount: u64, min_x: u64, min_y: u64, expiration: i64) -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(&amount.to_le_bytes());
+    data.extend_from_slice(&min_x.to_le_bytes());
+    data.extend_from_slice(&min_y.to_le_bytes());
+    data.extend_from_slice(&expiration.to_le_bytes());
+    data
+}
+
+#[test]
+fn test_fields_are_read_from_fixed_offsets() {
+    let parsed = WithdrawInstructionData::try_from(data(1, 2, 3, -4).as_slice()).unwrap();
+    assert_eq!((parsed.amount, parsed.min_x, parsed.min_y, parsed.expiration), (1, 2, 3, -4));
+    assert!(parsed.expected_lp_supply.is_none());
+}
+
+#[test]
+fn test_length_must_be_32_bytes_plus_known_suffixes() {
+    let bytes = data(1, 0, 0, 0);
+    assert_eq!(bytes.len(), 32);
+
+    assert_eq!(
+        WithdrawInstructionData::try_from(&bytes[..31]).err(),
+        Some(ProgramError::InvalidInstructionData)
+    );
+
+    // 单个末尾字节被当作 expiration_kind，未知的 kind 被拒绝
+    let mut longer = bytes.clone();
+    longer.push(2);
+    assert_eq!(
+        WithdrawInstructionData::try_from(longer.as_slice()).err(),
+        Some(ProgramError::InvalidInstructionData)
+    );
+}
+
+/// 倾斜的池子中用户可以接受某一侧取回 0
+#[test]
+fn test_zero_min_amounts_are_accepted() {
+    assert!(WithdrawInstructionData::try_from(data(1, 0, 5, 0).as_slice()).is_ok());
+    assert!(WithdrawInstructionData::try_from(data(1, 5, 0, 0).as_slice()).is_ok());
+    assert!(WithdrawInstructionData::try_from(data(1, 0, 0, 0).as_slice()).is_ok());
+}
+
+#[test]
+fn test_zero_amount_is_rejected() {
+    assert_eq!(
+        WithdrawInstructionData::try_from(data(0, 1, 1, 0).as_slice()).err(),
+        Some(ProgramError::from(AmmError::ZeroAmount))
+    );
+}
+
+#[test]
+fn test_close_lp_ata_flag_follows_expiration_kind() {
+    assert!(!WithdrawInstructionData::try_from(data(1, 0, 0, 0).as_slice()).unwrap().close_lp_ata);
+
+    // expiration_kind + close_lp_ata
+    let mut bytes = data(1, 0, 0, 0);
+    bytes.extend_from_slice(&[0, 1]);
+    assert!(WithdrawInstructionData::try_from(bytes.as_slice()).unwrap().close_lp_ata);
+
+    // ExpectedLpSupply + expiration_kind + close_lp_ata
+    let mut bytes = data(1, 0, 0, 0);
+    bytes.extend_from_slice(&100u64.to_le_bytes());
+    bytes.extend_from_slice(&50u16.to_le_bytes());
+    bytes.extend_from_slice(&[1, 1]);
+    let parsed = WithdrawInstructionData::try_from(bytes.as_slice()).unwrap();
+    assert!(parsed.close_lp_ata);
+    assert!(parsed.expected_lp_supply.is_some());
+
+    let mut bytes = data(1, 0, 0, 0);
+    bytes.extend_from_slice(&[0, 2]);
+    assert_eq!(
+        WithdrawInstructionData::try_from(bytes.as_slice()).err(),
+        Some(ProgramError::InvalidInstructionData)
+    );
+}
+
+#[test]
+fn test_exact_out_flag_follows_close_lp_ata() {
+    assert!(!WithdrawInstructionData::try_from(data(1, 0, 0, 0).as_slice()).unwrap().exact_out);
+
+    // expiration_kind + close_lp_ata + exact_out
+    let mut bytes = data(100, 5, 0, 0);
+    bytes.extend_from_slice(&[0, 0, 1]);
+    let parsed = WithdrawInstructionData::try_from(bytes.as_slice()).unwrap();
+    assert!(parsed.exact_out);
+    assert!(!parsed.close_lp_ata);
+
+    // ExpectedLpSupply + expiration_kind + close_lp_ata + exact_out
+    let mut bytes = data(100, 5, 5, 0);
+    bytes.extend_from_slice(&100u64.to_le_bytes());
+    bytes.extend_from_slice(&50u16.to_le_bytes());
+    bytes.extend_from_slice(&[1, 1, 1]);
+    let parsed = WithdrawInstructionData::try_from(bytes.as_slice()).unwrap();
+    assert!(parsed.exact_out && parsed.close_lp_ata);
+    assert!(parsed.expected_lp_supply.is_some());
+
+    let mut bytes = data(100, 5, 0, 0);
+    bytes.extend_from_slice(&[0, 0, 2]);
+    assert_eq!(
+        WithdrawInstructionData::try_from(bytes.as_slice()).err(),
+        Some(ProgramError::InvalidInstructionData)
+    );
+}
+
+/// exact-out 至少要有一侧的目标数量
+#[test]
+fn test_exact_out_requires_a_target() {
+    let mut bytes = data(100, 0, 0, 0);
+    bytes.extend_from_slice(&[0, 0, 1]);
+    assert_eq!(
+        WithdrawInstructionData::try_from(bytes.as_slice()).err(),
+        Some(ProgramError::from(AmmError::ZeroAmount))
+    );
+}
@@ -0,0 +1,94 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{curve::Curve, errors::AmmError},
+    common::*,
+    mollusk_svm::result::Check,
+    solana_sdk::{instruction::Instruction, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+const RESERVE_X: u64 = 3_000_000;
+const RESERVE_Y: u64 = 1_000_000;
+const SUPPLY: u64 = 1_000_000;
+const USER_LP: u64 = 100_000;
+
+/// expiration_kind = Timestamp，close_lp_ata = false，之后是 exact_out 标志
+fn withdraw_exact_out(pool: &Pool, user: &Pubkey, max_lp_in: u64, target_x: u64, target_y: u64) -> Instruction {
+    let mut ix = pool.withdraw_ix(user, max_lp_in, target_x, target_y);
+    ix.data.extend_from_slice(&[0, 0, 1]);
+    ix
+}
+
+/// 只销毁达到目标数量所需的 LP，余下的 LP 留在用户手里
+#[test]
+fn test_exact_out_burns_minimal_lp() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(311);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE_X, RESERVE_Y, SUPPLY);
+    accounts.extend(pool.user_accounts(&user, 0, 0, USER_LP));
+
+    let target_x = 12_345;
+    let curve = Curve::for_liquidity(RESERVE_X, RESERVE_Y, SUPPLY).unwrap();
+    let lp = curve.lp_for_withdraw(target_x, 0).unwrap();
+    let (x, y) = curve.withdraw_amounts(lp).unwrap();
+    assert!(x >= target_x);
+
+    let result = mollusk.process_and_validate_instruction(
+        &withdraw_exact_out(&pool, &user, USER_LP, target_x, 0),
+        &accounts,
+        &[Check::success()],
+    );
+
+    assert_eq!(token_amount(result.get_account(&pool.user_lp(&user)).unwrap()), USER_LP - lp);
+    assert_eq!(token_amount(result.get_account(&pool.user_x(&user)).unwrap()), x);
+    assert_eq!(token_amount(result.get_account(&pool.user_y(&user)).unwrap()), y);
+}
+
+/// 所需 LP 超过 max_lp_in 时拒绝
+#[test]
+fn test_exact_out_rejects_lp_above_max_lp_in() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(312);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE_X, RESERVE_Y, SUPPLY);
+    accounts.extend(pool.user_accounts(&user, 0, 0, USER_LP));
+
+    let target_y = 5_000;
+    let curve = Curve::for_liquidity(RESERVE_X, RESERVE_Y, SUPPLY).unwrap();
+    let lp = curve.lp_for_withdraw(0, target_y).unwrap();
+
+    mollusk.process_and_validate_instruction(
+        &withdraw_exact_out(&pool, &user, lp - 1, 0, target_y),
+        &accounts,
+        &[Check::err(amm_err(AmmError::SlippageExceeded))],
+    );
+
+    // 恰好等于 max_lp_in 时成功
+    let result = mollusk.process_and_validate_instruction(
+        &withdraw_exact_out(&pool, &user, lp, 0, target_y),
+        &accounts,
+        &[Check::success()],
+    );
+    assert_eq!(token_amount(result.get_account(&pool.user_lp(&user)).unwrap()), USER_LP - lp);
+}
+
+/// 没有 exact_out 标志时仍按 amount 销毁，min_x / min_y 只是下限
+#[test]
+fn test_default_mode_burns_amount() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(313);
+    let user = Pubkey::new_unique();
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE_X, RESERVE_Y, SUPPLY);
+    accounts.extend(pool.user_accounts(&user, 0, 0, USER_LP));
+
+    let result = mollusk.process_and_validate_instruction(
+        &pool.withdraw_ix(&user, USER_LP, 12_345, 0),
+        &accounts,
+        &[Check::success()],
+    );
+    assert_eq!(token_amount(result.get_account(&pool.user_lp(&user)).unwrap()), 0);
+}
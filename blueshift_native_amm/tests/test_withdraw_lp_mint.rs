@@ -0,0 +1,38 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::{errors::AmmError, state::LP_DECIMALS},
+    common::*,
+    mollusk_svm::result::Check,
+    solana_sdk::pubkey::Pubkey,
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+
+/// 用户自己创建 supply 为 1 的“LP mint”，销毁 1 份就能按 100% 的份额取走金库，必须被拒绝
+#[test]
+fn test_withdraw_rejects_foreign_lp_mint() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(255);
+    let user = Pubkey::new_unique();
+    let fake_mint = Pubkey::new_unique();
+    let fake_lp = ata(&user, &fake_mint, &pool.token_program);
+
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, 0, 0, 0));
+    accounts.push((fake_mint, create_mint_account(&user, LP_DECIMALS, 1, &pool.token_program)));
+    accounts.push((fake_lp, create_token_account(&fake_mint, &user, 1, &pool.token_program)));
+
+    let mut ix = pool.withdraw_ix(&user, 1, 1, 1);
+    ix.accounts[1].pubkey = fake_mint;
+    ix.accounts[6].pubkey = fake_lp;
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &accounts,
+        &[Check::err(amm_err(AmmError::InvalidLpMint))],
+    );
+}
@@ -0,0 +1,102 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::errors::AmmError,
+    common::*,
+    mollusk_svm::result::Check,
+    solana_sdk::pubkey::Pubkey,
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+
+/// 单边取 X 与“先取款再把 Y 换成 X”两步操作的结果一致，并且少于不收手续费时的价值
+#[test]
+fn test_single_sided_matches_withdraw_then_swap() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(141);
+    let user = Pubkey::new_unique();
+    let lp = 50_000;
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, 0, 0, lp));
+
+    let single = mollusk.process_and_validate_instruction(
+        &pool.withdraw_single_sided_ix(&user, true, lp, 1),
+        &accounts,
+        &[Check::success()],
+    );
+    let single_x = token_amount(single.get_account(&pool.user_x(&user)).unwrap());
+    assert_eq!(token_amount(single.get_account(&pool.user_y(&user)).unwrap()), 0);
+    assert_eq!(token_amount(single.get_account(&pool.vault_y).unwrap()), RESERVE);
+
+    let withdrawn = mollusk.process_and_validate_instruction(
+        &pool.withdraw_ix(&user, lp, 0, 0),
+        &accounts,
+        &[Check::success()],
+    );
+    let y = token_amount(withdrawn.get_account(&pool.user_y(&user)).unwrap());
+    let two_step = mollusk.process_and_validate_instruction(
+        &pool.swap_ix(&user, false, y, 1),
+        &withdrawn.resulting_accounts,
+        &[Check::success()],
+    );
+    let two_step_x = token_amount(two_step.get_account(&pool.user_x(&user)).unwrap());
+
+    assert!(single_x.abs_diff(two_step_x) <= 1, "single {single_x} two-step {two_step_x}");
+    // 按现价（1:1）不收手续费时约为 2 * lp 的份额价值
+    let fee_free = 2 * lp * RESERVE / (RESERVE + 1_000);
+    assert!(single_x < fee_free);
+}
+
+#[test]
+fn test_min_out_is_enforced() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(142);
+    let user = Pubkey::new_unique();
+    let lp = 50_000;
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, 0, 0, lp));
+
+    mollusk.process_and_validate_instruction(
+        &pool.withdraw_single_sided_ix(&user, false, lp, 2 * lp),
+        &accounts,
+        &[Check::err(amm_err(AmmError::SlippageExceeded))],
+    );
+}
+
+/// 取走大部分份额后剩余储备太浅，内部兑换的价格影响超过上限
+#[test]
+fn test_shallow_pool_is_rejected() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(143);
+    let user = Pubkey::new_unique();
+    let lp = 900_000;
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, 0, 0, lp));
+
+    mollusk.process_and_validate_instruction(
+        &pool.withdraw_single_sided_ix(&user, true, lp, 1),
+        &accounts,
+        &[Check::err(amm_err(AmmError::PriceImpactTooHigh))],
+    );
+}
+
+/// 金库必须是 config 的 ATA：传入 owner 同为 config、余额更大的 Y 代币账户不能抬高取回的数量
+#[test]
+fn test_foreign_vault_is_rejected() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(342);
+    let user = Pubkey::new_unique();
+    let lp = 50_000;
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, 0, 0, lp));
+
+    let fake_vault = Pubkey::new_unique();
+    accounts.push((fake_vault, create_token_account(&pool.mint_y, &pool.config, 10 * RESERVE, &pool.token_program)));
+
+    let mut ix = pool.withdraw_single_sided_ix(&user, true, lp, 1);
+    ix.accounts[3].pubkey = fake_vault;
+    mollusk.process_and_validate_instruction(&ix, &accounts, &[Check::err(amm_err(AmmError::InvalidVault))]);
+}
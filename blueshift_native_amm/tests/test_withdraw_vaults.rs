@@ -0,0 +1,56 @@
+#![cfg(feature = "test-sbf")]
+
+mod common;
+
+use {
+    blueshift_native_amm::errors::AmmError,
+    common::*,
+    mollusk_svm::result::Check,
+    solana_sdk::{program_error::ProgramError, pubkey::Pubkey},
+};
+
+const FEE: u16 = 30;
+const RESERVE: u64 = 1_000_000;
+
+/// 任何人都可以创建 owner 为 config、mint 为 mint_x 的代币账户；它不是 config 的 ATA，不能当作金库
+#[test]
+fn test_withdraw_rejects_config_owned_non_ata_vault() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(258);
+    let user = Pubkey::new_unique();
+    let fake_vault = Pubkey::new_unique();
+
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, 0, 0, 10_000));
+    accounts.push((fake_vault, create_token_account(&pool.mint_x, &pool.config, 10 * RESERVE, &pool.token_program)));
+
+    let mut ix = pool.withdraw_ix(&user, 10_000, 1, 1);
+    ix.accounts[2].pubkey = fake_vault;
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &accounts,
+        &[Check::err(amm_err(AmmError::InvalidVault))],
+    );
+}
+
+/// 用户的 LP ATA 必须属于签名的 user
+#[test]
+fn test_withdraw_rejects_foreign_user_lp_ata() {
+    let mollusk = setup_mollusk();
+    let pool = Pool::new(259);
+    let (user, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+    let mut accounts = pool.accounts(&mollusk, FEE, RESERVE, RESERVE, RESERVE);
+    accounts.extend(pool.user_accounts(&user, 0, 0, 0));
+    accounts.push((pool.user_lp(&other), create_token_account(&pool.mint_lp, &other, 10_000, &pool.token_program)));
+
+    let mut ix = pool.withdraw_ix(&user, 10_000, 1, 1);
+    ix.accounts[6].pubkey = pool.user_lp(&other);
+
+    mollusk.process_and_validate_instruction(
+        &ix,
+        &accounts,
+        &[Check::err(ProgramError::InvalidAccountOwner)],
+    );
+}